@@ -0,0 +1,56 @@
+use fractal::{
+    build_index, generate_fixture_project, page_workers, set_page_workers, validate_project,
+    validation_report, FractalError, Result,
+};
+use std::time::Instant;
+
+fn main() -> Result<()> {
+    let pages = std::env::args()
+        .nth(1)
+        .map(|pages| pages.parse::<usize>())
+        .transpose()
+        .map_err(|error| FractalError::invalid_input(error.to_string()))?
+        .unwrap_or(1000);
+    let dir = tempfile::Builder::new()
+        .prefix("fractal-throughput-")
+        .tempdir()?;
+    let root = dir.path().join("fixture");
+
+    let started = Instant::now();
+    generate_fixture_project(&root, pages)?;
+    report("generate fixture", pages, started);
+
+    let available = page_workers();
+    let mut workers = vec![1];
+    if available > 1 {
+        workers.push(available);
+    }
+    for workers in workers {
+        set_page_workers(workers);
+        println!("workers: {workers}");
+        measure("validate_project", pages, || {
+            validate_project(&root).map(drop)
+        })?;
+        measure("validation_report", pages, || {
+            validation_report(&root).map(drop)
+        })?;
+        measure("build_index", pages, || build_index(&root).map(drop))?;
+    }
+    Ok(())
+}
+
+fn measure(name: &str, pages: usize, run: impl Fn() -> Result<()>) -> Result<()> {
+    let started = Instant::now();
+    run()?;
+    report(name, pages, started);
+    Ok(())
+}
+
+fn report(name: &str, pages: usize, started: Instant) {
+    let elapsed = started.elapsed();
+    println!(
+        "  {name:<18} {pages} pages in {:>8.1} ms ({:.0} pages/s)",
+        elapsed.as_secs_f64() * 1000.0,
+        pages as f64 / elapsed.as_secs_f64()
+    );
+}
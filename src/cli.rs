@@ -1,15 +1,31 @@
+use crate::mcp::McpServer;
+use crate::project::paths::split_section_selector;
+use crate::rpc::RpcServer;
+use crate::types::{CommandResult, ContextPageData, ReportData};
 use crate::{
-    add_note, build_index, delete_directory, delete_page, editor_page_detail, export_page,
-    graph_backlinks_report, graph_neighbors_report, graph_notes_report, graph_orphans_report,
-    graph_outlinks_report, graph_page_report, graph_related_report, import_markdown,
-    init_project_at, list_editor_pages, neighbor_pages, new_page, patch_note, read_page_source,
-    remove_note, rename_page, repair_project, search_report, sync_project, update_editor_page,
-    validate_project, EditorPageUpdate, FractalError, OperationEvent, OperationReport, PageRename,
-    Result,
+    disable_history, editor_page_detail, enable_history, external_links, graph_backlinks_report,
+    graph_neighbors_report, graph_notes_report, graph_orphans_report, graph_outlinks_report,
+    graph_page_report, graph_related_report, history_diff, history_entry, history_log,
+    lint_project, list_assets, list_editor_pages, neighbor_pages, page_blame, page_blocks,
+    page_git_history, page_section, plan_add_asset, plan_add_note, plan_apply_repair_suggestions,
+    plan_build_index, plan_delete_asset, plan_delete_block, plan_delete_directory,
+    plan_delete_page, plan_demote_page, plan_export_page, plan_generate_fixture_project,
+    plan_import_markdown, plan_import_pages, plan_init_project_at, plan_insert_block,
+    plan_move_asset, plan_move_block, plan_new_page, plan_patch_note, plan_promote_note,
+    plan_remove_note, plan_rename_note, plan_rename_page, plan_replace_block,
+    plan_set_git_auto_commit, plan_sync_project, plan_undo_history, plan_update_editor_page,
+    preflight_repair_project, read_page_source, repair_project, repair_suggestions, search_report,
+    set_page_workers, shortest_page_path, unlock_project, unreferenced_assets, validate_project,
+    validation_report, BlockPosition, DiagnosticSeverity, EditorPageUpdate, FileChange,
+    FractalError, HistoryEntry, ImportFormat, MutationPlan, OperationEvent, OperationReport,
+    PageRename, ProjectWatcher, Result, ValidationReport, WatchBackend, WatchOptions, LINT_RULES,
 };
 use clap::{Parser, Subcommand, ValueEnum};
 use serde::Serialize;
+use std::io::BufReader;
+use std::ops::ControlFlow;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 #[derive(Debug, Parser)]
 #[command(name = "fractal")]
@@ -27,6 +43,16 @@ pub struct Cli {
     /// Print less human output where supported.
     #[arg(long, global = true)]
     quiet: bool,
+    /// Show the planned changes and diffs without writing anything.
+    #[arg(long, global = true)]
+    dry_run: bool,
+    /// Wait up to this many milliseconds for another writer's project lock.
+    /// Defaults to `lock_timeout_ms` in fractal.json.
+    #[arg(long, global = true)]
+    lock_timeout: Option<u64>,
+    /// Worker threads for page-level validation and indexing. 0 uses every available core.
+    #[arg(long, global = true, default_value_t = 0)]
+    jobs: usize,
     #[command(subcommand)]
     command: Command,
 }
@@ -64,6 +90,11 @@ enum Command {
         #[command(subcommand)]
         command: GraphCommand,
     },
+    /// Inspect links recorded in the generated project graph.
+    Link {
+        #[command(subcommand)]
+        command: LinkCommand,
+    },
     /// Search the generated project index.
     Search {
         #[command(subcommand)]
@@ -76,11 +107,26 @@ enum Command {
         #[command(subcommand)]
         command: PageCommand,
     },
+    /// Manage non-page files under pages/.
+    Asset {
+        #[command(subcommand)]
+        command: AssetCommand,
+    },
     /// Manage page-local notes.
     Note {
         #[command(subcommand)]
         command: NoteCommand,
     },
+    /// Inspect and undo recorded project history.
+    History {
+        #[command(subcommand)]
+        command: HistoryCommand,
+    },
+    /// Commit applied operations to the enclosing git repository.
+    Git {
+        #[command(subcommand)]
+        command: GitCommand,
+    },
     /// Build compact LLM/agent context packets.
     Context {
         #[command(subcommand)]
@@ -102,6 +148,42 @@ enum Command {
         /// Legacy output argument. Prefer `fractal export markdown <page> --to <path>`.
         output: Option<PathBuf>,
     },
+    /// Report knowledge-base hygiene problems configured under `lint` in fractal.json.
+    Lint {
+        /// List the available lint rules instead of running them.
+        #[arg(long)]
+        list_rules: bool,
+    },
+    /// Keep generated data fresh while pages/ and fractal.json change.
+    Watch {
+        /// Validate changed pages and report problems as warnings.
+        #[arg(long)]
+        validate: bool,
+        /// Poll the filesystem instead of using native change notifications.
+        #[arg(long)]
+        poll: bool,
+        /// Wait this many milliseconds for a burst of changes to settle.
+        #[arg(long, default_value_t = 200)]
+        debounce_ms: u64,
+        /// Milliseconds between scans when polling.
+        #[arg(long, default_value_t = 500)]
+        poll_interval_ms: u64,
+    },
+    /// Serve library operations as JSON-RPC 2.0 requests.
+    Serve {
+        /// Read requests from stdin and write responses to stdout, one per line.
+        #[arg(long)]
+        stdio: bool,
+        /// Poll the filesystem for change notifications instead of using native events.
+        #[arg(long)]
+        poll: bool,
+    },
+    /// Serve search, context, graph, and page tools to agents over the Model Context Protocol.
+    Mcp {
+        /// Also offer tools that change the project (note add/set, page set).
+        #[arg(long)]
+        allow_writes: bool,
+    },
     /// Describe machine-readable CLI capabilities.
     Schema {
         #[command(subcommand)]
@@ -129,12 +211,37 @@ enum ProjectCommand {
         #[arg(long)]
         name: Option<String>,
     },
+    /// Create a synthetic project of linked pages for throughput benchmarks.
+    Fixture {
+        /// Path of the project directory to create.
+        path: PathBuf,
+        /// Number of pages to generate.
+        #[arg(long, default_value_t = 1000)]
+        pages: usize,
+    },
     /// Validate the current Fractal project without writing.
-    Validate,
+    Validate {
+        /// Stop at the first invalid page instead of reporting every diagnostic.
+        #[arg(long)]
+        fail_fast: bool,
+    },
     /// Repair missing Fractal scaffold/page markers, then validate.
-    Repair,
+    Repair {
+        /// List reviewable patches for errors the safe repair cannot fix.
+        #[arg(long, conflicts_with = "accept")]
+        suggest: bool,
+        /// Apply the repair suggestion with this ID. Repeat to accept several.
+        #[arg(long, value_name = "ID")]
+        accept: Vec<String>,
+    },
     /// Rebuild generated data and sync inferred links across pages.
     Sync,
+    /// Remove a stale mutation lock left by a crashed writer.
+    Unlock {
+        /// Remove the lock even when its owner still appears to be running.
+        #[arg(long)]
+        force: bool,
+    },
 }
 
 #[derive(Debug, Subcommand)]
@@ -163,6 +270,8 @@ enum GraphCommand {
     Notes { page: PathBuf },
     /// List pages with no backlinks.
     Orphans,
+    /// Show the shortest chain of linked pages between two pages.
+    Path { from: PathBuf, to: PathBuf },
 }
 
 #[derive(Debug, Subcommand)]
@@ -195,6 +304,9 @@ enum PageCommand {
         tags: Vec<String>,
         #[arg(long = "body-file")]
         body_file: Option<PathBuf>,
+        /// Fail with a conflict unless the page still has this revision.
+        #[arg(long = "expected-revision")]
+        expected_revision: Option<String>,
     },
     /// Move/rename a page, optionally changing its title.
     Move {
@@ -203,12 +315,30 @@ enum PageCommand {
         to: PathBuf,
         #[arg(long)]
         title: Option<String>,
+        /// Fail with a conflict unless the page still has this revision.
+        #[arg(long = "expected-revision")]
+        expected_revision: Option<String>,
     },
     /// Delete a page.
     Delete {
         page: PathBuf,
         #[arg(long)]
         yes: bool,
+        /// Fail with a conflict unless the page still has this revision.
+        #[arg(long = "expected-revision")]
+        expected_revision: Option<String>,
+    },
+    /// Turn a page into a note on a host page, pointing the host's links at the note.
+    Demote {
+        page: PathBuf,
+        /// Page that receives the note.
+        #[arg(long)]
+        into: PathBuf,
+        #[arg(long)]
+        yes: bool,
+        /// Fail with a conflict unless the page still has this revision.
+        #[arg(long = "expected-revision")]
+        expected_revision: Option<String>,
     },
     /// Delete a folder under pages/.
     DeleteFolder {
@@ -224,6 +354,76 @@ enum PageCommand {
         #[command(subcommand)]
         command: PageSourceCommand,
     },
+    /// List git commits that touched a page, following renames.
+    History { page: PathBuf },
+    /// Map each main block and note of a page to the commit that last changed it.
+    Blame { page: PathBuf },
+    /// List and edit single main body blocks by ID.
+    Block {
+        #[command(subcommand)]
+        command: PageBlockCommand,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum PageBlockCommand {
+    /// List main body blocks with their content-derived IDs.
+    List { page: PathBuf },
+    /// Insert one block. Appends to the end of the body unless a position is given.
+    Insert {
+        page: PathBuf,
+        /// HTML for exactly one block element.
+        #[arg(long)]
+        html: String,
+        /// Insert before this block ID.
+        #[arg(long, conflicts_with_all = ["after", "start"])]
+        before: Option<String>,
+        /// Insert after this block ID.
+        #[arg(long, conflicts_with = "start")]
+        after: Option<String>,
+        /// Insert as the first body block.
+        #[arg(long)]
+        start: bool,
+        /// Fail with a conflict unless the page still has this revision.
+        #[arg(long = "expected-revision")]
+        expected_revision: Option<String>,
+    },
+    /// Replace one block with new HTML.
+    Replace {
+        page: PathBuf,
+        block: String,
+        /// HTML for exactly one block element.
+        #[arg(long)]
+        html: String,
+        /// Fail with a conflict unless the page still has this revision.
+        #[arg(long = "expected-revision")]
+        expected_revision: Option<String>,
+    },
+    /// Move one block. Moves to the end of the body unless a position is given.
+    Move {
+        page: PathBuf,
+        block: String,
+        /// Move before this block ID.
+        #[arg(long, conflicts_with_all = ["after", "start"])]
+        before: Option<String>,
+        /// Move after this block ID.
+        #[arg(long, conflicts_with = "start")]
+        after: Option<String>,
+        /// Move to the start of the body.
+        #[arg(long)]
+        start: bool,
+        /// Fail with a conflict unless the page still has this revision.
+        #[arg(long = "expected-revision")]
+        expected_revision: Option<String>,
+    },
+    /// Delete one block.
+    Delete {
+        page: PathBuf,
+        block: String,
+        /// Fail with a conflict unless the page still has this revision.
+        #[arg(long = "expected-revision")]
+        expected_revision: Option<String>,
+    },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
@@ -238,6 +438,44 @@ enum PageSourceCommand {
     Read { page: PathBuf },
 }
 
+#[derive(Debug, Subcommand)]
+enum LinkCommand {
+    /// List external links with their domains and citing pages. Does not fetch URLs.
+    External,
+}
+
+#[derive(Debug, Subcommand)]
+enum AssetCommand {
+    /// List assets with content hashes, MIME types, and referencing pages.
+    List {
+        /// Only list assets that no page references.
+        #[arg(long)]
+        unreferenced: bool,
+    },
+    /// Copy a file into pages/.
+    Add {
+        source: PathBuf,
+        /// Destination under pages/. Defaults to the source file name.
+        #[arg(long)]
+        to: Option<PathBuf>,
+    },
+    /// Move an asset and rewrite page references to it.
+    Move {
+        asset: PathBuf,
+        #[arg(long)]
+        to: PathBuf,
+    },
+    /// Delete an asset.
+    Delete {
+        asset: PathBuf,
+        /// Delete the asset even when pages still reference it.
+        #[arg(long)]
+        force: bool,
+        #[arg(long)]
+        yes: bool,
+    },
+}
+
 #[derive(Debug, Subcommand)]
 enum NoteCommand {
     /// Add a note whose id is derived from the trigger text.
@@ -246,9 +484,41 @@ enum NoteCommand {
         trigger: String,
         #[arg(long)]
         content: String,
+        /// Fail with a conflict unless the page still has this revision.
+        #[arg(long = "expected-revision")]
+        expected_revision: Option<String>,
     },
     /// Remove a note whose id is derived from the trigger text.
-    Remove { page: PathBuf, trigger: String },
+    Remove {
+        page: PathBuf,
+        trigger: String,
+        /// Fail with a conflict unless the page still has this revision.
+        #[arg(long = "expected-revision")]
+        expected_revision: Option<String>,
+    },
+    /// Turn a note into a new page next to its host, linking the host to it.
+    Promote {
+        page: PathBuf,
+        /// Note id or trigger text.
+        note: String,
+        #[arg(long)]
+        title: String,
+        /// Fail with a conflict unless the page still has this revision.
+        #[arg(long = "expected-revision")]
+        expected_revision: Option<String>,
+    },
+    /// Change a note id, rewriting links and embeds that target it.
+    Rename {
+        page: PathBuf,
+        /// Note id or trigger text.
+        note: String,
+        /// New trigger text; the new id is derived from it.
+        #[arg(long)]
+        to: String,
+        /// Fail with a conflict unless the page still has this revision.
+        #[arg(long = "expected-revision")]
+        expected_revision: Option<String>,
+    },
     /// Replace a note body.
     #[command(alias = "patch")]
     Set {
@@ -256,13 +526,44 @@ enum NoteCommand {
         trigger: String,
         #[arg(long)]
         content: String,
+        /// Fail with a conflict unless the page still has this revision.
+        #[arg(long = "expected-revision")]
+        expected_revision: Option<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum HistoryCommand {
+    /// Start recording applied mutations under .fractal/history/.
+    Enable,
+    /// Stop recording and delete the stored history.
+    Disable {
+        #[arg(long)]
+        yes: bool,
     },
+    /// List recorded operations, optionally only those touching a page.
+    Log { page: Option<PathBuf> },
+    /// Show one recorded operation.
+    Show { id: u64 },
+    /// Show the unified diff of one recorded operation.
+    Diff { id: u64 },
+    /// Apply the inverse of a recorded operation as a new operation.
+    Undo { id: u64 },
+}
+
+#[derive(Debug, Subcommand)]
+enum GitCommand {
+    /// Commit every applied operation to git with a message derived from its summary.
+    Enable,
+    /// Stop committing applied operations.
+    Disable,
 }
 
 #[derive(Debug, Subcommand)]
 enum ContextCommand {
     /// Return compact context for one page.
     Page {
+        /// Page path, or `page#heading-anchor` to focus on one section.
         page: PathBuf,
         #[arg(long)]
         budget: Option<usize>,
@@ -273,6 +574,10 @@ enum ContextCommand {
 enum ImportCommand {
     /// Import a markdown file.
     Markdown { source: PathBuf },
+    /// Import a Notion markdown/CSV export directory.
+    Notion { source: PathBuf },
+    /// Import a Logseq graph or outline files.
+    Logseq { source: PathBuf },
 }
 
 #[derive(Debug, Subcommand)]
@@ -292,44 +597,21 @@ enum SchemaCommand {
 }
 
 #[derive(Debug, Serialize)]
-struct CommandResult<T: Serialize> {
-    ok: bool,
-    schema: &'static str,
-    command: &'static str,
-    project: ProjectRef,
-    data: T,
-}
-
-#[derive(Debug, Serialize)]
-struct ProjectRef {
-    root: PathBuf,
-}
-
-#[derive(Debug, Serialize)]
-struct ReportData {
-    report: OperationReport,
-    summary: crate::OperationSummary,
-}
-
-#[derive(Debug, Serialize)]
-struct ContextPageData {
-    page: crate::EditorPageDetail,
-    neighbors: Vec<crate::GraphNeighborPage>,
-    budget: Option<usize>,
-}
-
-#[derive(Debug, Serialize)]
-struct SchemaCommandEntry {
-    name: &'static str,
-    kind: &'static str,
-    json: bool,
-    examples: &'static [&'static str],
+pub(crate) struct SchemaCommandEntry {
+    pub(crate) name: &'static str,
+    pub(crate) kind: &'static str,
+    pub(crate) json: bool,
+    pub(crate) examples: &'static [&'static str],
 }
 
 pub fn run() -> Result<()> {
     let cli = Cli::parse();
     let root = cli.project.clone();
     let output_format = cli.output_format();
+    let dry_run = cli.dry_run;
+    let quiet = cli.quiet;
+    let lock_timeout = cli.lock_timeout.map(Duration::from_millis);
+    set_page_workers(cli.jobs);
 
     match cli.command {
         Command::Project { command } => match command {
@@ -340,26 +622,108 @@ pub fn run() -> Result<()> {
                         .unwrap_or("fractal-project")
                         .to_string()
                 });
-                let report = init_project_at(&path, &name)?;
-                print_report_result(output_format, "project.init", &root, &report)
+                let plan = plan_init_project_at(&path, &name)?;
+                print_plan_result(
+                    output_format,
+                    lock_timeout,
+                    "project.init",
+                    &root,
+                    plan,
+                    dry_run,
+                )
+            }
+            ProjectCommand::Fixture { path, pages } => {
+                let plan = plan_generate_fixture_project(&path, pages)?;
+                print_plan_result(
+                    output_format,
+                    lock_timeout,
+                    "project.fixture",
+                    &root,
+                    plan,
+                    dry_run,
+                )
             }
-            ProjectCommand::Validate => {
+            ProjectCommand::Validate { fail_fast: true } => {
                 let report = validate_project(&root)?;
                 print_report_result(output_format, "project.validate", &root, &report)
             }
-            ProjectCommand::Repair => {
-                let report = repair_project(&root)?;
+            ProjectCommand::Validate { fail_fast: false } => {
+                let report = validation_report(&root)?;
+                print_data(output_format, "project.validate", &root, &report, || {
+                    print_validation_report(&report)
+                })?;
+                if report.is_valid() {
+                    Ok(())
+                } else {
+                    Err(FractalError::invalid_project(format!(
+                        "validation found {} error(s) in {} page(s)",
+                        report.error_count(),
+                        report.pages_checked
+                    )))
+                }
+            }
+            ProjectCommand::Repair { suggest: true, .. } => {
+                let suggestions = repair_suggestions(&root)?;
+                print_data(output_format, "project.repair", &root, &suggestions, || {
+                    for suggestion in &suggestions {
+                        println!("{}: {}", suggestion.id, suggestion.action);
+                        println!("  {}", suggestion.diagnostic.message);
+                        print!("{}", suggestion.diff.diff);
+                    }
+                    println!("{} suggestion(s)", suggestions.len());
+                    Ok(())
+                })
+            }
+            ProjectCommand::Repair { accept, .. } if !accept.is_empty() => {
+                let plan = plan_apply_repair_suggestions(&root, &accept)?;
+                print_plan_result(
+                    output_format,
+                    lock_timeout,
+                    "project.repair",
+                    &root,
+                    plan,
+                    dry_run,
+                )
+            }
+            ProjectCommand::Repair { .. } => {
+                let report = if dry_run {
+                    preflight_repair_project(&root)?
+                } else {
+                    repair_project(&root)?
+                };
                 print_report_result(output_format, "project.repair", &root, &report)
             }
             ProjectCommand::Sync => {
-                let report = sync_project(&root)?;
-                print_report_result(output_format, "project.sync", &root, &report)
+                let plan = plan_sync_project(&root)?;
+                print_plan_result(
+                    output_format,
+                    lock_timeout,
+                    "project.sync",
+                    &root,
+                    plan,
+                    dry_run,
+                )
+            }
+            ProjectCommand::Unlock { force } => {
+                let report = unlock_project(&root, force)?;
+                if report.events.is_empty() && output_format == OutputFormat::Human {
+                    println!("project is not locked");
+                    return Ok(());
+                }
+                print_report_result(output_format, "project.unlock", &root, &report)
             }
         },
         Command::Index { command } => match command {
             IndexCommand::Build => {
-                let report = build_index(&root)?;
-                print_report_result(output_format, "index.build", &root, &report)
+                let plan = plan_build_index(&root)?;
+                print_plan_result(
+                    output_format,
+                    lock_timeout,
+                    "index.build",
+                    &root,
+                    plan,
+                    dry_run,
+                )
             }
         },
         Command::Graph { command } => match command {
@@ -405,6 +769,16 @@ pub fn run() -> Result<()> {
                 &root,
                 graph_orphans_report(&root)?,
             ),
+            GraphCommand::Path { from, to } => {
+                let path = shortest_page_path(&root, &from, &to)?;
+                print_data(output_format, "graph.path", &root, &path, || {
+                    match &path {
+                        Some(pages) => println!("{}", pages.join(" -> ")),
+                        None => println!("no path"),
+                    }
+                    Ok(())
+                })
+            }
         },
         Command::Search { command, query } => {
             let query = match command {
@@ -438,6 +812,7 @@ pub fn run() -> Result<()> {
                     print_data(output_format, "page.read", &root, &detail, || {
                         println!("{}", detail.metadata.title);
                         println!("path: {}", detail.metadata.path);
+                        println!("revision: {}", detail.source.revision);
                         if let Some(summary) = &detail.metadata.summary {
                             println!("summary: {summary}");
                         }
@@ -469,8 +844,15 @@ pub fn run() -> Result<()> {
                 }
             },
             PageCommand::Create { page } => {
-                let report = new_page(&root, &page)?;
-                print_report_result(output_format, "page.create", &root, &report)
+                let plan = plan_new_page(&root, &page)?;
+                print_plan_result(
+                    output_format,
+                    lock_timeout,
+                    "page.create",
+                    &root,
+                    plan,
+                    dry_run,
+                )
             }
             PageCommand::Set {
                 page,
@@ -478,6 +860,7 @@ pub fn run() -> Result<()> {
                 summary,
                 tags,
                 body_file,
+                expected_revision,
             } => {
                 let body_html = match body_file {
                     Some(path) => Some(std::fs::read_to_string(path)?),
@@ -488,40 +871,98 @@ pub fn run() -> Result<()> {
                     body_html,
                     summary,
                     tags: if tags.is_empty() { None } else { Some(tags) },
+                    expected_revision,
                 };
-                let report = update_editor_page(&root, &page, update)?;
-                print_report_result(output_format, "page.set", &root, &report)
+                let plan = plan_update_editor_page(&root, &page, update)?;
+                print_plan_result(
+                    output_format,
+                    lock_timeout,
+                    "page.set",
+                    &root,
+                    plan,
+                    dry_run,
+                )
             }
-            PageCommand::Move { page, to, title } => {
-                let report = rename_page(
+            PageCommand::Move {
+                page,
+                to,
+                title,
+                expected_revision,
+            } => {
+                let plan = plan_rename_page(
                     &root,
                     &page,
                     PageRename {
                         path: Some(to),
                         title,
+                        expected_revision,
                     },
                 )?;
-                print_report_result(output_format, "page.move", &root, &report)
+                print_plan_result(
+                    output_format,
+                    lock_timeout,
+                    "page.move",
+                    &root,
+                    plan,
+                    dry_run,
+                )
             }
-            PageCommand::Delete { page, yes } => {
-                if !yes {
+            PageCommand::Delete {
+                page,
+                yes,
+                expected_revision,
+            } => {
+                if !yes && !dry_run {
                     return Err(FractalError::invalid_input("page delete requires --yes"));
                 }
-                let report = delete_page(&root, &page)?;
-                print_report_result(output_format, "page.delete", &root, &report)
+                let plan = plan_delete_page(&root, &page, expected_revision.as_deref())?;
+                print_plan_result(
+                    output_format,
+                    lock_timeout,
+                    "page.delete",
+                    &root,
+                    plan,
+                    dry_run,
+                )
+            }
+            PageCommand::Demote {
+                page,
+                into,
+                yes,
+                expected_revision,
+            } => {
+                if !yes && !dry_run {
+                    return Err(FractalError::invalid_input("page demote requires --yes"));
+                }
+                let plan = plan_demote_page(&root, &page, &into, expected_revision.as_deref())?;
+                print_plan_result(
+                    output_format,
+                    lock_timeout,
+                    "page.demote",
+                    &root,
+                    plan,
+                    dry_run,
+                )
             }
             PageCommand::DeleteFolder {
                 folder,
                 recursive,
                 yes,
             } => {
-                if !yes {
+                if !yes && !dry_run {
                     return Err(FractalError::invalid_input(
                         "page delete-folder requires --yes",
                     ));
                 }
-                let report = delete_directory(&root, &folder, recursive)?;
-                print_report_result(output_format, "page.delete_folder", &root, &report)
+                let plan = plan_delete_directory(&root, &folder, recursive)?;
+                print_plan_result(
+                    output_format,
+                    lock_timeout,
+                    "page.delete_folder",
+                    &root,
+                    plan,
+                    dry_run,
+                )
             }
             PageCommand::Source { command } => match command {
                 PageSourceCommand::Read { page } => {
@@ -532,36 +973,395 @@ pub fn run() -> Result<()> {
                     })
                 }
             },
+            PageCommand::History { page } => {
+                let commits = page_git_history(&root, &page)?;
+                print_data(output_format, "page.history", &root, &commits, || {
+                    for commit in &commits {
+                        println!(
+                            "{}\t{}\t{}\t{}",
+                            short_commit(&commit.id),
+                            commit.timestamp,
+                            commit.author,
+                            commit.subject
+                        );
+                    }
+                    Ok(())
+                })
+            }
+            PageCommand::Blame { page } => {
+                let blame = page_blame(&root, &page)?;
+                print_data(output_format, "page.blame", &root, &blame, || {
+                    for entry in &blame.entries {
+                        match &entry.commit {
+                            Some(commit) => println!(
+                                "{}\t{}\t{}",
+                                entry.key,
+                                short_commit(&commit.id),
+                                commit.subject
+                            ),
+                            None => println!("{}\tuncommitted", entry.key),
+                        }
+                    }
+                    Ok(())
+                })
+            }
+            PageCommand::Block { command } => match command {
+                PageBlockCommand::List { page } => {
+                    let blocks = page_blocks(&root, &page)?;
+                    print_data(output_format, "page.block.list", &root, &blocks, || {
+                        for block in &blocks {
+                            println!("{}\t<{}>\t{}", block.id, block.kind, block.text);
+                        }
+                        Ok(())
+                    })
+                }
+                PageBlockCommand::Insert {
+                    page,
+                    html,
+                    before,
+                    after,
+                    start,
+                    expected_revision,
+                } => {
+                    let position = BlockPosition::from_flags(before, after, start)?;
+                    let plan = plan_insert_block(
+                        &root,
+                        &page,
+                        position,
+                        &html,
+                        expected_revision.as_deref(),
+                    )?;
+                    print_plan_result(
+                        output_format,
+                        lock_timeout,
+                        "page.block.insert",
+                        &root,
+                        plan,
+                        dry_run,
+                    )
+                }
+                PageBlockCommand::Replace {
+                    page,
+                    block,
+                    html,
+                    expected_revision,
+                } => {
+                    let plan = plan_replace_block(
+                        &root,
+                        &page,
+                        &block,
+                        &html,
+                        expected_revision.as_deref(),
+                    )?;
+                    print_plan_result(
+                        output_format,
+                        lock_timeout,
+                        "page.block.replace",
+                        &root,
+                        plan,
+                        dry_run,
+                    )
+                }
+                PageBlockCommand::Move {
+                    page,
+                    block,
+                    before,
+                    after,
+                    start,
+                    expected_revision,
+                } => {
+                    let position = BlockPosition::from_flags(before, after, start)?;
+                    let plan = plan_move_block(
+                        &root,
+                        &page,
+                        &block,
+                        position,
+                        expected_revision.as_deref(),
+                    )?;
+                    print_plan_result(
+                        output_format,
+                        lock_timeout,
+                        "page.block.move",
+                        &root,
+                        plan,
+                        dry_run,
+                    )
+                }
+                PageBlockCommand::Delete {
+                    page,
+                    block,
+                    expected_revision,
+                } => {
+                    let plan =
+                        plan_delete_block(&root, &page, &block, expected_revision.as_deref())?;
+                    print_plan_result(
+                        output_format,
+                        lock_timeout,
+                        "page.block.delete",
+                        &root,
+                        plan,
+                        dry_run,
+                    )
+                }
+            },
+        },
+        Command::Link { command } => match command {
+            LinkCommand::External => {
+                let links = external_links(&root)?;
+                print_data(output_format, "link.external", &root, &links, || {
+                    if links.is_empty() {
+                        println!("(no external links)");
+                    }
+                    for link in &links {
+                        println!("{} ({})", link.url, link.domain);
+                        for source in &link.sources {
+                            println!("  - {} ({})", source.page, source.text);
+                        }
+                    }
+                    Ok(())
+                })
+            }
+        },
+        Command::Asset { command } => match command {
+            AssetCommand::List { unreferenced } => {
+                let assets = if unreferenced {
+                    unreferenced_assets(&root)?
+                } else {
+                    list_assets(&root)?
+                };
+                print_data(output_format, "asset.list", &root, &assets, || {
+                    for asset in &assets {
+                        println!(
+                            "{}\t{}\t{} reference(s)",
+                            asset.path,
+                            asset.mime_type,
+                            asset.referenced_by.len()
+                        );
+                    }
+                    Ok(())
+                })
+            }
+            AssetCommand::Add { source, to } => {
+                let destination = match to {
+                    Some(to) => to,
+                    None => source.file_name().map(PathBuf::from).ok_or_else(|| {
+                        FractalError::invalid_input("asset source must name a file")
+                    })?,
+                };
+                let plan = plan_add_asset(&root, &source, &destination)?;
+                print_plan_result(
+                    output_format,
+                    lock_timeout,
+                    "asset.add",
+                    &root,
+                    plan,
+                    dry_run,
+                )
+            }
+            AssetCommand::Move { asset, to } => {
+                let plan = plan_move_asset(&root, &asset, &to)?;
+                print_plan_result(
+                    output_format,
+                    lock_timeout,
+                    "asset.move",
+                    &root,
+                    plan,
+                    dry_run,
+                )
+            }
+            AssetCommand::Delete { asset, force, yes } => {
+                if !yes && !dry_run {
+                    return Err(FractalError::invalid_input("asset delete requires --yes"));
+                }
+                let plan = plan_delete_asset(&root, &asset, force)?;
+                print_plan_result(
+                    output_format,
+                    lock_timeout,
+                    "asset.delete",
+                    &root,
+                    plan,
+                    dry_run,
+                )
+            }
         },
         Command::Note { command } => match command {
             NoteCommand::Add {
                 page,
                 trigger,
                 content,
+                expected_revision,
             } => {
-                let report = add_note(&root, &page, &trigger, &content)?;
-                print_report_result(output_format, "note.add", &root, &report)
+                let plan = plan_add_note(
+                    &root,
+                    &page,
+                    &trigger,
+                    &content,
+                    expected_revision.as_deref(),
+                )?;
+                print_plan_result(
+                    output_format,
+                    lock_timeout,
+                    "note.add",
+                    &root,
+                    plan,
+                    dry_run,
+                )
+            }
+            NoteCommand::Remove {
+                page,
+                trigger,
+                expected_revision,
+            } => {
+                let plan = plan_remove_note(&root, &page, &trigger, expected_revision.as_deref())?;
+                print_plan_result(
+                    output_format,
+                    lock_timeout,
+                    "note.remove",
+                    &root,
+                    plan,
+                    dry_run,
+                )
+            }
+            NoteCommand::Promote {
+                page,
+                note,
+                title,
+                expected_revision,
+            } => {
+                let plan =
+                    plan_promote_note(&root, &page, &note, &title, expected_revision.as_deref())?;
+                print_plan_result(
+                    output_format,
+                    lock_timeout,
+                    "note.promote",
+                    &root,
+                    plan,
+                    dry_run,
+                )
             }
-            NoteCommand::Remove { page, trigger } => {
-                let report = remove_note(&root, &page, &trigger)?;
-                print_report_result(output_format, "note.remove", &root, &report)
+            NoteCommand::Rename {
+                page,
+                note,
+                to,
+                expected_revision,
+            } => {
+                let plan =
+                    plan_rename_note(&root, &page, &note, &to, expected_revision.as_deref())?;
+                print_plan_result(
+                    output_format,
+                    lock_timeout,
+                    "note.rename",
+                    &root,
+                    plan,
+                    dry_run,
+                )
             }
             NoteCommand::Set {
                 page,
                 trigger,
                 content,
+                expected_revision,
             } => {
-                let report = patch_note(&root, &page, &trigger, &content)?;
-                print_report_result(output_format, "note.set", &root, &report)
+                let plan = plan_patch_note(
+                    &root,
+                    &page,
+                    &trigger,
+                    &content,
+                    expected_revision.as_deref(),
+                )?;
+                print_plan_result(
+                    output_format,
+                    lock_timeout,
+                    "note.set",
+                    &root,
+                    plan,
+                    dry_run,
+                )
+            }
+        },
+        Command::History { command } => match command {
+            HistoryCommand::Enable => {
+                let report = enable_history(&root)?;
+                if report.events.is_empty() && output_format == OutputFormat::Human {
+                    println!("history is already enabled");
+                    return Ok(());
+                }
+                print_report_result(output_format, "history.enable", &root, &report)
+            }
+            HistoryCommand::Disable { yes } => {
+                if !yes {
+                    return Err(FractalError::invalid_input(
+                        "history disable requires --yes",
+                    ));
+                }
+                let report = disable_history(&root)?;
+                if report.events.is_empty() && output_format == OutputFormat::Human {
+                    println!("history is not enabled");
+                    return Ok(());
+                }
+                print_report_result(output_format, "history.disable", &root, &report)
+            }
+            HistoryCommand::Log { page } => {
+                let entries = history_log(&root, page.as_deref())?;
+                print_data(output_format, "history.log", &root, &entries, || {
+                    for entry in &entries {
+                        print_history_entry_line(entry);
+                    }
+                    Ok(())
+                })
+            }
+            HistoryCommand::Show { id } => {
+                let entry = history_entry(&root, id)?;
+                print_data(output_format, "history.show", &root, &entry, || {
+                    print_history_entry_line(&entry);
+                    print_operation_report(&OperationReport {
+                        events: entry.events.clone(),
+                    })?;
+                    for file in &entry.files {
+                        print_file_change(file.change, &file.path, file.from.as_deref(), "");
+                    }
+                    Ok(())
+                })
+            }
+            HistoryCommand::Diff { id } => {
+                let diffs = history_diff(&root, id)?;
+                print_data(output_format, "history.diff", &root, &diffs, || {
+                    for diff in &diffs {
+                        print!("{}", diff.diff);
+                    }
+                    Ok(())
+                })
+            }
+            HistoryCommand::Undo { id } => {
+                let plan = plan_undo_history(&root, id)?;
+                print_plan_result(
+                    output_format,
+                    lock_timeout,
+                    "history.undo",
+                    &root,
+                    plan,
+                    dry_run,
+                )
             }
         },
+        Command::Git { command } => {
+            let (command, enabled) = match command {
+                GitCommand::Enable => ("git.enable", true),
+                GitCommand::Disable => ("git.disable", false),
+            };
+            let plan = plan_set_git_auto_commit(&root, enabled)?;
+            print_plan_result(output_format, lock_timeout, command, &root, plan, dry_run)
+        }
         Command::Context { command } => match command {
             ContextCommand::Page { page, budget } => {
-                let detail = editor_page_detail(&root, &page)?;
-                let neighbors = neighbor_pages(&root, &page, 1)?;
+                let (page_path, anchor) = split_section_selector(&page)?;
+                let detail = editor_page_detail(&root, &page_path)?;
+                let neighbors = neighbor_pages(&root, &page_path, 1)?;
+                let section = anchor.map(|_| page_section(&root, &page)).transpose()?;
                 let data = ContextPageData {
                     page: detail,
                     neighbors,
+                    section,
                     budget,
                 };
                 print_data(output_format, "context.page", &root, &data, || {
@@ -570,23 +1370,60 @@ pub fn run() -> Result<()> {
                         println!("summary: {summary}");
                     }
                     println!("neighbors: {}", data.neighbors.len());
-                    println!("\n{}", data.page.body_html);
+                    match &data.section {
+                        Some(section) => {
+                            println!("section: {}", section.heading.text);
+                            println!("\n{}", section.html);
+                        }
+                        None => println!("\n{}", data.page.body_html),
+                    }
                     Ok(())
                 })
             }
         },
-        Command::Import { command, source } => {
-            let source = match command {
-                Some(ImportCommand::Markdown { source }) => source,
-                None => source.ok_or_else(|| {
-                    FractalError::invalid_input(
-                        "missing import source; use `fractal import markdown <source>`",
-                    )
-                })?,
-            };
-            let report = import_markdown(&root, &source)?;
-            print_report_result(output_format, "import.markdown", &root, &report)
-        }
+        Command::Import { command, source } => match command {
+            Some(ImportCommand::Notion { source }) => {
+                let plan = plan_import_pages(&root, &source, ImportFormat::Notion)?;
+                print_plan_result(
+                    output_format,
+                    lock_timeout,
+                    "import.notion",
+                    &root,
+                    plan,
+                    dry_run,
+                )
+            }
+            Some(ImportCommand::Logseq { source }) => {
+                let plan = plan_import_pages(&root, &source, ImportFormat::Logseq)?;
+                print_plan_result(
+                    output_format,
+                    lock_timeout,
+                    "import.logseq",
+                    &root,
+                    plan,
+                    dry_run,
+                )
+            }
+            command => {
+                let source = match command {
+                    Some(ImportCommand::Markdown { source }) => source,
+                    _ => source.ok_or_else(|| {
+                        FractalError::invalid_input(
+                            "missing import source; use `fractal import markdown <source>`",
+                        )
+                    })?,
+                };
+                let plan = plan_import_markdown(&root, &source)?;
+                print_plan_result(
+                    output_format,
+                    lock_timeout,
+                    "import.markdown",
+                    &root,
+                    plan,
+                    dry_run,
+                )
+            }
+        },
         Command::Export {
             command,
             page,
@@ -607,8 +1444,119 @@ pub fn run() -> Result<()> {
                     })?,
                 ),
             };
-            let report = export_page(&root, &page, &output)?;
-            print_report_result(output_format, "export.markdown", &root, &report)
+            let plan = plan_export_page(&root, &page, &output)?;
+            print_plan_result(
+                output_format,
+                lock_timeout,
+                "export.markdown",
+                &root,
+                plan,
+                dry_run,
+            )
+        }
+        Command::Lint { list_rules: true } => {
+            print_data(output_format, "lint", &root, &LINT_RULES, || {
+                for rule in LINT_RULES {
+                    let severity = severity_label(rule.severity);
+                    println!(
+                        "{} {} ({severity}): {}",
+                        rule.id, rule.name, rule.description
+                    );
+                }
+                Ok(())
+            })
+        }
+        Command::Lint { list_rules: false } => {
+            let report = lint_project(&root)?;
+            print_data(output_format, "lint", &root, &report, || {
+                print_validation_report(&report)
+            })?;
+            if report.is_valid() {
+                Ok(())
+            } else {
+                Err(FractalError::invalid_project(format!(
+                    "lint found {} error(s) in {} page(s)",
+                    report.error_count(),
+                    report.pages_checked
+                )))
+            }
+        }
+        Command::Watch {
+            validate,
+            poll,
+            debounce_ms,
+            poll_interval_ms,
+        } => {
+            if dry_run {
+                return Err(FractalError::invalid_input(
+                    "watch does not support --dry-run",
+                ));
+            }
+            let mut watcher = ProjectWatcher::new(
+                &root,
+                WatchOptions {
+                    debounce: Duration::from_millis(debounce_ms),
+                    poll_interval: Duration::from_millis(poll_interval_ms),
+                    validate,
+                    polling: poll,
+                },
+            )?;
+            if output_format == OutputFormat::Human && !quiet {
+                let backend = match watcher.backend() {
+                    WatchBackend::Inotify => "inotify",
+                    WatchBackend::Polling => "polling",
+                };
+                println!("watching {} ({backend})", root.display());
+            }
+            watcher.run(|event| {
+                let printed = match output_format {
+                    OutputFormat::Human => {
+                        print_operation_report(&OperationReport::from_event(event))
+                    }
+                    OutputFormat::Json => serde_json::to_string(&event)
+                        .map(|line| println!("{line}"))
+                        .map_err(FractalError::from),
+                };
+                if printed.is_ok() {
+                    ControlFlow::Continue(())
+                } else {
+                    ControlFlow::Break(())
+                }
+            })
+        }
+        Command::Serve { stdio, poll } => {
+            if dry_run {
+                return Err(FractalError::invalid_input(
+                    "serve does not support --dry-run; pass `dry_run` per request",
+                ));
+            }
+            if !stdio {
+                return Err(FractalError::invalid_input(
+                    "serve requires a transport; use `fractal serve --stdio`",
+                ));
+            }
+            let mut server = RpcServer::new(&root)?;
+            let watcher = ProjectWatcher::new(
+                &root,
+                WatchOptions {
+                    polling: poll,
+                    ..WatchOptions::default()
+                },
+            )?;
+            server.serve(
+                BufReader::new(std::io::stdin()),
+                std::io::stdout(),
+                Some(watcher),
+            )
+        }
+        Command::Mcp { allow_writes } => {
+            if dry_run {
+                return Err(FractalError::invalid_input(
+                    "mcp does not support --dry-run; pass `dry_run` per tool call",
+                ));
+            }
+            McpServer::new(&root, allow_writes)?
+                .serve(BufReader::new(std::io::stdin()), std::io::stdout())
         }
         Command::Schema { command } => match command {
             SchemaCommand::Commands => {
@@ -623,20 +1571,65 @@ pub fn run() -> Result<()> {
         },
         Command::Init { project_name } => {
             let path = PathBuf::from(&project_name);
-            let report = init_project_at(&path, &project_name)?;
-            print_report_result(output_format, "project.init", &root, &report)
+            let plan = plan_init_project_at(&path, &project_name)?;
+            print_plan_result(
+                output_format,
+                lock_timeout,
+                "project.init",
+                &root,
+                plan,
+                dry_run,
+            )
         }
         Command::Validate => {
             let report = validate_project(&root)?;
             print_report_result(output_format, "project.validate", &root, &report)
         }
         Command::Sync => {
-            let report = sync_project(&root)?;
-            print_report_result(output_format, "project.sync", &root, &report)
+            let plan = plan_sync_project(&root)?;
+            print_plan_result(
+                output_format,
+                lock_timeout,
+                "project.sync",
+                &root,
+                plan,
+                dry_run,
+            )
         }
     }
 }
 
+fn print_validation_report(report: &ValidationReport) -> Result<()> {
+    for diagnostic in &report.diagnostics {
+        let location = match (&diagnostic.page, &diagnostic.span) {
+            (Some(page), Some(span)) => format!("pages/{page}:{}:{}: ", span.line, span.column),
+            (Some(page), None) => format!("pages/{page}: "),
+            (None, _) => String::new(),
+        };
+        let severity = severity_label(diagnostic.severity);
+        let fixable = if diagnostic.fixable { " (fixable)" } else { "" };
+        println!(
+            "{location}{severity}[{}] {}{fixable}",
+            diagnostic.rule, diagnostic.message
+        );
+    }
+    println!(
+        "checked {} page(s): {} error(s), {} warning(s)",
+        report.pages_checked,
+        report.error_count(),
+        report.warning_count()
+    );
+    Ok(())
+}
+
+fn severity_label(severity: DiagnosticSeverity) -> &'static str {
+    match severity {
+        DiagnosticSeverity::Error => "error",
+        DiagnosticSeverity::Warning => "warning",
+        DiagnosticSeverity::Info => "info",
+    }
+}
+
 fn print_report_result(
     output_format: OutputFormat,
     command: &'static str,
@@ -645,17 +1638,65 @@ fn print_report_result(
 ) -> Result<()> {
     match output_format {
         OutputFormat::Human => print_operation_report(report),
-        OutputFormat::Json => print_data_json(
-            command,
-            root,
-            &ReportData {
-                report: report.clone(),
-                summary: report.summary(),
-            },
-        ),
+        OutputFormat::Json => print_data_json(command, root, &ReportData::new(report.clone())),
+    }
+}
+
+fn print_plan_result(
+    output_format: OutputFormat,
+    lock_timeout: Option<Duration>,
+    command: &'static str,
+    root: &Path,
+    plan: MutationPlan,
+    dry_run: bool,
+) -> Result<()> {
+    if !dry_run {
+        let plan = match lock_timeout {
+            Some(timeout) => plan.with_lock_timeout(timeout),
+            None => plan,
+        };
+        let report = plan.apply()?;
+        return print_report_result(output_format, command, root, &report);
+    }
+
+    let preview = plan.preview()?;
+    print_data(output_format, command, root, &preview, || {
+        print_operation_report(&preview.report)?;
+        for diff in &preview.diffs {
+            print_file_change(diff.change, &diff.path, diff.from.as_deref(), "would ");
+            print!("{}", diff.diff);
+        }
+        Ok(())
+    })
+}
+
+fn print_file_change(change: FileChange, path: &Path, from: Option<&Path>, prefix: &str) {
+    let path = path.display();
+    match (change, from) {
+        (FileChange::Moved, Some(from)) => println!("{prefix}move {} -> {path}", from.display()),
+        (FileChange::Created, _) => println!("{prefix}create {path}"),
+        (FileChange::Deleted, _) => println!("{prefix}delete {path}"),
+        _ => println!("{prefix}modify {path}"),
+    }
+}
+
+fn print_history_entry_line(entry: &HistoryEntry) {
+    let files = entry
+        .files
+        .iter()
+        .map(|file| file.path.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    match entry.undoes {
+        Some(undone) => println!("{}\t{}\tundo {undone}: {files}", entry.id, entry.created_at),
+        None => println!("{}\t{}\t{files}", entry.id, entry.created_at),
     }
 }
 
+fn short_commit(id: &str) -> &str {
+    id.get(..12).unwrap_or(id)
+}
+
 fn print_text_or_json(
     output_format: OutputFormat,
     command: &'static str,
@@ -689,15 +1730,7 @@ where
 }
 
 fn print_data_json<T: Serialize>(command: &'static str, root: &Path, data: &T) -> Result<()> {
-    let result = CommandResult {
-        ok: true,
-        schema: "fractal.command_result.v1",
-        command,
-        project: ProjectRef {
-            root: root.to_path_buf(),
-        },
-        data,
-    };
+    let result = CommandResult::new(command, root, data);
     println!("{}", serde_json::to_string_pretty(&result)?);
     Ok(())
 }
@@ -735,6 +1768,9 @@ fn print_operation_report(report: &OperationReport) -> Result<()> {
                     println!("would fix {}", path.display());
                 }
             }
+            OperationEvent::RepairSuggestionApplied { page, id, action } => {
+                println!("applied {id} to {}: {action}", page.display());
+            }
             OperationEvent::PageImported {
                 source,
                 destination,
@@ -744,9 +1780,28 @@ fn print_operation_report(report: &OperationReport) -> Result<()> {
             OperationEvent::PageMoved { from, to } => {
                 println!("moved page {} -> {}", from.display(), to.display());
             }
+            OperationEvent::AssetAdded { source, path } => {
+                println!("added asset {} -> {}", source.display(), path.display());
+            }
+            OperationEvent::AssetMoved { from, to } => {
+                println!("moved asset {} -> {}", from.display(), to.display());
+            }
+            OperationEvent::AssetDeleted { path } => println!("deleted asset {}", path.display()),
             OperationEvent::NoteUpdated { page, note_id } => {
                 println!("patched note {} in {}", note_id, page.display());
             }
+            OperationEvent::NoteRenamed {
+                page,
+                note_id,
+                new_note_id,
+            } => {
+                println!(
+                    "renamed note {} to {} in {}",
+                    note_id,
+                    new_note_id,
+                    page.display()
+                );
+            }
             OperationEvent::PageLinkImpact {
                 page,
                 backlinks,
@@ -774,6 +1829,31 @@ fn print_operation_report(report: &OperationReport) -> Result<()> {
             OperationEvent::PageLinksRewritten { page, count } => {
                 println!("updated {count} page link(s) in {}", page.display());
             }
+            OperationEvent::EmbedsRefreshed { page, count } => {
+                println!("refreshed {count} embed(s) in {}", page.display());
+            }
+            OperationEvent::PageHtmlSanitized {
+                page,
+                change,
+                count,
+            } => println!("sanitized {}: {change} ({count}x)", page.display()),
+            OperationEvent::BlockInserted { page, block_id } => {
+                println!("inserted block {block_id} in {}", page.display());
+            }
+            OperationEvent::BlockReplaced {
+                page,
+                block_id,
+                replacement_id,
+            } => println!(
+                "replaced block {block_id} with {replacement_id} in {}",
+                page.display()
+            ),
+            OperationEvent::BlockMoved { page, block_id } => {
+                println!("moved block {block_id} in {}", page.display());
+            }
+            OperationEvent::BlockDeleted { page, block_id } => {
+                println!("deleted block {block_id} from {}", page.display());
+            }
             OperationEvent::ManifestUpdated { path } => {
                 println!("updated project manifest {}", path.display());
             }
@@ -789,13 +1869,44 @@ fn print_operation_report(report: &OperationReport) -> Result<()> {
                 project_name,
                 manifest_path.display()
             ),
+            OperationEvent::ProjectUnlocked { path, owner, stale } => {
+                let owner = owner
+                    .pid
+                    .map(|pid| format!(" held by pid {pid}"))
+                    .unwrap_or_default();
+                let kind = if *stale { "stale" } else { "active" };
+                println!("removed {kind} lock {}{owner}", path.display())
+            }
+            OperationEvent::HistoryEnabled { path } => {
+                println!("enabled history in {}", path.display())
+            }
+            OperationEvent::HistoryDisabled { path } => {
+                println!("disabled history and removed {}", path.display())
+            }
+            OperationEvent::HistoryUndone { id } => println!("undid history entry {id}"),
+            OperationEvent::FileRestored { path } => println!("restored {}", path.display()),
+            OperationEvent::FileRemoved { path } => println!("removed {}", path.display()),
+            OperationEvent::GitCommitted { commit, message } => {
+                println!("committed {} {message}", short_commit(commit))
+            }
+            OperationEvent::GitCommitFailed { message } => {
+                println!("warning: git auto-commit failed: {message}")
+            }
+            OperationEvent::FilesChanged { paths } => println!(
+                "changed {}",
+                paths
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
             OperationEvent::Warning { message } => println!("warning: {message}"),
         }
     }
     Ok(())
 }
 
-fn schema_commands() -> Vec<SchemaCommandEntry> {
+pub(crate) fn schema_commands() -> Vec<SchemaCommandEntry> {
     vec![
         SchemaCommandEntry {
             name: "project.init",
@@ -803,17 +1914,36 @@ fn schema_commands() -> Vec<SchemaCommandEntry> {
             json: true,
             examples: &["fractal project init ./notes --name Notes"],
         },
+        SchemaCommandEntry {
+            name: "project.fixture",
+            kind: "write",
+            json: true,
+            examples: &["fractal project fixture /tmp/bench --pages 10000"],
+        },
         SchemaCommandEntry {
             name: "project.validate",
             kind: "read",
             json: true,
-            examples: &["fractal project validate --json"],
+            examples: &[
+                "fractal project validate --json",
+                "fractal project validate --fail-fast",
+            ],
+        },
+        SchemaCommandEntry {
+            name: "lint",
+            kind: "read",
+            json: true,
+            examples: &["fractal lint --json", "fractal lint --list-rules"],
         },
         SchemaCommandEntry {
             name: "project.repair",
             kind: "write",
             json: true,
-            examples: &["fractal project repair --json"],
+            examples: &[
+                "fractal project repair --json",
+                "fractal project repair --suggest",
+                "fractal project repair --accept FR-CONTENT-004:index.html:12:5",
+            ],
         },
         SchemaCommandEntry {
             name: "project.sync",
@@ -821,6 +1951,15 @@ fn schema_commands() -> Vec<SchemaCommandEntry> {
             json: true,
             examples: &["fractal project sync --json"],
         },
+        SchemaCommandEntry {
+            name: "project.unlock",
+            kind: "write",
+            json: true,
+            examples: &[
+                "fractal project unlock",
+                "fractal project unlock --force --json",
+            ],
+        },
         SchemaCommandEntry {
             name: "page.list",
             kind: "read",
@@ -843,7 +1982,10 @@ fn schema_commands() -> Vec<SchemaCommandEntry> {
             name: "page.set",
             kind: "write",
             json: true,
-            examples: &["fractal page set index --summary 'Entry point' --json"],
+            examples: &[
+                "fractal page set index --summary 'Entry point' --json",
+                "fractal page set index --title Home --expected-revision sha256:... --json",
+            ],
         },
         SchemaCommandEntry {
             name: "page.move",
@@ -855,7 +1997,91 @@ fn schema_commands() -> Vec<SchemaCommandEntry> {
             name: "page.delete",
             kind: "write",
             json: true,
-            examples: &["fractal page delete old --yes --json"],
+            examples: &[
+                "fractal page delete old --yes --json",
+                "fractal page delete old --dry-run",
+            ],
+        },
+        SchemaCommandEntry {
+            name: "page.demote",
+            kind: "write",
+            json: true,
+            examples: &["fractal page demote vertex --into glossary --yes --json"],
+        },
+        SchemaCommandEntry {
+            name: "page.history",
+            kind: "read",
+            json: true,
+            examples: &["fractal page history index --json"],
+        },
+        SchemaCommandEntry {
+            name: "page.blame",
+            kind: "read",
+            json: true,
+            examples: &["fractal page blame index --json"],
+        },
+        SchemaCommandEntry {
+            name: "page.block.list",
+            kind: "read",
+            json: true,
+            examples: &["fractal page block list index --json"],
+        },
+        SchemaCommandEntry {
+            name: "page.block.insert",
+            kind: "write",
+            json: true,
+            examples: &[
+                "fractal page block insert index --html '<p>New paragraph.</p>' --json",
+                "fractal page block insert index --after block-3fa9c01d2e4b --html '<h2>Next</h2>'",
+            ],
+        },
+        SchemaCommandEntry {
+            name: "page.block.replace",
+            kind: "write",
+            json: true,
+            examples: &["fractal page block replace index block-3fa9c01d2e4b --html '<p>Reworded.</p>' --json"],
+        },
+        SchemaCommandEntry {
+            name: "page.block.move",
+            kind: "write",
+            json: true,
+            examples: &["fractal page block move index block-3fa9c01d2e4b --start --json"],
+        },
+        SchemaCommandEntry {
+            name: "page.block.delete",
+            kind: "write",
+            json: true,
+            examples: &["fractal page block delete index block-3fa9c01d2e4b --dry-run"],
+        },
+        SchemaCommandEntry {
+            name: "link.external",
+            kind: "read",
+            json: true,
+            examples: &["fractal link external --json"],
+        },
+        SchemaCommandEntry {
+            name: "asset.list",
+            kind: "read",
+            json: true,
+            examples: &["fractal asset list --unreferenced --json"],
+        },
+        SchemaCommandEntry {
+            name: "asset.add",
+            kind: "write",
+            json: true,
+            examples: &["fractal asset add ./diagram.png --to images/diagram.png --json"],
+        },
+        SchemaCommandEntry {
+            name: "asset.move",
+            kind: "write",
+            json: true,
+            examples: &["fractal asset move images/diagram.png --to media/diagram.png --json"],
+        },
+        SchemaCommandEntry {
+            name: "asset.delete",
+            kind: "write",
+            json: true,
+            examples: &["fractal asset delete media/diagram.png --yes --json"],
         },
         SchemaCommandEntry {
             name: "note.add",
@@ -863,6 +2089,96 @@ fn schema_commands() -> Vec<SchemaCommandEntry> {
             json: true,
             examples: &["fractal note add index term --content 'Definition' --json"],
         },
+        SchemaCommandEntry {
+            name: "note.set",
+            kind: "write",
+            json: true,
+            examples: &["fractal note set index term --content 'Revised definition' --json"],
+        },
+        SchemaCommandEntry {
+            name: "note.promote",
+            kind: "write",
+            json: true,
+            examples: &["fractal note promote glossary note-vertex --title Vertex --json"],
+        },
+        SchemaCommandEntry {
+            name: "note.rename",
+            kind: "write",
+            json: true,
+            examples: &["fractal note rename glossary note-vertex --to Node --json"],
+        },
+        SchemaCommandEntry {
+            name: "history.enable",
+            kind: "write",
+            json: true,
+            examples: &["fractal history enable --json"],
+        },
+        SchemaCommandEntry {
+            name: "history.disable",
+            kind: "write",
+            json: true,
+            examples: &["fractal history disable --yes --json"],
+        },
+        SchemaCommandEntry {
+            name: "history.log",
+            kind: "read",
+            json: true,
+            examples: &[
+                "fractal history log --json",
+                "fractal history log index --json",
+            ],
+        },
+        SchemaCommandEntry {
+            name: "history.show",
+            kind: "read",
+            json: true,
+            examples: &["fractal history show 3 --json"],
+        },
+        SchemaCommandEntry {
+            name: "history.diff",
+            kind: "read",
+            json: true,
+            examples: &["fractal history diff 3"],
+        },
+        SchemaCommandEntry {
+            name: "history.undo",
+            kind: "write",
+            json: true,
+            examples: &[
+                "fractal history undo 3 --json",
+                "fractal history undo 3 --dry-run",
+            ],
+        },
+        SchemaCommandEntry {
+            name: "git.enable",
+            kind: "write",
+            json: true,
+            examples: &["fractal git enable --json"],
+        },
+        SchemaCommandEntry {
+            name: "git.disable",
+            kind: "write",
+            json: true,
+            examples: &["fractal git disable --json"],
+        },
+        SchemaCommandEntry {
+            name: "import.markdown",
+            kind: "write",
+            json: true,
+            examples: &["fractal import markdown ./notes.md --json"],
+        },
+        SchemaCommandEntry {
+            name: "import.notion",
+            kind: "write",
+            json: true,
+            examples: &["fractal import notion ./notion-export --json"],
+        },
+        SchemaCommandEntry {
+            name: "import.logseq",
+            kind: "write",
+            json: true,
+            examples: &["fractal import logseq ./logseq-graph --json"],
+        },
         SchemaCommandEntry {
             name: "search.text",
             kind: "read",
@@ -875,11 +2191,41 @@ fn schema_commands() -> Vec<SchemaCommandEntry> {
             json: true,
             examples: &["fractal graph neighbors index --depth 1 --json"],
         },
+        SchemaCommandEntry {
+            name: "graph.path",
+            kind: "read",
+            json: true,
+            examples: &["fractal graph path index topics/rust --json"],
+        },
         SchemaCommandEntry {
             name: "context.page",
             kind: "read",
             json: true,
-            examples: &["fractal context page index --budget 2000 --json"],
+            examples: &[
+                "fractal context page index --budget 2000 --json",
+                "fractal context page guide#setup --json",
+            ],
+        },
+        SchemaCommandEntry {
+            name: "watch",
+            kind: "write",
+            json: true,
+            examples: &[
+                "fractal watch --json",
+                "fractal watch --validate --poll --poll-interval-ms 1000",
+            ],
+        },
+        SchemaCommandEntry {
+            name: "serve",
+            kind: "write",
+            json: true,
+            examples: &["fractal serve --stdio"],
+        },
+        SchemaCommandEntry {
+            name: "mcp",
+            kind: "read",
+            json: true,
+            examples: &["fractal mcp", "fractal mcp --allow-writes"],
         },
         SchemaCommandEntry {
             name: "schema.commands",
@@ -907,6 +2253,453 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parses_import_notion_command() {
+        let cli = Cli::try_parse_from(["fractal", "import", "notion", "export", "--json"])
+            .expect("parse import notion");
+
+        match cli.command {
+            Command::Import {
+                command: Some(ImportCommand::Notion { source }),
+                ..
+            } => assert_eq!(source, PathBuf::from("export")),
+            command => panic!("unexpected command: {command:?}"),
+        }
+    }
+
+    #[test]
+    fn parses_link_external_command() {
+        let cli = Cli::try_parse_from(["fractal", "link", "external", "--json"])
+            .expect("parse link external");
+
+        assert_eq!(cli.output_format(), OutputFormat::Json);
+        assert!(matches!(
+            cli.command,
+            Command::Link {
+                command: LinkCommand::External
+            }
+        ));
+    }
+
+    #[test]
+    fn parses_asset_delete_command() {
+        let cli = Cli::try_parse_from([
+            "fractal",
+            "asset",
+            "delete",
+            "images/old.png",
+            "--force",
+            "--yes",
+        ])
+        .expect("parse asset delete");
+
+        match cli.command {
+            Command::Asset {
+                command: AssetCommand::Delete { asset, force, yes },
+            } => {
+                assert_eq!(asset, PathBuf::from("images/old.png"));
+                assert!(force);
+                assert!(yes);
+            }
+            command => panic!("unexpected command: {command:?}"),
+        }
+    }
+
+    #[test]
+    fn parses_note_promote_and_page_demote_commands() {
+        let cli = Cli::try_parse_from([
+            "fractal",
+            "note",
+            "promote",
+            "glossary",
+            "note-vertex",
+            "--title",
+            "Vertex",
+        ])
+        .expect("parse note promote");
+
+        match cli.command {
+            Command::Note {
+                command:
+                    NoteCommand::Promote {
+                        page,
+                        note,
+                        title,
+                        expected_revision,
+                    },
+            } => {
+                assert_eq!(page, PathBuf::from("glossary"));
+                assert_eq!(note, "note-vertex");
+                assert_eq!(title, "Vertex");
+                assert_eq!(expected_revision, None);
+            }
+            command => panic!("unexpected command: {command:?}"),
+        }
+
+        let cli = Cli::try_parse_from([
+            "fractal",
+            "page",
+            "demote",
+            "vertex",
+            "--into",
+            "glossary",
+            "--yes",
+            "--expected-revision",
+            "sha256:abc",
+        ])
+        .expect("parse page demote");
+
+        match cli.command {
+            Command::Page {
+                command:
+                    PageCommand::Demote {
+                        page,
+                        into,
+                        yes,
+                        expected_revision,
+                    },
+            } => {
+                assert_eq!(page, PathBuf::from("vertex"));
+                assert_eq!(into, PathBuf::from("glossary"));
+                assert!(yes);
+                assert_eq!(expected_revision.as_deref(), Some("sha256:abc"));
+            }
+            command => panic!("unexpected command: {command:?}"),
+        }
+    }
+
+    #[test]
+    fn parses_note_rename_command() {
+        let cli = Cli::try_parse_from([
+            "fractal",
+            "note",
+            "rename",
+            "glossary",
+            "note-vertex",
+            "--to",
+            "Node",
+            "--expected-revision",
+            "sha256:abc",
+        ])
+        .expect("parse note rename");
+
+        match cli.command {
+            Command::Note {
+                command:
+                    NoteCommand::Rename {
+                        page,
+                        note,
+                        to,
+                        expected_revision,
+                    },
+            } => {
+                assert_eq!(page, PathBuf::from("glossary"));
+                assert_eq!(note, "note-vertex");
+                assert_eq!(to, "Node");
+                assert_eq!(expected_revision.as_deref(), Some("sha256:abc"));
+            }
+            command => panic!("unexpected command: {command:?}"),
+        }
+    }
+
+    #[test]
+    fn parses_global_dry_run_flag() {
+        let cli = Cli::try_parse_from(["fractal", "page", "delete", "old", "--dry-run"])
+            .expect("parse dry run");
+
+        assert!(cli.dry_run);
+        match cli.command {
+            Command::Page {
+                command:
+                    PageCommand::Delete {
+                        page,
+                        yes,
+                        expected_revision,
+                    },
+            } => {
+                assert_eq!(page, PathBuf::from("old"));
+                assert!(!yes);
+                assert_eq!(expected_revision, None);
+            }
+            command => panic!("unexpected command: {command:?}"),
+        }
+    }
+
+    #[test]
+    fn parses_page_set_expected_revision() {
+        let cli = Cli::try_parse_from([
+            "fractal",
+            "page",
+            "set",
+            "index",
+            "--title",
+            "Home",
+            "--expected-revision",
+            "sha256:abc",
+        ])
+        .expect("parse page set");
+
+        match cli.command {
+            Command::Page {
+                command:
+                    PageCommand::Set {
+                        page,
+                        title,
+                        expected_revision,
+                        ..
+                    },
+            } => {
+                assert_eq!(page, PathBuf::from("index"));
+                assert_eq!(title.as_deref(), Some("Home"));
+                assert_eq!(expected_revision.as_deref(), Some("sha256:abc"));
+            }
+            command => panic!("unexpected command: {command:?}"),
+        }
+    }
+
+    #[test]
+    fn parses_history_commands() {
+        let cli =
+            Cli::try_parse_from(["fractal", "history", "log", "index"]).expect("parse history log");
+        match cli.command {
+            Command::History {
+                command: HistoryCommand::Log { page },
+            } => assert_eq!(page, Some(PathBuf::from("index"))),
+            command => panic!("unexpected command: {command:?}"),
+        }
+
+        let cli = Cli::try_parse_from(["fractal", "history", "undo", "3", "--dry-run"])
+            .expect("parse history undo");
+        assert!(cli.dry_run);
+        match cli.command {
+            Command::History {
+                command: HistoryCommand::Undo { id },
+            } => assert_eq!(id, 3),
+            command => panic!("unexpected command: {command:?}"),
+        }
+    }
+
+    #[test]
+    fn parses_git_and_page_provenance_commands() {
+        let cli = Cli::try_parse_from(["fractal", "git", "enable", "--dry-run"])
+            .expect("parse git enable");
+        assert!(cli.dry_run);
+        assert!(matches!(
+            cli.command,
+            Command::Git {
+                command: GitCommand::Enable
+            }
+        ));
+
+        let cli =
+            Cli::try_parse_from(["fractal", "page", "blame", "index"]).expect("parse page blame");
+        match cli.command {
+            Command::Page {
+                command: PageCommand::Blame { page },
+            } => assert_eq!(page, PathBuf::from("index")),
+            command => panic!("unexpected command: {command:?}"),
+        }
+    }
+
+    #[test]
+    fn parses_watch_command() {
+        let cli = Cli::try_parse_from([
+            "fractal",
+            "watch",
+            "--validate",
+            "--poll",
+            "--debounce-ms",
+            "50",
+            "--json",
+        ])
+        .expect("parse watch");
+        assert_eq!(cli.output_format(), OutputFormat::Json);
+        match cli.command {
+            Command::Watch {
+                validate,
+                poll,
+                debounce_ms,
+                poll_interval_ms,
+            } => {
+                assert!(validate && poll);
+                assert_eq!((debounce_ms, poll_interval_ms), (50, 500));
+            }
+            command => panic!("unexpected command: {command:?}"),
+        }
+    }
+
+    #[test]
+    fn parses_serve_command() {
+        let cli = Cli::try_parse_from(["fractal", "serve", "--stdio", "--project", "site"])
+            .expect("parse serve");
+        assert_eq!(cli.project, PathBuf::from("site"));
+        match cli.command {
+            Command::Serve { stdio, poll } => assert!(stdio && !poll),
+            command => panic!("unexpected command: {command:?}"),
+        }
+    }
+
+    #[test]
+    fn parses_project_fixture_with_jobs() {
+        let cli = Cli::try_parse_from([
+            "fractal",
+            "project",
+            "fixture",
+            "/tmp/bench",
+            "--pages",
+            "500",
+            "--jobs",
+            "4",
+        ])
+        .expect("parse project fixture");
+        assert_eq!(cli.jobs, 4);
+        match cli.command {
+            Command::Project {
+                command: ProjectCommand::Fixture { path, pages },
+            } => {
+                assert_eq!(path, PathBuf::from("/tmp/bench"));
+                assert_eq!(pages, 500);
+            }
+            command => panic!("unexpected command: {command:?}"),
+        }
+    }
+
+    #[test]
+    fn parses_repair_suggestion_flags() {
+        let cli = Cli::try_parse_from([
+            "fractal",
+            "project",
+            "repair",
+            "--accept",
+            "FR-CONTENT-001:index.html:9:5",
+            "--accept",
+            "FR-NOTE-004:index.html:14:5",
+        ])
+        .expect("parse repair accept");
+        match cli.command {
+            Command::Project {
+                command: ProjectCommand::Repair { suggest, accept },
+            } => {
+                assert!(!suggest);
+                assert_eq!(accept.len(), 2);
+            }
+            command => panic!("unexpected command: {command:?}"),
+        }
+
+        assert!(Cli::try_parse_from([
+            "fractal",
+            "project",
+            "repair",
+            "--suggest",
+            "--accept",
+            "x"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn parses_page_block_commands() {
+        let cli = Cli::try_parse_from([
+            "fractal",
+            "page",
+            "block",
+            "insert",
+            "index",
+            "--html",
+            "<p>New.</p>",
+            "--after",
+            "block-3fa9c01d2e4b",
+            "--expected-revision",
+            "sha256:abc",
+        ])
+        .expect("parse block insert");
+        match cli.command {
+            Command::Page {
+                command:
+                    PageCommand::Block {
+                        command:
+                            PageBlockCommand::Insert {
+                                page,
+                                html,
+                                before,
+                                after,
+                                start,
+                                expected_revision,
+                            },
+                    },
+            } => {
+                assert_eq!(page, PathBuf::from("index"));
+                assert_eq!(html, "<p>New.</p>");
+                assert_eq!(before, None);
+                assert_eq!(after.as_deref(), Some("block-3fa9c01d2e4b"));
+                assert!(!start);
+                assert_eq!(expected_revision.as_deref(), Some("sha256:abc"));
+            }
+            command => panic!("unexpected command: {command:?}"),
+        }
+
+        assert!(Cli::try_parse_from([
+            "fractal",
+            "page",
+            "block",
+            "move",
+            "index",
+            "block-3fa9c01d2e4b",
+            "--start",
+            "--after",
+            "block-000000000000",
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn parses_lint_command() {
+        let cli = Cli::try_parse_from(["fractal", "lint", "--list-rules"]).expect("parse lint");
+        match cli.command {
+            Command::Lint { list_rules } => assert!(list_rules),
+            command => panic!("unexpected command: {command:?}"),
+        }
+    }
+
+    #[test]
+    fn parses_mcp_and_graph_path_commands() {
+        let cli = Cli::try_parse_from(["fractal", "mcp", "--allow-writes"]).expect("parse mcp");
+        match cli.command {
+            Command::Mcp { allow_writes } => assert!(allow_writes),
+            command => panic!("unexpected command: {command:?}"),
+        }
+
+        let cli = Cli::try_parse_from(["fractal", "graph", "path", "index", "topics/rust"])
+            .expect("parse graph path");
+        match cli.command {
+            Command::Graph {
+                command: GraphCommand::Path { from, to },
+            } => assert_eq!((from, to), ("index".into(), "topics/rust".into())),
+            command => panic!("unexpected command: {command:?}"),
+        }
+    }
+
+    #[test]
+    fn parses_project_unlock_command_with_lock_timeout() {
+        let cli = Cli::try_parse_from([
+            "fractal",
+            "project",
+            "unlock",
+            "--force",
+            "--lock-timeout",
+            "500",
+        ])
+        .expect("parse project unlock");
+
+        assert_eq!(cli.lock_timeout, Some(500));
+        match cli.command {
+            Command::Project {
+                command: ProjectCommand::Unlock { force },
+            } => assert!(force),
+            command => panic!("unexpected command: {command:?}"),
+        }
+    }
+
     #[test]
     fn parses_project_validate_command() {
         let cli = Cli::try_parse_from(["fractal", "project", "validate", "--json"])
@@ -915,7 +2708,7 @@ mod tests {
         assert_eq!(cli.output_format(), OutputFormat::Json);
         match cli.command {
             Command::Project {
-                command: ProjectCommand::Validate,
+                command: ProjectCommand::Validate { fail_fast: false },
             } => {}
             command => panic!("unexpected command: {command:?}"),
         }
@@ -941,11 +2734,13 @@ mod tests {
                         page,
                         trigger,
                         content,
+                        expected_revision,
                     },
             } => {
                 assert_eq!(page, PathBuf::from("index"));
                 assert_eq!(trigger, "java");
                 assert_eq!(content, "note body");
+                assert_eq!(expected_revision, None);
             }
             command => panic!("unexpected command: {command:?}"),
         }
@@ -0,0 +1,89 @@
+use crate::document::page::PageDocument;
+use crate::io::hash::sha256_hex;
+use crate::{FractalError, Result};
+use brik::NodeRef;
+use std::collections::BTreeMap;
+
+const BLOCK_ID_PREFIX: &str = "block-";
+const BLOCK_ID_HASH_LENGTH: usize = 12;
+const BLOCK_INDENT: &str = "\n      ";
+
+impl PageDocument {
+    pub(crate) fn blocks(&self) -> Result<Vec<(String, NodeRef)>> {
+        let mut seen = BTreeMap::<String, usize>::new();
+        self.main_body_nodes()?
+            .into_iter()
+            .map(|node| {
+                let mut serialized = Vec::new();
+                node.serialize(&mut serialized)?;
+                let hash = sha256_hex(&serialized);
+                let base = format!("{BLOCK_ID_PREFIX}{}", &hash[..BLOCK_ID_HASH_LENGTH]);
+                let occurrence = seen.entry(base.clone()).or_default();
+                *occurrence += 1;
+                let id = match *occurrence {
+                    1 => base,
+                    occurrence => format!("{base}-{occurrence}"),
+                };
+                Ok((id, node))
+            })
+            .collect()
+    }
+
+    pub(crate) fn block(&self, block_id: &str) -> Result<NodeRef> {
+        self.blocks()?
+            .into_iter()
+            .find(|(id, _)| id == block_id)
+            .map(|(_, node)| node)
+            .ok_or_else(|| {
+                FractalError::not_found(format!(
+                    "block not found: {block_id}; list the page blocks again for current IDs"
+                ))
+            })
+    }
+
+    pub(crate) fn block_id(&self, node: &NodeRef) -> Result<Option<String>> {
+        Ok(self
+            .blocks()?
+            .into_iter()
+            .find(|(_, candidate)| candidate == node)
+            .map(|(id, _)| id))
+    }
+
+    pub(crate) fn append_block(&self, block: NodeRef) -> Result<()> {
+        let main = self.main_node()?;
+        let trailing = main.last_child().filter(is_blank_text);
+        match trailing {
+            Some(trailing) => {
+                trailing.insert_before(NodeRef::new_text(BLOCK_INDENT));
+                trailing.insert_before(block);
+            }
+            None => {
+                main.append(NodeRef::new_text(BLOCK_INDENT));
+                main.append(block);
+            }
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn insert_block_before(anchor: &NodeRef, block: NodeRef) {
+    anchor.insert_before(block);
+    anchor.insert_before(NodeRef::new_text(BLOCK_INDENT));
+}
+
+pub(crate) fn insert_block_after(anchor: &NodeRef, block: NodeRef) {
+    anchor.insert_after(block);
+    anchor.insert_after(NodeRef::new_text(BLOCK_INDENT));
+}
+
+pub(crate) fn detach_block(block: &NodeRef) {
+    if let Some(previous) = block.previous_sibling().filter(is_blank_text) {
+        previous.detach();
+    }
+    block.detach();
+}
+
+fn is_blank_text(node: &NodeRef) -> bool {
+    node.as_text()
+        .is_some_and(|text| text.borrow().trim().is_empty())
+}
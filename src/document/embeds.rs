@@ -0,0 +1,227 @@
+use crate::document::page::{is_element_named, PageDocument};
+use crate::graph::links::{
+    relative_href, resolve_asset_src, resolve_page_href, resolve_section_href,
+};
+use crate::types::EmbedEntry;
+use crate::Result;
+use brik::NodeRef;
+use std::collections::{BTreeMap, BTreeSet};
+
+pub(crate) const EMBED_ATTRIBUTE: &str = "data-fractal-embed";
+pub(crate) const EMBED_SELECTOR: &str = "section[data-fractal-embed]";
+
+impl PageDocument {
+    pub(crate) fn embeds(&self) -> Vec<EmbedEntry> {
+        self.embed_nodes()
+            .into_iter()
+            .filter(|(_, node)| !is_inside_embed(node))
+            .map(|(target, _)| EmbedEntry { target })
+            .collect()
+    }
+
+    pub(crate) fn embed_nodes(&self) -> Vec<(String, NodeRef)> {
+        self.document
+            .select(EMBED_SELECTOR)
+            .expect("static selector should parse")
+            .filter_map(|element| {
+                let target = element
+                    .attributes
+                    .borrow()
+                    .get(EMBED_ATTRIBUTE)?
+                    .to_string();
+                Some((target, element.as_node().clone()))
+            })
+            .collect()
+    }
+
+    pub(crate) fn page_fragments(&self) -> BTreeSet<String> {
+        self.notes()
+            .into_iter()
+            .map(|note| note.id)
+            .chain(self.headings().into_iter().map(|heading| heading.anchor))
+            .collect()
+    }
+
+    pub(crate) fn refresh_embeds(
+        &self,
+        page_path: &str,
+        mut source_html: impl FnMut(&str) -> Option<String>,
+    ) -> Result<usize> {
+        let mut sources = BTreeMap::<String, Option<PageDocument>>::new();
+        let mut refreshed = 0;
+
+        for (target, embed) in self.embed_nodes() {
+            if is_inside_embed(&embed) {
+                continue;
+            }
+            let Some((source_page, fragment)) = resolve_section_href(page_path, &target) else {
+                continue;
+            };
+            let source = sources.entry(source_page.clone()).or_insert_with(|| {
+                source_html(&source_page).map(|html| PageDocument::parse(&html))
+            });
+            let Some(nodes) = source
+                .as_ref()
+                .and_then(|source| source.embed_source_nodes(&fragment))
+            else {
+                continue;
+            };
+
+            let copy = embed_copy(&nodes, &source_page, page_path)?;
+            if inner_html(&embed)? == inner_html(&copy)? {
+                continue;
+            }
+            for child in embed.children().collect::<Vec<_>>() {
+                child.detach();
+            }
+            for child in copy.children().collect::<Vec<_>>() {
+                embed.append(child);
+            }
+            refreshed += 1;
+        }
+
+        Ok(refreshed)
+    }
+
+    pub(crate) fn repair_embeds(
+        &self,
+        page_path: &str,
+        mut target_fragments: impl FnMut(&str) -> Option<BTreeSet<String>>,
+    ) -> usize {
+        let mut repaired = 0;
+        for (target, embed) in self.embed_nodes() {
+            let resolved =
+                resolve_section_href(page_path, &target).is_some_and(|(source_page, fragment)| {
+                    target_fragments(&source_page)
+                        .is_some_and(|fragments| fragments.contains(&fragment))
+                });
+            if !resolved {
+                unwrap_node(&embed);
+                repaired += 1;
+            }
+        }
+        repaired
+    }
+
+    pub(crate) fn unwrap_page_embeds(&self, from_page: &str, target_page: &str) -> usize {
+        self.unwrap_embeds_where(|target| {
+            resolve_page_href(from_page, target).as_deref() == Some(target_page)
+        })
+    }
+
+    pub(crate) fn unwrap_note_embeds(
+        &self,
+        from_page: &str,
+        target_page: &str,
+        note_id: &str,
+    ) -> usize {
+        self.unwrap_embeds_where(|target| {
+            resolve_section_href(from_page, target)
+                .is_some_and(|(page, fragment)| page == target_page && fragment == note_id)
+        })
+    }
+
+    pub(crate) fn unwrap_embeds(&self) -> usize {
+        self.unwrap_embeds_where(|_| true)
+    }
+
+    fn unwrap_embeds_where(&self, mut matches: impl FnMut(&str) -> bool) -> usize {
+        let embeds = self
+            .embed_nodes()
+            .into_iter()
+            .filter(|(target, _)| matches(target))
+            .collect::<Vec<_>>();
+        for (_, embed) in &embeds {
+            unwrap_node(embed);
+        }
+        embeds.len()
+    }
+
+    fn embed_source_nodes(&self, fragment: &str) -> Option<Vec<NodeRef>> {
+        match self.note_node(fragment) {
+            Some(note) => Some(note.children().collect()),
+            None => self.section(fragment).ok().map(|(_, nodes)| nodes),
+        }
+    }
+}
+
+pub(crate) fn is_inside_embed(node: &NodeRef) -> bool {
+    node.ancestors().any(|ancestor| is_embed(&ancestor))
+}
+
+pub(crate) fn is_embed(node: &NodeRef) -> bool {
+    is_element_named(node, "section")
+        && node
+            .as_element()
+            .is_some_and(|element| element.attributes.borrow().contains(EMBED_ATTRIBUTE))
+}
+
+fn embed_copy(nodes: &[NodeRef], source_page: &str, page_path: &str) -> Result<NodeRef> {
+    let mut serialized = Vec::new();
+    for node in nodes {
+        node.serialize(&mut serialized)?;
+    }
+    let copy = PageDocument::parse(&format!("<main>{}</main>", String::from_utf8(serialized)?));
+    let main = copy.main_node()?;
+
+    for nested in select_within(&main, EMBED_SELECTOR) {
+        nested.detach();
+    }
+    for heading in select_within(&main, "h2, h3, h4, h5, h6") {
+        if let Some(element) = heading.as_element() {
+            element.attributes.borrow_mut().remove("id");
+        }
+    }
+    for link in select_within(&main, "a[data-fractal-link=note]") {
+        unwrap_node(&link);
+    }
+    for link in select_within(
+        &main,
+        "a[href][data-fractal-link=page], a[href][data-fractal-link=section]",
+    ) {
+        rebase_attribute(&link, "href", |href| {
+            let target = resolve_page_href(source_page, href)?;
+            let fragment = href.find('#').map_or("", |index| &href[index..]);
+            Some(format!("{}{fragment}", relative_href(page_path, &target)))
+        });
+    }
+    for image in select_within(&main, "img[src]") {
+        rebase_attribute(&image, "src", |src| {
+            resolve_asset_src(source_page, src).map(|asset| relative_href(page_path, &asset))
+        });
+    }
+
+    Ok(main)
+}
+
+fn rebase_attribute(node: &NodeRef, name: &str, rebase: impl FnOnce(&str) -> Option<String>) {
+    let Some(element) = node.as_element() else {
+        return;
+    };
+    let mut attributes = element.attributes.borrow_mut();
+    if let Some(rebased) = attributes.get(name).and_then(rebase) {
+        attributes.insert(name, rebased);
+    }
+}
+
+fn unwrap_node(node: &NodeRef) {
+    for child in node.children().collect::<Vec<_>>() {
+        node.insert_before(child);
+    }
+    node.detach();
+}
+
+fn inner_html(node: &NodeRef) -> Result<String> {
+    let mut serialized = Vec::new();
+    for child in node.children() {
+        child.serialize(&mut serialized)?;
+    }
+    Ok(String::from_utf8(serialized)?)
+}
+
+fn select_within(root: &NodeRef, selector: &str) -> Vec<NodeRef> {
+    root.select(selector)
+        .expect("static selector should parse")
+        .map(|element| element.as_node().clone())
+        .collect()
+}
@@ -0,0 +1,118 @@
+use crate::document::embeds::is_inside_embed;
+use crate::document::page::{extracted_text, PageDocument};
+use crate::graph::links::normalize_link_label;
+use crate::project::paths::page_slug_from_title;
+use crate::types::HeadingEntry;
+use crate::{FractalError, Result};
+use brik::NodeRef;
+use std::collections::BTreeSet;
+
+const HEADING_SELECTOR: &str = "main h2, main h3, main h4, main h5, main h6";
+const FALLBACK_ANCHOR: &str = "section";
+const RESERVED_ANCHOR_PREFIX: &str = "note-";
+
+impl PageDocument {
+    pub(crate) fn headings(&self) -> Vec<HeadingEntry> {
+        self.heading_nodes()
+            .into_iter()
+            .map(|(heading, _)| heading)
+            .collect()
+    }
+
+    pub(crate) fn heading_nodes(&self) -> Vec<(HeadingEntry, NodeRef)> {
+        let mut taken = BTreeSet::new();
+        self.document
+            .select(HEADING_SELECTOR)
+            .expect("static selector should parse")
+            .filter(|element| !is_inside_embed(element.as_node()))
+            .filter_map(|element| {
+                let node = element.as_node().clone();
+                let level = heading_level(&node)?;
+                let text = normalize_link_label(&node.text_contents());
+                let base = heading_anchor_base(&text);
+                let mut anchor = base.clone();
+                let mut occurrence = 1;
+                while !taken.insert(anchor.clone()) {
+                    occurrence += 1;
+                    anchor = format!("{base}-{occurrence}");
+                }
+                Some((
+                    HeadingEntry {
+                        level,
+                        text,
+                        anchor,
+                    },
+                    node,
+                ))
+            })
+            .collect()
+    }
+
+    pub(crate) fn ensure_heading_anchors(&self) -> usize {
+        self.assign_heading_anchors(true)
+    }
+
+    pub(crate) fn repair_heading_anchors(&self) -> usize {
+        self.assign_heading_anchors(false)
+    }
+
+    pub(crate) fn section(&self, anchor: &str) -> Result<(HeadingEntry, Vec<NodeRef>)> {
+        let (heading, node) = self
+            .heading_nodes()
+            .into_iter()
+            .find(|(heading, _)| heading.anchor == anchor)
+            .ok_or_else(|| FractalError::not_found(format!("section not found: #{anchor}")))?;
+        let mut nodes = vec![node.clone()];
+        for sibling in node.following_siblings() {
+            if heading_level(&sibling).is_some_and(|level| level <= heading.level) {
+                break;
+            }
+            nodes.push(sibling);
+        }
+        Ok((heading, nodes))
+    }
+
+    pub(crate) fn section_text(&self, anchor: &str) -> Result<String> {
+        let (_, nodes) = self.section(anchor)?;
+        Ok(extracted_text(&nodes))
+    }
+
+    fn assign_heading_anchors(&self, fill_missing: bool) -> usize {
+        let mut updated = 0;
+        for (heading, node) in self.heading_nodes() {
+            let Some(element) = node.as_element() else {
+                continue;
+            };
+            let mut attributes = element.attributes.borrow_mut();
+            match attributes.get("id") {
+                Some(id) if id == heading.anchor => continue,
+                None if !fill_missing => continue,
+                _ => {}
+            }
+            attributes.insert("id", heading.anchor);
+            updated += 1;
+        }
+        updated
+    }
+}
+
+fn heading_level(node: &NodeRef) -> Option<u8> {
+    let element = node.as_element()?;
+    match &*element.name.local {
+        "h2" => Some(2),
+        "h3" => Some(3),
+        "h4" => Some(4),
+        "h5" => Some(5),
+        "h6" => Some(6),
+        _ => None,
+    }
+}
+
+fn heading_anchor_base(text: &str) -> String {
+    let slug = page_slug_from_title(text).unwrap_or_else(|_| FALLBACK_ANCHOR.to_string());
+    if slug.starts_with(RESERVED_ANCHOR_PREFIX) {
+        format!("{FALLBACK_ANCHOR}-{slug}")
+    } else {
+        slug
+    }
+}
@@ -19,6 +19,10 @@ pub(crate) fn find_case_insensitive(haystack: &str, needle: &str, start: usize)
     None
 }
 
+pub(crate) fn is_inline_formatting_element(name: &str) -> bool {
+    matches!(name, "strong" | "em" | "sub" | "sup" | "mark" | "s")
+}
+
 pub(crate) fn escape_html(input: &str) -> String {
     input
         .replace('&', "&amp;")
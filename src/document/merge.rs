@@ -0,0 +1,362 @@
+use crate::document::notes::parse_note_aside;
+use crate::document::page::{is_element_named, parse_main_fragment_children};
+use crate::document::PageDocument;
+use crate::io::diff::matching_indices;
+use crate::types::{MergeConflict, MergeRegion, PageMerge};
+use crate::Result;
+use brik::NodeRef;
+use std::collections::{BTreeMap, BTreeSet};
+
+pub fn merge_page_edit(base: &str, ours: &str, theirs: &str) -> Result<PageMerge> {
+    let base = PageDocument::parse(base);
+    let merged = PageDocument::parse(ours);
+    let theirs = PageDocument::parse(theirs);
+    let mut conflicts = Vec::new();
+
+    let base_blocks = main_blocks(&base)?;
+    let ours_blocks = main_blocks(&merged)?;
+    let theirs_blocks = main_blocks(&theirs)?;
+    let blocks = merge_sequences(
+        &base_blocks,
+        &ours_blocks,
+        &theirs_blocks,
+        MergeRegion::Block,
+        "main",
+        &mut conflicts,
+    )?;
+    if blocks != ours_blocks {
+        merged.set_main_body_html(&blocks.concat())?;
+    }
+
+    let (base_title, ours_title, theirs_title) = (base.title(), merged.title(), theirs.title());
+    match merge_value(&base_title, &ours_title, &theirs_title) {
+        Some(Some(title)) if Some(title) != ours_title.as_ref() => {
+            merged.set_title(title)?;
+        }
+        Some(_) => {}
+        None => conflicts.push(MergeConflict {
+            region: MergeRegion::Title,
+            key: "title".to_string(),
+            base: base_title,
+            ours: ours_title,
+            theirs: theirs_title,
+        }),
+    }
+
+    let base_meta = base.fractal_meta();
+    let ours_meta = merged.fractal_meta();
+    let theirs_meta = theirs.fractal_meta();
+    let names = base_meta
+        .keys()
+        .chain(ours_meta.keys())
+        .chain(theirs_meta.keys())
+        .collect::<BTreeSet<_>>();
+    for name in names {
+        let values = (
+            base_meta.get(name).cloned(),
+            ours_meta.get(name).cloned(),
+            theirs_meta.get(name).cloned(),
+        );
+        match merge_value(&values.0, &values.1, &values.2) {
+            Some(Some(content)) => {
+                merged.set_meta_tag(name, content)?;
+            }
+            Some(None) => {
+                merged.remove_meta_tag(name);
+            }
+            None => conflicts.push(MergeConflict {
+                region: MergeRegion::Metadata,
+                key: name.clone(),
+                base: values.0,
+                ours: values.1,
+                theirs: values.2,
+            }),
+        }
+    }
+
+    let base_notes = note_asides(&base)?.into_iter().collect::<BTreeMap<_, _>>();
+    let ours_notes = note_asides(&merged)?;
+    let theirs_notes = note_asides(&theirs)?;
+    let ours_by_id = ours_notes.iter().cloned().collect::<BTreeMap<_, _>>();
+    let theirs_by_id = theirs_notes.iter().cloned().collect::<BTreeMap<_, _>>();
+    let mut note_ids = ours_notes
+        .iter()
+        .map(|(id, _)| id.clone())
+        .collect::<Vec<_>>();
+    for (id, _) in &theirs_notes {
+        if !ours_by_id.contains_key(id) {
+            note_ids.push(id.clone());
+        }
+    }
+
+    let mut notes = Vec::new();
+    for id in note_ids {
+        let values = (
+            base_notes.get(&id).cloned(),
+            ours_by_id.get(&id).cloned(),
+            theirs_by_id.get(&id).cloned(),
+        );
+        match merge_value(&values.0, &values.1, &values.2) {
+            Some(Some(note)) => notes.push(note.clone()),
+            Some(None) => {}
+            None => {
+                notes.extend(values.1.clone());
+                conflicts.push(MergeConflict {
+                    region: MergeRegion::Note,
+                    key: id,
+                    base: values.0,
+                    ours: values.1,
+                    theirs: values.2,
+                });
+            }
+        }
+    }
+    if notes
+        != ours_notes
+            .into_iter()
+            .map(|(_, note)| note)
+            .collect::<Vec<_>>()
+    {
+        merged.ensure_single_notes_section()?;
+        let section = merged.single_notes_section()?;
+        for child in section.children().collect::<Vec<_>>() {
+            child.detach();
+        }
+        for note in notes {
+            section.append(NodeRef::new_text("\n    "));
+            section.append(parse_note_aside(&note)?);
+        }
+        section.append(NodeRef::new_text("\n  "));
+    }
+
+    Ok(PageMerge {
+        html: merged.to_html()?,
+        conflicts,
+    })
+}
+
+fn merge_value<'a, T: PartialEq + ?Sized>(
+    base: &'a T,
+    ours: &'a T,
+    theirs: &'a T,
+) -> Option<&'a T> {
+    if ours == theirs || theirs == base {
+        Some(ours)
+    } else if ours == base {
+        Some(theirs)
+    } else {
+        None
+    }
+}
+
+fn merge_sequences(
+    base: &[String],
+    ours: &[String],
+    theirs: &[String],
+    region: MergeRegion,
+    key: &str,
+    conflicts: &mut Vec<MergeConflict>,
+) -> Result<Vec<String>> {
+    let ours_matches = matching_indices(base, ours)
+        .into_iter()
+        .collect::<BTreeMap<_, _>>();
+    let theirs_matches = matching_indices(base, theirs)
+        .into_iter()
+        .collect::<BTreeMap<_, _>>();
+    let mut merged = Vec::new();
+    let (mut base_start, mut ours_start, mut theirs_start) = (0, 0, 0);
+
+    loop {
+        let anchor = (base_start..base.len()).find_map(|index| {
+            Some((
+                index,
+                *ours_matches.get(&index)?,
+                *theirs_matches.get(&index)?,
+            ))
+        });
+        let (base_end, ours_end, theirs_end) =
+            anchor.unwrap_or((base.len(), ours.len(), theirs.len()));
+
+        let base_chunk = &base[base_start..base_end];
+        let ours_chunk = &ours[ours_start..ours_end];
+        let theirs_chunk = &theirs[theirs_start..theirs_end];
+        if let Some(resolved) = merge_value(base_chunk, ours_chunk, theirs_chunk) {
+            merged.extend_from_slice(resolved);
+        } else if base_chunk.len() == ours_chunk.len() && ours_chunk.len() == theirs_chunk.len() {
+            for (offset, ((base_block, ours_block), theirs_block)) in base_chunk
+                .iter()
+                .zip(ours_chunk)
+                .zip(theirs_chunk)
+                .enumerate()
+            {
+                let block_key = format!("{key}[{}]", base_start + offset);
+                merged.push(merge_block(
+                    base_block,
+                    ours_block,
+                    theirs_block,
+                    region,
+                    block_key,
+                    conflicts,
+                )?);
+            }
+        } else {
+            merged.extend_from_slice(ours_chunk);
+            conflicts.push(chunk_conflict(
+                region,
+                format!("{key}[{base_start}]"),
+                base_chunk,
+                ours_chunk,
+                theirs_chunk,
+            ));
+        }
+
+        let Some((base_index, ours_index, theirs_index)) = anchor else {
+            break;
+        };
+        merged.push(base[base_index].clone());
+        base_start = base_index + 1;
+        ours_start = ours_index + 1;
+        theirs_start = theirs_index + 1;
+    }
+
+    Ok(merged)
+}
+
+fn merge_block(
+    base: &str,
+    ours: &str,
+    theirs: &str,
+    region: MergeRegion,
+    key: String,
+    conflicts: &mut Vec<MergeConflict>,
+) -> Result<String> {
+    if let Some(resolved) = merge_value(base, ours, theirs) {
+        return Ok(resolved.to_string());
+    }
+    if let Some(list) = merge_list_items(base, ours, theirs, &key, conflicts)? {
+        return Ok(list);
+    }
+
+    conflicts.push(MergeConflict {
+        region,
+        key,
+        base: Some(base.to_string()),
+        ours: Some(ours.to_string()),
+        theirs: Some(theirs.to_string()),
+    });
+    Ok(ours.to_string())
+}
+
+fn merge_list_items(
+    base: &str,
+    ours: &str,
+    theirs: &str,
+    key: &str,
+    conflicts: &mut Vec<MergeConflict>,
+) -> Result<Option<String>> {
+    let (
+        Some((base_list, base_items)),
+        Some((ours_list, ours_items)),
+        Some((theirs_list, theirs_items)),
+    ) = (list_block(base)?, list_block(ours)?, list_block(theirs)?)
+    else {
+        return Ok(None);
+    };
+    let list_name = |node: &NodeRef| node.as_element().map(|element| element.name.local.clone());
+    if list_name(&base_list) != list_name(&ours_list)
+        || list_name(&ours_list) != list_name(&theirs_list)
+    {
+        return Ok(None);
+    }
+
+    let items = merge_sequences(
+        &base_items,
+        &ours_items,
+        &theirs_items,
+        MergeRegion::ListItem,
+        &format!("{key}/li"),
+        conflicts,
+    )?;
+    for child in ours_list.children().collect::<Vec<_>>() {
+        child.detach();
+    }
+    for item in items {
+        for node in parse_main_fragment_children(&item)? {
+            ours_list.append(NodeRef::new_text("\n        "));
+            ours_list.append(node);
+        }
+    }
+    ours_list.append(NodeRef::new_text("\n      "));
+
+    serialize_node(&ours_list).map(Some)
+}
+
+fn list_block(html: &str) -> Result<Option<(NodeRef, Vec<String>)>> {
+    let nodes = parse_main_fragment_children(html)?;
+    let [list] = nodes.as_slice() else {
+        return Ok(None);
+    };
+    if !is_element_named(list, "ul") && !is_element_named(list, "ol") {
+        return Ok(None);
+    }
+
+    let mut items = Vec::new();
+    for child in list.children() {
+        if is_element_named(&child, "li") {
+            items.push(serialize_node(&child)?);
+        } else if child
+            .as_text()
+            .is_none_or(|text| !text.borrow().trim().is_empty())
+        {
+            return Ok(None);
+        }
+    }
+
+    Ok(Some((list.clone(), items)))
+}
+
+fn chunk_conflict(
+    region: MergeRegion,
+    key: String,
+    base: &[String],
+    ours: &[String],
+    theirs: &[String],
+) -> MergeConflict {
+    let joined = |blocks: &[String]| (!blocks.is_empty()).then(|| blocks.concat());
+    MergeConflict {
+        region,
+        key,
+        base: joined(base),
+        ours: joined(ours),
+        theirs: joined(theirs),
+    }
+}
+
+pub(crate) fn main_blocks(document: &PageDocument) -> Result<Vec<String>> {
+    document
+        .main_body_nodes()?
+        .iter()
+        .map(serialize_node)
+        .collect()
+}
+
+pub(crate) fn note_asides(document: &PageDocument) -> Result<Vec<(String, String)>> {
+    let mut notes = Vec::new();
+    for element in document
+        .document
+        .select("section[data-fractal-notes] aside[data-fractal-note]")
+        .expect("static selector should parse")
+    {
+        let Some(id) = element.attributes.borrow().get("id").map(str::to_string) else {
+            continue;
+        };
+        notes.push((id, serialize_node(element.as_node())?));
+    }
+    Ok(notes)
+}
+
+fn serialize_node(node: &NodeRef) -> Result<String> {
+    let mut serialized = Vec::new();
+    node.serialize(&mut serialized)?;
+    Ok(String::from_utf8(serialized)?)
+}
@@ -1,15 +1,14 @@
 use crate::document::PageDocument;
 use crate::graph::links::normalize_link_label;
-use crate::index::build_index;
-use crate::ops::mutation::MutationPlan;
+use crate::ops::mutation::{read_revisioned, MutationPlan};
 use crate::project::constants::{DEFAULT_SUMMARY, DEFAULT_TAGS};
 use crate::project::paths::{page_relative_path, resolve_existing_page};
 use crate::types::{OperationEvent, OperationReport, PageMetadata};
 use crate::Result;
 use std::collections::{BTreeMap, BTreeSet};
-use std::fs;
 use std::path::Path;
 
+pub(crate) const VERSION_META: &str = "fractal:version";
 pub(crate) const SUMMARY_META: &str = "fractal:summary";
 pub(crate) const TAGS_META: &str = "fractal:tags";
 
@@ -37,28 +36,74 @@ pub fn set_page_summary(
     root: impl AsRef<Path>,
     page: impl AsRef<Path>,
     summary: &str,
+    expected_revision: Option<&str>,
 ) -> Result<OperationReport> {
-    update_page_meta(root, page, SUMMARY_META, summary.trim())
+    plan_set_page_summary(root, page, summary, expected_revision)?.apply()
+}
+
+pub fn plan_set_page_summary(
+    root: impl AsRef<Path>,
+    page: impl AsRef<Path>,
+    summary: &str,
+    expected_revision: Option<&str>,
+) -> Result<MutationPlan> {
+    plan_page_meta_updates(
+        root,
+        page,
+        &[(SUMMARY_META, summary.trim().to_string())],
+        expected_revision,
+    )
 }
 
 pub fn set_page_tags(
     root: impl AsRef<Path>,
     page: impl AsRef<Path>,
     tags: impl IntoIterator<Item = impl AsRef<str>>,
+    expected_revision: Option<&str>,
 ) -> Result<OperationReport> {
+    plan_set_page_tags(root, page, tags, expected_revision)?.apply()
+}
+
+pub fn plan_set_page_tags(
+    root: impl AsRef<Path>,
+    page: impl AsRef<Path>,
+    tags: impl IntoIterator<Item = impl AsRef<str>>,
+    expected_revision: Option<&str>,
+) -> Result<MutationPlan> {
     let tags = normalize_tags(tags);
-    update_page_meta(root, page, TAGS_META, &tags.join(", "))
+    plan_page_meta_updates(
+        root,
+        page,
+        &[(TAGS_META, tags.join(", "))],
+        expected_revision,
+    )
 }
 
 pub fn reset_page_metadata(
     root: impl AsRef<Path>,
     page: impl AsRef<Path>,
+    expected_revision: Option<&str>,
 ) -> Result<OperationReport> {
-    let root = root.as_ref();
-    let page = page.as_ref();
-    let mut report = set_page_summary(root, page, DEFAULT_SUMMARY)?;
-    report.extend(set_page_tags(root, page, DEFAULT_TAGS.split(','))?);
-    Ok(report)
+    plan_reset_page_metadata(root, page, expected_revision)?.apply()
+}
+
+pub fn plan_reset_page_metadata(
+    root: impl AsRef<Path>,
+    page: impl AsRef<Path>,
+    expected_revision: Option<&str>,
+) -> Result<MutationPlan> {
+    plan_page_meta_updates(
+        root,
+        page,
+        &[
+            (SUMMARY_META, DEFAULT_SUMMARY.trim().to_string()),
+            (
+                TAGS_META,
+                normalize_tags(DEFAULT_TAGS.split(',')).join(", "),
+            ),
+        ],
+        expected_revision,
+    )
 }
 
 pub fn page_metadata_report(root: impl AsRef<Path>, page: impl AsRef<Path>) -> Result<String> {
@@ -81,33 +126,40 @@ pub fn page_metadata_report(root: impl AsRef<Path>, page: impl AsRef<Path>) -> R
     Ok(report)
 }
 
-fn update_page_meta(
+fn plan_page_meta_updates(
     root: impl AsRef<Path>,
     page: impl AsRef<Path>,
-    name: &str,
-    content: &str,
-) -> Result<OperationReport> {
+    updates: &[(&str, String)],
+    expected_revision: Option<&str>,
+) -> Result<MutationPlan> {
     let root = root.as_ref();
     let page = resolve_existing_page(root, page.as_ref())?;
-    let html = fs::read_to_string(&page)?;
+    let (html, revision) = read_revisioned(&page)?;
     let document = PageDocument::parse(&html);
 
-    let mut plan = MutationPlan::new();
-    if document.set_meta_tag(name, content)? {
-        plan.write_always(
-            page.clone(),
-            document.to_html()?.into_bytes(),
-            OperationEvent::PageMetadataUpdated {
-                page,
+    let mut events = Vec::new();
+    for (name, content) in updates {
+        if document.set_meta_tag(name, content)? {
+            events.push(OperationEvent::PageMetadataUpdated {
+                page: page.clone(),
                 name: name.to_string(),
-                content: content.to_string(),
-            },
-        );
+                content: content.clone(),
+            });
+        }
+    }
+
+    let mut plan = MutationPlan::new(root);
+    plan.require_revision(&page, revision)?;
+    plan.require_optional_revision(&page, expected_revision)?;
+    if !events.is_empty() {
+        plan.write_silent(page, document.to_html()?.into_bytes());
+        for event in events {
+            plan.event(event);
+        }
     }
 
-    let mut report = plan.apply(root)?;
-    report.extend(build_index(root)?);
-    Ok(report.relative_to(root))
+    plan.rebuild_generated_data();
+    Ok(plan)
 }
 
 pub(crate) fn summary_from_meta(meta: &BTreeMap<String, String>) -> Option<String> {
@@ -1,9 +1,15 @@
 pub mod html;
+pub mod merge;
 pub mod metadata;
 pub mod notes;
 pub mod render;
 
+mod blocks;
+pub(crate) mod embeds;
+mod headings;
 pub(crate) mod page;
 mod page_links;
+pub(crate) mod sanitize;
 
+pub(crate) use blocks::{detach_block, insert_block_after, insert_block_before};
 pub(crate) use page::PageDocument;
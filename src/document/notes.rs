@@ -1,12 +1,11 @@
 use crate::document::html::escape_html;
 use crate::document::PageDocument;
-use crate::index::build_index;
-use crate::ops::mutation::MutationPlan;
-use crate::project::paths::resolve_existing_page;
+use crate::ops::mutation::{read_revisioned, MutationPlan};
+use crate::ops::{plan_page_reference_rewrites, push_planned_page_rewrites};
+use crate::project::paths::{page_relative_path, resolve_existing_page};
 use crate::types::{OperationEvent, OperationReport};
 use crate::{FractalError, Result};
 use brik::NodeRef;
-use std::fs;
 use std::path::Path;
 
 pub fn add_note(
@@ -14,11 +13,22 @@ pub fn add_note(
     page: impl AsRef<Path>,
     trigger: &str,
     content: &str,
+    expected_revision: Option<&str>,
 ) -> Result<OperationReport> {
+    plan_add_note(root, page, trigger, content, expected_revision)?.apply()
+}
+
+pub fn plan_add_note(
+    root: impl AsRef<Path>,
+    page: impl AsRef<Path>,
+    trigger: &str,
+    content: &str,
+    expected_revision: Option<&str>,
+) -> Result<MutationPlan> {
     let root = root.as_ref();
     let page = resolve_existing_page(root, page.as_ref())?;
     let note_id = note_id_from_trigger(trigger)?;
-    let html = fs::read_to_string(&page)?;
+    let (html, revision) = read_revisioned(&page)?;
 
     let document = PageDocument::parse(&html);
     if document.note_node(&note_id).is_some() {
@@ -28,36 +38,129 @@ pub fn add_note(
     }
 
     let html = insert_note_into_document(&html, &render_note_aside(&note_id, content))?;
-    let mut plan = MutationPlan::new();
+    let mut plan = MutationPlan::new(root);
+    plan.require_revision(&page, revision)?;
+    plan.require_optional_revision(&page, expected_revision)?;
     plan.write_always(
         page.clone(),
         html.into_bytes(),
         OperationEvent::NoteAdded { page, note_id },
     );
-    let mut report = plan.apply(root)?;
-    report.extend(build_index(root)?);
-    Ok(report.relative_to(root))
+    plan.rebuild_generated_data();
+    Ok(plan)
 }
 
 pub fn remove_note(
     root: impl AsRef<Path>,
     page: impl AsRef<Path>,
     trigger: &str,
+    expected_revision: Option<&str>,
 ) -> Result<OperationReport> {
+    plan_remove_note(root, page, trigger, expected_revision)?.apply()
+}
+
+pub fn plan_remove_note(
+    root: impl AsRef<Path>,
+    page: impl AsRef<Path>,
+    trigger: &str,
+    expected_revision: Option<&str>,
+) -> Result<MutationPlan> {
     let root = root.as_ref();
     let page = resolve_existing_page(root, page.as_ref())?;
     let note_id = note_id_from_trigger(trigger)?;
-    let html = fs::read_to_string(&page)?;
+    let (html, revision) = read_revisioned(&page)?;
     let html = remove_note_from_document(&html, &note_id)?;
-    let mut plan = MutationPlan::new();
+    let host_page = relative_page_string(root, &page)?;
+    let reference_rewrites =
+        plan_page_reference_rewrites(root, &[&host_page], |page_path, document| {
+            document.unwrap_note_embeds(page_path, &host_page, &note_id)
+                + document.unwrap_cross_page_note_links(page_path, &host_page, &note_id)
+        })?;
+    let mut plan = MutationPlan::new(root);
+    plan.require_revision(&page, revision)?;
+    plan.require_optional_revision(&page, expected_revision)?;
     plan.write_always(
         page.clone(),
         html.into_bytes(),
         OperationEvent::NoteRemoved { page, note_id },
     );
-    let mut report = plan.apply(root)?;
-    report.extend(build_index(root)?);
-    Ok(report.relative_to(root))
+    push_planned_page_rewrites(&mut plan, reference_rewrites);
+    plan.rebuild_generated_data();
+    Ok(plan)
+}
+
+pub fn rename_note(
+    root: impl AsRef<Path>,
+    page: impl AsRef<Path>,
+    note: &str,
+    trigger: &str,
+    expected_revision: Option<&str>,
+) -> Result<OperationReport> {
+    plan_rename_note(root, page, note, trigger, expected_revision)?.apply()
+}
+
+pub fn plan_rename_note(
+    root: impl AsRef<Path>,
+    page: impl AsRef<Path>,
+    note: &str,
+    trigger: &str,
+    expected_revision: Option<&str>,
+) -> Result<MutationPlan> {
+    let root = root.as_ref();
+    let page = resolve_existing_page(root, page.as_ref())?;
+    let host_page = relative_page_string(root, &page)?;
+    let note_id = resolve_note_id(note)?;
+    let new_note_id = note_id_from_trigger(trigger)?;
+    let (html, revision) = read_revisioned(&page)?;
+    let document = PageDocument::parse(&html);
+    document.single_notes_section()?;
+    let note = document
+        .note_node(&note_id)
+        .ok_or_else(|| FractalError::not_found(format!("note does not exist: {note_id}")))?;
+    if new_note_id == note_id {
+        return Err(FractalError::invalid_input(format!(
+            "note already has id {note_id}"
+        )));
+    }
+    if document.note_node(&new_note_id).is_some() {
+        return Err(FractalError::already_exists(format!(
+            "note already exists: {new_note_id}"
+        )));
+    }
+
+    if let Some(element) = note.as_element() {
+        element
+            .attributes
+            .borrow_mut()
+            .insert("id", new_note_id.clone());
+    }
+    let host_links = document.rewrite_note_hrefs(&host_page, &host_page, &note_id, &new_note_id);
+    let reference_rewrites =
+        plan_page_reference_rewrites(root, &[&host_page], |page_path, document| {
+            document.rewrite_note_hrefs(page_path, &host_page, &note_id, &new_note_id)
+        })?;
+
+    let mut plan = MutationPlan::new(root);
+    plan.require_revision(&page, revision)?;
+    plan.require_optional_revision(&page, expected_revision)?;
+    plan.write_always(
+        page.clone(),
+        document.to_html()?.into_bytes(),
+        OperationEvent::NoteRenamed {
+            page: page.clone(),
+            note_id,
+            new_note_id,
+        },
+    );
+    if host_links > 0 {
+        plan.event(OperationEvent::PageLinksRewritten {
+            page,
+            count: host_links,
+        });
+    }
+    push_planned_page_rewrites(&mut plan, reference_rewrites);
+    plan.rebuild_generated_data();
+    Ok(plan)
 }
 
 pub fn patch_note(
@@ -65,21 +168,33 @@ pub fn patch_note(
     page: impl AsRef<Path>,
     trigger: &str,
     content: &str,
+    expected_revision: Option<&str>,
 ) -> Result<OperationReport> {
+    plan_patch_note(root, page, trigger, content, expected_revision)?.apply()
+}
+
+pub fn plan_patch_note(
+    root: impl AsRef<Path>,
+    page: impl AsRef<Path>,
+    trigger: &str,
+    content: &str,
+    expected_revision: Option<&str>,
+) -> Result<MutationPlan> {
     let root = root.as_ref();
     let page = resolve_existing_page(root, page.as_ref())?;
     let note_id = note_id_from_trigger(trigger)?;
-    let html = fs::read_to_string(&page)?;
+    let (html, revision) = read_revisioned(&page)?;
     let html = patch_note_in_document(&html, &note_id, content)?;
-    let mut plan = MutationPlan::new();
+    let mut plan = MutationPlan::new(root);
+    plan.require_revision(&page, revision)?;
+    plan.require_optional_revision(&page, expected_revision)?;
     plan.write_always(
         page.clone(),
         html.into_bytes(),
         OperationEvent::NoteUpdated { page, note_id },
     );
-    let mut report = plan.apply(root)?;
-    report.extend(build_index(root)?);
-    Ok(report.relative_to(root))
+    plan.rebuild_generated_data();
+    Ok(plan)
 }
 
 pub(crate) fn note_id_from_trigger(trigger: &str) -> Result<String> {
@@ -115,6 +230,14 @@ pub(crate) fn note_id_from_trigger(trigger: &str) -> Result<String> {
     Ok(format!("note-{slug}"))
 }
 
+pub(crate) fn resolve_note_id(note: &str) -> Result<String> {
+    if is_valid_note_id(note) {
+        Ok(note.to_string())
+    } else {
+        note_id_from_trigger(note)
+    }
+}
+
 pub(crate) fn is_valid_note_id(note_id: &str) -> bool {
     let Some(slug) = note_id.strip_prefix("note-") else {
         return false;
@@ -141,6 +264,12 @@ pub(crate) fn insert_note_into_document(html: &str, note: &str) -> Result<String
     document.to_html()
 }
 
+fn relative_page_string(root: &Path, page: &Path) -> Result<String> {
+    Ok(page_relative_path(root, page)?
+        .to_string_lossy()
+        .replace('\\', "/"))
+}
+
 fn remove_note_from_document(html: &str, note_id: &str) -> Result<String> {
     let document = PageDocument::parse(html);
     document.single_notes_section()?;
@@ -166,7 +295,7 @@ fn patch_note_in_document(html: &str, note_id: &str, content: &str) -> Result<St
     document.to_html()
 }
 
-fn parse_note_aside(note: &str) -> Result<NodeRef> {
+pub(crate) fn parse_note_aside(note: &str) -> Result<NodeRef> {
     let document = PageDocument::parse(note);
     let aside = document
         .document
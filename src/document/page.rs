@@ -1,6 +1,8 @@
-use crate::document::html::{escape_html, escape_html_attribute};
+use crate::document::html::{escape_html, escape_html_attribute, is_inline_formatting_element};
+use crate::document::metadata::VERSION_META;
 use crate::graph::links::{normalize_link_label, note_label_from_id};
-use crate::types::NoteEntry;
+use crate::project::constants::{DEFAULT_VERSION, LEGACY_VERSION};
+use crate::types::{ImageEntry, NoteEntry};
 use crate::{FractalError, Result};
 use brik::traits::*;
 use brik::NodeRef;
@@ -100,6 +102,27 @@ impl PageDocument {
         self.ensure_meta_tag(name, content)
     }
 
+    pub(crate) fn remove_meta_tag(&self, name: &str) -> bool {
+        let elements = self
+            .document
+            .select("meta[name]")
+            .expect("static selector should parse")
+            .filter(|element| element.attributes.borrow().get("name") == Some(name))
+            .map(|element| element.as_node().clone())
+            .collect::<Vec<_>>();
+        for element in &elements {
+            if let Some(indent) = element.previous_sibling().filter(|sibling| {
+                sibling
+                    .as_text()
+                    .is_some_and(|text| text.borrow().trim().is_empty())
+            }) {
+                indent.detach();
+            }
+            element.detach();
+        }
+        !elements.is_empty()
+    }
+
     pub(crate) fn ensure_stylesheet_link(&self, href: &str) -> Result<bool> {
         if self
             .document
@@ -239,8 +262,30 @@ impl PageDocument {
         Ok(String::from_utf8(serialized)?)
     }
 
+    pub(crate) fn main_body_nodes(&self) -> Result<Vec<NodeRef>> {
+        let main = self.main_node()?;
+        let children = main.children().collect::<Vec<_>>();
+        let body_start = children
+            .iter()
+            .position(|child| is_element_named(child, "h1"))
+            .map(|index| index + 1)
+            .unwrap_or(0);
+
+        Ok(children
+            .into_iter()
+            .skip(body_start)
+            .filter(|child| {
+                child
+                    .as_text()
+                    .is_none_or(|text| !text.borrow().trim().is_empty())
+            })
+            .collect())
+    }
+
     pub(crate) fn main_text(&self) -> Result<String> {
-        Ok(normalize_extracted_text(&self.main_node()?.text_contents()))
+        let mut text = String::new();
+        push_extracted_text(&self.main_node()?, &mut text);
+        Ok(normalize_extracted_text(&text))
     }
 
     pub(crate) fn set_title(&self, title: &str) -> Result<bool> {
@@ -308,6 +353,23 @@ impl PageDocument {
         Ok(before != body_html)
     }
 
+    pub(crate) fn uses_format_extensions(&self) -> bool {
+        self.document
+            .select(
+                "body strong, body em, body br, body sub, body sup, body mark, body s, body table, body img, body figure, body a[data-fractal-link=external], body a[data-fractal-link=section], body section[data-fractal-embed]",
+            )
+            .expect("static selector should parse")
+            .next()
+            .is_some()
+    }
+
+    pub(crate) fn upgrade_format_version(&self) -> Result<bool> {
+        if self.fractal_meta().get(VERSION_META).map(String::as_str) != Some(LEGACY_VERSION) {
+            return Ok(false);
+        }
+        self.set_meta_tag(VERSION_META, DEFAULT_VERSION)
+    }
+
     pub(crate) fn title(&self) -> Option<String> {
         self.element_text("title")
             .or_else(|| self.element_text("h1"))
@@ -340,6 +402,20 @@ impl PageDocument {
         meta
     }
 
+    pub(crate) fn images(&self) -> Vec<ImageEntry> {
+        self.document
+            .select("img[src]")
+            .expect("static selector should parse")
+            .filter_map(|element| {
+                let attributes = element.attributes.borrow();
+                Some(ImageEntry {
+                    src: attributes.get("src")?.to_string(),
+                    alt: normalize_link_label(attributes.get("alt").unwrap_or_default()),
+                })
+            })
+            .collect()
+    }
+
     pub(crate) fn notes(&self) -> Vec<NoteEntry> {
         let selector = if self.has_notes_section() {
             "section[data-fractal-notes] aside[data-fractal-note]"
@@ -401,7 +477,7 @@ impl PageDocument {
             .clone())
     }
 
-    fn main_node(&self) -> Result<NodeRef> {
+    pub(crate) fn main_node(&self) -> Result<NodeRef> {
         Ok(self
             .document
             .select_first("main")
@@ -423,7 +499,7 @@ fn parse_document_node(html: &str, selector: &str) -> Result<NodeRef> {
     Ok(node)
 }
 
-fn parse_main_fragment_children(html: &str) -> Result<Vec<NodeRef>> {
+pub(crate) fn parse_main_fragment_children(html: &str) -> Result<Vec<NodeRef>> {
     let document = PageDocument::parse(&format!("<main>{html}</main>"));
     let main = document.main_node()?;
     let children = main.children().collect::<Vec<_>>();
@@ -433,7 +509,7 @@ fn parse_main_fragment_children(html: &str) -> Result<Vec<NodeRef>> {
     Ok(children)
 }
 
-fn is_element_named(node: &NodeRef, name: &str) -> bool {
+pub(crate) fn is_element_named(node: &NodeRef, name: &str) -> bool {
     node.as_element()
         .map(|element| element.name.local.to_string() == name)
         .unwrap_or(false)
@@ -460,6 +536,46 @@ fn prepend_child(parent: &NodeRef, child: NodeRef) {
     }
 }
 
+pub(crate) fn extracted_text(nodes: &[NodeRef]) -> String {
+    let mut text = String::new();
+    for node in nodes {
+        push_node_text(node, &mut text);
+    }
+    normalize_extracted_text(&text)
+}
+
+fn push_extracted_text(node: &NodeRef, text: &mut String) {
+    for child in node.children() {
+        push_node_text(&child, text);
+    }
+}
+
+fn push_node_text(node: &NodeRef, text: &mut String) {
+    if let Some(content) = node.as_text() {
+        text.push_str(&content.borrow());
+        return;
+    }
+    let Some(element) = node.as_element() else {
+        return;
+    };
+    let name = element.name.local.to_string();
+    if name == "img" {
+        text.push(' ');
+        text.push_str(element.attributes.borrow().get("alt").unwrap_or_default());
+        text.push(' ');
+        return;
+    }
+
+    let separated = !is_inline_formatting_element(&name) && !matches!(name.as_str(), "a" | "code");
+    if separated {
+        text.push(' ');
+    }
+    push_extracted_text(node, text);
+    if separated {
+        text.push(' ');
+    }
+}
+
 fn normalize_extracted_text(text: &str) -> String {
     text.split_whitespace().collect::<Vec<_>>().join(" ")
 }
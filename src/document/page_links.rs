@@ -1,11 +1,20 @@
+use crate::document::embeds::{is_inside_embed, EMBED_ATTRIBUTE, EMBED_SELECTOR};
 use crate::document::page::PageDocument;
 use crate::graph::links::{
-    inferred_link_scope, is_external_href, normalize_link_label, page_link_text_matches,
-    relative_href, resolve_page_href,
+    inferred_link_scope, is_allowed_external_href, is_external_href, link_label_key,
+    normalize_link_label, page_link_text_matches, relative_href, resolve_asset_src,
+    resolve_page_href, resolve_section_href,
 };
-use crate::types::LinkEntry;
+use crate::types::{HeadingEntry, LinkEntry};
 use brik::NodeRef;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
+
+const PAGE_REFERENCE_ATTRIBUTES: [(&str, &str); 2] =
+    [("a[href]", "href"), (EMBED_SELECTOR, EMBED_ATTRIBUTE)];
+const NOTE_REFERENCE_ATTRIBUTES: [(&str, &str); 2] = [
+    ("a[href][data-fractal-link=note]", "href"),
+    (EMBED_SELECTOR, EMBED_ATTRIBUTE),
+];
 
 impl PageDocument {
     pub(crate) fn repair_invalid_links(
@@ -47,10 +56,23 @@ impl PageDocument {
                     replace_children_with_text(&link, title);
                     repaired += 1;
                 }
+                (None, _) if is_allowed_external_href(&href) => {
+                    if let Some(element) = link.as_element() {
+                        element
+                            .attributes
+                            .borrow_mut()
+                            .insert("data-fractal-link", "external".to_string());
+                    }
+                    repaired += 1;
+                }
                 (None, _) => {
                     unwrap_link_node(&link, None);
                     repaired += 1;
                 }
+                (Some("external"), _) if !is_allowed_external_href(&href) => {
+                    unwrap_link_node(&link, None);
+                    repaired += 1;
+                }
                 _ => {}
             }
         }
@@ -58,6 +80,60 @@ impl PageDocument {
         repaired
     }
 
+    pub(crate) fn repair_section_links(
+        &self,
+        page_path: &str,
+        mut target_headings: impl FnMut(&str) -> Option<Vec<HeadingEntry>>,
+    ) -> usize {
+        let links = self
+            .document
+            .select("a[href][data-fractal-link=section]")
+            .expect("static selector should parse")
+            .map(|element| element.as_node().clone())
+            .collect::<Vec<_>>();
+        let mut repaired = 0;
+
+        for link in links {
+            let Some(element) = link.as_element() else {
+                continue;
+            };
+            let href = element
+                .attributes
+                .borrow()
+                .get("href")
+                .unwrap_or_default()
+                .to_string();
+            let Some((target, anchor)) = resolve_section_href(page_path, &href) else {
+                continue;
+            };
+            let Some(headings) = target_headings(&target) else {
+                continue;
+            };
+            let text_key = link_label_key(&link.text_contents());
+
+            match headings.iter().find(|heading| heading.anchor == anchor) {
+                Some(heading) if link_label_key(&heading.text) == text_key => continue,
+                Some(heading) => replace_children_with_text(&link, &heading.text),
+                None => match headings
+                    .iter()
+                    .find(|heading| link_label_key(&heading.text) == text_key)
+                {
+                    Some(heading) => {
+                        let path = href.split_once('#').map_or("", |(path, _)| path);
+                        element
+                            .attributes
+                            .borrow_mut()
+                            .insert("href", format!("{path}#{}", heading.anchor));
+                    }
+                    None => unwrap_link_node(&link, None),
+                },
+            }
+            repaired += 1;
+        }
+
+        repaired
+    }
+
     pub(crate) fn links(&self) -> Vec<LinkEntry> {
         let mut links = Vec::new();
 
@@ -65,6 +141,7 @@ impl PageDocument {
             .document
             .select("a[href]")
             .expect("static selector should parse")
+            .filter(|element| !is_inside_embed(element.as_node()))
         {
             let attributes = element.attributes.borrow();
             let Some(href) = attributes.get("href") else {
@@ -133,27 +210,29 @@ impl PageDocument {
     ) -> usize {
         let mut updated = 0;
 
-        for element in self
-            .document
-            .select("a[href]")
-            .expect("static selector should parse")
-        {
-            let mut attributes = element.attributes.borrow_mut();
-            let Some(href) = attributes.get("href") else {
-                continue;
-            };
+        for (selector, name) in PAGE_REFERENCE_ATTRIBUTES {
+            for element in self
+                .document
+                .select(selector)
+                .expect("static selector should parse")
+            {
+                let mut attributes = element.attributes.borrow_mut();
+                let Some(href) = attributes.get(name) else {
+                    continue;
+                };
+
+                if resolve_page_href(from_page, href).as_deref() != Some(old_target) {
+                    continue;
+                }
 
-            if resolve_page_href(from_page, href).as_deref() != Some(old_target) {
-                continue;
-            }
+                let rewritten = rewrite_href_path(href, new_href);
+                if rewritten == href {
+                    continue;
+                }
 
-            let rewritten = rewrite_href_path(href, new_href);
-            if rewritten == href {
-                continue;
+                attributes.insert(name, rewritten);
+                updated += 1;
             }
-
-            attributes.insert("href", rewritten);
-            updated += 1;
         }
 
         updated
@@ -166,7 +245,15 @@ impl PageDocument {
             .expect("static selector should parse")
             .filter(|element| {
                 let attributes = element.attributes.borrow();
-                attributes.get("data-fractal-link") == Some("page")
+                let cross_page_note = attributes.get("data-fractal-link") == Some("note")
+                    && !attributes
+                        .get("href")
+                        .is_some_and(|href| href.starts_with('#'));
+                (cross_page_note
+                    || matches!(
+                        attributes.get("data-fractal-link"),
+                        Some("page" | "section")
+                    ))
                     && attributes
                         .get("href")
                         .and_then(|href| resolve_page_href(from_page, href))
@@ -188,6 +275,167 @@ impl PageDocument {
         count
     }
 
+    pub(crate) fn retarget_note_links_to_page(
+        &self,
+        from_page: &str,
+        host_page: &str,
+        note_id: &str,
+        target_page: &str,
+        title: &str,
+    ) -> usize {
+        let href = relative_href(from_page, target_page);
+        let mut updated = 0;
+
+        for element in self
+            .document
+            .select("a[href][data-fractal-link=note]")
+            .expect("static selector should parse")
+        {
+            let mut attributes = element.attributes.borrow_mut();
+            if !targets_note(from_page, attributes.get("href"), host_page, note_id) {
+                continue;
+            }
+            attributes.insert("href", href.clone());
+            attributes.insert("data-fractal-link", "page".to_string());
+            drop(attributes);
+
+            let text = normalize_link_label(&element.text_contents());
+            if !page_link_text_matches(target_page, title, &text) {
+                replace_children_with_text(element.as_node(), title);
+            }
+            updated += 1;
+        }
+
+        updated
+    }
+
+    pub(crate) fn retarget_page_links_to_note(
+        &self,
+        from_page: &str,
+        target_page: &str,
+        note_href: &str,
+    ) -> usize {
+        let mut updated = 0;
+
+        for element in self
+            .document
+            .select("a[href][data-fractal-link=page], a[href][data-fractal-link=section]")
+            .expect("static selector should parse")
+        {
+            let mut attributes = element.attributes.borrow_mut();
+            let targets_page = attributes
+                .get("href")
+                .and_then(|href| resolve_page_href(from_page, href))
+                .as_deref()
+                == Some(target_page);
+            if !targets_page {
+                continue;
+            }
+            attributes.insert("href", note_href.to_string());
+            attributes.insert("data-fractal-link", "note".to_string());
+            updated += 1;
+        }
+
+        updated
+    }
+
+    pub(crate) fn rewrite_note_hrefs(
+        &self,
+        from_page: &str,
+        host_page: &str,
+        note_id: &str,
+        new_note_id: &str,
+    ) -> usize {
+        let mut updated = 0;
+
+        for (selector, name) in NOTE_REFERENCE_ATTRIBUTES {
+            for element in self
+                .document
+                .select(selector)
+                .expect("static selector should parse")
+            {
+                let mut attributes = element.attributes.borrow_mut();
+                let Some(href) = attributes.get(name) else {
+                    continue;
+                };
+                if !targets_note(from_page, Some(href), host_page, note_id) {
+                    continue;
+                }
+
+                let path = href.split_once('#').map_or("", |(path, _)| path);
+                let rewritten = format!("{path}#{new_note_id}");
+                attributes.insert(name, rewritten);
+                updated += 1;
+            }
+        }
+
+        updated
+    }
+
+    pub(crate) fn unwrap_cross_page_note_links(
+        &self,
+        from_page: &str,
+        host_page: &str,
+        note_id: &str,
+    ) -> usize {
+        let links = self
+            .document
+            .select("a[href][data-fractal-link=note]")
+            .expect("static selector should parse")
+            .filter(|element| {
+                let attributes = element.attributes.borrow();
+                let href = attributes.get("href");
+                !href.is_some_and(|href| href.starts_with('#'))
+                    && targets_note(from_page, href, host_page, note_id)
+            })
+            .map(|element| element.as_node().clone())
+            .collect::<Vec<_>>();
+        for link in &links {
+            unwrap_link_node(link, None);
+        }
+        links.len()
+    }
+
+    pub(crate) fn repair_note_links(
+        &self,
+        page_path: &str,
+        mut target_notes: impl FnMut(&str) -> Option<BTreeSet<String>>,
+    ) -> usize {
+        let links = self
+            .document
+            .select("a[href][data-fractal-link=note]")
+            .expect("static selector should parse")
+            .filter(|element| {
+                let attributes = element.attributes.borrow();
+                let Some(href) = attributes.get("href").filter(|href| !href.starts_with('#'))
+                else {
+                    return false;
+                };
+                resolve_section_href(page_path, href).is_some_and(|(target, note_id)| {
+                    !target_notes(&target).is_some_and(|notes| notes.contains(&note_id))
+                })
+            })
+            .map(|element| element.as_node().clone())
+            .collect::<Vec<_>>();
+        for link in &links {
+            unwrap_link_node(link, None);
+        }
+        links.len()
+    }
+
+    pub(crate) fn unwrap_note_links(&self) -> usize {
+        let links = self
+            .document
+            .select("a[data-fractal-link=note]")
+            .expect("static selector should parse")
+            .map(|element| element.as_node().clone())
+            .collect::<Vec<_>>();
+        for link in &links {
+            unwrap_link_node(link, None);
+        }
+        links.len()
+    }
+
     pub(crate) fn rewrite_relative_page_hrefs_for_move(
         &self,
         old_page: &str,
@@ -195,32 +443,95 @@ impl PageDocument {
     ) -> usize {
         let mut updated = 0;
 
+        for (selector, name) in PAGE_REFERENCE_ATTRIBUTES {
+            for element in self
+                .document
+                .select(selector)
+                .expect("static selector should parse")
+            {
+                let mut attributes = element.attributes.borrow_mut();
+                let Some(href) = attributes.get(name) else {
+                    continue;
+                };
+                if href.starts_with('#') {
+                    continue;
+                }
+
+                let Some(mut target) = resolve_page_href(old_page, href) else {
+                    continue;
+                };
+                if target == old_page {
+                    target = new_page.to_string();
+                }
+
+                let rewritten = rewrite_href_path(href, &relative_href(new_page, &target));
+                if rewritten == href {
+                    continue;
+                }
+
+                attributes.insert(name, rewritten);
+                updated += 1;
+            }
+        }
+
+        updated
+    }
+
+    pub(crate) fn rewrite_image_srcs(
+        &self,
+        from_page: &str,
+        old_target: &str,
+        new_src: &str,
+    ) -> usize {
+        let mut updated = 0;
+
         for element in self
             .document
-            .select("a[href]")
+            .select("img[src]")
             .expect("static selector should parse")
         {
             let mut attributes = element.attributes.borrow_mut();
-            let Some(href) = attributes.get("href") else {
+            let Some(src) = attributes.get("src") else {
                 continue;
             };
-            if href.starts_with('#') {
+
+            if resolve_asset_src(from_page, src).as_deref() != Some(old_target) || src == new_src {
                 continue;
             }
 
-            let Some(mut target) = resolve_page_href(old_page, href) else {
+            attributes.insert("src", new_src.to_string());
+            updated += 1;
+        }
+
+        updated
+    }
+
+    pub(crate) fn rewrite_relative_image_srcs_for_move(
+        &self,
+        old_page: &str,
+        new_page: &str,
+    ) -> usize {
+        let mut updated = 0;
+
+        for element in self
+            .document
+            .select("img[src]")
+            .expect("static selector should parse")
+        {
+            let mut attributes = element.attributes.borrow_mut();
+            let Some(src) = attributes.get("src") else {
+                continue;
+            };
+            let Some(target) = resolve_asset_src(old_page, src) else {
                 continue;
             };
-            if target == old_page {
-                target = new_page.to_string();
-            }
 
-            let rewritten = rewrite_href_path(href, &relative_href(new_page, &target));
-            if rewritten == href {
+            let rewritten = relative_href(new_page, &target);
+            if rewritten == src {
                 continue;
             }
 
-            attributes.insert("href", rewritten);
+            attributes.insert("src", rewritten);
             updated += 1;
         }
 
@@ -228,6 +539,11 @@ impl PageDocument {
     }
 }
 
+fn targets_note(from_page: &str, href: Option<&str>, host_page: &str, note_id: &str) -> bool {
+    href.and_then(|href| resolve_section_href(from_page, href))
+        .is_some_and(|(target, fragment)| target == host_page && fragment == note_id)
+}
+
 fn unwrap_link_node(link: &NodeRef, suffix: Option<&str>) {
     let children = link.children().collect::<Vec<_>>();
     for child in children {
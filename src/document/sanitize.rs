@@ -0,0 +1,326 @@
+use crate::document::html::is_inline_formatting_element;
+use crate::document::page::{is_element_named, parse_main_fragment_children};
+use crate::document::PageDocument;
+use crate::{FractalError, Result};
+use brik::NodeRef;
+
+pub(crate) struct SanitizedHtml {
+    pub(crate) html: String,
+    pub(crate) changes: Vec<(String, usize)>,
+}
+
+pub(crate) fn sanitize_editor_html(body_html: &str) -> Result<SanitizedHtml> {
+    let document = PageDocument::parse(&format!("<main>{body_html}</main>"));
+    let main = document.main_node()?;
+    let mut changes = Changes::default();
+
+    rename_inline_aliases(&main, &mut changes)?;
+    normalize_containers(&main, &mut changes)?;
+    demote_headings(&main, &mut changes)?;
+    wrap_loose_inline_content(&main, &mut changes)?;
+    normalize_lists(&main, &mut changes)?;
+    remove_stray_breaks(&main, &mut changes);
+
+    if changes.0.is_empty() {
+        return Ok(SanitizedHtml {
+            html: body_html.to_string(),
+            changes: Vec::new(),
+        });
+    }
+    let mut serialized = Vec::new();
+    for child in main.children() {
+        child.serialize(&mut serialized)?;
+    }
+    Ok(SanitizedHtml {
+        html: String::from_utf8(serialized)?,
+        changes: changes.0,
+    })
+}
+
+pub(crate) fn rename_element(node: &NodeRef, name: &str) -> Result<NodeRef> {
+    let replacement = new_element(name)?;
+    if let (Some(from), Some(to)) = (node.as_element(), replacement.as_element()) {
+        *to.attributes.borrow_mut() = from.attributes.borrow().clone();
+    }
+    for child in node.children().collect::<Vec<_>>() {
+        replacement.append(child);
+    }
+    node.insert_before(replacement.clone());
+    node.detach();
+    Ok(replacement)
+}
+
+pub(crate) fn unwrap_element(node: &NodeRef) {
+    for child in node.children().collect::<Vec<_>>() {
+        node.insert_before(child);
+    }
+    node.detach();
+}
+
+pub(crate) fn has_only_inline_content(node: &NodeRef) -> bool {
+    node.descendants().all(|child| {
+        child
+            .as_element()
+            .is_none_or(|element| is_phrasing_element(&element.name.local))
+    })
+}
+
+#[derive(Default)]
+struct Changes(Vec<(String, usize)>);
+
+impl Changes {
+    fn record(&mut self, change: String) {
+        match self.0.iter_mut().find(|(existing, _)| *existing == change) {
+            Some((_, count)) => *count += 1,
+            None => self.0.push((change, 1)),
+        }
+    }
+}
+
+fn rename_inline_aliases(main: &NodeRef, changes: &mut Changes) -> Result<()> {
+    for node in elements_within(main) {
+        let name = element_name(&node);
+        match name.as_str() {
+            "b" | "i" | "strike" | "del" => {
+                let target = match name.as_str() {
+                    "b" => "strong",
+                    "i" => "em",
+                    _ => "s",
+                };
+                rename_element(&node, target)?;
+                changes.record(format!("renamed <{name}> to <{target}>"));
+            }
+            "span" | "font" | "u" | "ins" | "small" | "big" => {
+                unwrap_element(&node);
+                changes.record(format!("unwrapped <{name}>"));
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn normalize_containers(main: &NodeRef, changes: &mut Changes) -> Result<()> {
+    let divs = elements_within(main)
+        .into_iter()
+        .filter(|node| is_element_named(node, "div"))
+        .collect::<Vec<_>>();
+    for div in divs.into_iter().rev() {
+        if is_empty_block(&div) {
+            div.detach();
+            changes.record("removed empty <div>".to_string());
+        } else if has_only_inline_content(&div)
+            && div.parent().is_some_and(|parent| accepts_blocks(&parent))
+        {
+            rename_element(&div, "p")?;
+            changes.record("turned <div> into <p>".to_string());
+        } else {
+            unwrap_element(&div);
+            changes.record("unwrapped <div>".to_string());
+        }
+    }
+    Ok(())
+}
+
+fn demote_headings(main: &NodeRef, changes: &mut Changes) -> Result<()> {
+    for heading in elements_within(main)
+        .into_iter()
+        .filter(|node| is_element_named(node, "h1"))
+    {
+        rename_element(&heading, "h2")?;
+        changes.record("demoted <h1> to <h2>".to_string());
+    }
+    Ok(())
+}
+
+fn wrap_loose_inline_content(main: &NodeRef, changes: &mut Changes) -> Result<()> {
+    for run in phrasing_runs(main) {
+        let needs_wrapper = run.iter().any(|node| {
+            node.as_text()
+                .is_some_and(|text| !text.borrow().trim().is_empty())
+                || node
+                    .as_element()
+                    .is_some_and(|element| is_inline_formatting_element(&element.name.local))
+        });
+        if !needs_wrapper {
+            continue;
+        }
+        wrap_run(&run, "p")?;
+        changes.record("wrapped loose inline content in <p>".to_string());
+    }
+    Ok(())
+}
+
+fn normalize_lists(main: &NodeRef, changes: &mut Changes) -> Result<()> {
+    let lists = elements_within(main)
+        .into_iter()
+        .filter(|node| is_element_named(node, "ul") || is_element_named(node, "ol"))
+        .collect::<Vec<_>>();
+    for list in lists {
+        for child in list.children().collect::<Vec<_>>() {
+            if !(is_element_named(&child, "ul") || is_element_named(&child, "ol")) {
+                continue;
+            }
+            let previous_item = child
+                .preceding_siblings()
+                .find(|sibling| !is_blank_text(sibling));
+            match previous_item.filter(|sibling| is_element_named(sibling, "li")) {
+                Some(item) => item.append(child),
+                None => {
+                    wrap_run(std::slice::from_ref(&child), "li")?;
+                }
+            }
+            changes.record("moved nested list into <li>".to_string());
+        }
+
+        let mut run = Vec::new();
+        for child in list.children().collect::<Vec<_>>() {
+            if is_element_named(&child, "li") {
+                wrap_list_run(&mut run, changes)?;
+            } else {
+                run.push(child);
+            }
+        }
+        wrap_list_run(&mut run, changes)?;
+    }
+    Ok(())
+}
+
+fn wrap_list_run(run: &mut Vec<NodeRef>, changes: &mut Changes) -> Result<()> {
+    let content = std::mem::take(run);
+    let start = content.iter().position(|node| !is_blank_text(node));
+    let end = content.iter().rposition(|node| !is_blank_text(node));
+    if let (Some(start), Some(end)) = (start, end) {
+        wrap_run(&content[start..=end], "li")?;
+        changes.record("wrapped list content in <li>".to_string());
+    }
+    Ok(())
+}
+
+fn remove_stray_breaks(main: &NodeRef, changes: &mut Changes) {
+    for node in main.children().collect::<Vec<_>>() {
+        if is_element_named(&node, "br") {
+            node.detach();
+            changes.record("removed stray <br>".to_string());
+        }
+    }
+
+    let blocks = elements_within(main)
+        .into_iter()
+        .filter(|node| accepts_blocks(node) || is_text_block(node))
+        .collect::<Vec<_>>();
+    for block in blocks {
+        for edge in [Edge::Start, Edge::End] {
+            while let Some(br) = edge_break(&block, edge) {
+                br.detach();
+                changes.record("removed stray <br>".to_string());
+            }
+        }
+        if is_element_named(&block, "p") && is_empty_block(&block) {
+            block.detach();
+            changes.record("removed empty <p>".to_string());
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Edge {
+    Start,
+    End,
+}
+
+fn edge_break(block: &NodeRef, edge: Edge) -> Option<NodeRef> {
+    let mut children = block.children().filter(|child| !is_blank_text(child));
+    let child = match edge {
+        Edge::Start => children.next(),
+        Edge::End => children.next_back(),
+    }?;
+    is_element_named(&child, "br").then_some(child)
+}
+
+fn phrasing_runs(parent: &NodeRef) -> Vec<Vec<NodeRef>> {
+    let mut runs = Vec::new();
+    let mut run = Vec::new();
+    for child in parent.children() {
+        let phrasing = child.as_text().is_some()
+            || child
+                .as_element()
+                .is_some_and(|element| is_phrasing_element(&element.name.local));
+        if phrasing {
+            run.push(child);
+        } else if !run.is_empty() {
+            runs.push(std::mem::take(&mut run));
+        }
+    }
+    if !run.is_empty() {
+        runs.push(run);
+    }
+    runs.into_iter()
+        .filter_map(|run| {
+            let start = run.iter().position(|node| !is_blank_text(node))?;
+            let end = run.iter().rposition(|node| !is_blank_text(node))?;
+            Some(run[start..=end].to_vec())
+        })
+        .collect()
+}
+
+fn wrap_run(run: &[NodeRef], name: &str) -> Result<NodeRef> {
+    let wrapper = new_element(name)?;
+    let first = run.first().ok_or_else(|| {
+        FractalError::invalid_input(format!("cannot wrap empty content in <{name}>"))
+    })?;
+    first.insert_before(wrapper.clone());
+    for node in run {
+        wrapper.append(node.clone());
+    }
+    Ok(wrapper)
+}
+
+fn new_element(name: &str) -> Result<NodeRef> {
+    parse_main_fragment_children(&format!("<{name}></{name}>"))?
+        .into_iter()
+        .find(|child| is_element_named(child, name))
+        .ok_or_else(|| FractalError::invalid_input(format!("cannot create <{name}>")))
+}
+
+fn elements_within(root: &NodeRef) -> Vec<NodeRef> {
+    root.descendants()
+        .filter(|node| node.as_element().is_some())
+        .collect()
+}
+
+fn element_name(node: &NodeRef) -> String {
+    node.as_element()
+        .map(|element| element.name.local.to_string())
+        .unwrap_or_default()
+}
+
+fn is_phrasing_element(name: &str) -> bool {
+    is_inline_formatting_element(name) || matches!(name, "br" | "a" | "code" | "img")
+}
+
+fn accepts_blocks(node: &NodeRef) -> bool {
+    ["main", "blockquote", "li", "div"]
+        .iter()
+        .any(|name| is_element_named(node, name))
+}
+
+fn is_text_block(node: &NodeRef) -> bool {
+    ["p", "h2", "h3", "h4", "h5", "h6"]
+        .iter()
+        .any(|name| is_element_named(node, name))
+}
+
+fn is_empty_block(node: &NodeRef) -> bool {
+    node.text_contents().trim().is_empty()
+        && node.descendants().all(|child| {
+            child
+                .as_element()
+                .is_none_or(|element| &*element.name.local == "br")
+        })
+}
+
+fn is_blank_text(node: &NodeRef) -> bool {
+    node.as_text()
+        .is_some_and(|text| text.borrow().trim().is_empty())
+}
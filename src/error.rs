@@ -5,6 +5,8 @@ use std::fmt;
 pub struct FractalError {
     pub code: FractalErrorCode,
     pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current_revision: Option<String>,
 }
 
 impl FractalError {
@@ -12,6 +14,7 @@ impl FractalError {
         Self {
             code,
             message: message.into(),
+            current_revision: None,
         }
     }
 
@@ -38,6 +41,17 @@ impl FractalError {
     pub fn project_locked(message: impl Into<String>) -> Self {
         Self::new(FractalErrorCode::ProjectLocked, message)
     }
+
+    pub fn conflict(message: impl Into<String>, current_revision: impl Into<String>) -> Self {
+        Self {
+            current_revision: Some(current_revision.into()),
+            ..Self::new(FractalErrorCode::Conflict, message)
+        }
+    }
+
+    pub fn transaction_recovered(message: impl Into<String>) -> Self {
+        Self::new(FractalErrorCode::TransactionRecovered, message)
+    }
 }
 
 impl fmt::Display for FractalError {
@@ -93,6 +107,7 @@ impl From<std::path::StripPrefixError> for FractalError {
 #[serde(rename_all = "snake_case")]
 pub enum FractalErrorCode {
     AlreadyExists,
+    Conflict,
     InvalidInput,
     InvalidProject,
     Io,
@@ -100,6 +115,7 @@ pub enum FractalErrorCode {
     NotFound,
     Path,
     ProjectLocked,
+    TransactionRecovered,
     UnsupportedVersion,
     Utf8,
 }
@@ -103,6 +103,21 @@ pub(crate) fn resolve_page_href(from_page: &str, href: &str) -> Option<String> {
     normalize_project_relative_path(&base.join(href_path))
 }
 
+pub(crate) fn resolve_section_href(from_page: &str, href: &str) -> Option<(String, String)> {
+    let (_, anchor) = href.split_once('#')?;
+    if anchor.is_empty() {
+        return None;
+    }
+    Some((resolve_page_href(from_page, href)?, anchor.to_string()))
+}
+
+pub(crate) fn resolve_asset_src(from_page: &str, src: &str) -> Option<String> {
+    if src.is_empty() || src.contains(['#', '?', ':']) {
+        return None;
+    }
+    resolve_page_href(from_page, src)
+}
+
 pub(crate) fn normalize_project_relative_path(path: &Path) -> Option<String> {
     let mut parts = Vec::new();
 
@@ -120,6 +135,43 @@ pub(crate) fn normalize_project_relative_path(path: &Path) -> Option<String> {
     (!parts.is_empty()).then(|| parts.join("/"))
 }
 
+pub(crate) fn is_allowed_external_href(href: &str) -> bool {
+    if href
+        .chars()
+        .any(|character| character.is_whitespace() || character.is_control())
+    {
+        return false;
+    }
+    external_href_scheme(href).is_some() && external_href_domain(href).is_some()
+}
+
+pub(crate) fn external_href_scheme(href: &str) -> Option<&'static str> {
+    let (scheme, _) = href.split_once(':')?;
+    ["http", "https", "mailto"]
+        .into_iter()
+        .find(|allowed| scheme.eq_ignore_ascii_case(allowed))
+}
+
+pub(crate) fn external_href_domain(href: &str) -> Option<String> {
+    let (_, rest) = href.split_once(':')?;
+    let domain = if external_href_scheme(href)? == "mailto" {
+        let address = rest.split('?').next().unwrap_or(rest);
+        address.rsplit_once('@')?.1
+    } else {
+        let authority = rest.strip_prefix("//")?;
+        let authority = authority.split(['/', '?', '#']).next().unwrap_or(authority);
+        let host = authority
+            .rsplit_once('@')
+            .map_or(authority, |(_, host)| host);
+        match host.strip_prefix('[') {
+            Some(bracketed) => bracketed.split(']').next().unwrap_or(bracketed),
+            None => host.split(':').next().unwrap_or(host),
+        }
+    };
+
+    (!domain.is_empty()).then(|| domain.to_ascii_lowercase())
+}
+
 pub(crate) fn is_external_href(href: &str) -> bool {
     href.contains("://") || href.starts_with("mailto:") || href.starts_with("tel:")
 }
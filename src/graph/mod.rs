@@ -1,11 +1,14 @@
 pub(crate) mod links;
 
-use crate::graph::links::{is_external_href, resolve_page_href};
+use crate::graph::links::{
+    external_href_domain, external_href_scheme, is_allowed_external_href, is_external_href,
+    resolve_asset_src, resolve_page_href, resolve_section_href,
+};
 use crate::project::constants::{GRAPH_FILE, GRAPH_VERSION, WORKSPACE_DIR};
 use crate::project::paths::{load_manifest, page_relative_path};
 use crate::types::{
-    GraphEdge, GraphNeighborPage, GraphNode, GraphNoteLink, GraphPageLink, GraphRelatedPage,
-    LinkEntry, PageGraphEntry, ProjectGraph, ProjectIndex,
+    ExternalLinkEntry, ExternalLinkSource, GraphEdge, GraphNeighborPage, GraphNode, GraphNoteLink,
+    GraphPageLink, GraphRelatedPage, LinkEntry, PageGraphEntry, ProjectGraph, ProjectIndex,
 };
 use crate::{FractalError, Result};
 use std::collections::{BTreeMap, BTreeSet};
@@ -18,7 +21,16 @@ pub(crate) fn build_project_graph(index: &ProjectIndex) -> ProjectGraph {
         .iter()
         .map(|page| page.path.as_str())
         .collect::<BTreeSet<_>>();
+    let asset_paths = index
+        .files
+        .iter()
+        .filter(|file| file.kind != "page")
+        .map(|file| file.path.as_str())
+        .collect::<BTreeSet<_>>();
     let mut note_ids = BTreeSet::new();
+    let mut embedded_assets = BTreeSet::new();
+    let mut external_urls = BTreeSet::new();
+    let mut external_domains = BTreeSet::new();
     let mut nodes = Vec::new();
     let mut edges = Vec::new();
 
@@ -50,6 +62,45 @@ pub(crate) fn build_project_graph(index: &ProjectIndex) -> ProjectGraph {
     }
 
     for page in &index.pages {
+        for link in page
+            .links
+            .iter()
+            .filter(|link| link.scope == "external" && is_allowed_external_href(&link.href))
+        {
+            if external_urls.insert(link.href.clone()) {
+                nodes.push(GraphNode {
+                    id: url_node_id(&link.href),
+                    kind: "url".to_string(),
+                    label: link.href.clone(),
+                    path: None,
+                });
+                if let Some(domain) = external_href_domain(&link.href) {
+                    if external_domains.insert(domain.clone()) {
+                        nodes.push(GraphNode {
+                            id: domain_node_id(&domain),
+                            kind: "domain".to_string(),
+                            label: domain.clone(),
+                            path: None,
+                        });
+                    }
+                    edges.push(GraphEdge {
+                        from: url_node_id(&link.href),
+                        to: domain_node_id(&domain),
+                        kind: "hosted_on".to_string(),
+                        text: None,
+                        href: None,
+                    });
+                }
+            }
+            edges.push(GraphEdge {
+                from: page_node_id(&page.path),
+                to: url_node_id(&link.href),
+                kind: "links_to_url".to_string(),
+                text: Some(link.text.clone()),
+                href: Some(link.href.clone()),
+            });
+        }
+
         for link in &page.links {
             if let Some((target, kind)) =
                 graph_target_for_link(&page.path, link, &page_paths, &note_ids)
@@ -63,6 +114,51 @@ pub(crate) fn build_project_graph(index: &ProjectIndex) -> ProjectGraph {
                 });
             }
         }
+
+        for embed in &page.embeds {
+            let Some((target_path, fragment)) = resolve_section_href(&page.path, &embed.target)
+            else {
+                continue;
+            };
+            let note_id = note_node_id(&target_path, &fragment);
+            let target = if note_ids.contains(&note_id) {
+                note_id
+            } else if page_paths.contains(target_path.as_str()) {
+                page_node_id(&target_path)
+            } else {
+                continue;
+            };
+            edges.push(GraphEdge {
+                from: page_node_id(&page.path),
+                to: target,
+                kind: "embeds".to_string(),
+                text: None,
+                href: Some(embed.target.clone()),
+            });
+        }
+
+        for image in &page.images {
+            let Some(target) = resolve_asset_src(&page.path, &image.src) else {
+                continue;
+            };
+            if asset_paths.contains(target.as_str()) {
+                if embedded_assets.insert(target.clone()) {
+                    nodes.push(GraphNode {
+                        id: asset_node_id(&target),
+                        kind: "asset".to_string(),
+                        label: target.clone(),
+                        path: Some(target.clone()),
+                    });
+                }
+                edges.push(GraphEdge {
+                    from: page_node_id(&page.path),
+                    to: asset_node_id(&target),
+                    kind: "embeds_asset".to_string(),
+                    text: Some(image.alt.clone()),
+                    href: Some(image.src.clone()),
+                });
+            }
+        }
     }
 
     nodes.sort_by(|left, right| left.id.cmp(&right.id));
@@ -119,19 +215,72 @@ pub fn load_project_graph(root: impl AsRef<Path>) -> Result<ProjectGraph> {
 pub fn graph_page(root: impl AsRef<Path>, page: impl AsRef<Path>) -> Result<PageGraphEntry> {
     let root = root.as_ref();
     let page_path = normalize_graph_page_path(root, page.as_ref())?;
-    load_project_graph(root)?
+    graph_page_in(&load_project_graph(root)?, &page_path)
+}
+
+pub(crate) fn graph_page_in(graph: &ProjectGraph, page_path: &str) -> Result<PageGraphEntry> {
+    graph
         .pages
-        .into_iter()
+        .iter()
         .find(|entry| entry.path == page_path)
+        .cloned()
         .ok_or_else(|| FractalError::not_found(format!("page not found in graph: {page_path}")))
 }
 
 pub fn orphan_pages(root: impl AsRef<Path>) -> Result<Vec<PageGraphEntry>> {
-    Ok(load_project_graph(root)?
+    Ok(orphan_pages_in(&load_project_graph(root)?))
+}
+
+pub(crate) fn orphan_pages_in(graph: &ProjectGraph) -> Vec<PageGraphEntry> {
+    graph
         .pages
-        .into_iter()
+        .iter()
         .filter(|entry| entry.backlinks.is_empty())
-        .collect())
+        .cloned()
+        .collect()
+}
+
+pub fn external_links(root: impl AsRef<Path>) -> Result<Vec<ExternalLinkEntry>> {
+    Ok(external_links_in(&load_project_graph(root)?))
+}
+
+pub(crate) fn external_links_in(graph: &ProjectGraph) -> Vec<ExternalLinkEntry> {
+    let mut entries = graph
+        .nodes
+        .iter()
+        .filter(|node| node.kind == "url")
+        .map(|node| {
+            (
+                node.id.clone(),
+                ExternalLinkEntry {
+                    url: node.label.clone(),
+                    scheme: external_href_scheme(&node.label)
+                        .unwrap_or_default()
+                        .to_string(),
+                    domain: external_href_domain(&node.label).unwrap_or_default(),
+                    sources: Vec::new(),
+                },
+            )
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    for edge in graph
+        .edges
+        .iter()
+        .filter(|edge| edge.kind == "links_to_url")
+    {
+        let Some(page) = edge.from.strip_prefix("page:") else {
+            continue;
+        };
+        if let Some(entry) = entries.get_mut(&edge.to) {
+            entry.sources.push(ExternalLinkSource {
+                page: page.to_string(),
+                text: edge.text.clone().unwrap_or_default(),
+            });
+        }
+    }
+
+    entries.into_values().collect()
 }
 
 pub fn page_backlinks(
@@ -149,7 +298,10 @@ pub fn related_pages(
     root: impl AsRef<Path>,
     page: impl AsRef<Path>,
 ) -> Result<Vec<GraphRelatedPage>> {
-    let entry = graph_page(root, page)?;
+    Ok(related_pages_for(graph_page(root, page)?))
+}
+
+pub(crate) fn related_pages_for(entry: PageGraphEntry) -> Vec<GraphRelatedPage> {
     let mut related = Vec::new();
 
     related.extend(entry.outlinks.into_iter().map(|link| GraphRelatedPage {
@@ -165,7 +317,7 @@ pub fn related_pages(
 
     related.sort();
     related.dedup();
-    Ok(related)
+    related
 }
 
 pub fn neighbor_pages(
@@ -179,20 +331,31 @@ pub fn neighbor_pages(
 
     let root = root.as_ref();
     let page_path = normalize_graph_page_path(root, page.as_ref())?;
-    let graph = load_project_graph(root)?;
+    neighbor_pages_in(&load_project_graph(root)?, &page_path, depth)
+}
+
+pub(crate) fn neighbor_pages_in(
+    graph: &ProjectGraph,
+    page_path: &str,
+    depth: usize,
+) -> Result<Vec<GraphNeighborPage>> {
+    if depth == 0 {
+        return Ok(Vec::new());
+    }
+
     let pages = graph
         .pages
         .iter()
         .map(|entry| (entry.path.clone(), entry))
         .collect::<BTreeMap<_, _>>();
-    if !pages.contains_key(&page_path) {
+    if !pages.contains_key(page_path) {
         return Err(FractalError::not_found(format!(
             "page not found in graph: {page_path}"
         )));
     }
 
-    let mut visited = BTreeSet::from([page_path.clone()]);
-    let mut frontier = BTreeSet::from([page_path]);
+    let mut visited = BTreeSet::from([page_path.to_string()]);
+    let mut frontier = BTreeSet::from([page_path.to_string()]);
     let mut neighbors = Vec::new();
 
     for distance in 1..=depth {
@@ -229,11 +392,80 @@ pub fn neighbor_pages(
     Ok(neighbors)
 }
 
+pub fn shortest_page_path(
+    root: impl AsRef<Path>,
+    from: impl AsRef<Path>,
+    to: impl AsRef<Path>,
+) -> Result<Option<Vec<String>>> {
+    let root = root.as_ref();
+    let from = normalize_graph_page_path(root, from.as_ref())?;
+    let to = normalize_graph_page_path(root, to.as_ref())?;
+    shortest_page_path_in(&load_project_graph(root)?, &from, &to)
+}
+
+pub(crate) fn shortest_page_path_in(
+    graph: &ProjectGraph,
+    from: &str,
+    to: &str,
+) -> Result<Option<Vec<String>>> {
+    let pages = graph
+        .pages
+        .iter()
+        .map(|entry| (entry.path.as_str(), entry))
+        .collect::<BTreeMap<_, _>>();
+    for page_path in [from, to] {
+        if !pages.contains_key(page_path) {
+            return Err(FractalError::not_found(format!(
+                "page not found in graph: {page_path}"
+            )));
+        }
+    }
+
+    let mut previous = BTreeMap::from([(from, from)]);
+    let mut frontier = vec![from];
+    while !frontier.is_empty() && !previous.contains_key(to) {
+        let mut next_frontier = Vec::new();
+        for current in frontier {
+            let Some(entry) = pages.get(current) else {
+                continue;
+            };
+            let adjacent = entry
+                .outlinks
+                .iter()
+                .chain(entry.backlinks.iter())
+                .map(|link| link.page.as_str())
+                .collect::<BTreeSet<_>>();
+            for page in adjacent {
+                if !previous.contains_key(page) {
+                    previous.insert(page, current);
+                    next_frontier.push(page);
+                }
+            }
+        }
+        frontier = next_frontier;
+    }
+
+    if !previous.contains_key(to) {
+        return Ok(None);
+    }
+    let mut path = vec![to.to_string()];
+    let mut current = to;
+    while current != from {
+        current = previous[current];
+        path.push(current.to_string());
+    }
+    path.reverse();
+    Ok(Some(path))
+}
+
 pub fn page_notes(root: impl AsRef<Path>, page: impl AsRef<Path>) -> Result<Vec<GraphNoteLink>> {
     let root = root.as_ref();
     let page_path = normalize_graph_page_path(root, page.as_ref())?;
-    let graph = load_project_graph(root)?;
-    let page_id = page_node_id(&page_path);
+    Ok(page_notes_in(&load_project_graph(root)?, &page_path))
+}
+
+pub(crate) fn page_notes_in(graph: &ProjectGraph, page_path: &str) -> Vec<GraphNoteLink> {
+    let page_id = page_node_id(page_path);
     let labels = graph
         .nodes
         .iter()
@@ -242,24 +474,45 @@ pub fn page_notes(root: impl AsRef<Path>, page: impl AsRef<Path>) -> Result<Vec<
 
     let mut notes = graph
         .edges
-        .into_iter()
+        .iter()
         .filter(|edge| edge.from == page_id && edge.kind == "contains_note")
         .filter_map(|edge| {
             let id = edge.to.split('#').next_back()?.to_string();
-            let href = edge.href.unwrap_or_else(|| format!("#{id}"));
+            let href = edge.href.clone().unwrap_or_else(|| format!("#{id}"));
             let label = labels
                 .get(edge.to.as_str())
                 .cloned()
-                .or(edge.text)
+                .or_else(|| edge.text.clone())
                 .unwrap_or_else(|| id.clone());
 
-            Some(GraphNoteLink { id, label, href })
+            let mut backlinks = graph
+                .edges
+                .iter()
+                .filter(|link| {
+                    link.to == edge.to && link.from != page_id && link.kind == "links_to_note"
+                })
+                .filter_map(|link| {
+                    Some(GraphPageLink {
+                        page: link.from.strip_prefix("page:")?.to_string(),
+                        text: link.text.clone().unwrap_or_default(),
+                    })
+                })
+                .collect::<Vec<_>>();
+            backlinks.sort();
+            backlinks.dedup();
+
+            Some(GraphNoteLink {
+                id,
+                label,
+                href,
+                backlinks,
+            })
         })
         .collect::<Vec<_>>();
 
     notes.sort();
     notes.dedup();
-    Ok(notes)
+    notes
 }
 
 pub fn graph_page_report(root: impl AsRef<Path>, page: impl AsRef<Path>) -> Result<String> {
@@ -369,11 +622,14 @@ pub fn graph_notes_report(root: impl AsRef<Path>, page: impl AsRef<Path>) -> Res
 
     for note in notes {
         report.push_str(&format!("  - {} ({})\n", note.id, note.label));
+        for link in note.backlinks {
+            report.push_str(&format!("      <- {} ({})\n", link.page, link.text));
+        }
     }
     Ok(report)
 }
 
-fn normalize_graph_page_path(root: &Path, page: &Path) -> Result<String> {
+pub(crate) fn normalize_graph_page_path(root: &Path, page: &Path) -> Result<String> {
     Ok(page_relative_path(root, page)?
         .to_string_lossy()
         .replace('\\', "/"))
@@ -397,8 +653,9 @@ fn graph_target_for_link(
     page_paths: &BTreeSet<&str>,
     note_ids: &BTreeSet<String>,
 ) -> Option<(String, &'static str)> {
-    if link.scope == "note" && link.href.starts_with('#') {
-        let note_id = note_node_id(page_path, link.href.trim_start_matches('#'));
+    if link.scope == "note" {
+        let (target_path, fragment) = resolve_section_href(page_path, &link.href)?;
+        let note_id = note_node_id(&target_path, &fragment);
         return note_ids
             .contains(&note_id)
             .then_some((note_id, "links_to_note"));
@@ -409,6 +666,9 @@ fn graph_target_for_link(
     }
 
     let target_path = resolve_page_href(page_path, &link.href)?;
+    if link.scope == "section" && target_path == page_path {
+        return None;
+    }
     page_paths
         .contains(target_path.as_str())
         .then(|| (page_node_id(&target_path), "links_to_page"))
@@ -430,11 +690,16 @@ fn build_page_graph_entries(index: &ProjectIndex, edges: &[GraphEdge]) -> Vec<Pa
         })
         .collect::<BTreeMap<_, _>>();
 
-    for edge in edges.iter().filter(|edge| edge.kind == "links_to_page") {
+    for edge in edges {
         let Some(source) = edge.from.strip_prefix("page:") else {
             continue;
         };
-        let Some(target) = edge.to.strip_prefix("page:") else {
+        let target = match edge.kind.as_str() {
+            "links_to_page" => edge.to.strip_prefix("page:"),
+            "links_to_note" => note_node_page(&edge.to).filter(|target| *target != source),
+            _ => None,
+        };
+        let Some(target) = target else {
             continue;
         };
         let text = edge.text.clone().unwrap_or_default();
@@ -470,6 +735,24 @@ fn page_node_id(path: &str) -> String {
     format!("page:{path}")
 }
 
+fn asset_node_id(path: &str) -> String {
+    format!("asset:{path}")
+}
+
+fn url_node_id(href: &str) -> String {
+    format!("url:{href}")
+}
+
+fn domain_node_id(domain: &str) -> String {
+    format!("domain:{domain}")
+}
+
 pub(crate) fn note_node_id(page_path: &str, note_id: &str) -> String {
     format!("note:{page_path}#{note_id}")
 }
+
+fn note_node_page(id: &str) -> Option<&str> {
+    id.strip_prefix("note:")?
+        .rsplit_once('#')
+        .map(|(page, _)| page)
+}
@@ -5,19 +5,27 @@ use crate::graph::build_project_graph;
 use crate::graph::links::{
     link_label_key, normalize_link_label, page_label_from_path, page_link_labels,
 };
+use crate::io::hash::content_hash;
+use crate::io::parallel::map_pages;
 use crate::ops::mutation::MutationPlan;
 use crate::project::constants::{GRAPH_FILE, INDEX_FILE, INDEX_VERSION, PAGES_DIR, WORKSPACE_DIR};
-use crate::project::paths::{collect_page_paths, file_kind, is_html_path, load_manifest};
+use crate::project::paths::{
+    asset_mime_type, collect_page_paths, file_kind, is_html_path, load_manifest,
+};
 use crate::types::{FileEntry, OperationEvent, OperationReport, PageEntry, ProjectIndex};
 use crate::{FractalError, Result};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
 use std::path::Path;
 
 pub fn build_index(root: impl AsRef<Path>) -> Result<OperationReport> {
+    plan_build_index(root)?.apply()
+}
+
+pub fn plan_build_index(root: impl AsRef<Path>) -> Result<MutationPlan> {
     let root = root.as_ref();
     let index = build_project_index(root)?;
-    write_generated_project_data(root, &index)
+    plan_generated_project_data(root, &index)
 }
 
 pub fn load_project_index(root: impl AsRef<Path>) -> Result<ProjectIndex> {
@@ -45,6 +53,21 @@ pub fn load_project_index(root: impl AsRef<Path>) -> Result<ProjectIndex> {
 }
 
 pub(crate) fn build_project_index(root: &Path) -> Result<ProjectIndex> {
+    rebuild_project_index(root, None)
+}
+
+pub(crate) fn update_project_index(
+    root: &Path,
+    previous: &ProjectIndex,
+    changed: &BTreeSet<String>,
+) -> Result<ProjectIndex> {
+    rebuild_project_index(root, Some((previous, changed)))
+}
+
+fn rebuild_project_index(
+    root: &Path,
+    reuse: Option<(&ProjectIndex, &BTreeSet<String>)>,
+) -> Result<ProjectIndex> {
     load_manifest(root)?;
 
     let workspace_dir = root.join(WORKSPACE_DIR);
@@ -68,19 +91,33 @@ pub(crate) fn build_project_index(root: &Path) -> Result<ProjectIndex> {
     collect_page_paths(&pages_dir, &pages_dir, &mut paths)?;
     paths.sort();
 
-    let files = paths
-        .iter()
-        .map(|path| FileEntry {
-            path: path.clone(),
-            kind: file_kind(path).to_string(),
-        })
-        .collect();
+    let unchanged = |path: &str| reuse.filter(|(_, changed)| !changed.contains(path));
 
-    let pages = paths
+    let files = map_pages(&paths, |path| {
+        let previous = unchanged(path)
+            .and_then(|(previous, _)| previous.files.iter().find(|entry| entry.path == *path));
+        match previous {
+            Some(entry) => Ok(entry.clone()),
+            None => build_file_entry(&pages_dir, path),
+        }
+    })
+    .into_iter()
+    .collect::<Result<Vec<_>>>()?;
+
+    let page_paths = paths
         .into_iter()
         .filter(|path| is_html_path(path))
-        .map(|path| build_page_entry(&pages_dir, path))
-        .collect::<Result<Vec<_>>>()?;
+        .collect::<Vec<_>>();
+    let pages = map_pages(&page_paths, |path| {
+        let previous = unchanged(path)
+            .and_then(|(previous, _)| previous.pages.iter().find(|entry| entry.path == *path));
+        match previous {
+            Some(entry) => Ok(entry.clone()),
+            None => build_page_entry(&pages_dir, path.clone()),
+        }
+    })
+    .into_iter()
+    .collect::<Result<Vec<_>>>()?;
     validate_unique_page_labels(&pages)?;
 
     Ok(ProjectIndex {
@@ -90,6 +127,26 @@ pub(crate) fn build_project_index(root: &Path) -> Result<ProjectIndex> {
     })
 }
 
+fn build_file_entry(pages_dir: &Path, path: &str) -> Result<FileEntry> {
+    let kind = file_kind(path);
+    if is_html_path(path) {
+        return Ok(FileEntry {
+            path: path.to_string(),
+            kind: kind.to_string(),
+            content_hash: None,
+            mime_type: None,
+        });
+    }
+
+    let bytes = fs::read(pages_dir.join(path))?;
+    Ok(FileEntry {
+        path: path.to_string(),
+        kind: kind.to_string(),
+        content_hash: Some(content_hash(&bytes)),
+        mime_type: Some(asset_mime_type(path).to_string()),
+    })
+}
+
 pub(crate) fn ensure_page_labels_available(root: &Path, path: &str, title: &str) -> Result<()> {
     ensure_page_labels_available_for(root, None, path, title)
 }
@@ -106,25 +163,46 @@ pub(crate) fn ensure_page_labels_available_for(
         .into_iter()
         .filter(|page| Some(page.path.as_str()) != current_path)
         .collect::<Vec<_>>();
-    pages.push(PageEntry {
+    pages.push(candidate_page_entry(path, title));
+
+    validate_unique_page_labels(&pages)
+}
+
+pub(crate) fn ensure_page_labels_available_for_all(
+    root: &Path,
+    candidates: &[(String, String)],
+) -> Result<()> {
+    let mut pages = build_project_index(root)?.pages;
+    pages.extend(
+        candidates
+            .iter()
+            .map(|(path, title)| candidate_page_entry(path, title)),
+    );
+
+    validate_unique_page_labels(&pages)
+}
+
+fn candidate_page_entry(path: &str, title: &str) -> PageEntry {
+    PageEntry {
         path: path.to_string(),
         title: normalize_link_label(title),
         meta: BTreeMap::new(),
         notes: Vec::new(),
         links: Vec::new(),
-    });
-
-    validate_unique_page_labels(&pages)
+        images: Vec::new(),
+        headings: Vec::new(),
+        embeds: Vec::new(),
+    }
 }
 
-pub(crate) fn write_generated_project_data(
+pub(crate) fn plan_generated_project_data(
     root: &Path,
     index: &ProjectIndex,
-) -> Result<OperationReport> {
+) -> Result<MutationPlan> {
     let index_path = root.join(WORKSPACE_DIR).join(INDEX_FILE);
     let graph_path = root.join(WORKSPACE_DIR).join(GRAPH_FILE);
     let graph = build_project_graph(index);
-    let mut plan = MutationPlan::new();
+    let mut plan = MutationPlan::new(root);
 
     plan.write_if_changed(
         index_path.clone(),
@@ -137,7 +215,7 @@ pub(crate) fn write_generated_project_data(
         OperationEvent::GeneratedGraphBuilt { path: graph_path },
     );
 
-    Ok(plan.apply(root)?.relative_to(root))
+    Ok(plan)
 }
 
 fn build_page_entry(pages_dir: &Path, path: String) -> Result<PageEntry> {
@@ -150,6 +228,9 @@ fn build_page_entry(pages_dir: &Path, path: String) -> Result<PageEntry> {
         .unwrap_or_else(|| page_label_from_path(&path));
     let notes = document.notes();
     let links = document.links();
+    let images = document.images();
+    let headings = document.headings();
+    let embeds = document.embeds();
 
     Ok(PageEntry {
         path,
@@ -157,6 +238,9 @@ fn build_page_entry(pages_dir: &Path, path: String) -> Result<PageEntry> {
         meta,
         notes,
         links,
+        images,
+        headings,
+        embeds,
     })
 }
 
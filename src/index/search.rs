@@ -2,15 +2,22 @@ use crate::document::html::find_case_insensitive;
 use crate::document::PageDocument;
 use crate::index::load_project_index;
 use crate::project::constants::PAGES_DIR;
-use crate::types::{PageEntry, SearchMatch, SearchResult};
+use crate::types::{PageEntry, ProjectIndex, SearchMatch, SearchResult};
 use crate::{FractalError, Result};
 use std::collections::BTreeSet;
 use std::path::Path;
 
 pub fn search_project(root: impl AsRef<Path>, query: &str) -> Result<Vec<SearchResult>> {
     let root = root.as_ref();
+    search_index(root, &load_project_index(root)?, query)
+}
+
+pub(crate) fn search_index(
+    root: &Path,
+    index: &ProjectIndex,
+    query: &str,
+) -> Result<Vec<SearchResult>> {
     let terms = query_terms(query)?;
-    let index = load_project_index(root)?;
     let mut results = index
         .pages
         .iter()
@@ -0,0 +1,207 @@
+const CONTEXT_LINES: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LineChange {
+    Context,
+    Removed,
+    Added,
+}
+
+pub(crate) fn unified_diff(
+    old_label: &str,
+    new_label: &str,
+    old: Option<&[u8]>,
+    new: Option<&[u8]>,
+) -> String {
+    if old == new {
+        return String::new();
+    }
+
+    let (old_text, new_text) = match (text_or_empty(old), text_or_empty(new)) {
+        (Some(old_text), Some(new_text)) => (old_text, new_text),
+        _ => return format!("Binary files {old_label} and {new_label} differ\n"),
+    };
+
+    let old_lines = old_text.split_inclusive('\n').collect::<Vec<_>>();
+    let new_lines = new_text.split_inclusive('\n').collect::<Vec<_>>();
+    let lines = diff_lines(&old_lines, &new_lines);
+
+    let mut output = format!("--- {old_label}\n+++ {new_label}\n");
+    for (start, end) in hunk_ranges(&lines) {
+        let old_offset = lines[..start]
+            .iter()
+            .filter(|(change, _)| *change != LineChange::Added)
+            .count();
+        let new_offset = lines[..start]
+            .iter()
+            .filter(|(change, _)| *change != LineChange::Removed)
+            .count();
+        let old_count = lines[start..end]
+            .iter()
+            .filter(|(change, _)| *change != LineChange::Added)
+            .count();
+        let new_count = lines[start..end]
+            .iter()
+            .filter(|(change, _)| *change != LineChange::Removed)
+            .count();
+
+        output.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            hunk_span(old_offset, old_count),
+            hunk_span(new_offset, new_count)
+        ));
+        for (change, line) in &lines[start..end] {
+            output.push(match change {
+                LineChange::Context => ' ',
+                LineChange::Removed => '-',
+                LineChange::Added => '+',
+            });
+            output.push_str(line);
+            if !line.ends_with('\n') {
+                output.push_str("\n\\ No newline at end of file\n");
+            }
+        }
+    }
+
+    output
+}
+
+fn text_or_empty(bytes: Option<&[u8]>) -> Option<&str> {
+    match bytes {
+        Some(bytes) => std::str::from_utf8(bytes).ok(),
+        None => Some(""),
+    }
+}
+
+fn hunk_span(offset: usize, count: usize) -> String {
+    let start = if count == 0 { offset } else { offset + 1 };
+    if count == 1 {
+        start.to_string()
+    } else {
+        format!("{start},{count}")
+    }
+}
+
+fn hunk_ranges(lines: &[(LineChange, &str)]) -> Vec<(usize, usize)> {
+    let mut ranges = Vec::<(usize, usize)>::new();
+    for (index, _) in lines
+        .iter()
+        .enumerate()
+        .filter(|(_, (change, _))| *change != LineChange::Context)
+    {
+        let start = index.saturating_sub(CONTEXT_LINES);
+        let end = (index + CONTEXT_LINES + 1).min(lines.len());
+        match ranges.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => ranges.push((start, end)),
+        }
+    }
+    ranges
+}
+
+pub(crate) fn matching_indices<T: PartialEq>(old: &[T], new: &[T]) -> Vec<(usize, usize)> {
+    let (mut x, mut y) = (0, 0);
+    let mut matches = Vec::new();
+    for change in edit_script(old, new) {
+        match change {
+            LineChange::Context => {
+                matches.push((x, y));
+                x += 1;
+                y += 1;
+            }
+            LineChange::Removed => x += 1,
+            LineChange::Added => y += 1,
+        }
+    }
+    matches
+}
+
+fn diff_lines<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<(LineChange, &'a str)> {
+    let (mut x, mut y) = (0, 0);
+    edit_script(old, new)
+        .into_iter()
+        .map(|change| match change {
+            LineChange::Context => {
+                x += 1;
+                y += 1;
+                (change, old[x - 1])
+            }
+            LineChange::Removed => {
+                x += 1;
+                (change, old[x - 1])
+            }
+            LineChange::Added => {
+                y += 1;
+                (change, new[y - 1])
+            }
+        })
+        .collect()
+}
+
+fn edit_script<T: PartialEq>(old: &[T], new: &[T]) -> Vec<LineChange> {
+    let old_len = old.len() as isize;
+    let new_len = new.len() as isize;
+    let max = old_len + new_len;
+    let offset = max + 1;
+    let mut frontier = vec![0isize; 2 * max as usize + 3];
+    let mut trace = Vec::new();
+
+    'search: for depth in 0..=max {
+        trace.push(frontier.clone());
+        for diagonal in (-depth..=depth).step_by(2) {
+            let slot = (diagonal + offset) as usize;
+            let mut x = if diagonal == -depth
+                || (diagonal != depth && frontier[slot - 1] < frontier[slot + 1])
+            {
+                frontier[slot + 1]
+            } else {
+                frontier[slot - 1] + 1
+            };
+            let mut y = x - diagonal;
+            while x < old_len && y < new_len && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            frontier[slot] = x;
+            if x >= old_len && y >= new_len {
+                break 'search;
+            }
+        }
+    }
+
+    let mut changes = Vec::new();
+    let (mut x, mut y) = (old_len, new_len);
+    for (depth, frontier) in trace.iter().enumerate().rev() {
+        let depth = depth as isize;
+        let diagonal = x - y;
+        let previous_diagonal = if diagonal == -depth
+            || (diagonal != depth
+                && frontier[(diagonal - 1 + offset) as usize]
+                    < frontier[(diagonal + 1 + offset) as usize])
+        {
+            diagonal + 1
+        } else {
+            diagonal - 1
+        };
+        let previous_x = frontier[(previous_diagonal + offset) as usize];
+        let previous_y = previous_x - previous_diagonal;
+
+        while x > previous_x && y > previous_y {
+            changes.push(LineChange::Context);
+            x -= 1;
+            y -= 1;
+        }
+        if depth > 0 {
+            if x == previous_x {
+                changes.push(LineChange::Added);
+            } else {
+                changes.push(LineChange::Removed);
+            }
+        }
+        x = previous_x;
+        y = previous_y;
+    }
+
+    changes.reverse();
+    changes
+}
@@ -0,0 +1,215 @@
+use crate::document::metadata::normalize_tags;
+use crate::graph::links::normalize_link_label;
+use crate::io::markdown::{markdown_to_html, parse_external_markdown_link};
+use crate::{FractalError, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub(crate) struct ImportedPage {
+    pub(crate) source: PathBuf,
+    pub(crate) destination: PathBuf,
+    pub(crate) title: String,
+    pub(crate) body: String,
+    pub(crate) summary: Option<String>,
+    pub(crate) tags: Vec<String>,
+    pub(crate) notes: Vec<ImportedNote>,
+    pub(crate) warnings: Vec<String>,
+}
+
+pub(crate) struct ImportedNote {
+    pub(crate) trigger: String,
+    pub(crate) content: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ImportProperty {
+    Summary,
+    Tags,
+}
+
+pub(crate) fn markdown_page(source: &Path) -> Result<ImportedPage> {
+    if source.extension().and_then(|ext| ext.to_str()) != Some("md") {
+        return Err(FractalError::invalid_input(format!(
+            "expected a markdown file: {}",
+            source.display()
+        )));
+    }
+
+    let markdown = fs::read_to_string(source)?;
+    let stem = source
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .ok_or_else(|| {
+            FractalError::invalid_input("could not derive page name from source file")
+        })?;
+    let (title, body) = markdown_to_html(stem, &markdown);
+
+    Ok(ImportedPage {
+        source: source.to_path_buf(),
+        destination: PathBuf::from(stem),
+        title,
+        body,
+        summary: None,
+        tags: Vec::new(),
+        notes: Vec::new(),
+        warnings: Vec::new(),
+    })
+}
+
+pub(crate) fn collect_source_files(
+    source: &Path,
+    extensions: &[&str],
+    skipped_dirs: &[&str],
+) -> Result<Vec<PathBuf>> {
+    if source.is_file() {
+        return Ok(vec![source.to_path_buf()]);
+    }
+    if !source.is_dir() {
+        return Err(FractalError::not_found(format!(
+            "import source does not exist: {}",
+            source.display()
+        )));
+    }
+
+    let mut files = Vec::new();
+    collect_source_files_in(source, extensions, skipped_dirs, &mut files)?;
+    files.sort();
+    Ok(files)
+}
+
+fn collect_source_files_in(
+    current: &Path,
+    extensions: &[&str],
+    skipped_dirs: &[&str],
+    files: &mut Vec<PathBuf>,
+) -> Result<()> {
+    for entry in fs::read_dir(current)? {
+        let entry = entry?;
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+
+        if file_type.is_dir() {
+            if name.starts_with('.') || skipped_dirs.contains(&name.as_str()) {
+                continue;
+            }
+            collect_source_files_in(&path, extensions, skipped_dirs, files)?;
+            continue;
+        }
+
+        let extension = path.extension().and_then(|ext| ext.to_str());
+        if file_type.is_file() && extension.is_some_and(|ext| extensions.contains(&ext)) {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+pub(crate) fn import_property(key: &str) -> Option<ImportProperty> {
+    match key.trim().to_lowercase().as_str() {
+        "summary" | "description" => Some(ImportProperty::Summary),
+        "tags" | "tag" => Some(ImportProperty::Tags),
+        _ => None,
+    }
+}
+
+pub(crate) fn apply_import_property(page: &mut ImportedPage, key: &str, value: &str) {
+    match import_property(key) {
+        Some(ImportProperty::Summary) => {
+            let summary = normalize_link_label(&strip_inline_markdown(value));
+            if !summary.is_empty() {
+                page.summary = Some(summary);
+            }
+        }
+        Some(ImportProperty::Tags) => {
+            let tags = split_import_tags(value);
+            page.tags = normalize_tags(page.tags.iter().cloned().chain(tags));
+        }
+        None => {}
+    }
+}
+
+pub(crate) fn split_import_tags(value: &str) -> Vec<String> {
+    let cleaned = value.replace("[[", "").replace("]]", "");
+    normalize_tags(
+        cleaned
+            .split(',')
+            .map(|tag| tag.trim().trim_start_matches('#').to_string()),
+    )
+}
+
+pub(crate) fn strip_inline_markdown(text: &str) -> String {
+    strip_markdown_markup(text, true)
+}
+
+pub(crate) fn strip_markdown_links(text: &str) -> String {
+    strip_markdown_markup(text, false)
+}
+
+fn strip_markdown_markup(text: &str, strip_formatting: bool) -> String {
+    let mut output = String::new();
+    let mut rest = text;
+
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("[[") {
+            if let Some(end) = after.find("]]") {
+                output.push_str(&after[..end]);
+                rest = &after[end + 2..];
+                continue;
+            }
+        }
+
+        if let Some((_, _, remaining)) =
+            parse_external_markdown_link(rest).filter(|_| !strip_formatting)
+        {
+            output.push_str(&rest[..rest.len() - remaining.len()]);
+            rest = remaining;
+            continue;
+        }
+
+        if let Some(after) = rest.strip_prefix('[') {
+            if let Some((label, remaining)) = split_markdown_link(after) {
+                output.push_str(label);
+                rest = remaining;
+                continue;
+            }
+        }
+
+        if let Some(after) = ["**", "__", "~~", "`"]
+            .iter()
+            .filter(|_| strip_formatting)
+            .find_map(|marker| rest.strip_prefix(marker))
+        {
+            rest = after;
+            continue;
+        }
+
+        let character = rest.chars().next().expect("rest is not empty");
+        output.push(character);
+        rest = &rest[character.len_utf8()..];
+    }
+
+    output
+}
+
+fn split_markdown_link(after_open: &str) -> Option<(&str, &str)> {
+    let label_end = after_open.find("](")?;
+    let target = &after_open[label_end + 2..];
+    let target_end = target.find(')')?;
+    Some((&after_open[..label_end], &target[target_end + 1..]))
+}
+
+pub(crate) fn trigger_from_text(text: &str) -> String {
+    strip_inline_markdown(text)
+        .split_whitespace()
+        .map(|word| {
+            word.chars()
+                .filter(|character| character.is_alphanumeric())
+                .collect::<String>()
+        })
+        .filter(|word| !word.is_empty())
+        .take(6)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -0,0 +1,361 @@
+use crate::graph::links::normalize_link_label;
+use crate::io::import::{
+    apply_import_property, collect_source_files, strip_inline_markdown, strip_markdown_links,
+    trigger_from_text, ImportedNote, ImportedPage,
+};
+use crate::io::markdown::inline_markdown_to_html;
+use crate::project::paths::page_slug_from_title;
+use crate::Result;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const SKIPPED_LOGSEQ_DIRS: &[&str] = &["logseq", "assets", "draws", "whiteboards"];
+
+pub(crate) fn logseq_pages(source: &Path) -> Result<Vec<ImportedPage>> {
+    let files = collect_source_files(source, &["md"], SKIPPED_LOGSEQ_DIRS)?;
+    let outlines = files
+        .iter()
+        .map(|path| Ok(parse_logseq_outline(path, &fs::read_to_string(path)?)))
+        .collect::<Result<Vec<_>>>()?;
+    let block_refs = outlines
+        .iter()
+        .flat_map(|outline| &outline.blocks)
+        .filter_map(|block| {
+            block
+                .id
+                .as_ref()
+                .map(|id| (id.clone(), strip_inline_markdown(&block.text)))
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    outlines
+        .into_iter()
+        .map(|outline| logseq_page(outline, &block_refs))
+        .collect()
+}
+
+struct LogseqOutline {
+    source: PathBuf,
+    name: String,
+    properties: Vec<(String, String)>,
+    blocks: Vec<LogseqBlock>,
+}
+
+struct LogseqBlock {
+    level: usize,
+    text: String,
+    id: Option<String>,
+    properties_only: bool,
+}
+
+fn parse_logseq_outline(source: &Path, markdown: &str) -> LogseqOutline {
+    let name = source
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .map(decode_logseq_file_name)
+        .unwrap_or_default();
+    let mut outline = LogseqOutline {
+        source: source.to_path_buf(),
+        name,
+        properties: Vec::new(),
+        blocks: Vec::new(),
+    };
+
+    for line in markdown.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        let level = logseq_indent_level(line);
+        let bullet = trimmed
+            .strip_prefix("- ")
+            .or_else(|| (trimmed == "-").then_some(""));
+
+        if let Some(content) = bullet {
+            let content = content.trim();
+            if let Some((key, value)) = parse_logseq_property(content) {
+                let page_properties = outline.blocks.is_empty();
+                outline.blocks.push(LogseqBlock {
+                    level,
+                    text: String::new(),
+                    id: None,
+                    properties_only: page_properties,
+                });
+                push_logseq_property(&mut outline, key, value);
+            } else {
+                outline.blocks.push(LogseqBlock {
+                    level,
+                    text: content.to_string(),
+                    id: None,
+                    properties_only: false,
+                });
+            }
+            continue;
+        }
+
+        if let Some((key, value)) = parse_logseq_property(trimmed) {
+            push_logseq_property(&mut outline, key, value);
+            continue;
+        }
+
+        match outline.blocks.last_mut() {
+            Some(block) if !block.properties_only => {
+                if !block.text.is_empty() {
+                    block.text.push(' ');
+                }
+                block.text.push_str(trimmed);
+            }
+            _ => outline.blocks.push(LogseqBlock {
+                level: 0,
+                text: trimmed.to_string(),
+                id: None,
+                properties_only: false,
+            }),
+        }
+    }
+
+    outline
+}
+
+fn push_logseq_property(outline: &mut LogseqOutline, key: &str, value: &str) {
+    match outline.blocks.last_mut() {
+        Some(block) if !block.properties_only => {
+            if key.eq_ignore_ascii_case("id") {
+                block.id = Some(value.to_string());
+            }
+        }
+        _ => outline
+            .properties
+            .push((key.to_string(), value.to_string())),
+    }
+}
+
+fn parse_logseq_property(line: &str) -> Option<(&str, &str)> {
+    let (key, value) = line.split_once(":: ").or_else(|| {
+        line.strip_suffix("::")
+            .map(|key| (key, ""))
+            .filter(|(key, _)| !key.contains(' '))
+    })?;
+    let key = key.trim();
+    (!key.is_empty()
+        && key
+            .chars()
+            .all(|character| character.is_alphanumeric() || matches!(character, '-' | '_')))
+    .then(|| (key, value.trim()))
+}
+
+fn logseq_indent_level(line: &str) -> usize {
+    let mut tabs = 0;
+    let mut spaces = 0;
+    for character in line.chars() {
+        match character {
+            '\t' => tabs += 1,
+            ' ' => spaces += 1,
+            _ => break,
+        }
+    }
+    tabs + spaces / 2
+}
+
+fn decode_logseq_file_name(name: &str) -> String {
+    let name = name.replace("___", "/");
+    let mut decoded = Vec::new();
+    let bytes = name.as_bytes();
+    let mut index = 0;
+
+    while index < bytes.len() {
+        if bytes[index] == b'%' && index + 2 < bytes.len() {
+            let byte = std::str::from_utf8(&bytes[index + 1..index + 3])
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+            if let Some(byte) = byte {
+                decoded.push(byte);
+                index += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[index]);
+        index += 1;
+    }
+
+    String::from_utf8(decoded).unwrap_or(name)
+}
+
+fn logseq_page(
+    outline: LogseqOutline,
+    block_refs: &BTreeMap<String, String>,
+) -> Result<ImportedPage> {
+    let title = outline
+        .properties
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case("title"))
+        .map(|(_, value)| normalize_link_label(value))
+        .filter(|title| !title.is_empty())
+        .unwrap_or_else(|| normalize_link_label(&outline.name));
+
+    let mut destination = PathBuf::new();
+    let namespace = title.split('/').map(str::trim).collect::<Vec<_>>();
+    if let Some((name, directories)) = namespace.split_last() {
+        for directory in directories {
+            destination.push(page_slug_from_title(directory)?);
+        }
+        destination.push(page_slug_from_title(name)?);
+    }
+
+    let mut page = ImportedPage {
+        source: outline.source,
+        destination,
+        title,
+        body: String::new(),
+        summary: None,
+        tags: Vec::new(),
+        notes: Vec::new(),
+        warnings: Vec::new(),
+    };
+    for (key, value) in &outline.properties {
+        apply_import_property(&mut page, key, value);
+    }
+
+    let mut referenced = BTreeSet::new();
+    let blocks = outline
+        .blocks
+        .into_iter()
+        .filter(|block| !block.properties_only)
+        .filter_map(|block| {
+            let text = resolve_block_refs(&block.text, block_refs, &mut page, &mut referenced);
+            let text = normalize_link_label(&strip_markdown_links(&text));
+            (!text.is_empty()).then_some((block.level, text))
+        })
+        .collect::<Vec<_>>();
+    page.body = render_logseq_blocks(&blocks);
+    Ok(page)
+}
+
+fn resolve_block_refs(
+    text: &str,
+    block_refs: &BTreeMap<String, String>,
+    page: &mut ImportedPage,
+    referenced: &mut BTreeSet<String>,
+) -> String {
+    let text = text.replace("{{embed ((", "((").replace(")) }}", "))");
+    let text = text.replace("))}}", "))");
+    let mut output = String::new();
+    let mut rest = text.as_str();
+
+    while let Some(start) = rest.find("((") {
+        let Some(length) = rest[start + 2..].find("))") else {
+            break;
+        };
+        output.push_str(&rest[..start]);
+        let id = rest[start + 2..start + 2 + length].trim();
+        rest = &rest[start + 2 + length + 2..];
+
+        let Some(content) = block_refs.get(id) else {
+            page.warnings.push(format!(
+                "unresolved Logseq block reference (({id})) in {}",
+                page.source.display()
+            ));
+            continue;
+        };
+        let trigger = trigger_from_text(content);
+        if trigger.is_empty() {
+            page.warnings.push(format!(
+                "dropped Logseq block reference (({id})) in {}: the block has no text to link",
+                page.source.display()
+            ));
+            continue;
+        }
+        output.push_str(&trigger);
+        if referenced.insert(trigger.to_lowercase()) {
+            page.notes.push(ImportedNote {
+                trigger,
+                content: normalize_link_label(content),
+            });
+        }
+    }
+
+    output.push_str(rest);
+    output
+}
+
+fn render_logseq_blocks(blocks: &[(usize, String)]) -> String {
+    let mut body = Vec::new();
+    let mut index = 0;
+
+    while index < blocks.len() {
+        let (level, text) = &blocks[index];
+        if *level > 0 {
+            let end = blocks[index..]
+                .iter()
+                .position(|(level, _)| *level == 0)
+                .map(|offset| index + offset)
+                .unwrap_or(blocks.len());
+            body.push(render_logseq_list(&blocks[index..end]));
+            index = end;
+            continue;
+        }
+
+        body.push(render_logseq_block(text));
+        index += 1;
+        let end = blocks[index..]
+            .iter()
+            .position(|(level, _)| *level == 0)
+            .map(|offset| index + offset)
+            .unwrap_or(blocks.len());
+        if end > index {
+            body.push(render_logseq_list(&blocks[index..end]));
+            index = end;
+        }
+    }
+
+    body.join("\n      ")
+}
+
+fn render_logseq_block(text: &str) -> String {
+    let level = text
+        .chars()
+        .take_while(|character| *character == '#')
+        .count();
+    if (1..=6).contains(&level) {
+        if let Some(heading) = text[level..].strip_prefix(' ') {
+            let level = level.max(2);
+            return format!(
+                "<h{level}>{}</h{level}>",
+                inline_markdown_to_html(heading.trim())
+            );
+        }
+    }
+    format!("<p>{}</p>", inline_markdown_to_html(text))
+}
+
+fn render_logseq_list(blocks: &[(usize, String)]) -> String {
+    let base = blocks.iter().map(|(level, _)| *level).min().unwrap_or(1);
+    let mut html = String::new();
+    let mut depth = 0;
+
+    for (level, text) in blocks {
+        let target = (level - base + 1).min(depth + 1);
+        if target > depth {
+            html.push_str("<ul>");
+            depth = target;
+        } else {
+            html.push_str("</li>");
+            while depth > target {
+                html.push_str("</ul></li>");
+                depth -= 1;
+            }
+        }
+        html.push_str("<li>");
+        html.push_str(&inline_markdown_to_html(
+            text.trim_start_matches('#').trim(),
+        ));
+    }
+
+    while depth > 0 {
+        html.push_str("</li></ul>");
+        depth -= 1;
+    }
+    html
+}
@@ -1,11 +1,12 @@
-use crate::document::html::escape_html;
+use crate::document::html::{escape_html, escape_html_attribute};
 use crate::document::PageDocument;
+use crate::graph::links::is_allowed_external_href;
 use brik::NodeRef;
 
 pub(crate) fn markdown_to_html(default_title: &str, markdown: &str) -> (String, String) {
     let blocks = parse_markdown_blocks(markdown);
     let title = match blocks.first() {
-        Some(MarkdownBlock::Heading { level: 1, text }) => text.clone(),
+        Some(MarkdownBlock::Heading { level: 1, text }) => strip_inline_markers(text),
         _ => default_title.to_string(),
     };
     let mut body = String::new();
@@ -22,10 +23,26 @@ pub(crate) fn markdown_to_html(default_title: &str, markdown: &str) -> (String,
 
         match block {
             MarkdownBlock::Heading { level, text } => {
-                body.push_str(&format!("<h{level}>{}</h{level}>", escape_html(text)));
+                body.push_str(&format!(
+                    "<h{level}>{}</h{level}>",
+                    inline_markdown_to_html(text)
+                ));
             }
             MarkdownBlock::Paragraph(text) => {
-                body.push_str(&format!("<p>{}</p>", escape_html(text)));
+                body.push_str(&format!("<p>{}</p>", inline_markdown_to_html(text)));
+            }
+            MarkdownBlock::Figure(image) => {
+                body.push_str(&format!("<figure>{}", render_markdown_image(image)));
+                if let Some(caption) = &image.caption {
+                    body.push_str(&format!(
+                        "<figcaption>{}</figcaption>",
+                        inline_markdown_to_html(caption)
+                    ));
+                }
+                body.push_str("</figure>");
+            }
+            MarkdownBlock::Table { header, rows } => {
+                body.push_str(&render_markdown_table(header, rows));
             }
         }
     }
@@ -36,20 +53,46 @@ pub(crate) fn markdown_to_html(default_title: &str, markdown: &str) -> (String,
 fn parse_markdown_blocks(markdown: &str) -> Vec<MarkdownBlock> {
     let mut blocks = Vec::new();
     let mut paragraph = Vec::new();
+    let lines = markdown.lines().collect::<Vec<_>>();
+    let mut index = 0;
 
-    for line in markdown.lines() {
+    while index < lines.len() {
+        let line = lines[index];
         let trimmed = line.trim();
+        index += 1;
         if trimmed.is_empty() {
             flush_paragraph(&mut blocks, &mut paragraph);
             continue;
         }
 
-        if let Some((level, text)) = parse_markdown_heading(trimmed) {
+        if let Some(header) = parse_table_header(trimmed, lines.get(index).copied()) {
+            flush_paragraph(&mut blocks, &mut paragraph);
+            index += 1;
+            let mut rows = Vec::new();
+            while let Some(row) = lines
+                .get(index)
+                .map(|line| line.trim())
+                .filter(|line| line.starts_with('|'))
+            {
+                rows.push(split_table_row(row));
+                index += 1;
+            }
+            blocks.push(MarkdownBlock::Table { header, rows });
+        } else if let Some((image, "")) = parse_markdown_image(trimmed) {
+            flush_paragraph(&mut blocks, &mut paragraph);
+            blocks.push(MarkdownBlock::Figure(image));
+        } else if let Some((level, text)) = parse_markdown_heading(trimmed) {
             flush_paragraph(&mut blocks, &mut paragraph);
             blocks.push(MarkdownBlock::Heading {
                 level,
                 text: text.to_string(),
             });
+        } else if let Some(text) = trimmed.strip_suffix('\\') {
+            paragraph.push(text.trim_end());
+            paragraph.push("\n");
+        } else if line.ends_with("  ") {
+            paragraph.push(trimmed);
+            paragraph.push("\n");
         } else {
             paragraph.push(trimmed);
         }
@@ -80,18 +123,277 @@ fn flush_paragraph(blocks: &mut Vec<MarkdownBlock>, paragraph: &mut Vec<&str>) {
         return;
     }
 
-    blocks.push(MarkdownBlock::Paragraph(paragraph.join(" ")));
+    while paragraph.last() == Some(&"\n") {
+        paragraph.pop();
+    }
+    let mut text = String::new();
+    for line in paragraph.iter() {
+        if !text.is_empty() && !text.ends_with('\n') && *line != "\n" {
+            text.push(' ');
+        }
+        text.push_str(line);
+    }
+    blocks.push(MarkdownBlock::Paragraph(text));
     paragraph.clear();
 }
 
+fn parse_table_header(line: &str, separator: Option<&str>) -> Option<Vec<String>> {
+    let separator = separator?.trim();
+    if !line.starts_with('|') || !separator.starts_with('|') {
+        return None;
+    }
+    let is_separator = split_table_row(separator).iter().all(|cell| {
+        let dashes = cell.trim_start_matches(':').trim_end_matches(':');
+        !dashes.is_empty() && dashes.chars().all(|character| character == '-')
+    });
+    is_separator.then(|| split_table_row(line))
+}
+
+fn split_table_row(line: &str) -> Vec<String> {
+    let line = line.trim().trim_start_matches('|');
+    let line = line
+        .strip_suffix('|')
+        .filter(|rest| !rest.ends_with('\\'))
+        .unwrap_or(line);
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut characters = line.chars().peekable();
+
+    while let Some(character) = characters.next() {
+        match character {
+            '\\' if characters.peek() == Some(&'|') => {
+                cell.push('|');
+                characters.next();
+            }
+            '|' => cells.push(std::mem::take(&mut cell).trim().to_string()),
+            character => cell.push(character),
+        }
+    }
+    cells.push(cell.trim().to_string());
+    cells
+}
+
+fn render_markdown_table(header: &[String], rows: &[Vec<String>]) -> String {
+    let render_row = |cells: &[String], tag: &str| {
+        let cells = (0..header.len())
+            .map(|column| {
+                let cell = cells.get(column).map(String::as_str).unwrap_or_default();
+                format!("<{tag}>{}</{tag}>", inline_markdown_to_html(cell))
+            })
+            .collect::<String>();
+        format!("<tr>{cells}</tr>")
+    };
+
+    let mut html = format!("<table><thead>{}</thead>", render_row(header, "th"));
+    if !rows.is_empty() {
+        html.push_str("<tbody>");
+        for row in rows {
+            html.push_str(&render_row(row, "td"));
+        }
+        html.push_str("</tbody>");
+    }
+    html.push_str("</table>");
+    html
+}
+
+fn parse_markdown_image(text: &str) -> Option<(MarkdownImage, &str)> {
+    let rest = text.strip_prefix("![")?;
+    let alt_end = rest.find("](")?;
+    let target = &rest[alt_end + 2..];
+    let target_end = target.find(')')?;
+    let (src, caption) = match target[..target_end].split_once(' ') {
+        Some((src, title)) => {
+            let title = title.trim();
+            let caption = title
+                .strip_prefix('"')
+                .and_then(|title| title.strip_suffix('"'))?;
+            (src, Some(caption.to_string()))
+        }
+        None => (&target[..target_end], None),
+    };
+    let alt = rest[..alt_end].trim();
+    if alt.is_empty() || src.is_empty() {
+        return None;
+    }
+
+    Some((
+        MarkdownImage {
+            src: src.to_string(),
+            alt: alt.to_string(),
+            caption: caption.filter(|caption| !caption.is_empty()),
+        },
+        &target[target_end + 1..],
+    ))
+}
+
+pub(crate) fn parse_external_markdown_link(text: &str) -> Option<(&str, &str, &str)> {
+    if let Some(rest) = text.strip_prefix('<') {
+        let end = rest.find('>')?;
+        let href = &rest[..end];
+        return is_allowed_external_href(href).then(|| (href, href, &rest[end + 1..]));
+    }
+
+    let rest = text.strip_prefix('[')?;
+    let label_end = rest.find("](")?;
+    let label = &rest[..label_end];
+    let target = &rest[label_end + 2..];
+    let target_end = target.find(')')?;
+    let href = target[..target_end].split_whitespace().next()?;
+    (!label.trim().is_empty() && is_allowed_external_href(href))
+        .then(|| (label, href, &target[target_end + 1..]))
+}
+
+fn render_markdown_image(image: &MarkdownImage) -> String {
+    format!(
+        "<img src=\"{}\" alt=\"{}\">",
+        escape_html_attribute(&image.src),
+        escape_html_attribute(&image.alt)
+    )
+}
+
+const INLINE_MARKERS: &[(&str, &str)] = &[
+    ("`", "code"),
+    ("**", "strong"),
+    ("__", "strong"),
+    ("~~", "s"),
+    ("==", "mark"),
+    ("*", "em"),
+    ("_", "em"),
+    ("~", "sub"),
+    ("^", "sup"),
+];
+
+pub(crate) fn inline_markdown_to_html(text: &str) -> String {
+    let mut html = String::new();
+    let mut rest = text;
+
+    while let Some(character) = rest.chars().next() {
+        if character == '\n' {
+            html.push_str("<br>");
+            rest = &rest[1..];
+            continue;
+        }
+
+        if let Some((image, remaining)) = parse_markdown_image(rest) {
+            html.push_str(&render_markdown_image(&image));
+            rest = remaining;
+            continue;
+        }
+
+        if let Some((label, href, remaining)) = parse_external_markdown_link(rest) {
+            let label = if label == href {
+                escape_html(label)
+            } else {
+                inline_markdown_to_html(label)
+            };
+            html.push_str(&format!(
+                "<a href=\"{}\" data-fractal-link=\"external\">{label}</a>",
+                escape_html_attribute(href)
+            ));
+            rest = remaining;
+            continue;
+        }
+
+        if let Some((tag, content, remaining)) = inline_span(text, rest) {
+            let inner = if tag == "code" {
+                escape_html(content)
+            } else {
+                inline_markdown_to_html(content)
+            };
+            html.push_str(&format!("<{tag}>{inner}</{tag}>"));
+            rest = remaining;
+            continue;
+        }
+
+        html.push_str(&escape_html(&character.to_string()));
+        rest = &rest[character.len_utf8()..];
+    }
+
+    html
+}
+
+fn strip_inline_markers(text: &str) -> String {
+    let mut output = String::new();
+    let mut rest = text;
+
+    while let Some(character) = rest.chars().next() {
+        if let Some((label, _, remaining)) = parse_external_markdown_link(rest) {
+            output.push_str(&strip_inline_markers(label));
+            rest = remaining;
+            continue;
+        }
+        if let Some((_, content, remaining)) = inline_span(text, rest) {
+            output.push_str(&strip_inline_markers(content));
+            rest = remaining;
+            continue;
+        }
+        output.push(character);
+        rest = &rest[character.len_utf8()..];
+    }
+
+    output
+}
+
+fn inline_span<'a>(text: &'a str, rest: &'a str) -> Option<(&'static str, &'a str, &'a str)> {
+    let offset = text.len() - rest.len();
+    let previous = text[..offset].chars().next_back();
+
+    INLINE_MARKERS.iter().find_map(|(marker, tag)| {
+        let after = rest.strip_prefix(marker)?;
+        if marker.starts_with('_') && previous.is_some_and(char::is_alphanumeric) {
+            return None;
+        }
+        let end = find_closing_marker(after, marker)?;
+        let content = &after[..end];
+        let remaining = &after[end + marker.len()..];
+        if content.is_empty()
+            || content.starts_with(char::is_whitespace)
+            || content.ends_with(char::is_whitespace)
+            || content.contains('\n')
+        {
+            return None;
+        }
+        if marker.starts_with('_') && remaining.starts_with(char::is_alphanumeric) {
+            return None;
+        }
+        Some((*tag, content, remaining))
+    })
+}
+
+fn find_closing_marker(text: &str, marker: &str) -> Option<usize> {
+    let mut search = 0;
+    while let Some(found) = text[search..].find(marker) {
+        let index = search + found;
+        let doubled = marker.len() == 1
+            && marker != "`"
+            && (text[index + 1..].starts_with(marker) || text[..index].ends_with(marker));
+        if !doubled {
+            return Some(index);
+        }
+        search = index + marker.len();
+        while text[search..].starts_with(marker) {
+            search += marker.len();
+        }
+    }
+    None
+}
+
 pub(crate) fn html_to_markdown(html: &str) -> String {
     let document = PageDocument::parse(html);
     let Ok(main) = document.document.select_first("main") else {
         return String::new();
     };
 
+    let main = main.as_node();
     let mut markdown = String::new();
-    for node in main.as_node().descendants() {
+    for node in main.descendants() {
+        let inside_embedded_block = node
+            .ancestors()
+            .take_while(|ancestor| ancestor != main)
+            .any(|ancestor| is_embedded_block(&ancestor));
+        if inside_embedded_block {
+            continue;
+        }
         if let Some(block) = markdown_block_from_node(&node) {
             push_markdown_block(&mut markdown, &block);
         }
@@ -100,11 +402,26 @@ pub(crate) fn html_to_markdown(html: &str) -> String {
     markdown
 }
 
+fn is_embedded_block(node: &NodeRef) -> bool {
+    node.as_element()
+        .is_some_and(|element| matches!(element.name.local.as_ref(), "table" | "figure"))
+}
+
 fn markdown_block_from_node(node: &NodeRef) -> Option<String> {
     let element = node.as_element()?;
     let tag = element.name.local.to_string();
-    let text = node.text_contents();
-    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    match tag.as_str() {
+        "table" => return table_to_markdown(node),
+        "figure" => return figure_to_markdown(node),
+        _ => {}
+    }
+
+    let text = inline_html_to_markdown(node)
+        .lines()
+        .map(str::trim)
+        .collect::<Vec<_>>()
+        .join("\n");
+    let text = text.trim().to_string();
     if text.is_empty() {
         return None;
     }
@@ -125,6 +442,182 @@ fn markdown_block_from_node(node: &NodeRef) -> Option<String> {
     None
 }
 
+fn table_to_markdown(table: &NodeRef) -> Option<String> {
+    let mut rows = table
+        .select("tr")
+        .expect("static selector should parse")
+        .map(|row| {
+            row.as_node()
+                .children()
+                .filter(|cell| {
+                    cell.as_element()
+                        .is_some_and(|element| matches!(element.name.local.as_ref(), "th" | "td"))
+                })
+                .map(|cell| {
+                    inline_html_to_markdown(&cell)
+                        .replace("\\\n", " ")
+                        .replace('|', "\\|")
+                        .trim()
+                        .to_string()
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>()
+        .into_iter();
+    let header = rows.next()?;
+    let width = header.len().max(1);
+    let render_row = |cells: &[String]| {
+        let cells = (0..width)
+            .map(|column| cells.get(column).map(String::as_str).unwrap_or_default())
+            .collect::<Vec<_>>();
+        format!("| {} |", cells.join(" | "))
+    };
+
+    let mut lines = vec![
+        render_row(&header),
+        render_row(&vec!["---".to_string(); width]),
+    ];
+    lines.extend(rows.map(|row| render_row(&row)));
+    Some(lines.join("\n"))
+}
+
+fn figure_to_markdown(figure: &NodeRef) -> Option<String> {
+    let caption = figure
+        .select_first("figcaption")
+        .ok()
+        .map(|caption| {
+            inline_html_to_markdown(caption.as_node())
+                .trim()
+                .to_string()
+        })
+        .filter(|caption| !caption.is_empty());
+    let images = figure
+        .select("img")
+        .expect("static selector should parse")
+        .map(|image| image.as_node().clone())
+        .collect::<Vec<_>>();
+    if let ([image], Some(caption)) = (images.as_slice(), &caption) {
+        if !caption.contains('"') {
+            return Some(image_to_markdown(image, Some(caption)));
+        }
+    }
+
+    let mut blocks = Vec::new();
+    for child in figure.children() {
+        if child
+            .as_element()
+            .is_some_and(|element| element.name.local.as_ref() == "figcaption")
+        {
+            continue;
+        }
+        let block = if child
+            .as_element()
+            .is_some_and(|element| element.name.local.as_ref() == "img")
+        {
+            Some(image_to_markdown(&child, None))
+        } else {
+            markdown_block_from_node(&child)
+        };
+        blocks.extend(block);
+    }
+    blocks.extend(caption);
+    (!blocks.is_empty()).then(|| blocks.join("\n\n"))
+}
+
+fn image_to_markdown(image: &NodeRef, caption: Option<&str>) -> String {
+    let Some(element) = image.as_element() else {
+        return String::new();
+    };
+    let attributes = element.attributes.borrow();
+    let alt = attributes.get("alt").unwrap_or_default();
+    let src = attributes.get("src").unwrap_or_default();
+    match caption {
+        Some(caption) => format!("![{alt}]({src} \"{caption}\")"),
+        None => format!("![{alt}]({src})"),
+    }
+}
+
+fn inline_html_to_markdown(node: &NodeRef) -> String {
+    let mut markdown = String::new();
+
+    for child in node.children() {
+        if let Some(text) = child.as_text() {
+            let text = text.borrow();
+            let mut previous_space = markdown.ends_with(' ') || markdown.ends_with('\n');
+            for character in text.chars() {
+                if character.is_whitespace() {
+                    if !previous_space {
+                        markdown.push(' ');
+                    }
+                    previous_space = true;
+                } else {
+                    markdown.push(character);
+                    previous_space = false;
+                }
+            }
+            continue;
+        }
+
+        let Some(element) = child.as_element() else {
+            continue;
+        };
+        let marker = match element.name.local.to_string().as_str() {
+            "br" => {
+                markdown.truncate(markdown.trim_end().len());
+                markdown.push_str("\\\n");
+                continue;
+            }
+            "code" => {
+                markdown.push_str(&format!("`{}`", child.text_contents()));
+                continue;
+            }
+            "img" => {
+                markdown.push_str(&image_to_markdown(&child, None));
+                continue;
+            }
+            "a" if element.attributes.borrow().get("data-fractal-link") == Some("external") => {
+                let href = element
+                    .attributes
+                    .borrow()
+                    .get("href")
+                    .unwrap_or_default()
+                    .to_string();
+                let text = inline_html_to_markdown(&child);
+                let text = text.trim();
+                if text == href {
+                    markdown.push_str(&format!("<{href}>"));
+                } else {
+                    markdown.push_str(&format!("[{text}]({href})"));
+                }
+                continue;
+            }
+            "strong" => "**",
+            "em" => "*",
+            "s" => "~~",
+            "mark" => "==",
+            "sub" => "~",
+            "sup" => "^",
+            _ => "",
+        };
+
+        let inner = inline_html_to_markdown(&child);
+        let trimmed = inner.trim();
+        if marker.is_empty() || trimmed.is_empty() {
+            markdown.push_str(&inner);
+            continue;
+        }
+        if inner.starts_with(char::is_whitespace) && !markdown.ends_with(' ') {
+            markdown.push(' ');
+        }
+        markdown.push_str(&format!("{marker}{trimmed}{marker}"));
+        if inner.ends_with(char::is_whitespace) {
+            markdown.push(' ');
+        }
+    }
+
+    markdown
+}
+
 fn push_markdown_block(markdown: &mut String, block: &str) {
     if !markdown.is_empty() {
         markdown.push_str("\n\n");
@@ -134,6 +627,21 @@ fn push_markdown_block(markdown: &mut String, block: &str) {
 
 #[derive(Debug, PartialEq, Eq)]
 enum MarkdownBlock {
-    Heading { level: usize, text: String },
+    Heading {
+        level: usize,
+        text: String,
+    },
     Paragraph(String),
+    Figure(MarkdownImage),
+    Table {
+        header: Vec<String>,
+        rows: Vec<Vec<String>>,
+    },
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct MarkdownImage {
+    src: String,
+    alt: String,
+    caption: Option<String>,
 }
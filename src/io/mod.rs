@@ -1,2 +1,8 @@
+pub(crate) mod diff;
 pub(crate) mod fs;
+pub(crate) mod hash;
+pub(crate) mod import;
+pub(crate) mod logseq;
 pub(crate) mod markdown;
+pub(crate) mod notion;
+pub(crate) mod parallel;
@@ -0,0 +1,217 @@
+use crate::graph::links::{link_label_key, normalize_link_label};
+use crate::io::import::{
+    apply_import_property, collect_source_files, import_property, strip_markdown_links,
+    ImportedPage,
+};
+use crate::io::markdown::markdown_to_html;
+use crate::project::paths::page_slug_from_title;
+use crate::Result;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const NOTION_ID_LENGTH: usize = 32;
+
+pub(crate) fn notion_pages(source: &Path) -> Result<Vec<ImportedPage>> {
+    let base = if source.is_file() {
+        source.parent().unwrap_or_else(|| Path::new(""))
+    } else {
+        source
+    };
+    let files = collect_source_files(source, &["md", "csv"], &[])?;
+    let database = notion_database_properties(&files)?;
+
+    files
+        .iter()
+        .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("md"))
+        .map(|path| notion_page(base, path, &database))
+        .collect()
+}
+
+pub(crate) fn clean_notion_name(name: &str) -> String {
+    let name = name.trim();
+    let Some((prefix, id)) = name.rsplit_once(' ') else {
+        return name.to_string();
+    };
+    if id.len() == NOTION_ID_LENGTH && id.chars().all(|character| character.is_ascii_hexdigit()) {
+        prefix.trim().to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+fn notion_page(base: &Path, path: &Path, database: &NotionDatabase) -> Result<ImportedPage> {
+    let markdown = fs::read_to_string(path)?;
+    let relative = path.strip_prefix(base).unwrap_or(path);
+    let name = clean_notion_name(
+        relative
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or_default(),
+    );
+
+    let mut lines = markdown.lines().map(str::to_string).collect::<Vec<_>>();
+    let title = lines
+        .iter()
+        .find_map(|line| line.trim().strip_prefix("# ").map(normalize_link_label))
+        .filter(|title| !title.is_empty())
+        .unwrap_or_else(|| name.clone());
+    let properties = take_notion_property_lines(&mut lines, database);
+    let markdown = lines
+        .iter()
+        .map(|line| strip_markdown_links(line))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let (title, body) = markdown_to_html(&title, &markdown);
+
+    let mut destination = PathBuf::new();
+    if let Some(parent) = relative.parent() {
+        for component in parent.components() {
+            let directory = clean_notion_name(&component.as_os_str().to_string_lossy());
+            destination.push(page_slug_from_title(&directory)?);
+        }
+    }
+    destination.push(page_slug_from_title(&title)?);
+
+    let mut page = ImportedPage {
+        source: path.to_path_buf(),
+        destination,
+        title,
+        body,
+        summary: None,
+        tags: Vec::new(),
+        notes: Vec::new(),
+        warnings: Vec::new(),
+    };
+
+    let row_properties = database
+        .rows
+        .get(&link_label_key(&page.title))
+        .or_else(|| database.rows.get(&link_label_key(&name)));
+    for (key, value) in row_properties.into_iter().flatten().chain(&properties) {
+        apply_import_property(&mut page, key, value);
+    }
+
+    Ok(page)
+}
+
+fn take_notion_property_lines(
+    lines: &mut Vec<String>,
+    database: &NotionDatabase,
+) -> Vec<(String, String)> {
+    let Some(heading) = lines.iter().position(|line| line.trim().starts_with("# ")) else {
+        return Vec::new();
+    };
+    let start = lines
+        .iter()
+        .skip(heading + 1)
+        .position(|line| !line.trim().is_empty())
+        .map(|offset| heading + 1 + offset);
+    let Some(start) = start else {
+        return Vec::new();
+    };
+    let end = lines
+        .iter()
+        .skip(start)
+        .position(|line| line.trim().is_empty())
+        .map(|offset| start + offset)
+        .unwrap_or(lines.len());
+
+    let mut properties = Vec::new();
+    for line in &lines[start..end] {
+        let Some((key, value)) = line.split_once(": ") else {
+            return Vec::new();
+        };
+        let key = key.trim();
+        if !database.columns.contains(&key.to_lowercase()) && import_property(key).is_none() {
+            return Vec::new();
+        }
+        properties.push((key.to_string(), value.trim().to_string()));
+    }
+
+    lines.drain(start..end);
+    properties
+}
+
+#[derive(Default)]
+struct NotionDatabase {
+    columns: BTreeSet<String>,
+    rows: BTreeMap<String, Vec<(String, String)>>,
+}
+
+fn notion_database_properties(files: &[PathBuf]) -> Result<NotionDatabase> {
+    let mut database = NotionDatabase::default();
+
+    for path in files
+        .iter()
+        .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("csv"))
+    {
+        let csv = fs::read_to_string(path)?;
+        let mut records = parse_csv(csv.trim_start_matches('\u{feff}')).into_iter();
+        let Some(header) = records.next() else {
+            continue;
+        };
+        database.columns.extend(
+            header
+                .iter()
+                .skip(1)
+                .map(|column| column.trim().to_lowercase()),
+        );
+
+        for record in records {
+            let Some(title) = record.first() else {
+                continue;
+            };
+            let properties = header
+                .iter()
+                .skip(1)
+                .zip(record.iter().skip(1))
+                .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+                .collect::<Vec<_>>();
+            database
+                .rows
+                .entry(link_label_key(&clean_notion_name(title)))
+                .or_default()
+                .extend(properties);
+        }
+    }
+
+    Ok(database)
+}
+
+fn parse_csv(csv: &str) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut characters = csv.chars().peekable();
+
+    while let Some(character) = characters.next() {
+        match (quoted, character) {
+            (true, '"') if characters.peek() == Some(&'"') => {
+                characters.next();
+                field.push('"');
+            }
+            (true, '"') => quoted = false,
+            (true, character) => field.push(character),
+            (false, '"') if field.is_empty() => quoted = true,
+            (false, ',') => record.push(std::mem::take(&mut field)),
+            (false, '\r') => {}
+            (false, '\n') => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            (false, character) => field.push(character),
+        }
+    }
+
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+
+    records
+        .into_iter()
+        .filter(|record| record.iter().any(|field| !field.trim().is_empty()))
+        .collect()
+}
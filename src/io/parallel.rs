@@ -0,0 +1,55 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+static PAGE_WORKERS: AtomicUsize = AtomicUsize::new(0);
+
+pub fn set_page_workers(workers: usize) {
+    PAGE_WORKERS.store(workers, Ordering::Relaxed);
+}
+
+pub fn page_workers() -> usize {
+    match PAGE_WORKERS.load(Ordering::Relaxed) {
+        0 => thread::available_parallelism().map_or(1, |workers| workers.get()),
+        workers => workers,
+    }
+}
+
+pub(crate) fn map_pages<T, R, F>(items: &[T], work: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let workers = page_workers().min(items.len());
+    if workers <= 1 {
+        return items.iter().map(work).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(Vec::with_capacity(items.len()));
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| {
+                let mut done = Vec::new();
+                loop {
+                    let position = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(item) = items.get(position) else {
+                        break;
+                    };
+                    done.push((position, work(item)));
+                }
+                results
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+                    .extend(done);
+            });
+        }
+    });
+
+    let mut results = results
+        .into_inner()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    results.sort_by_key(|(position, _)| *position);
+    results.into_iter().map(|(_, result)| result).collect()
+}
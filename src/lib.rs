@@ -5,39 +5,72 @@ mod error;
 pub mod graph;
 pub mod index;
 mod io;
+pub mod lint;
+#[cfg(feature = "cli")]
+pub mod mcp;
 pub mod ops;
 mod project;
+pub mod rpc;
 mod types;
 pub mod validation;
 
+pub use document::merge::merge_page_edit;
 pub use document::metadata::{
-    page_metadata, page_metadata_report, reset_page_metadata, set_page_summary, set_page_tags,
+    page_metadata, page_metadata_report, plan_reset_page_metadata, plan_set_page_summary,
+    plan_set_page_tags, reset_page_metadata, set_page_summary, set_page_tags,
+};
+pub use document::notes::{
+    add_note, patch_note, plan_add_note, plan_patch_note, plan_remove_note, plan_rename_note,
+    remove_note, rename_note,
 };
-pub use document::notes::{add_note, patch_note, remove_note};
 pub use error::{FractalError, FractalErrorCode};
 pub use graph::{
-    graph_backlinks_report, graph_neighbors_report, graph_notes_report, graph_orphans_report,
-    graph_outlinks_report, graph_page, graph_page_report, graph_related_report, load_project_graph,
-    neighbor_pages, orphan_pages, page_backlinks, page_notes, page_outlinks, related_pages,
+    external_links, graph_backlinks_report, graph_neighbors_report, graph_notes_report,
+    graph_orphans_report, graph_outlinks_report, graph_page, graph_page_report,
+    graph_related_report, load_project_graph, neighbor_pages, orphan_pages, page_backlinks,
+    page_notes, page_outlinks, related_pages, shortest_page_path,
 };
 pub use index::search::{search_project, search_report};
-pub use index::{build_index, load_project_index};
+pub use index::{build_index, load_project_index, plan_build_index};
+pub use io::parallel::{page_workers, set_page_workers};
+pub use lint::{lint_project, LintRule, LINT_RULES};
 pub use ops::{
-    create_directory, create_page, delete_directory, delete_page, editor_page_detail, export_page,
-    extract_page_text, import_markdown, init_project, init_project_at, list_editor_pages,
-    load_project_manifest, new_page, preflight_delete_page, preflight_rename_page, project_summary,
-    read_page_source, rename_page, set_page_title, sync_project, update_editor_page,
-    update_page_body, write_page_source,
+    add_asset, apply_repair_suggestions, create_directory, create_page, delete_asset, delete_block,
+    delete_directory, delete_page, demote_page, disable_history, editor_page_detail,
+    enable_history, export_page, extract_page_text, generate_fixture_project,
+    git_auto_commit_enabled, history_diff, history_enabled, history_entry, history_log,
+    import_markdown, import_pages, init_project, init_project_at, insert_block, list_assets,
+    list_editor_pages, load_project_manifest, move_asset, move_block, mutation_lock_owner,
+    new_page, page_blame, page_blocks, page_git_history, page_section, plan_add_asset,
+    plan_apply_repair_suggestions, plan_create_directory, plan_create_page, plan_delete_asset,
+    plan_delete_block, plan_delete_directory, plan_delete_page, plan_demote_page, plan_export_page,
+    plan_generate_fixture_project, plan_import_markdown, plan_import_pages, plan_init_project,
+    plan_init_project_at, plan_insert_block, plan_move_asset, plan_move_block, plan_new_page,
+    plan_promote_note, plan_rename_page, plan_replace_block, plan_set_git_auto_commit,
+    plan_set_page_title, plan_sync_project, plan_undo_history, plan_update_editor_page,
+    plan_update_page_body, plan_write_page_source, preflight_delete_page, preflight_demote_page,
+    preflight_import_pages, preflight_promote_note, preflight_rename_page, project_summary,
+    promote_note, read_page_source, rename_page, repair_suggestions, replace_block,
+    set_git_auto_commit, set_page_title, sync_project, undo_history, unlock_project,
+    unreferenced_assets, update_editor_page, update_page_body, write_page_source, MutationPlan,
+    ProjectWatcher, WatchBackend, WatchOptions,
 };
 pub use types::{
+    AssetEntry, BlameEntry, BlameRegion, BlockPosition, Diagnostic, DiagnosticSeverity,
     EditorLinkDetail, EditorNoteDetail, EditorPageDetail, EditorPageListEntry, EditorPageUpdate,
-    FileEntry, GraphEdge, GraphNeighborPage, GraphNode, GraphNoteLink, GraphPageLink,
-    GraphRelatedPage, LinkEntry, NoteEntry, OperationEvent, OperationReport, OperationSummary,
-    PageCreate, PageDeletePreflight, PageEntry, PageGraphEntry, PageMetadata, PageRename,
-    PageRenamePreflight, PageSource, PathMove, ProjectGraph, ProjectIndex, ProjectManifest,
-    ProjectSummary, SearchMatch, SearchResult, Theme,
+    EmbedEntry, ExternalLinkEntry, ExternalLinkSource, FileChange, FileDiff, FileEntry, GitCommit,
+    GraphEdge, GraphNeighborPage, GraphNode, GraphNoteLink, GraphPageLink, GraphRelatedPage,
+    HeadingEntry, HistoryEntry, HistoryFileChange, ImageEntry, ImportFormat, LinkEntry,
+    LintRuleConfig, MergeConflict, MergeRegion, MutationLockOwner, MutationPreview, NoteEntry,
+    NotePromotePreflight, OperationEvent, OperationReport, OperationSummary, PageBlame, PageBlock,
+    PageCreate, PageDeletePreflight, PageDemotePreflight, PageEntry, PageGraphEntry,
+    PageImportPreflight, PageMerge, PageMetadata, PageRename, PageRenamePreflight, PageSection,
+    PageSource, PathMove, ProjectGraph, ProjectIndex, ProjectManifest, ProjectSummary,
+    RepairSuggestion, SearchMatch, SearchResult, SourceSpan, Theme, ValidationReport,
+};
+pub use validation::{
+    preflight_repair_project, repair_project, validate_project, validation_report,
 };
-pub use validation::{preflight_repair_project, repair_project, validate_project};
 
 pub type Result<T> = std::result::Result<T, FractalError>;
 
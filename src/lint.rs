@@ -0,0 +1,398 @@
+use crate::document::metadata::{tags_from_meta, SUMMARY_META};
+use crate::document::PageDocument;
+use crate::graph::links::normalize_link_label;
+use crate::graph::{build_project_graph, note_node_id, orphan_pages_in};
+use crate::index::build_project_index;
+use crate::project::constants::PAGES_DIR;
+use crate::project::paths::load_manifest;
+use crate::types::{
+    Diagnostic, DiagnosticSeverity, PageEntry, ProjectGraph, ProjectIndex, ProjectManifest,
+    ValidationReport,
+};
+use crate::validation::{node_label, source_span};
+use crate::{FractalError, Result};
+use brik::NodeRef;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+pub struct LintRule {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub severity: DiagnosticSeverity,
+    pub description: &'static str,
+}
+
+const EMPTY_SUMMARY: LintRule = LintRule {
+    id: "FR-LINT-001",
+    name: "empty-summary",
+    severity: DiagnosticSeverity::Warning,
+    description: "page has an empty fractal:summary",
+};
+const MISSING_TAGS: LintRule = LintRule {
+    id: "FR-LINT-002",
+    name: "missing-tags",
+    severity: DiagnosticSeverity::Info,
+    description: "page has no tags",
+};
+const ORPHAN_PAGE: LintRule = LintRule {
+    id: "FR-LINT-003",
+    name: "orphan-page",
+    severity: DiagnosticSeverity::Warning,
+    description: "no other page links to the page",
+};
+const LONG_PAGE: LintRule = LintRule {
+    id: "FR-LINT-004",
+    name: "long-page",
+    severity: DiagnosticSeverity::Warning,
+    description: "page has more words than `max_words`",
+};
+const HEADING_SKIP: LintRule = LintRule {
+    id: "FR-LINT-005",
+    name: "heading-skip",
+    severity: DiagnosticSeverity::Warning,
+    description: "heading skips a level",
+};
+const UNREFERENCED_NOTE: LintRule = LintRule {
+    id: "FR-LINT-006",
+    name: "unreferenced-note",
+    severity: DiagnosticSeverity::Info,
+    description: "note is never referenced by a note link",
+};
+const SINGLE_USE_TAG: LintRule = LintRule {
+    id: "FR-LINT-007",
+    name: "single-use-tag",
+    severity: DiagnosticSeverity::Info,
+    description: "tag is used by only one page",
+};
+const NEAR_DUPLICATE_TITLE: LintRule = LintRule {
+    id: "FR-LINT-008",
+    name: "near-duplicate-title",
+    severity: DiagnosticSeverity::Warning,
+    description: "title is within `max_distance` edits of another title",
+};
+
+pub const LINT_RULES: &[LintRule] = &[
+    EMPTY_SUMMARY,
+    MISSING_TAGS,
+    ORPHAN_PAGE,
+    LONG_PAGE,
+    HEADING_SKIP,
+    UNREFERENCED_NOTE,
+    SINGLE_USE_TAG,
+    NEAR_DUPLICATE_TITLE,
+];
+
+const DEFAULT_MAX_WORDS: usize = 2000;
+const DEFAULT_MAX_DISTANCE: usize = 2;
+
+#[derive(Debug, Clone, Copy)]
+struct ActiveRule {
+    severity: DiagnosticSeverity,
+    max_words: usize,
+    max_distance: usize,
+}
+
+struct Lint {
+    rules: BTreeMap<&'static str, ActiveRule>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Lint {
+    fn rule(&self, rule: LintRule) -> Option<ActiveRule> {
+        self.rules.get(rule.id).copied()
+    }
+
+    fn report(
+        &mut self,
+        rule: LintRule,
+        page: &str,
+        location: Option<(&str, &PageDocument, &NodeRef)>,
+        message: String,
+    ) {
+        let Some(active) = self.rule(rule) else {
+            return;
+        };
+        let (span, node) = match location {
+            Some((source, document, node)) => {
+                (source_span(source, document, node), Some(node_label(node)))
+            }
+            None => (None, None),
+        };
+        self.diagnostics.push(Diagnostic {
+            rule: rule.id.to_string(),
+            severity: active.severity,
+            page: Some(page.to_string()),
+            message,
+            span,
+            node,
+            fixable: false,
+        });
+    }
+}
+
+pub fn lint_project(root: impl AsRef<Path>) -> Result<ValidationReport> {
+    let root = root.as_ref();
+    let manifest = load_manifest(root)?;
+    let index = build_project_index(root)?;
+    let graph = build_project_graph(&index);
+    lint_project_in(root, &manifest, &index, &graph)
+}
+
+pub(crate) fn lint_project_in(
+    root: &Path,
+    manifest: &ProjectManifest,
+    index: &ProjectIndex,
+    graph: &ProjectGraph,
+) -> Result<ValidationReport> {
+    let mut lint = Lint {
+        rules: configured_rules(manifest)?,
+        diagnostics: Vec::new(),
+    };
+
+    let linked_notes = graph
+        .edges
+        .iter()
+        .filter(|edge| edge.kind == "links_to_note")
+        .map(|edge| edge.to.as_str())
+        .collect::<BTreeSet<_>>();
+    let mut tag_pages = BTreeMap::<String, (String, Vec<&str>)>::new();
+
+    for page in &index.pages {
+        let summary = page
+            .meta
+            .get(SUMMARY_META)
+            .map_or("", |summary| summary.trim());
+        if summary.is_empty() {
+            lint.report(
+                EMPTY_SUMMARY,
+                &page.path,
+                None,
+                "page has an empty summary".to_string(),
+            );
+        }
+
+        let tags = tags_from_meta(&page.meta);
+        if tags.is_empty() {
+            lint.report(
+                MISSING_TAGS,
+                &page.path,
+                None,
+                "page has no tags".to_string(),
+            );
+        }
+        for tag in tags {
+            tag_pages
+                .entry(tag.to_lowercase())
+                .or_insert_with(|| (tag, Vec::new()))
+                .1
+                .push(&page.path);
+        }
+
+        if lint.rule(LONG_PAGE).is_some()
+            || lint.rule(HEADING_SKIP).is_some()
+            || lint.rule(UNREFERENCED_NOTE).is_some()
+        {
+            lint_page_source(root, page, &linked_notes, &mut lint)?;
+        }
+    }
+
+    let default_page = manifest
+        .default_page
+        .strip_prefix(&format!("{PAGES_DIR}/"))
+        .unwrap_or(&manifest.default_page);
+    for orphan in orphan_pages_in(graph) {
+        if orphan.path != default_page {
+            lint.report(
+                ORPHAN_PAGE,
+                &orphan.path,
+                None,
+                "no other page links to this page".to_string(),
+            );
+        }
+    }
+
+    for (tag, pages) in tag_pages.values() {
+        if let [page] = pages.as_slice() {
+            lint.report(
+                SINGLE_USE_TAG,
+                page,
+                None,
+                format!("tag `{tag}` is not used by any other page"),
+            );
+        }
+    }
+
+    if let Some(active) = lint.rule(NEAR_DUPLICATE_TITLE) {
+        lint_near_duplicate_titles(index, active.max_distance, &mut lint);
+    }
+
+    lint.diagnostics
+        .sort_by(|left, right| left.page.cmp(&right.page));
+    Ok(ValidationReport {
+        pages_checked: index.pages.len(),
+        diagnostics: lint.diagnostics,
+    })
+}
+
+fn configured_rules(manifest: &ProjectManifest) -> Result<BTreeMap<&'static str, ActiveRule>> {
+    for key in manifest.lint.keys() {
+        if !LINT_RULES
+            .iter()
+            .any(|rule| rule.id == key || rule.name == key)
+        {
+            return Err(FractalError::invalid_project(format!(
+                "unknown lint rule in fractal.json: {key}"
+            )));
+        }
+    }
+
+    let mut rules = BTreeMap::new();
+    for rule in LINT_RULES {
+        let config = manifest
+            .lint
+            .get(rule.id)
+            .or_else(|| manifest.lint.get(rule.name))
+            .cloned()
+            .unwrap_or_default();
+        if config.enabled == Some(false) {
+            continue;
+        }
+        rules.insert(
+            rule.id,
+            ActiveRule {
+                severity: config.severity.unwrap_or(rule.severity),
+                max_words: config.max_words.unwrap_or(DEFAULT_MAX_WORDS),
+                max_distance: config.max_distance.unwrap_or(DEFAULT_MAX_DISTANCE),
+            },
+        );
+    }
+    Ok(rules)
+}
+
+fn lint_page_source(
+    root: &Path,
+    page: &PageEntry,
+    linked_notes: &BTreeSet<&str>,
+    lint: &mut Lint,
+) -> Result<()> {
+    let source = fs::read_to_string(root.join(PAGES_DIR).join(&page.path))?;
+    let document = PageDocument::parse(&source);
+
+    if let Some(active) = lint.rule(LONG_PAGE) {
+        let words = document.main_text()?.split_whitespace().count();
+        if words > active.max_words {
+            lint.report(
+                LONG_PAGE,
+                &page.path,
+                None,
+                format!("page has {words} words; the limit is {}", active.max_words),
+            );
+        }
+    }
+
+    let mut previous_level = None;
+    for node in document.main_node()?.descendants() {
+        let Some(level) = heading_level(&node) else {
+            continue;
+        };
+        if let Some(previous) = previous_level {
+            if level > previous + 1 {
+                lint.report(
+                    HEADING_SKIP,
+                    &page.path,
+                    Some((&source, &document, &node)),
+                    format!("heading level skips from h{previous} to h{level}"),
+                );
+            }
+        }
+        previous_level = Some(level);
+    }
+
+    for note in &page.notes {
+        if linked_notes.contains(note_node_id(&page.path, &note.id).as_str()) {
+            continue;
+        }
+        let message = format!("note `{}` is never referenced by a note link", note.id);
+        match document.note_node(&note.id) {
+            Some(node) => lint.report(
+                UNREFERENCED_NOTE,
+                &page.path,
+                Some((&source, &document, &node)),
+                message,
+            ),
+            None => lint.report(UNREFERENCED_NOTE, &page.path, None, message),
+        }
+    }
+    Ok(())
+}
+
+fn heading_level(node: &NodeRef) -> Option<usize> {
+    let element = node.as_element()?;
+    let name = element.name.local.to_string();
+    let level = name.strip_prefix('h')?.parse::<usize>().ok()?;
+    (1..=6).contains(&level).then_some(level)
+}
+
+fn lint_near_duplicate_titles(index: &ProjectIndex, max_distance: usize, lint: &mut Lint) {
+    let titles = index
+        .pages
+        .iter()
+        .map(|page| {
+            let label = normalize_link_label(&page.title).to_lowercase();
+            (page, label.chars().collect::<Vec<_>>())
+        })
+        .collect::<Vec<_>>();
+
+    for (position, (page, title)) in titles.iter().enumerate() {
+        for (other, other_title) in &titles[..position] {
+            if title.len().min(other_title.len()) <= max_distance * 2
+                || differs_only_in_digits(title, other_title)
+            {
+                continue;
+            }
+            let distance = edit_distance(title, other_title);
+            if distance > 0 && distance <= max_distance {
+                lint.report(
+                    NEAR_DUPLICATE_TITLE,
+                    &page.path,
+                    None,
+                    format!(
+                        "title `{}` is nearly the same as `{}` in pages/{}",
+                        page.title, other.title, other.path
+                    ),
+                );
+            }
+        }
+    }
+}
+
+fn differs_only_in_digits(left: &[char], right: &[char]) -> bool {
+    let letters = |title: &[char]| {
+        title
+            .iter()
+            .filter(|character| !character.is_ascii_digit())
+            .copied()
+            .collect::<Vec<_>>()
+    };
+    letters(left) == letters(right)
+}
+
+fn edit_distance(left: &[char], right: &[char]) -> usize {
+    let mut previous = (0..=right.len()).collect::<Vec<_>>();
+    for (row, left_char) in left.iter().enumerate() {
+        let mut current = vec![row + 1];
+        for (column, right_char) in right.iter().enumerate() {
+            let substitution = previous[column] + usize::from(left_char != right_char);
+            current.push(
+                substitution
+                    .min(previous[column + 1] + 1)
+                    .min(current[column] + 1),
+            );
+        }
+        previous = current;
+    }
+    previous[right.len()]
+}
@@ -0,0 +1,358 @@
+use crate::cli::{schema_commands, Cli};
+use crate::rpc::{
+    error_response, RpcError, RpcServer, INVALID_PARAMS, INVALID_REQUEST, METHOD_NOT_FOUND,
+    PARSE_ERROR,
+};
+use crate::{FractalError, Result};
+use clap::{ArgAction, CommandFactory};
+use serde_json::{json, Map, Value};
+use std::any::TypeId;
+use std::io::{BufRead, Write};
+use std::path::Path;
+
+pub const PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+pub const RESOURCE_PREFIX: &str = "fractal:///pages/";
+
+/// What a tool does to existing content, reported to clients as MCP tool annotations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ToolEffect {
+    Read,
+    /// Adds or relocates content without discarding any.
+    Additive,
+    /// Replaces or deletes content; repeating the same call changes nothing further.
+    Overwrite,
+    /// Replaces content, and repeating the same call may change it again.
+    Destructive,
+}
+
+const MCP_TOOLS: &[(&str, &[&str], ToolEffect)] = &[
+    ("search.text", &[], ToolEffect::Read),
+    ("context.page", &[], ToolEffect::Read),
+    ("graph.neighbors", &[], ToolEffect::Read),
+    ("graph.path", &[], ToolEffect::Read),
+    ("page.read", &["view"], ToolEffect::Read),
+    ("note.add", &[], ToolEffect::Additive),
+    ("note.set", &[], ToolEffect::Overwrite),
+    ("page.set", &["body_file"], ToolEffect::Overwrite),
+    ("page.block.list", &[], ToolEffect::Read),
+    ("page.block.insert", &[], ToolEffect::Additive),
+    ("page.block.replace", &[], ToolEffect::Overwrite),
+    ("page.block.move", &[], ToolEffect::Additive),
+    ("page.block.delete", &[], ToolEffect::Overwrite),
+    ("lint", &[], ToolEffect::Read),
+    ("asset.list", &[], ToolEffect::Read),
+    ("asset.add", &[], ToolEffect::Additive),
+    ("asset.move", &[], ToolEffect::Additive),
+    ("asset.delete", &["yes"], ToolEffect::Overwrite),
+    ("history.log", &[], ToolEffect::Read),
+    ("history.show", &[], ToolEffect::Read),
+    ("history.diff", &[], ToolEffect::Read),
+    ("history.undo", &[], ToolEffect::Destructive),
+    ("import.markdown", &[], ToolEffect::Additive),
+    ("import.notion", &[], ToolEffect::Additive),
+    ("import.logseq", &[], ToolEffect::Additive),
+];
+
+pub struct McpServer {
+    rpc: RpcServer,
+    tools: Vec<McpTool>,
+    allow_writes: bool,
+}
+
+struct McpTool {
+    name: String,
+    method: &'static str,
+    write: bool,
+    definition: Value,
+}
+
+impl McpServer {
+    pub fn new(root: impl AsRef<Path>, allow_writes: bool) -> Result<Self> {
+        Ok(Self {
+            rpc: RpcServer::new(root)?,
+            tools: mcp_tools(),
+            allow_writes,
+        })
+    }
+
+    pub fn handle(&mut self, message: &str) -> Vec<Value> {
+        let request = match serde_json::from_str::<Value>(message) {
+            Ok(request) => request,
+            Err(error) => {
+                return vec![error_response(
+                    Value::Null,
+                    RpcError {
+                        code: PARSE_ERROR,
+                        message: error.to_string(),
+                        data: None,
+                    },
+                )];
+            }
+        };
+        let id = request.get("id").cloned();
+        let Some(method) = request.get("method").and_then(Value::as_str) else {
+            return vec![error_response(
+                id.unwrap_or(Value::Null),
+                RpcError {
+                    code: INVALID_REQUEST,
+                    message: "request is missing a method".to_string(),
+                    data: None,
+                },
+            )];
+        };
+        let params = request.get("params").cloned().unwrap_or_else(|| json!({}));
+
+        let result = self.dispatch(method, params);
+        let mut messages = Vec::new();
+        if let Some(id) = id {
+            messages.push(match result {
+                Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+                Err(error) => error_response(id, error),
+            });
+        }
+        if !self.rpc.take_notifications().is_empty() {
+            messages.push(json!({
+                "jsonrpc": "2.0",
+                "method": "notifications/resources/list_changed",
+            }));
+        }
+        messages
+    }
+
+    pub fn serve(&mut self, input: impl BufRead, mut output: impl Write) -> Result<()> {
+        for line in input.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            for message in self.handle(&line) {
+                writeln!(output, "{}", serde_json::to_string(&message)?)?;
+            }
+            output.flush()?;
+        }
+        Ok(())
+    }
+
+    fn dispatch(&mut self, method: &str, params: Value) -> std::result::Result<Value, RpcError> {
+        match method {
+            "initialize" => {
+                let requested = params.get("protocolVersion").and_then(Value::as_str);
+                let version = requested
+                    .filter(|version| PROTOCOL_VERSIONS.contains(version))
+                    .unwrap_or(PROTOCOL_VERSIONS[0]);
+                Ok(json!({
+                    "protocolVersion": version,
+                    "capabilities": {
+                        "tools": { "listChanged": false },
+                        "resources": { "listChanged": true, "subscribe": false },
+                    },
+                    "serverInfo": {
+                        "name": "fractal",
+                        "version": env!("CARGO_PKG_VERSION"),
+                    },
+                }))
+            }
+            "ping" => Ok(json!({})),
+            method if method.starts_with("notifications/") => Ok(Value::Null),
+            "tools/list" => Ok(json!({
+                "tools": self
+                    .tools
+                    .iter()
+                    .filter(|tool| self.allow_writes || !tool.write)
+                    .map(|tool| tool.definition.clone())
+                    .collect::<Vec<_>>(),
+            })),
+            "tools/call" => self.call_tool(params),
+            "resources/list" => {
+                let pages = self.rpc.dispatch("page.list", json!({}))?;
+                let resources = pages["data"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .map(|page| {
+                        let path = page["path"].as_str().unwrap_or_default();
+                        let mut resource = json!({
+                            "uri": format!("{RESOURCE_PREFIX}{path}"),
+                            "name": path,
+                            "title": page["title"],
+                            "mimeType": "text/html",
+                        });
+                        if let Some(summary) = page["summary"].as_str() {
+                            resource["description"] = json!(summary);
+                        }
+                        resource
+                    })
+                    .collect::<Vec<_>>();
+                Ok(json!({ "resources": resources }))
+            }
+            "resources/templates/list" => Ok(json!({
+                "resourceTemplates": [{
+                    "uriTemplate": format!("{RESOURCE_PREFIX}{{path}}"),
+                    "name": "page",
+                    "description": "Fractal page source by path relative to pages/",
+                    "mimeType": "text/html",
+                }],
+            })),
+            "resources/read" => {
+                let uri = params
+                    .get("uri")
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| invalid_params("resources/read requires `uri`"))?;
+                let page = uri.strip_prefix(RESOURCE_PREFIX).ok_or_else(|| {
+                    RpcError::from(FractalError::not_found(format!("unknown resource: {uri}")))
+                })?;
+                let source = self.rpc.dispatch("page.source", json!({ "page": page }))?;
+                Ok(json!({
+                    "contents": [{
+                        "uri": uri,
+                        "mimeType": "text/html",
+                        "text": source["data"]["html"],
+                    }],
+                }))
+            }
+            _ => Err(RpcError {
+                code: METHOD_NOT_FOUND,
+                message: format!("unknown method: {method}"),
+                data: None,
+            }),
+        }
+    }
+
+    fn call_tool(&mut self, params: Value) -> std::result::Result<Value, RpcError> {
+        let name = params
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or_else(|| invalid_params("tools/call requires `name`"))?;
+        let tool = self
+            .tools
+            .iter()
+            .find(|tool| tool.name == name)
+            .ok_or_else(|| invalid_params(format!("unknown tool: {name}")))?;
+        if tool.write && !self.allow_writes {
+            return Err(invalid_params(format!(
+                "{name} changes the project; start the server with --allow-writes"
+            )));
+        }
+
+        let method = tool.method;
+        let arguments = params
+            .get("arguments")
+            .cloned()
+            .unwrap_or_else(|| json!({}));
+        match self.rpc.dispatch(method, arguments) {
+            Ok(result) => Ok(json!({
+                "content": [{ "type": "text", "text": result.to_string() }],
+                "structuredContent": result,
+                "isError": false,
+            })),
+            Err(RpcError {
+                data: Some(error), ..
+            }) => Ok(json!({
+                "content": [{ "type": "text", "text": json!(error).to_string() }],
+                "isError": true,
+            })),
+            Err(error) => Err(error),
+        }
+    }
+}
+
+fn mcp_tools() -> Vec<McpTool> {
+    let cli = Cli::command();
+    let commands = schema_commands();
+    MCP_TOOLS
+        .iter()
+        .filter_map(|(method, skipped, effect)| {
+            let entry = commands.iter().find(|entry| entry.name == *method)?;
+            let command = method
+                .split('.')
+                .try_fold(&cli, |command, name| command.find_subcommand(name))?;
+            let write = entry.kind == "write";
+
+            let mut properties = Map::new();
+            let mut required = Vec::new();
+            for arg in command.get_arguments() {
+                let id = arg.get_id().as_str();
+                if arg.is_global_set() || skipped.contains(&id) {
+                    continue;
+                }
+                let mut property = match arg.get_action() {
+                    ArgAction::SetTrue | ArgAction::SetFalse => json!({ "type": "boolean" }),
+                    ArgAction::Append => {
+                        json!({ "type": "array", "items": { "type": "string" } })
+                    }
+                    _ => {
+                        let type_id = arg.get_value_parser().type_id();
+                        if type_id == TypeId::of::<usize>() || type_id == TypeId::of::<u64>() {
+                            json!({ "type": "integer", "minimum": 0 })
+                        } else {
+                            json!({ "type": "string" })
+                        }
+                    }
+                };
+                if let Some(help) = arg.get_help() {
+                    property["description"] = json!(help.to_string());
+                }
+                if let Some(default) = arg.get_default_values().first() {
+                    let default = default.to_string_lossy();
+                    property["default"] = match default.parse::<u64>() {
+                        Ok(number) => json!(number),
+                        Err(_) => json!(default),
+                    };
+                }
+                if arg.is_required_set() {
+                    required.push(id.to_string());
+                }
+                properties.insert(id.to_string(), property);
+            }
+            if write {
+                properties.insert(
+                    "dry_run".to_string(),
+                    json!({
+                        "type": "boolean",
+                        "description": "Return the planned report and diffs without writing.",
+                    }),
+                );
+            }
+
+            let name = method.replace('.', "_");
+            let mut description = command
+                .get_about()
+                .map(|about| about.to_string())
+                .unwrap_or_default();
+            if let Some(example) = entry.examples.first() {
+                description.push_str(&format!(". CLI equivalent: `{example}`."));
+            }
+            let definition = json!({
+                "name": name,
+                "title": method,
+                "description": description.trim(),
+                "inputSchema": {
+                    "type": "object",
+                    "properties": properties,
+                    "required": required,
+                    "additionalProperties": false,
+                },
+                "annotations": {
+                    "readOnlyHint": !write,
+                    "destructiveHint": matches!(effect, ToolEffect::Overwrite | ToolEffect::Destructive),
+                    "idempotentHint": matches!(effect, ToolEffect::Read | ToolEffect::Overwrite),
+                },
+            });
+
+            Some(McpTool {
+                name,
+                method,
+                write,
+                definition,
+            })
+        })
+        .collect()
+}
+
+fn invalid_params(message: impl Into<String>) -> RpcError {
+    RpcError {
+        code: INVALID_PARAMS,
+        message: message.into(),
+        data: None,
+    }
+}
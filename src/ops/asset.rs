@@ -0,0 +1,242 @@
+use crate::document::PageDocument;
+use crate::graph::links::{relative_href, resolve_asset_src};
+use crate::index::build_project_index;
+use crate::ops::mutation::MutationPlan;
+use crate::ops::page::{push_planned_page_rewrites, PlannedPageRewrite};
+use crate::project::constants::PAGES_DIR;
+use crate::project::paths::{
+    asset_mime_type, asset_relative_path, collect_page_paths, is_html_path, load_manifest,
+    resolve_asset_destination, resolve_existing_asset,
+};
+use crate::types::{AssetEntry, OperationEvent, OperationReport, ProjectIndex};
+use crate::{FractalError, Result};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+pub fn list_assets(root: impl AsRef<Path>) -> Result<Vec<AssetEntry>> {
+    let index = build_project_index(root.as_ref())?;
+    Ok(asset_entries(&index))
+}
+
+pub fn unreferenced_assets(root: impl AsRef<Path>) -> Result<Vec<AssetEntry>> {
+    Ok(list_assets(root)?
+        .into_iter()
+        .filter(|asset| asset.referenced_by.is_empty())
+        .collect())
+}
+
+pub fn add_asset(
+    root: impl AsRef<Path>,
+    source: impl AsRef<Path>,
+    destination: impl AsRef<Path>,
+) -> Result<OperationReport> {
+    plan_add_asset(root, source, destination)?.apply()
+}
+
+pub fn plan_add_asset(
+    root: impl AsRef<Path>,
+    source: impl AsRef<Path>,
+    destination: impl AsRef<Path>,
+) -> Result<MutationPlan> {
+    let root = root.as_ref();
+    let source = source.as_ref();
+    load_manifest(root)?;
+    if !source.is_file() {
+        return Err(FractalError::not_found(format!(
+            "asset source does not exist: {}",
+            source.display()
+        )));
+    }
+
+    let destination = resolve_asset_destination(root, destination.as_ref())?;
+    if destination.exists() {
+        return Err(FractalError::already_exists(format!(
+            "asset already exists: {}",
+            destination.display()
+        )));
+    }
+
+    let bytes = fs::read(source)?;
+    let mut plan = MutationPlan::new(root);
+    if let Some(parent) = destination.parent() {
+        plan.ensure_dir(parent.to_path_buf());
+    }
+    plan.write_always(
+        destination.clone(),
+        bytes,
+        OperationEvent::AssetAdded {
+            source: source.to_path_buf(),
+            path: destination,
+        },
+    );
+
+    plan.rebuild_generated_data();
+    Ok(plan)
+}
+
+pub fn move_asset(
+    root: impl AsRef<Path>,
+    asset: impl AsRef<Path>,
+    destination: impl AsRef<Path>,
+) -> Result<OperationReport> {
+    plan_move_asset(root, asset, destination)?.apply()
+}
+
+pub fn plan_move_asset(
+    root: impl AsRef<Path>,
+    asset: impl AsRef<Path>,
+    destination: impl AsRef<Path>,
+) -> Result<MutationPlan> {
+    let root = root.as_ref();
+    load_manifest(root)?;
+    let source = resolve_existing_asset(root, asset.as_ref())?;
+    let source_asset = asset_relative_path(root, &source)?;
+    let destination = resolve_asset_destination(root, destination.as_ref())?;
+    let destination_asset = asset_relative_path(root, &destination)?;
+
+    if source == destination {
+        return Err(FractalError::invalid_input(
+            "asset move requires a new asset path",
+        ));
+    }
+    if destination.exists() {
+        return Err(FractalError::already_exists(format!(
+            "asset already exists: {}",
+            destination.display()
+        )));
+    }
+
+    let reference_rewrites =
+        plan_moved_asset_reference_rewrites(root, &source_asset, &destination_asset)?;
+
+    let mut plan = MutationPlan::new(root);
+    if let Some(parent) = destination.parent() {
+        plan.ensure_dir(parent.to_path_buf());
+    }
+    plan.move_file(
+        source.clone(),
+        destination.clone(),
+        OperationEvent::AssetMoved {
+            from: source,
+            to: destination,
+        },
+    );
+
+    push_planned_page_rewrites(&mut plan, reference_rewrites);
+
+    plan.rebuild_generated_data();
+    Ok(plan)
+}
+
+pub fn delete_asset(
+    root: impl AsRef<Path>,
+    asset: impl AsRef<Path>,
+    force: bool,
+) -> Result<OperationReport> {
+    plan_delete_asset(root, asset, force)?.apply()
+}
+
+pub fn plan_delete_asset(
+    root: impl AsRef<Path>,
+    asset: impl AsRef<Path>,
+    force: bool,
+) -> Result<MutationPlan> {
+    let root = root.as_ref();
+    load_manifest(root)?;
+    let path = resolve_existing_asset(root, asset.as_ref())?;
+    let asset_path = asset_relative_path(root, &path)?;
+    let index = build_project_index(root)?;
+    let referenced_by = asset_entries(&index)
+        .into_iter()
+        .find(|entry| entry.path == asset_path)
+        .map(|entry| entry.referenced_by)
+        .unwrap_or_default();
+
+    if !referenced_by.is_empty() && !force {
+        return Err(FractalError::invalid_input(format!(
+            "asset {asset_path} is referenced by {}; use --force to delete it anyway",
+            referenced_by.join(", ")
+        )));
+    }
+
+    let mut plan = MutationPlan::new(root);
+    for page in &referenced_by {
+        plan.event(OperationEvent::Warning {
+            message: format!("{page} still references deleted asset {asset_path}"),
+        });
+    }
+    plan.remove_file(path.clone(), OperationEvent::AssetDeleted { path });
+
+    plan.rebuild_generated_data();
+    Ok(plan)
+}
+
+pub(crate) fn asset_entries(index: &ProjectIndex) -> Vec<AssetEntry> {
+    let mut references = BTreeMap::<String, BTreeSet<String>>::new();
+    for page in &index.pages {
+        for target in page
+            .images
+            .iter()
+            .filter_map(|image| resolve_asset_src(&page.path, &image.src))
+        {
+            references
+                .entry(target)
+                .or_default()
+                .insert(page.path.clone());
+        }
+    }
+
+    index
+        .files
+        .iter()
+        .filter(|file| file.kind != "page")
+        .map(|file| AssetEntry {
+            path: file.path.clone(),
+            mime_type: file
+                .mime_type
+                .clone()
+                .unwrap_or_else(|| asset_mime_type(&file.path).to_string()),
+            content_hash: file.content_hash.clone().unwrap_or_default(),
+            referenced_by: references
+                .remove(&file.path)
+                .unwrap_or_default()
+                .into_iter()
+                .collect(),
+        })
+        .collect()
+}
+
+fn plan_moved_asset_reference_rewrites(
+    root: &Path,
+    source_asset: &str,
+    destination_asset: &str,
+) -> Result<Vec<PlannedPageRewrite>> {
+    let pages_dir = root.join(PAGES_DIR);
+    let mut page_paths = Vec::new();
+    collect_page_paths(&pages_dir, &pages_dir, &mut page_paths)?;
+    page_paths.sort();
+
+    let mut planned = Vec::new();
+    for page_path in page_paths.into_iter().filter(|path| is_html_path(path)) {
+        let page = pages_dir.join(&page_path);
+        let html = fs::read_to_string(&page)?;
+        let document = PageDocument::parse(&html);
+        let updated = document.rewrite_image_srcs(
+            &page_path,
+            source_asset,
+            &relative_href(&page_path, destination_asset),
+        );
+        if updated == 0 {
+            continue;
+        }
+
+        planned.push(PlannedPageRewrite {
+            page,
+            html: document.to_html()?,
+            count: updated,
+        });
+    }
+
+    Ok(planned)
+}
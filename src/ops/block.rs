@@ -0,0 +1,289 @@
+use crate::document::page::parse_main_fragment_children;
+use crate::document::sanitize::sanitize_editor_html;
+use crate::document::{detach_block, insert_block_after, insert_block_before, PageDocument};
+use crate::ops::mutation::{read_revisioned, MutationPlan};
+use crate::project::paths::{page_relative_path, resolve_existing_page};
+use crate::types::{BlockPosition, OperationEvent, OperationReport, PageBlock};
+use crate::validation::validate_page_html_for_project;
+use crate::{FractalError, Result};
+use brik::NodeRef;
+use std::path::{Path, PathBuf};
+
+pub fn page_blocks(root: impl AsRef<Path>, page: impl AsRef<Path>) -> Result<Vec<PageBlock>> {
+    let page = resolve_existing_page(root.as_ref(), page.as_ref())?;
+    let document = PageDocument::from_path(&page)?;
+    document
+        .blocks()?
+        .into_iter()
+        .map(|(id, node)| {
+            let mut html = Vec::new();
+            node.serialize(&mut html)?;
+            Ok(PageBlock {
+                id,
+                kind: node
+                    .as_element()
+                    .map(|element| element.name.local.to_string())
+                    .unwrap_or_else(|| "text".to_string()),
+                html: String::from_utf8(html)?,
+                text: node
+                    .text_contents()
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            })
+        })
+        .collect()
+}
+
+pub fn insert_block(
+    root: impl AsRef<Path>,
+    page: impl AsRef<Path>,
+    position: BlockPosition,
+    html: &str,
+    expected_revision: Option<&str>,
+) -> Result<OperationReport> {
+    plan_insert_block(root, page, position, html, expected_revision)?.apply()
+}
+
+pub fn plan_insert_block(
+    root: impl AsRef<Path>,
+    page: impl AsRef<Path>,
+    position: BlockPosition,
+    html: &str,
+    expected_revision: Option<&str>,
+) -> Result<MutationPlan> {
+    plan_block_edit(
+        root.as_ref(),
+        page.as_ref(),
+        expected_revision,
+        |document, page| {
+            let (block, mut events) = parse_block(html, page)?;
+            let anchor = resolve_position(document, &position, None)?;
+            place_block(document, anchor, block.clone())?;
+            document.ensure_heading_anchors();
+            events.insert(
+                0,
+                OperationEvent::BlockInserted {
+                    page: page.to_path_buf(),
+                    block_id: placed_block_id(document, &block)?,
+                },
+            );
+            Ok(events)
+        },
+    )
+}
+
+pub fn replace_block(
+    root: impl AsRef<Path>,
+    page: impl AsRef<Path>,
+    block_id: &str,
+    html: &str,
+    expected_revision: Option<&str>,
+) -> Result<OperationReport> {
+    plan_replace_block(root, page, block_id, html, expected_revision)?.apply()
+}
+
+pub fn plan_replace_block(
+    root: impl AsRef<Path>,
+    page: impl AsRef<Path>,
+    block_id: &str,
+    html: &str,
+    expected_revision: Option<&str>,
+) -> Result<MutationPlan> {
+    plan_block_edit(
+        root.as_ref(),
+        page.as_ref(),
+        expected_revision,
+        |document, page| {
+            let target = document.block(block_id)?;
+            let (block, mut events) = parse_block(html, page)?;
+            target.insert_before(block.clone());
+            target.detach();
+            document.ensure_heading_anchors();
+            events.insert(
+                0,
+                OperationEvent::BlockReplaced {
+                    page: page.to_path_buf(),
+                    block_id: block_id.to_string(),
+                    replacement_id: placed_block_id(document, &block)?,
+                },
+            );
+            Ok(events)
+        },
+    )
+}
+
+pub fn move_block(
+    root: impl AsRef<Path>,
+    page: impl AsRef<Path>,
+    block_id: &str,
+    position: BlockPosition,
+    expected_revision: Option<&str>,
+) -> Result<OperationReport> {
+    plan_move_block(root, page, block_id, position, expected_revision)?.apply()
+}
+
+pub fn plan_move_block(
+    root: impl AsRef<Path>,
+    page: impl AsRef<Path>,
+    block_id: &str,
+    position: BlockPosition,
+    expected_revision: Option<&str>,
+) -> Result<MutationPlan> {
+    plan_block_edit(
+        root.as_ref(),
+        page.as_ref(),
+        expected_revision,
+        |document, page| {
+            let block = document.block(block_id)?;
+            let anchor = resolve_position(document, &position, Some(&block))?;
+            detach_block(&block);
+            place_block(document, anchor, block.clone())?;
+            document.ensure_heading_anchors();
+            Ok(vec![OperationEvent::BlockMoved {
+                page: page.to_path_buf(),
+                block_id: placed_block_id(document, &block)?,
+            }])
+        },
+    )
+}
+
+pub fn delete_block(
+    root: impl AsRef<Path>,
+    page: impl AsRef<Path>,
+    block_id: &str,
+    expected_revision: Option<&str>,
+) -> Result<OperationReport> {
+    plan_delete_block(root, page, block_id, expected_revision)?.apply()
+}
+
+pub fn plan_delete_block(
+    root: impl AsRef<Path>,
+    page: impl AsRef<Path>,
+    block_id: &str,
+    expected_revision: Option<&str>,
+) -> Result<MutationPlan> {
+    plan_block_edit(
+        root.as_ref(),
+        page.as_ref(),
+        expected_revision,
+        |document, page| {
+            detach_block(&document.block(block_id)?);
+            Ok(vec![OperationEvent::BlockDeleted {
+                page: page.to_path_buf(),
+                block_id: block_id.to_string(),
+            }])
+        },
+    )
+}
+
+enum Anchor {
+    Before(NodeRef),
+    After(NodeRef),
+    End,
+}
+
+fn plan_block_edit(
+    root: &Path,
+    page: &Path,
+    expected_revision: Option<&str>,
+    edit: impl FnOnce(&PageDocument, &Path) -> Result<Vec<OperationEvent>>,
+) -> Result<MutationPlan> {
+    let page = resolve_existing_page(root, page)?;
+    let path = page_relative_path(root, &page)?
+        .to_string_lossy()
+        .replace('\\', "/");
+    let (source, revision) = read_revisioned(&page)?;
+    let document = PageDocument::parse(&source);
+
+    let events = edit(&document, &page)?;
+    document.ensure_heading_anchors();
+    if document.uses_format_extensions() {
+        document.upgrade_format_version()?;
+    }
+    let html = document.to_html()?;
+    validate_page_html_for_project(root, &path, &html)?;
+
+    let mut plan = MutationPlan::new(root);
+    plan.require_revision(page.clone(), revision)?;
+    plan.require_optional_revision(page.clone(), expected_revision)?;
+    plan.write_silent(page, html.into_bytes());
+    for event in events {
+        plan.event(event);
+    }
+    plan.rebuild_generated_data();
+    Ok(plan)
+}
+
+fn parse_block(html: &str, page: &Path) -> Result<(NodeRef, Vec<OperationEvent>)> {
+    let sanitized = sanitize_editor_html(html)?;
+    let nodes = parse_main_fragment_children(&sanitized.html)?
+        .into_iter()
+        .filter(|node| {
+            node.as_text()
+                .is_none_or(|text| !text.borrow().trim().is_empty())
+        })
+        .collect::<Vec<_>>();
+    let [block] = nodes.as_slice() else {
+        return Err(FractalError::invalid_input(format!(
+            "block HTML must contain exactly one top-level element, found {}",
+            nodes.len()
+        )));
+    };
+
+    let events = sanitized
+        .changes
+        .into_iter()
+        .map(|(change, count)| OperationEvent::PageHtmlSanitized {
+            page: PathBuf::from(page),
+            change,
+            count,
+        })
+        .collect();
+    Ok((block.clone(), events))
+}
+
+fn resolve_position(
+    document: &PageDocument,
+    position: &BlockPosition,
+    moving: Option<&NodeRef>,
+) -> Result<Anchor> {
+    let anchor = match position {
+        BlockPosition::Start => {
+            return Ok(document
+                .blocks()?
+                .into_iter()
+                .map(|(_, node)| node)
+                .find(|node| Some(node) != moving)
+                .map_or(Anchor::End, Anchor::Before));
+        }
+        BlockPosition::End => return Ok(Anchor::End),
+        BlockPosition::Before(block_id) | BlockPosition::After(block_id) => {
+            document.block(block_id)?
+        }
+    };
+    if Some(&anchor) == moving {
+        return Err(FractalError::invalid_input(
+            "cannot move a block relative to itself",
+        ));
+    }
+    Ok(match position {
+        BlockPosition::Before(_) => Anchor::Before(anchor),
+        _ => Anchor::After(anchor),
+    })
+}
+
+fn place_block(document: &PageDocument, anchor: Anchor, block: NodeRef) -> Result<()> {
+    match anchor {
+        Anchor::Before(anchor) => insert_block_before(&anchor, block),
+        Anchor::After(anchor) => insert_block_after(&anchor, block),
+        Anchor::End => document.append_block(block)?,
+    }
+    Ok(())
+}
+
+fn placed_block_id(document: &PageDocument, block: &NodeRef) -> Result<String> {
+    document
+        .block_id(block)?
+        .ok_or_else(|| FractalError::invalid_input("block is not part of the page body"))
+}
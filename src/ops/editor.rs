@@ -1,16 +1,20 @@
 use crate::document::metadata::{
     normalize_tags, summary_from_meta, tags_from_meta, SUMMARY_META, TAGS_META,
 };
+use crate::document::sanitize::sanitize_editor_html;
 use crate::document::PageDocument;
 use crate::graph::build_project_graph;
-use crate::graph::links::{normalize_link_label, page_link_text_matches, resolve_page_href};
-use crate::index::{build_index, build_project_index, ensure_page_labels_available_for};
-use crate::ops::mutation::MutationPlan;
-use crate::ops::page::rename_page;
+use crate::graph::links::{
+    normalize_link_label, page_link_text_matches, resolve_page_href, resolve_section_href,
+};
+use crate::index::{build_project_index, ensure_page_labels_available_for};
+use crate::ops::mutation::{read_revisioned, MutationPlan};
+use crate::ops::page::plan_rename_page;
 use crate::project::paths::{page_relative_path, resolve_existing_page};
 use crate::types::{
     EditorLinkDetail, EditorNoteDetail, EditorPageDetail, EditorPageListEntry, EditorPageUpdate,
-    LinkEntry, OperationEvent, OperationReport, PageMetadata, PageRename, PageSource,
+    LinkEntry, OperationEvent, OperationReport, PageMetadata, PageRename, PageSource, ProjectGraph,
+    ProjectIndex,
 };
 use crate::validation::{known_page_titles_for_candidate, validate_page_html_for_project};
 use crate::{FractalError, Result};
@@ -21,26 +25,32 @@ use std::path::Path;
 pub fn list_editor_pages(root: impl AsRef<Path>) -> Result<Vec<EditorPageListEntry>> {
     let root = root.as_ref();
     let index = build_project_index(root)?;
-    let graph = build_project_graph(&index);
+    editor_page_entries(&index, &build_project_graph(&index))
+}
+
+pub(crate) fn editor_page_entries(
+    index: &ProjectIndex,
+    graph: &ProjectGraph,
+) -> Result<Vec<EditorPageListEntry>> {
     let graph_pages = graph
         .pages
-        .into_iter()
-        .map(|page| (page.path.clone(), page))
+        .iter()
+        .map(|page| (page.path.as_str(), page))
         .collect::<BTreeMap<_, _>>();
 
     index
         .pages
-        .into_iter()
+        .iter()
         .map(|page| {
-            let graph_page = graph_pages.get(&page.path).ok_or_else(|| {
+            let graph_page = graph_pages.get(page.path.as_str()).ok_or_else(|| {
                 FractalError::invalid_project(format!(
                     "missing graph entry for page: {}",
                     page.path
                 ))
             })?;
             Ok(EditorPageListEntry {
-                path: page.path,
-                title: page.title,
+                path: page.path.clone(),
+                title: page.title.clone(),
                 summary: summary_from_meta(&page.meta),
                 tags: tags_from_meta(&page.meta),
                 backlink_count: graph_page.backlinks.len(),
@@ -58,7 +68,7 @@ pub fn editor_page_detail(
     let page = resolve_existing_page(root, page.as_ref())?;
     let relative_page = page_relative_path(root, &page)?;
     let path = relative_page.to_string_lossy().replace('\\', "/");
-    let html = fs::read_to_string(&page)?;
+    let (html, revision) = read_revisioned(&page)?;
     let document = PageDocument::parse(&html);
 
     let index = build_project_index(root)?;
@@ -67,6 +77,15 @@ pub fn editor_page_detail(
         .iter()
         .map(|page| (page.path.clone(), page.title.clone()))
         .collect::<BTreeMap<_, _>>();
+    let project_notes = index
+        .pages
+        .iter()
+        .flat_map(|page| {
+            page.notes
+                .iter()
+                .map(|note| (page.path.clone(), note.id.clone()))
+        })
+        .collect();
     let graph = build_project_graph(&index);
     let page_entry = index
         .pages
@@ -88,17 +107,17 @@ pub fn editor_page_detail(
     };
 
     let notes = editor_note_details(&document);
-    let note_ids = notes.iter().map(|note| note.id.clone()).collect();
 
     Ok(EditorPageDetail {
         source: PageSource {
             path: path.clone(),
             html,
+            revision,
         },
         body_html: document.main_body_html()?,
         metadata,
         notes,
-        links: editor_link_details(&path, page_entry.links, &page_titles, &note_ids),
+        links: editor_link_details(&path, page_entry.links, &page_titles, &project_notes),
         backlinks: graph_entry.backlinks,
         outlinks: graph_entry.outlinks,
     })
@@ -109,11 +128,24 @@ pub fn update_editor_page(
     page: impl AsRef<Path>,
     update: EditorPageUpdate,
 ) -> Result<OperationReport> {
+    plan_update_editor_page(root, page, update)?.apply()
+}
+
+pub fn plan_update_editor_page(
+    root: impl AsRef<Path>,
+    page: impl AsRef<Path>,
+    update: EditorPageUpdate,
+) -> Result<MutationPlan> {
     let root = root.as_ref();
     let page = resolve_existing_page(root, page.as_ref())?;
     let relative_page = page_relative_path(root, &page)?;
     let path = relative_page.to_string_lossy().replace('\\', "/");
 
+    let mut plan = MutationPlan::new(root);
+    if let Some(expected_revision) = update.expected_revision {
+        plan.require_revision(&page, expected_revision)?;
+    }
+
     if let Some(title) = update.title.as_deref() {
         let title = normalize_editor_title(title)?;
         ensure_page_labels_available_for(root, Some(&path), &path, &title)?;
@@ -135,8 +167,18 @@ pub fn update_editor_page(
 
     let body_html_was_supplied = update.body_html.is_some();
     if let Some(body_html) = update.body_html {
-        if document.set_main_body_html(&body_html)? {
+        let sanitized = sanitize_editor_html(&body_html)?;
+        if document.set_main_body_html(&sanitized.html)? {
+            document.ensure_heading_anchors();
+            document.upgrade_format_version()?;
             events.push(OperationEvent::PageContentUpdated { page: page.clone() });
+            for (change, count) in sanitized.changes {
+                events.push(OperationEvent::PageHtmlSanitized {
+                    page: page.clone(),
+                    change,
+                    count,
+                });
+            }
         }
     }
 
@@ -173,7 +215,6 @@ pub fn update_editor_page(
         }
     }
 
-    let mut plan = MutationPlan::new();
     if !events.is_empty() {
         let html = document.to_html()?;
         validate_page_html_for_project(root, &path, &html)?;
@@ -183,22 +224,32 @@ pub fn update_editor_page(
         }
     }
 
-    let mut report = plan.apply(root)?;
-    report.extend(build_index(root)?);
-    Ok(report.relative_to(root))
+    plan.rebuild_generated_data();
+    Ok(plan)
 }
 
 pub fn set_page_title(
     root: impl AsRef<Path>,
     page: impl AsRef<Path>,
     title: &str,
+    expected_revision: Option<&str>,
 ) -> Result<OperationReport> {
-    rename_page(
+    plan_set_page_title(root, page, title, expected_revision)?.apply()
+}
+
+pub fn plan_set_page_title(
+    root: impl AsRef<Path>,
+    page: impl AsRef<Path>,
+    title: &str,
+    expected_revision: Option<&str>,
+) -> Result<MutationPlan> {
+    plan_rename_page(
         root,
         page,
         PageRename {
             path: None,
             title: Some(title.to_string()),
+            expected_revision: expected_revision.map(str::to_string),
         },
     )
 }
@@ -207,12 +258,23 @@ pub fn update_page_body(
     root: impl AsRef<Path>,
     page: impl AsRef<Path>,
     body_html: &str,
+    expected_revision: Option<&str>,
 ) -> Result<OperationReport> {
-    update_editor_page(
+    plan_update_page_body(root, page, body_html, expected_revision)?.apply()
+}
+
+pub fn plan_update_page_body(
+    root: impl AsRef<Path>,
+    page: impl AsRef<Path>,
+    body_html: &str,
+    expected_revision: Option<&str>,
+) -> Result<MutationPlan> {
+    plan_update_editor_page(
         root,
         page,
         EditorPageUpdate {
             body_html: Some(body_html.to_string()),
+            expected_revision: expected_revision.map(str::to_string),
             ..EditorPageUpdate::default()
         },
     )
@@ -241,31 +303,39 @@ fn editor_link_details(
     page_path: &str,
     links: Vec<LinkEntry>,
     page_titles: &BTreeMap<String, String>,
-    note_ids: &BTreeSet<String>,
+    project_notes: &BTreeSet<(String, String)>,
 ) -> Vec<EditorLinkDetail> {
     links
         .into_iter()
         .map(|link| {
-            let target_note = link
-                .href
-                .strip_prefix('#')
-                .filter(|note_id| note_ids.contains(*note_id))
-                .map(str::to_string);
-            let target_page = (!link.href.starts_with('#'))
-                .then(|| resolve_page_href(page_path, &link.href))
+            let note_target = (link.href.starts_with('#') || link.scope == "note")
+                .then(|| resolve_section_href(page_path, &link.href))
                 .flatten()
-                .and_then(|target| {
-                    page_titles.get(&target).and_then(|title| {
-                        page_link_text_matches(&target, title, &link.text).then_some(target)
-                    })
-                });
+                .filter(|target| project_notes.contains(target));
+            let target_section = (link.scope == "section")
+                .then(|| resolve_section_href(page_path, &link.href))
+                .flatten()
+                .filter(|(target, _)| page_titles.contains_key(target));
+            let target_page = match (&target_section, &note_target) {
+                (Some((target, _)), _) => Some(target.clone()),
+                (None, Some((target, _))) if target != page_path => Some(target.clone()),
+                _ => (!link.href.starts_with('#'))
+                    .then(|| resolve_page_href(page_path, &link.href))
+                    .flatten()
+                    .and_then(|target| {
+                        page_titles.get(&target).and_then(|title| {
+                            page_link_text_matches(&target, title, &link.text).then_some(target)
+                        })
+                    }),
+            };
 
             EditorLinkDetail {
                 href: link.href,
                 text: link.text,
                 scope: link.scope,
                 target_page,
-                target_note,
+                target_note: note_target.map(|(_, note_id)| note_id),
+                target_section: target_section.map(|(_, anchor)| anchor),
             }
         })
         .collect()
@@ -0,0 +1,125 @@
+use crate::document::html::escape_html;
+use crate::document::metadata::{SUMMARY_META, TAGS_META};
+use crate::document::notes::{insert_note_into_document, render_note_aside};
+use crate::document::render::{render_page_document, stylesheet_href};
+use crate::document::PageDocument;
+use crate::io::parallel::map_pages;
+use crate::ops::mutation::MutationPlan;
+use crate::ops::page::plan_init_project_at;
+use crate::project::constants::PAGES_DIR;
+use crate::types::{OperationEvent, OperationReport, Theme};
+use crate::{FractalError, Result};
+use std::path::Path;
+
+const FIXTURE_WORDS: &[&str] = &[
+    "graph", "note", "page", "link", "index", "search", "title", "summary", "tag", "section",
+    "parser", "render", "journal", "history", "context", "budget", "schema", "project", "source",
+    "editor", "anchor", "block", "signal", "token", "cache", "label", "scope", "asset", "theme",
+    "layout", "query", "report",
+];
+const FIXTURE_GROUPS: usize = 10;
+
+pub fn generate_fixture_project(root: impl AsRef<Path>, pages: usize) -> Result<OperationReport> {
+    plan_generate_fixture_project(root, pages)?.apply()
+}
+
+pub fn plan_generate_fixture_project(root: impl AsRef<Path>, pages: usize) -> Result<MutationPlan> {
+    let root = root.as_ref();
+    if pages == 0 {
+        return Err(FractalError::invalid_input(
+            "fixture project needs at least one page",
+        ));
+    }
+
+    let name = root
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("fractal-fixture");
+    let mut plan = plan_init_project_at(root, name)?;
+    let width = pages.to_string().len();
+    let numbers = (0..pages).collect::<Vec<_>>();
+    let rendered = map_pages(&numbers, |number| fixture_page(*number, pages, width))
+        .into_iter()
+        .collect::<Result<Vec<_>>>()?;
+
+    for (number, html) in rendered.into_iter().enumerate() {
+        let path = root
+            .join(PAGES_DIR)
+            .join(format!("{}.html", fixture_stem(number, width)));
+        plan.write_always(
+            path.clone(),
+            html.into_bytes(),
+            OperationEvent::PageCreated { path },
+        );
+    }
+    plan.rebuild_generated_data();
+    Ok(plan)
+}
+
+fn fixture_page(number: usize, pages: usize, width: usize) -> Result<String> {
+    let mut seed = number as u64;
+    let mut word = || {
+        seed = seed
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        FIXTURE_WORDS[(seed >> 33) as usize % FIXTURE_WORDS.len()]
+    };
+
+    let mut body = String::new();
+    for section in 0..3 {
+        body.push_str(&format!("<h2>Section {}</h2>\n<p>", section + 1));
+        let words = (0..40).map(|_| word()).collect::<Vec<_>>();
+        body.push_str(&escape_html(&words.join(" ")));
+        body.push_str("</p>\n");
+    }
+
+    let mut targets = vec![(number + 1) % pages, (number * 7 + 3) % pages];
+    targets.retain(|target| *target != number);
+    targets.dedup();
+    if !targets.is_empty() {
+        let links = targets
+            .iter()
+            .map(|target| {
+                format!(
+                    "<a href=\"{}.html\" data-fractal-link=\"page\">{}</a>",
+                    fixture_stem(*target, width),
+                    fixture_title(*target, width)
+                )
+            })
+            .collect::<Vec<_>>();
+        body.push_str(&format!("<p>See {}.</p>\n", links.join(" and ")));
+    }
+    body.push_str(&format!(
+        "<p>Read the <a href=\"#note-detail-{number}\" data-fractal-link=\"note\">detail</a>.</p>"
+    ));
+
+    let stem = fixture_stem(number, width);
+    let html = render_page_document(
+        &fixture_title(number, width),
+        &body,
+        Theme::default(),
+        stylesheet_href(Path::new(&format!("{stem}.html"))),
+    );
+    let html = insert_note_into_document(
+        &html,
+        &render_note_aside(
+            &format!("note-detail-{number}"),
+            &format!("Synthetic note for page {number}."),
+        ),
+    )?;
+    let document = PageDocument::parse(&html);
+    document.set_meta_tag(SUMMARY_META, &format!("Synthetic fixture page {number}."))?;
+    document.set_meta_tag(
+        TAGS_META,
+        &format!("fixture, group {}", number % FIXTURE_GROUPS),
+    )?;
+    document.to_html()
+}
+
+fn fixture_title(number: usize, width: usize) -> String {
+    format!("Topic {number:0width$}")
+}
+
+fn fixture_stem(number: usize, width: usize) -> String {
+    format!("topic-{number:0width$}")
+}
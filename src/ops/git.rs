@@ -0,0 +1,395 @@
+use crate::document::merge::{main_blocks, note_asides};
+use crate::document::PageDocument;
+use crate::ops::mutation::MutationPlan;
+use crate::project::constants::{MANIFEST_FILE, PAGES_DIR};
+use crate::project::paths::{load_manifest, page_relative_path, resolve_existing_page};
+use crate::types::{
+    BlameEntry, BlameRegion, GitCommit, OperationEvent, OperationReport, OperationSummary,
+    PageBlame,
+};
+use crate::{FractalError, FractalErrorCode, Result};
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+const FIELD_SEPARATOR: char = '\u{1f}';
+const RECORD_SEPARATOR: char = '\u{1e}';
+
+pub fn git_auto_commit_enabled(root: impl AsRef<Path>) -> bool {
+    load_manifest(root.as_ref()).is_ok_and(|manifest| manifest.git_auto_commit)
+}
+
+pub fn set_git_auto_commit(root: impl AsRef<Path>, enabled: bool) -> Result<OperationReport> {
+    plan_set_git_auto_commit(root, enabled)?.apply()
+}
+
+pub fn plan_set_git_auto_commit(root: impl AsRef<Path>, enabled: bool) -> Result<MutationPlan> {
+    let root = root.as_ref();
+    let mut manifest = load_manifest(root)?;
+    if enabled {
+        require_work_tree(root)?;
+    }
+
+    let mut plan = MutationPlan::new(root);
+    if manifest.git_auto_commit == enabled {
+        return Ok(plan);
+    }
+
+    manifest.git_auto_commit = enabled;
+    let manifest_path = root.join(MANIFEST_FILE);
+    plan.write_always(
+        manifest_path.clone(),
+        serde_json::to_string_pretty(&manifest)?.into_bytes(),
+        OperationEvent::ManifestUpdated {
+            path: manifest_path,
+        },
+    );
+    Ok(plan)
+}
+
+pub fn page_git_history(root: impl AsRef<Path>, page: impl AsRef<Path>) -> Result<Vec<GitCommit>> {
+    let root = root.as_ref();
+    let page = project_page_path(root, page.as_ref())?;
+    require_work_tree(root)?;
+    Ok(page_revisions(root, &page)?
+        .into_iter()
+        .map(|(commit, _)| commit)
+        .collect())
+}
+
+pub fn page_blame(root: impl AsRef<Path>, page: impl AsRef<Path>) -> Result<PageBlame> {
+    let root = root.as_ref();
+    let page = project_page_path(root, page.as_ref())?;
+    require_work_tree(root)?;
+
+    let current = PageDocument::parse(&fs::read_to_string(root.join(&page))?);
+    let mut entries = Vec::new();
+    for (index, html) in main_blocks(&current)?.into_iter().enumerate() {
+        entries.push((
+            BlameEntry {
+                region: BlameRegion::Block,
+                key: format!("main[{index}]"),
+                commit: None,
+            },
+            html,
+        ));
+    }
+    for (id, html) in note_asides(&current)? {
+        entries.push((
+            BlameEntry {
+                region: BlameRegion::Note,
+                key: id,
+                commit: None,
+            },
+            html,
+        ));
+    }
+
+    let mut open = vec![true; entries.len()];
+    for (commit, path) in page_revisions(root, &page)? {
+        if !open.contains(&true) {
+            break;
+        }
+        let contents = git(
+            root,
+            [
+                OsString::from("show"),
+                format!("{}:{path}", commit.id).into(),
+            ],
+        )
+        .unwrap_or_default();
+        let document = PageDocument::parse(&contents);
+        let blocks = main_blocks(&document).unwrap_or_default();
+        let notes = note_asides(&document).unwrap_or_default();
+
+        for ((entry, html), open) in entries.iter_mut().zip(open.iter_mut()) {
+            if !*open {
+                continue;
+            }
+            let present = match entry.region {
+                BlameRegion::Block => blocks.contains(html),
+                BlameRegion::Note => notes
+                    .iter()
+                    .any(|(id, note)| *id == entry.key && note == html),
+            };
+            if present {
+                entry.commit = Some(commit.clone());
+            } else {
+                *open = false;
+            }
+        }
+    }
+
+    Ok(PageBlame {
+        page: page.to_string_lossy().into_owned(),
+        entries: entries.into_iter().map(|(entry, _)| entry).collect(),
+    })
+}
+
+pub(crate) fn commit_operation(root: &Path, report: &OperationReport) -> Option<OperationEvent> {
+    let summary = report.summary();
+    if summary.noop {
+        return None;
+    }
+
+    match commit_summary_paths(root, &summary) {
+        Ok(Some((commit, message))) => Some(OperationEvent::GitCommitted { commit, message }),
+        Ok(None) => None,
+        Err(error) => Some(OperationEvent::GitCommitFailed {
+            message: error.message,
+        }),
+    }
+}
+
+fn commit_summary_paths(
+    root: &Path,
+    summary: &OperationSummary,
+) -> Result<Option<(String, String)>> {
+    if !inside_work_tree(root) {
+        return Ok(None);
+    }
+
+    let mut candidates = Vec::new();
+    for path in summary
+        .changed_paths
+        .iter()
+        .chain(&summary.deleted_paths)
+        .chain(summary.moved_paths.iter().map(|entry| &entry.from))
+    {
+        if path.is_absolute()
+            || summary.external_output_paths.contains(path)
+            || candidates.contains(path)
+        {
+            continue;
+        }
+        candidates.push(path.clone());
+    }
+    if candidates.is_empty() {
+        return Ok(None);
+    }
+
+    let tracked = split_nul(&git(root, path_args(["ls-files", "-z"], &candidates))?);
+    let untracked = candidates
+        .iter()
+        .filter(|path| {
+            root.join(path).exists() && !tracked.iter().any(|file| file.starts_with(path))
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    let ignored = if untracked.is_empty() {
+        Vec::new()
+    } else {
+        let output = git_output(root, path_args(["check-ignore", "-z"], &untracked))?;
+        split_nul(&String::from_utf8(output.stdout)?)
+    };
+    let paths = candidates
+        .into_iter()
+        .filter(|path| {
+            if untracked.contains(path) {
+                !ignored.contains(path)
+            } else {
+                tracked.iter().any(|file| file.starts_with(path))
+            }
+        })
+        .collect::<Vec<_>>();
+    if paths.is_empty() {
+        return Ok(None);
+    }
+
+    git(root, path_args(["add", "-A"], &paths))?;
+    if git_output(root, path_args(["diff", "--cached", "--quiet"], &paths))?
+        .status
+        .success()
+    {
+        return Ok(None);
+    }
+
+    let (subject, body) = commit_message(summary);
+    let mut args = vec![OsString::from("commit"), "-q".into(), "-m".into()];
+    args.push(subject.clone().into());
+    if !body.is_empty() {
+        args.extend(["-m".into(), body.into()]);
+    }
+    args.push("--".into());
+    args.extend(paths.iter().map(OsString::from));
+    git(root, args)?;
+
+    let commit = git(root, ["rev-parse", "HEAD"])?.trim().to_string();
+    Ok(Some((commit, subject)))
+}
+
+fn commit_message(summary: &OperationSummary) -> (String, String) {
+    let mut actions = Vec::new();
+    for path in &summary.created_paths {
+        if !summary.external_output_paths.contains(path) {
+            actions.push(format!("create {}", path.display()));
+        }
+    }
+    for entry in &summary.moved_paths {
+        actions.push(format!(
+            "move {} -> {}",
+            entry.from.display(),
+            entry.to.display()
+        ));
+    }
+    for path in &summary.deleted_paths {
+        actions.push(format!("delete {}", path.display()));
+    }
+    for path in &summary.changed_paths {
+        if summary.created_paths.contains(path)
+            || summary.generated_paths_changed.contains(path)
+            || summary.external_output_paths.contains(path)
+            || summary.moved_paths.iter().any(|entry| entry.to == *path)
+        {
+            continue;
+        }
+        actions.push(format!("update {}", path.display()));
+    }
+    if actions.is_empty() && summary.generated_files_changed {
+        actions.push("rebuild generated data".to_string());
+    }
+
+    let subject = match actions.as_slice() {
+        [] => "fractal: update project".to_string(),
+        [action] => format!("fractal: {action}"),
+        [action, rest @ ..] => format!("fractal: {action} and {} more", rest.len()),
+    };
+    let mut body = if actions.len() > 1 {
+        actions
+            .iter()
+            .map(|action| format!("- {action}"))
+            .collect::<Vec<_>>()
+    } else {
+        Vec::new()
+    };
+    if summary.links_rewritten_count > 0 {
+        body.push(format!(
+            "links rewritten: {}",
+            summary.links_rewritten_count
+        ));
+    }
+    (subject, body.join("\n"))
+}
+
+fn page_revisions(root: &Path, page: &Path) -> Result<Vec<(GitCommit, String)>> {
+    if !git_output(root, ["rev-parse", "--verify", "-q", "HEAD"])?
+        .status
+        .success()
+    {
+        return Ok(Vec::new());
+    }
+
+    let format = format!(
+        "--format={RECORD_SEPARATOR}%H{FIELD_SEPARATOR}%an{FIELD_SEPARATOR}%at{FIELD_SEPARATOR}%s"
+    );
+    let output = git(
+        root,
+        path_args(["log", "--follow", "--name-only", format.as_str()], [page]),
+    )?;
+
+    let mut revisions = Vec::new();
+    let mut path = String::new();
+    for record in output.split(RECORD_SEPARATOR) {
+        let mut lines = record.lines();
+        let Some(header) = lines.next() else {
+            continue;
+        };
+        let mut fields = header.splitn(4, FIELD_SEPARATOR);
+        let (Some(id), Some(author), Some(timestamp), Some(subject)) =
+            (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        if let Some(name) = lines.find(|line| !line.trim().is_empty()) {
+            path = name.to_string();
+        }
+        revisions.push((
+            GitCommit {
+                id: id.to_string(),
+                author: author.to_string(),
+                timestamp: timestamp.parse().unwrap_or_default(),
+                subject: subject.to_string(),
+            },
+            path.clone(),
+        ));
+    }
+    Ok(revisions)
+}
+
+fn project_page_path(root: &Path, page: &Path) -> Result<PathBuf> {
+    load_manifest(root)?;
+    resolve_existing_page(root, page)?;
+    Ok(Path::new(PAGES_DIR).join(page_relative_path(root, page)?))
+}
+
+fn require_work_tree(root: &Path) -> Result<()> {
+    if inside_work_tree(root) {
+        Ok(())
+    } else {
+        Err(FractalError::invalid_project(format!(
+            "project is not inside a git work tree: {}",
+            root.display()
+        )))
+    }
+}
+
+fn inside_work_tree(root: &Path) -> bool {
+    git(root, ["rev-parse", "--is-inside-work-tree"]).is_ok_and(|output| output.trim() == "true")
+}
+
+fn path_args<'a, P: AsRef<Path> + 'a>(
+    args: impl IntoIterator<Item = &'a str>,
+    paths: impl IntoIterator<Item = P>,
+) -> Vec<OsString> {
+    let mut combined = args.into_iter().map(OsString::from).collect::<Vec<_>>();
+    combined.push("--".into());
+    combined.extend(
+        paths
+            .into_iter()
+            .map(|path| path.as_ref().as_os_str().to_owned()),
+    );
+    combined
+}
+
+fn split_nul(output: &str) -> Vec<PathBuf> {
+    output
+        .split('\0')
+        .filter(|entry| !entry.is_empty())
+        .map(PathBuf::from)
+        .collect()
+}
+
+fn git<I, S>(root: &Path, args: I) -> Result<String>
+where
+    I: IntoIterator<Item = S>,
+    S: Into<OsString>,
+{
+    let args = args.into_iter().map(Into::into).collect::<Vec<OsString>>();
+    let output = git_output(root, &args)?;
+    if !output.status.success() {
+        return Err(FractalError::new(
+            FractalErrorCode::Io,
+            format!(
+                "git {} failed: {}",
+                args.first()
+                    .map(|arg| arg.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        ));
+    }
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+fn git_output<I, S>(root: &Path, args: I) -> Result<Output>
+where
+    I: IntoIterator<Item = S>,
+    S: Into<OsString>,
+{
+    Ok(Command::new("git")
+        .args(["--literal-pathspecs", "-c", "core.quotePath=false", "-C"])
+        .arg(root)
+        .args(args.into_iter().map(Into::into))
+        .output()?)
+}
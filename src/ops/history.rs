@@ -0,0 +1,281 @@
+use crate::io::fs::atomic_write;
+use crate::io::hash::content_hash;
+use crate::ops::lock::{configured_lock_timeout, unix_timestamp, ProjectLock};
+use crate::ops::mutation::{file_diff, MutationPlan, PlannedFileChange};
+use crate::project::constants::{PAGES_DIR, WORKSPACE_DIR};
+use crate::project::paths::{load_manifest, page_relative_path};
+use crate::types::{FileDiff, HistoryEntry, HistoryFileChange, OperationEvent, OperationReport};
+use crate::{FractalError, Result};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+pub(crate) const HISTORY_DIR: &str = "history";
+const OBJECTS_DIR: &str = "objects";
+const LOG_FILE: &str = "log.jsonl";
+
+pub fn history_enabled(root: impl AsRef<Path>) -> bool {
+    history_dir(root.as_ref()).is_dir()
+}
+
+pub fn enable_history(root: impl AsRef<Path>) -> Result<OperationReport> {
+    let root = root.as_ref();
+    load_manifest(root)?;
+    let dir = history_dir(root);
+    if dir.is_dir() {
+        return Ok(OperationReport::new());
+    }
+
+    let _lock = ProjectLock::acquire(root, configured_lock_timeout(root))?;
+    fs::create_dir_all(dir.join(OBJECTS_DIR))?;
+    Ok(OperationReport::from_event(OperationEvent::HistoryEnabled { path: dir }).relative_to(root))
+}
+
+pub fn disable_history(root: impl AsRef<Path>) -> Result<OperationReport> {
+    let root = root.as_ref();
+    load_manifest(root)?;
+    let dir = history_dir(root);
+    if !dir.is_dir() {
+        return Ok(OperationReport::new());
+    }
+
+    let _lock = ProjectLock::acquire(root, configured_lock_timeout(root))?;
+    fs::remove_dir_all(&dir)?;
+    Ok(
+        OperationReport::from_event(OperationEvent::HistoryDisabled { path: dir })
+            .relative_to(root),
+    )
+}
+
+pub fn history_log(root: impl AsRef<Path>, page: Option<&Path>) -> Result<Vec<HistoryEntry>> {
+    let root = root.as_ref();
+    load_manifest(root)?;
+    let entries = read_history_log(root)?;
+    let Some(page) = page else {
+        return Ok(entries);
+    };
+
+    let page = Path::new(PAGES_DIR).join(page_relative_path(root, page)?);
+    Ok(entries
+        .into_iter()
+        .filter(|entry| {
+            entry
+                .files
+                .iter()
+                .any(|file| file.path == page || file.from.as_ref() == Some(&page))
+        })
+        .collect())
+}
+
+pub fn history_entry(root: impl AsRef<Path>, id: u64) -> Result<HistoryEntry> {
+    let root = root.as_ref();
+    load_manifest(root)?;
+    read_history_log(root)?
+        .into_iter()
+        .find(|entry| entry.id == id)
+        .ok_or_else(|| FractalError::not_found(format!("history entry does not exist: {id}")))
+}
+
+pub fn history_diff(root: impl AsRef<Path>, id: u64) -> Result<Vec<FileDiff>> {
+    let root = root.as_ref();
+    let entry = history_entry(root, id)?;
+    entry
+        .files
+        .into_iter()
+        .map(|file| {
+            let before = read_optional_object(root, file.before.as_deref())?;
+            let after = read_optional_object(root, file.after.as_deref())?;
+            Ok(file_diff(
+                file.path,
+                file.change,
+                file.from,
+                before.as_deref(),
+                after.as_deref(),
+            ))
+        })
+        .collect()
+}
+
+pub fn undo_history(root: impl AsRef<Path>, id: u64) -> Result<OperationReport> {
+    plan_undo_history(root, id)?.apply()
+}
+
+pub fn plan_undo_history(root: impl AsRef<Path>, id: u64) -> Result<MutationPlan> {
+    let root = root.as_ref();
+    let entry = history_entry(root, id)?;
+    let mut plan = MutationPlan::new(root);
+
+    for file in entry.files.iter().rev() {
+        let path = root.join(&file.path);
+        require_state(&mut plan, &path, file.after.as_deref())?;
+
+        if let Some(from) = &file.from {
+            let from = root.join(from);
+            plan.require_absent(&from)?;
+            if let Some(parent) = from.parent() {
+                plan.ensure_dir(parent.to_path_buf());
+            }
+            plan.move_file(
+                path.clone(),
+                from.clone(),
+                OperationEvent::FileRestored { path: from.clone() },
+            );
+            if file.before != file.after {
+                if let Some(before) = &file.before {
+                    plan.write_silent(from, read_object(root, before)?);
+                }
+            }
+            plan.event(OperationEvent::FileRemoved { path });
+            continue;
+        }
+
+        match &file.before {
+            Some(before) => {
+                if let Some(parent) = path.parent() {
+                    plan.ensure_dir(parent.to_path_buf());
+                }
+                plan.write_always(
+                    path.clone(),
+                    read_object(root, before)?,
+                    OperationEvent::FileRestored { path },
+                );
+            }
+            None => plan.remove_file(path.clone(), OperationEvent::FileRemoved { path }),
+        }
+    }
+
+    plan.event(OperationEvent::HistoryUndone { id });
+    plan.record_undo_of(id);
+    plan.rebuild_generated_data();
+    Ok(plan)
+}
+
+pub(crate) fn store_history_objects(
+    root: &Path,
+    changes: Vec<PlannedFileChange>,
+) -> Result<Vec<HistoryFileChange>> {
+    let workspace = root.join(WORKSPACE_DIR);
+    let mut files = Vec::new();
+
+    for change in changes {
+        if change.path.starts_with(&workspace) {
+            continue;
+        }
+        let Ok(path) = change.path.strip_prefix(root) else {
+            continue;
+        };
+        let from = match &change.from {
+            Some(from) => match from.strip_prefix(root) {
+                Ok(from) => Some(from.to_path_buf()),
+                Err(_) => continue,
+            },
+            None => None,
+        };
+
+        files.push(HistoryFileChange {
+            path: path.to_path_buf(),
+            change: change.change,
+            from,
+            before: change
+                .before
+                .as_deref()
+                .map(|contents| store_object(root, contents))
+                .transpose()?,
+            after: change
+                .after
+                .as_deref()
+                .map(|contents| store_object(root, contents))
+                .transpose()?,
+        });
+    }
+
+    Ok(files)
+}
+
+pub(crate) fn append_history_entry(
+    root: &Path,
+    files: Vec<HistoryFileChange>,
+    undoes: Option<u64>,
+    report: &OperationReport,
+) -> Result<()> {
+    if files.is_empty() {
+        return Ok(());
+    }
+
+    let entry = HistoryEntry {
+        id: read_history_log(root)?
+            .last()
+            .map(|entry| entry.id + 1)
+            .unwrap_or(1),
+        created_at: unix_timestamp(),
+        undoes,
+        events: report.clone().relative_to(root).events,
+        files,
+    };
+    let mut log = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(history_dir(root).join(LOG_FILE))?;
+    writeln!(log, "{}", serde_json::to_string(&entry)?)?;
+    log.sync_all()?;
+    Ok(())
+}
+
+fn require_state(plan: &mut MutationPlan, path: &Path, revision: Option<&str>) -> Result<()> {
+    match revision {
+        Some(revision) => plan.require_revision(path, revision),
+        None => plan.require_absent(path),
+    }
+}
+
+fn read_history_log(root: &Path) -> Result<Vec<HistoryEntry>> {
+    let dir = history_dir(root);
+    if !dir.is_dir() {
+        return Err(FractalError::not_found(
+            "project history is not enabled; run `fractal history enable`",
+        ));
+    }
+
+    let contents = match fs::read_to_string(dir.join(LOG_FILE)) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(error) => return Err(error.into()),
+    };
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| Ok(serde_json::from_str(line)?))
+        .collect()
+}
+
+fn store_object(root: &Path, contents: &[u8]) -> Result<String> {
+    let hash = content_hash(contents);
+    let path = object_path(root, &hash);
+    if !path.is_file() {
+        atomic_write(&path, contents)?;
+    }
+    Ok(hash)
+}
+
+fn read_object(root: &Path, hash: &str) -> Result<Vec<u8>> {
+    let path = object_path(root, hash);
+    if !path.is_file() {
+        return Err(FractalError::not_found(format!(
+            "history object is missing: {hash}"
+        )));
+    }
+    Ok(fs::read(path)?)
+}
+
+fn read_optional_object(root: &Path, hash: Option<&str>) -> Result<Option<Vec<u8>>> {
+    hash.map(|hash| read_object(root, hash)).transpose()
+}
+
+fn object_path(root: &Path, hash: &str) -> PathBuf {
+    let name = hash.strip_prefix("sha256:").unwrap_or(hash);
+    history_dir(root).join(OBJECTS_DIR).join(name)
+}
+
+fn history_dir(root: &Path) -> PathBuf {
+    root.join(WORKSPACE_DIR).join(HISTORY_DIR)
+}
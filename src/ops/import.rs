@@ -0,0 +1,199 @@
+use crate::document::metadata::{SUMMARY_META, TAGS_META};
+use crate::document::notes::{insert_note_into_document, note_id_from_trigger, render_note_aside};
+use crate::document::render::{render_page_document, stylesheet_href};
+use crate::document::PageDocument;
+use crate::index::ensure_page_labels_available_for_all;
+use crate::io::import::{collect_source_files, markdown_page, ImportedPage};
+use crate::io::logseq::logseq_pages;
+use crate::io::notion::notion_pages;
+use crate::ops::mutation::MutationPlan;
+use crate::ops::sync::link_note_triggers;
+use crate::project::paths::{load_manifest, page_relative_path, resolve_page_destination};
+use crate::types::{ImportFormat, OperationEvent, OperationReport, PageImportPreflight, Theme};
+use crate::{FractalError, Result};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+pub fn preflight_import_pages(
+    root: impl AsRef<Path>,
+    source: impl AsRef<Path>,
+    format: ImportFormat,
+) -> Result<Vec<PageImportPreflight>> {
+    let root = root.as_ref();
+    let pages = imported_pages(source.as_ref(), format)?;
+    Ok(plan_page_imports(root, pages)?
+        .into_iter()
+        .map(|planned| planned.preflight)
+        .collect())
+}
+
+pub fn import_pages(
+    root: impl AsRef<Path>,
+    source: impl AsRef<Path>,
+    format: ImportFormat,
+) -> Result<OperationReport> {
+    plan_import_pages(root, source, format)?.apply()
+}
+
+pub fn plan_import_pages(
+    root: impl AsRef<Path>,
+    source: impl AsRef<Path>,
+    format: ImportFormat,
+) -> Result<MutationPlan> {
+    let root = root.as_ref();
+    let pages = imported_pages(source.as_ref(), format)?;
+    plan_imported_pages(root, pages)
+}
+
+pub(crate) fn plan_imported_pages(root: &Path, pages: Vec<ImportedPage>) -> Result<MutationPlan> {
+    let planned = plan_page_imports(root, pages)?;
+
+    let mut plan = MutationPlan::new(root);
+    for page in planned {
+        let destination = page.preflight.destination_path;
+        if let Some(parent) = destination.parent() {
+            plan.ensure_dir(parent.to_path_buf());
+        }
+        plan.write_always(
+            destination.clone(),
+            page.html.into_bytes(),
+            OperationEvent::PageImported {
+                source: page.preflight.source,
+                destination,
+            },
+        );
+        for message in page.preflight.warnings {
+            plan.event(OperationEvent::Warning { message });
+        }
+    }
+
+    plan.rebuild_generated_data();
+    Ok(plan)
+}
+
+fn imported_pages(source: &Path, format: ImportFormat) -> Result<Vec<ImportedPage>> {
+    let pages = match format {
+        ImportFormat::Markdown => collect_source_files(source, &["md"], &[])?
+            .iter()
+            .map(|path| markdown_page(path))
+            .collect::<Result<Vec<_>>>()?,
+        ImportFormat::Notion => notion_pages(source)?,
+        ImportFormat::Logseq => logseq_pages(source)?,
+    };
+
+    if pages.is_empty() {
+        return Err(FractalError::invalid_input(format!(
+            "no importable pages found in {}",
+            source.display()
+        )));
+    }
+    Ok(pages)
+}
+
+struct PlannedPageImport {
+    preflight: PageImportPreflight,
+    html: String,
+}
+
+fn plan_page_imports(root: &Path, pages: Vec<ImportedPage>) -> Result<Vec<PlannedPageImport>> {
+    let manifest = load_manifest(root)?;
+    let mut sources_by_destination = BTreeMap::new();
+    for page in &pages {
+        let destination = resolve_page_destination(root, &page.destination)?;
+        if let Some(earlier) = sources_by_destination.insert(destination.clone(), &page.source) {
+            return Err(FractalError::already_exists(format!(
+                "import sources {} and {} both map to page {}; rename one of them",
+                earlier.display(),
+                page.source.display(),
+                page_relative_path(root, &destination)?.display()
+            )));
+        }
+    }
+    let mut labels = Vec::new();
+    let mut planned = Vec::new();
+
+    for page in pages {
+        let destination = resolve_page_destination(root, &page.destination)?;
+        if destination.exists() {
+            return Err(FractalError::already_exists(format!(
+                "page already exists: {}",
+                destination.display()
+            )));
+        }
+
+        let relative_page = page_relative_path(root, &destination)?;
+        let relative_page_string = relative_page.to_string_lossy().replace('\\', "/");
+        labels.push((relative_page_string.clone(), page.title.clone()));
+
+        let (html, note_ids) = render_imported_page(&page, &relative_page, manifest.theme)?;
+        planned.push(PlannedPageImport {
+            preflight: PageImportPreflight {
+                source: page.source,
+                destination_page: relative_page_string,
+                destination_path: destination,
+                title: page.title,
+                summary: page.summary,
+                tags: page.tags,
+                note_ids,
+                warnings: page.warnings,
+            },
+            html,
+        });
+    }
+
+    ensure_page_labels_available_for_all(root, &labels)?;
+    Ok(planned)
+}
+
+fn render_imported_page(
+    page: &ImportedPage,
+    relative_page: &Path,
+    theme: Theme,
+) -> Result<(String, Vec<String>)> {
+    let mut html = render_page_document(
+        &page.title,
+        &page.body,
+        theme,
+        stylesheet_href(relative_page),
+    );
+    let mut note_ids = Vec::new();
+
+    for note in &page.notes {
+        let note_id = note_id_from_trigger(&note.trigger)?;
+        if note_ids.contains(&note_id) {
+            continue;
+        }
+        html = insert_note_into_document(&html, &render_note_aside(&note_id, &note.content))?;
+        note_ids.push(note_id);
+    }
+
+    if page.notes.is_empty() && page.summary.is_none() && page.tags.is_empty() {
+        return Ok((html, note_ids));
+    }
+
+    let document = PageDocument::parse(&html);
+    if !page.notes.is_empty() {
+        for note in &page.notes {
+            let note_id = note_id_from_trigger(&note.trigger)?;
+            if let Some(element) = document
+                .note_node(&note_id)
+                .as_ref()
+                .and_then(|node| node.as_element())
+            {
+                let mut attributes = element.attributes.borrow_mut();
+                if !attributes.contains("data-fractal-trigger") {
+                    attributes.insert("data-fractal-trigger", note.trigger.clone());
+                }
+            }
+        }
+        link_note_triggers(&document)?;
+    }
+    if let Some(summary) = &page.summary {
+        document.set_meta_tag(SUMMARY_META, summary)?;
+    }
+    if !page.tags.is_empty() {
+        document.set_meta_tag(TAGS_META, &page.tags.join(", "))?;
+    }
+
+    Ok((document.to_html()?, note_ids))
+}
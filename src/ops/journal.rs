@@ -0,0 +1,270 @@
+use crate::io::fs::atomic_write;
+use crate::project::constants::WORKSPACE_DIR;
+use crate::{FractalError, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+pub(crate) const TRANSACTION_DIR: &str = "transaction";
+const JOURNAL_FILE: &str = "journal.json";
+const JOURNAL_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct JournalData {
+    version: u32,
+    entries: Vec<JournalEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum JournalEntry {
+    FileCreated { path: PathBuf },
+    FileReplaced { path: PathBuf, backup: PathBuf },
+    FileMoved { from: PathBuf, to: PathBuf },
+    Stashed { path: PathBuf, stash: PathBuf },
+    DirCreated { path: PathBuf },
+    DirRemoved { path: PathBuf },
+}
+
+#[derive(Debug)]
+pub(crate) struct Journal {
+    root: PathBuf,
+    dir: PathBuf,
+    data: JournalData,
+}
+
+impl Journal {
+    pub(crate) fn begin(root: &Path) -> Result<Self> {
+        let dir = transaction_dir(root);
+        fs::create_dir_all(&dir)?;
+        let journal = Self {
+            root: root.to_path_buf(),
+            dir,
+            data: JournalData {
+                version: JOURNAL_VERSION,
+                entries: Vec::new(),
+            },
+        };
+        journal.persist()?;
+        Ok(journal)
+    }
+
+    pub(crate) fn write_file(&mut self, path: &Path, contents: &[u8]) -> Result<bool> {
+        match fs::read(path) {
+            Ok(existing) if existing == contents => return Ok(false),
+            Ok(existing) => {
+                let backup = self.next_stash_path();
+                write_synced(&backup, &existing)?;
+                self.record(JournalEntry::FileReplaced {
+                    path: self.journal_path(path),
+                    backup: self.journal_path(&backup),
+                })?;
+            }
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                self.record(JournalEntry::FileCreated {
+                    path: self.journal_path(path),
+                })?;
+            }
+            Err(error) => return Err(error.into()),
+        }
+
+        atomic_write(path, contents)
+    }
+
+    pub(crate) fn create_dir(&mut self, path: &Path) -> Result<()> {
+        fs::create_dir(path)?;
+        self.record(JournalEntry::DirCreated {
+            path: self.journal_path(path),
+        })
+    }
+
+    pub(crate) fn ensure_dir(&mut self, path: &Path) -> Result<()> {
+        let mut missing = path
+            .ancestors()
+            .take_while(|ancestor| !ancestor.as_os_str().is_empty() && !ancestor.exists())
+            .map(Path::to_path_buf)
+            .collect::<Vec<_>>();
+        missing.reverse();
+
+        for directory in missing {
+            self.create_dir(&directory)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn move_file(&mut self, from: &Path, to: &Path) -> Result<()> {
+        if to.exists() {
+            self.stash(to)?;
+        }
+        self.record(JournalEntry::FileMoved {
+            from: self.journal_path(from),
+            to: self.journal_path(to),
+        })?;
+        fs::rename(from, to)?;
+        Ok(())
+    }
+
+    pub(crate) fn remove_file(&mut self, path: &Path) -> Result<()> {
+        if !path.is_file() {
+            fs::remove_file(path)?;
+        }
+        self.stash(path)
+    }
+
+    pub(crate) fn remove_dir(&mut self, path: &Path, recursive: bool) -> Result<()> {
+        if recursive {
+            if !path.is_dir() {
+                fs::remove_dir_all(path)?;
+            }
+            return self.stash(path);
+        }
+
+        self.record(JournalEntry::DirRemoved {
+            path: self.journal_path(path),
+        })?;
+        fs::remove_dir(path)?;
+        Ok(())
+    }
+
+    pub(crate) fn commit(self) -> Result<()> {
+        fs::remove_dir_all(&self.dir)?;
+        Ok(())
+    }
+
+    pub(crate) fn rollback(self) -> Result<usize> {
+        for entry in self.data.entries.iter().rev() {
+            undo_entry(&self.root, entry)?;
+        }
+        fs::remove_dir_all(&self.dir)?;
+        Ok(self.data.entries.len())
+    }
+
+    fn stash(&mut self, path: &Path) -> Result<()> {
+        let stash = self.next_stash_path();
+        self.record(JournalEntry::Stashed {
+            path: self.journal_path(path),
+            stash: self.journal_path(&stash),
+        })?;
+        fs::rename(path, &stash)?;
+        Ok(())
+    }
+
+    fn record(&mut self, entry: JournalEntry) -> Result<()> {
+        self.data.entries.push(entry);
+        self.persist()
+    }
+
+    fn persist(&self) -> Result<()> {
+        atomic_write(
+            self.dir.join(JOURNAL_FILE),
+            serde_json::to_string_pretty(&self.data)?,
+        )?;
+        Ok(())
+    }
+
+    fn next_stash_path(&self) -> PathBuf {
+        self.dir.join(format!("stash-{}", self.data.entries.len()))
+    }
+
+    fn journal_path(&self, path: &Path) -> PathBuf {
+        path.strip_prefix(&self.root)
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|_| path.to_path_buf())
+    }
+}
+
+pub(crate) fn has_interrupted_transaction(root: &Path) -> bool {
+    transaction_dir(root).exists()
+}
+
+pub(crate) fn recover_interrupted_transaction(root: &Path) -> Result<()> {
+    let dir = transaction_dir(root);
+    if !dir.exists() {
+        return Ok(());
+    }
+
+    let journal_path = dir.join(JOURNAL_FILE);
+    let data = match fs::read_to_string(&journal_path) {
+        Ok(contents) => serde_json::from_str::<JournalData>(&contents)?,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => JournalData {
+            version: JOURNAL_VERSION,
+            entries: Vec::new(),
+        },
+        Err(error) => return Err(error.into()),
+    };
+    if data.version != JOURNAL_VERSION {
+        return Err(FractalError::unsupported_version(format!(
+            "unsupported transaction journal version in {}: {} (expected {})",
+            journal_path.display(),
+            data.version,
+            JOURNAL_VERSION
+        )));
+    }
+
+    let steps = Journal {
+        root: root.to_path_buf(),
+        dir: dir.clone(),
+        data,
+    }
+    .rollback()?;
+
+    Err(FractalError::transaction_recovered(format!(
+        "rolled back an interrupted transaction ({steps} step(s)) from {}; retry the operation",
+        dir.display()
+    )))
+}
+
+fn transaction_dir(root: &Path) -> PathBuf {
+    root.join(WORKSPACE_DIR).join(TRANSACTION_DIR)
+}
+
+fn undo_entry(root: &Path, entry: &JournalEntry) -> Result<()> {
+    match entry {
+        JournalEntry::FileCreated { path } => remove_if_exists(&root.join(path)),
+        JournalEntry::FileReplaced { path, backup } => {
+            let backup = root.join(backup);
+            if backup.exists() {
+                let contents = fs::read(&backup)?;
+                atomic_write(root.join(path), contents)?;
+            }
+            Ok(())
+        }
+        JournalEntry::FileMoved { from, to } => {
+            let (from, to) = (root.join(from), root.join(to));
+            if to.exists() && !from.exists() {
+                fs::rename(to, from)?;
+            }
+            Ok(())
+        }
+        JournalEntry::Stashed { path, stash } => {
+            let (path, stash) = (root.join(path), root.join(stash));
+            if stash.exists() && !path.exists() {
+                fs::rename(stash, path)?;
+            }
+            Ok(())
+        }
+        JournalEntry::DirCreated { path } => match fs::remove_dir(root.join(path)) {
+            Err(error) if error.kind() != std::io::ErrorKind::NotFound => Err(error.into()),
+            _ => Ok(()),
+        },
+        JournalEntry::DirRemoved { path } => {
+            fs::create_dir_all(root.join(path))?;
+            Ok(())
+        }
+    }
+}
+
+fn remove_if_exists(path: &Path) -> Result<()> {
+    match fs::remove_file(path) {
+        Err(error) if error.kind() != std::io::ErrorKind::NotFound => Err(error.into()),
+        _ => Ok(()),
+    }
+}
+
+fn write_synced(path: &Path, contents: &[u8]) -> Result<()> {
+    let mut file = File::create(path)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    Ok(())
+}
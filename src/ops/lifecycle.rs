@@ -0,0 +1,378 @@
+use crate::document::notes::{insert_note_into_document, note_id_from_trigger, resolve_note_id};
+use crate::document::render::{render_page_document, stylesheet_href};
+use crate::document::PageDocument;
+use crate::graph::build_project_graph;
+use crate::graph::links::{
+    page_label_from_path, relative_href, resolve_page_href, resolve_section_href,
+};
+use crate::index::{build_project_index, ensure_page_labels_available};
+use crate::ops::mutation::{read_revisioned, MutationPlan};
+use crate::ops::page::{
+    manifest_default_page_path, normalize_page_title, plan_page_reference_rewrites,
+    push_planned_page_rewrites,
+};
+use crate::project::constants::{MANIFEST_FILE, PAGES_DIR};
+use crate::project::paths::{
+    load_manifest, page_destination_in_directory, page_relative_path, resolve_existing_page,
+};
+use crate::types::{
+    NotePromotePreflight, OperationEvent, OperationReport, PageDemotePreflight, ProjectIndex,
+};
+use crate::validation::validate_page_html_for_project;
+use crate::{FractalError, Result};
+use std::path::Path;
+
+pub fn preflight_promote_note(
+    root: impl AsRef<Path>,
+    page: impl AsRef<Path>,
+    note: &str,
+    title: &str,
+) -> Result<NotePromotePreflight> {
+    let root = root.as_ref();
+    load_manifest(root)?;
+    let host_path = resolve_existing_page(root, page.as_ref())?;
+    let host_page = relative_page_string(root, &host_path)?;
+    let note_id = resolve_note_id(note)?;
+    let document = PageDocument::from_path(&host_path)?;
+    if document.note_node(&note_id).is_none() {
+        return Err(FractalError::not_found(format!(
+            "note does not exist: {note_id}"
+        )));
+    }
+
+    let title = normalize_page_title(title)?;
+    let directory = Path::new(&host_page)
+        .parent()
+        .unwrap_or_else(|| Path::new(""));
+    let destination_path = page_destination_in_directory(root, directory, &title)?;
+    if destination_path.exists() {
+        return Err(FractalError::already_exists(format!(
+            "page already exists: {}",
+            destination_path.display()
+        )));
+    }
+    let destination_page = relative_page_string(root, &destination_path)?;
+    ensure_page_labels_available(root, &destination_page, &title)?;
+
+    let note_links = document.retarget_note_links_to_page(
+        &host_page,
+        &host_page,
+        &note_id,
+        &destination_page,
+        &title,
+    );
+    let index = build_project_index(root)?;
+    let targets_note = |page: &str, href: &str| {
+        resolve_section_href(page, href)
+            .is_some_and(|(target_page, fragment)| target_page == host_page && fragment == note_id)
+    };
+    let cited_by = index
+        .pages
+        .iter()
+        .filter(|page| {
+            page.path != host_page
+                && page
+                    .links
+                    .iter()
+                    .any(|link| link.scope == "note" && targets_note(&page.path, &link.href))
+        })
+        .map(|page| page.path.clone())
+        .collect();
+    let embedded_by = pages_embedding(&index, targets_note);
+
+    Ok(NotePromotePreflight {
+        host_page,
+        host_path,
+        note_id,
+        destination_page,
+        destination_path,
+        title,
+        note_links,
+        cited_by,
+        embedded_by,
+    })
+}
+
+pub fn promote_note(
+    root: impl AsRef<Path>,
+    page: impl AsRef<Path>,
+    note: &str,
+    title: &str,
+    expected_revision: Option<&str>,
+) -> Result<OperationReport> {
+    plan_promote_note(root, page, note, title, expected_revision)?.apply()
+}
+
+pub fn plan_promote_note(
+    root: impl AsRef<Path>,
+    page: impl AsRef<Path>,
+    note: &str,
+    title: &str,
+    expected_revision: Option<&str>,
+) -> Result<MutationPlan> {
+    let root = root.as_ref();
+    let manifest = load_manifest(root)?;
+    let preflight = preflight_promote_note(root, page, note, title)?;
+    let (host_html, host_revision) = read_revisioned(&preflight.host_path)?;
+    let document = PageDocument::parse(&host_html);
+    let note = document.note_node(&preflight.note_id).ok_or_else(|| {
+        FractalError::not_found(format!("note does not exist: {}", preflight.note_id))
+    })?;
+
+    let mut serialized = Vec::new();
+    for child in note.children() {
+        child.serialize(&mut serialized)?;
+    }
+    let content = PageDocument::parse(&format!("<main>{}</main>", String::from_utf8(serialized)?));
+    content.unwrap_note_links();
+    let body = content.main_body_html()?;
+    let promoted = PageDocument::parse(&render_page_document(
+        &preflight.title,
+        body.trim(),
+        manifest.theme,
+        stylesheet_href(Path::new(&preflight.destination_page)),
+    ));
+    promoted.ensure_heading_anchors();
+    let promoted_html = promoted.to_html()?;
+    validate_page_html_for_project(root, &preflight.destination_page, &promoted_html)?;
+
+    note.detach();
+    document.unwrap_note_embeds(
+        &preflight.host_page,
+        &preflight.host_page,
+        &preflight.note_id,
+    );
+    let note_links = document.retarget_note_links_to_page(
+        &preflight.host_page,
+        &preflight.host_page,
+        &preflight.note_id,
+        &preflight.destination_page,
+        &preflight.title,
+    );
+    let reference_rewrites =
+        plan_page_reference_rewrites(root, &[&preflight.host_page], |page_path, document| {
+            document.unwrap_note_embeds(page_path, &preflight.host_page, &preflight.note_id)
+                + document.retarget_note_links_to_page(
+                    page_path,
+                    &preflight.host_page,
+                    &preflight.note_id,
+                    &preflight.destination_page,
+                    &preflight.title,
+                )
+        })?;
+
+    let mut plan = MutationPlan::new(root);
+    plan.require_revision(&preflight.host_path, host_revision)?;
+    plan.require_optional_revision(&preflight.host_path, expected_revision)?;
+    plan.write_always(
+        preflight.destination_path.clone(),
+        promoted_html.into_bytes(),
+        OperationEvent::PageCreated {
+            path: preflight.destination_path,
+        },
+    );
+    plan.write_always(
+        preflight.host_path.clone(),
+        document.to_html()?.into_bytes(),
+        OperationEvent::NoteRemoved {
+            page: preflight.host_path.clone(),
+            note_id: preflight.note_id,
+        },
+    );
+    if note_links > 0 {
+        plan.event(OperationEvent::PageLinksRewritten {
+            page: preflight.host_path,
+            count: note_links,
+        });
+    }
+    push_planned_page_rewrites(&mut plan, reference_rewrites);
+    plan.rebuild_generated_data();
+    Ok(plan)
+}
+
+pub fn preflight_demote_page(
+    root: impl AsRef<Path>,
+    page: impl AsRef<Path>,
+    host_page: impl AsRef<Path>,
+) -> Result<PageDemotePreflight> {
+    let root = root.as_ref();
+    let manifest = load_manifest(root)?;
+    let path = resolve_existing_page(root, page.as_ref())?;
+    let page = relative_page_string(root, &path)?;
+    let host_path = resolve_existing_page(root, host_page.as_ref())?;
+    let host_page = relative_page_string(root, &host_path)?;
+    if page == host_page {
+        return Err(FractalError::invalid_input(
+            "cannot demote a page into itself",
+        ));
+    }
+
+    let document = PageDocument::from_path(&path)?;
+    if !document.notes().is_empty() {
+        return Err(FractalError::invalid_input(format!(
+            "page has notes; promote or remove them before demoting: {page}"
+        )));
+    }
+    let title = document
+        .title()
+        .unwrap_or_else(|| page_label_from_path(&page));
+    let note_id = note_id_from_trigger(&title)?;
+    if PageDocument::from_path(&host_path)?
+        .note_node(&note_id)
+        .is_some()
+    {
+        return Err(FractalError::already_exists(format!(
+            "note already exists in {host_page}: {note_id}"
+        )));
+    }
+
+    let index = build_project_index(root)?;
+    let graph = build_project_graph(&index);
+    let graph_entry = graph
+        .pages
+        .into_iter()
+        .find(|entry| entry.path == page)
+        .ok_or_else(|| FractalError::not_found(format!("page not found in graph: {page}")))?;
+    let embedded_by = pages_embedding(&index, |from, target| {
+        from != page && resolve_page_href(from, target).as_deref() == Some(page.as_str())
+    });
+
+    Ok(PageDemotePreflight {
+        deleting_default: manifest_default_page_path(root, &manifest)? == page,
+        page,
+        path,
+        host_page,
+        host_path,
+        note_id,
+        backlinks: graph_entry.backlinks,
+        outlinks: graph_entry.outlinks,
+        embedded_by,
+    })
+}
+
+pub fn demote_page(
+    root: impl AsRef<Path>,
+    page: impl AsRef<Path>,
+    host_page: impl AsRef<Path>,
+    expected_revision: Option<&str>,
+) -> Result<OperationReport> {
+    plan_demote_page(root, page, host_page, expected_revision)?.apply()
+}
+
+pub fn plan_demote_page(
+    root: impl AsRef<Path>,
+    page: impl AsRef<Path>,
+    host_page: impl AsRef<Path>,
+    expected_revision: Option<&str>,
+) -> Result<MutationPlan> {
+    let root = root.as_ref();
+    let mut manifest = load_manifest(root)?;
+    let preflight = preflight_demote_page(root, page, host_page)?;
+
+    let (html, revision) = read_revisioned(&preflight.path)?;
+    let document = PageDocument::parse(&html);
+    document.unwrap_embeds();
+    document.unwrap_generated_page_hrefs(&preflight.page, &preflight.page);
+    document.rewrite_relative_page_hrefs_for_move(&preflight.page, &preflight.host_page);
+    document.rewrite_relative_image_srcs_for_move(&preflight.page, &preflight.host_page);
+    let body = document.main_body_html()?;
+    let note = format!(
+        "    <aside id=\"{}\" data-fractal-note>\n      {}\n    </aside>\n",
+        preflight.note_id,
+        body.trim()
+    );
+
+    let (host_html, host_revision) = read_revisioned(&preflight.host_path)?;
+    let host_html = insert_note_into_document(&host_html, &note)?;
+    let host = PageDocument::parse(&host_html);
+    host.unwrap_page_embeds(&preflight.host_page, &preflight.page);
+    let host_links = host.retarget_page_links_to_note(
+        &preflight.host_page,
+        &preflight.page,
+        &format!("#{}", preflight.note_id),
+    );
+    let host_html = host.to_html()?;
+    validate_page_html_for_project(root, &preflight.host_page, &host_html)?;
+
+    let backlink_rewrites = plan_page_reference_rewrites(
+        root,
+        &[&preflight.page, &preflight.host_page],
+        |page_path, document| {
+            let note_href = format!(
+                "{}#{}",
+                relative_href(page_path, &preflight.host_page),
+                preflight.note_id
+            );
+            document.unwrap_page_embeds(page_path, &preflight.page)
+                + document.retarget_page_links_to_note(page_path, &preflight.page, &note_href)
+        },
+    )?;
+
+    let mut plan = MutationPlan::new(root);
+    plan.require_revision(&preflight.path, revision)?;
+    plan.require_optional_revision(&preflight.path, expected_revision)?;
+    plan.require_revision(&preflight.host_path, host_revision)?;
+    plan.event(OperationEvent::PageLinkImpact {
+        page: preflight.page.clone(),
+        backlinks: preflight.backlinks,
+        outlinks: preflight.outlinks,
+    });
+    plan.write_always(
+        preflight.host_path.clone(),
+        host_html.into_bytes(),
+        OperationEvent::NoteAdded {
+            page: preflight.host_path.clone(),
+            note_id: preflight.note_id,
+        },
+    );
+    if host_links > 0 {
+        plan.event(OperationEvent::PageLinksRewritten {
+            page: preflight.host_path,
+            count: host_links,
+        });
+    }
+    plan.remove_file(
+        preflight.path.clone(),
+        OperationEvent::PageDeleted {
+            path: preflight.path,
+        },
+    );
+    push_planned_page_rewrites(&mut plan, backlink_rewrites);
+
+    if preflight.deleting_default {
+        manifest.default_page = format!("{PAGES_DIR}/{}", preflight.host_page);
+        let manifest_path = root.join(MANIFEST_FILE);
+        plan.write_always(
+            manifest_path.clone(),
+            serde_json::to_string_pretty(&manifest)?.into_bytes(),
+            OperationEvent::ManifestUpdated {
+                path: manifest_path,
+            },
+        );
+    }
+
+    plan.rebuild_generated_data();
+    Ok(plan)
+}
+
+fn relative_page_string(root: &Path, page: &Path) -> Result<String> {
+    Ok(page_relative_path(root, page)?
+        .to_string_lossy()
+        .replace('\\', "/"))
+}
+
+fn pages_embedding(
+    index: &ProjectIndex,
+    mut targets: impl FnMut(&str, &str) -> bool,
+) -> Vec<String> {
+    index
+        .pages
+        .iter()
+        .filter(|page| {
+            page.embeds
+                .iter()
+                .any(|embed| targets(&page.path, &embed.target))
+        })
+        .map(|page| page.path.clone())
+        .collect()
+}
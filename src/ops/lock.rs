@@ -0,0 +1,273 @@
+use crate::project::constants::WORKSPACE_DIR;
+use crate::project::paths::load_manifest;
+use crate::types::{MutationLockOwner, OperationEvent, OperationReport};
+use crate::{FractalError, Result};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+pub(crate) const MUTATION_LOCK_FILE: &str = "mutation.lock";
+pub(crate) const STEAL_GUARD_FILE: &str = "mutation-steal.lock";
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(25);
+
+static LOCK_TOKENS: AtomicU64 = AtomicU64::new(0);
+
+pub(crate) fn configured_lock_timeout(root: &Path) -> Duration {
+    load_manifest(root)
+        .map(|manifest| Duration::from_millis(manifest.lock_timeout_ms))
+        .unwrap_or_default()
+}
+
+pub fn mutation_lock_owner(root: impl AsRef<Path>) -> Result<Option<MutationLockOwner>> {
+    let path = lock_path(root.as_ref());
+    match fs::read_to_string(&path) {
+        Ok(contents) => Ok(Some(parse_lock_owner(&contents))),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(error) => Err(error.into()),
+    }
+}
+
+pub fn unlock_project(root: impl AsRef<Path>, force: bool) -> Result<OperationReport> {
+    let root = root.as_ref();
+    load_manifest(root)?;
+    let path = lock_path(root);
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(OperationReport::new())
+        }
+        Err(error) => return Err(error.into()),
+    };
+    let owner = parse_lock_owner(&contents);
+
+    let stale = owner_is_stale(&owner);
+    if !stale && !force {
+        return Err(FractalError::project_locked(format!(
+            "{}; use --force to remove it anyway",
+            locked_message(&path, &owner)
+        )));
+    }
+
+    if !steal_stale_lock(&path, &contents)? && path.exists() {
+        return Err(FractalError::project_locked(format!(
+            "mutation lock changed while unlocking; run unlock again: {}",
+            path.display()
+        )));
+    }
+
+    Ok(
+        OperationReport::from_event(OperationEvent::ProjectUnlocked { path, owner, stale })
+            .relative_to(root),
+    )
+}
+
+#[derive(Debug)]
+pub(crate) struct ProjectLock {
+    path: PathBuf,
+    contents: String,
+}
+
+impl ProjectLock {
+    pub(crate) fn acquire(root: &Path, timeout: Duration) -> Result<Self> {
+        let path = lock_path(root);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let deadline = Instant::now() + timeout;
+        loop {
+            match Self::try_create(&path) {
+                Ok(lock) => return Ok(lock),
+                Err(error) if error.kind() == std::io::ErrorKind::AlreadyExists => {}
+                Err(error) => return Err(error.into()),
+            }
+
+            let contents = match fs::read_to_string(&path) {
+                Ok(contents) => contents,
+                Err(error) if error.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(error) => return Err(error.into()),
+            };
+            let owner = parse_lock_owner(&contents);
+            if owner_is_stale(&owner) {
+                steal_stale_lock(&path, &contents)?;
+                continue;
+            }
+
+            if Instant::now() >= deadline {
+                return Err(FractalError::project_locked(locked_message(&path, &owner)));
+            }
+            thread::sleep(LOCK_RETRY_INTERVAL);
+        }
+    }
+
+    fn try_create(path: &Path) -> std::io::Result<Self> {
+        let contents = format!(
+            "pid={}\nhostname={}\ncreated_at={}\ntoken={}\n",
+            std::process::id(),
+            current_hostname(),
+            unix_timestamp(),
+            unique_token()
+        );
+        let mut file = OpenOptions::new().write(true).create_new(true).open(path)?;
+        let written = file
+            .write_all(contents.as_bytes())
+            .and_then(|_| file.sync_all());
+        if let Err(error) = written {
+            let _ = fs::remove_file(path);
+            return Err(error);
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            contents,
+        })
+    }
+}
+
+impl Drop for ProjectLock {
+    fn drop(&mut self) {
+        if fs::read_to_string(&self.path).is_ok_and(|contents| contents == self.contents) {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Removes the lock file only while it still holds `stale_contents`, returning whether it did.
+pub(crate) fn steal_stale_lock(path: &Path, stale_contents: &str) -> Result<bool> {
+    let _guard = lock_steal_guard(path)?;
+    match fs::read_to_string(path) {
+        Ok(contents) if contents == stale_contents => match fs::remove_file(path) {
+            Err(error) if error.kind() != std::io::ErrorKind::NotFound => Err(error.into()),
+            _ => Ok(true),
+        },
+        Ok(_) => Ok(false),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(error) => Err(error.into()),
+    }
+}
+
+/// Serializes every removal of a lock by anyone but its holder, so the check and the removal in
+/// `steal_stale_lock` cannot interleave. The OS releases the guard if its holder dies.
+pub(crate) fn lock_steal_guard(path: &Path) -> Result<fs::File> {
+    let guard = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path.with_file_name(STEAL_GUARD_FILE))?;
+    guard.lock()?;
+    Ok(guard)
+}
+
+fn unique_token() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_nanos())
+        .unwrap_or_default();
+    format!(
+        "{}-{nanos}-{}",
+        std::process::id(),
+        LOCK_TOKENS.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+fn lock_path(root: &Path) -> PathBuf {
+    root.join(WORKSPACE_DIR).join(MUTATION_LOCK_FILE)
+}
+
+fn parse_lock_owner(contents: &str) -> MutationLockOwner {
+    let mut owner = MutationLockOwner::default();
+    for line in contents.lines() {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "pid" => owner.pid = value.parse().ok(),
+            "hostname" => {
+                owner.hostname = Some(value.to_string()).filter(|hostname| !hostname.is_empty())
+            }
+            "created_at" => owner.created_at = value.parse().ok(),
+            _ => {}
+        }
+    }
+    owner
+}
+
+fn owner_is_stale(owner: &MutationLockOwner) -> bool {
+    let Some(pid) = owner.pid else {
+        return false;
+    };
+    let same_host = owner
+        .hostname
+        .as_deref()
+        .is_none_or(|hostname| hostname == current_hostname());
+    same_host && process_is_gone(pid)
+}
+
+fn locked_message(path: &Path, owner: &MutationLockOwner) -> String {
+    let mut holder = Vec::new();
+    if let Some(pid) = owner.pid {
+        holder.push(format!("pid {pid}"));
+    }
+    if let Some(hostname) = &owner.hostname {
+        holder.push(format!("host {hostname}"));
+    }
+    if let Some(created_at) = owner.created_at {
+        let age = unix_timestamp().saturating_sub(created_at);
+        holder.push(format!("acquired {age}s ago"));
+    }
+
+    if holder.is_empty() {
+        format!("project is locked for mutation: {}", path.display())
+    } else {
+        format!(
+            "project is locked for mutation by {}: {}",
+            holder.join(", "),
+            path.display()
+        )
+    }
+}
+
+fn current_hostname() -> String {
+    ["/proc/sys/kernel/hostname", "/etc/hostname"]
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .chain(
+            ["HOSTNAME", "COMPUTERNAME"]
+                .iter()
+                .filter_map(|name| std::env::var(name).ok()),
+        )
+        .map(|hostname| hostname.trim().to_string())
+        .find(|hostname| !hostname.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+pub(crate) fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(target_os = "linux")]
+fn process_is_gone(pid: u32) -> bool {
+    !Path::new("/proc").join(pid.to_string()).exists()
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn process_is_gone(pid: u32) -> bool {
+    std::process::Command::new("kill")
+        .args(["-0", &pid.to_string()])
+        .stderr(std::process::Stdio::null())
+        .status()
+        .map(|status| !status.success())
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn process_is_gone(_pid: u32) -> bool {
+    false
+}
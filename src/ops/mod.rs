@@ -1,16 +1,60 @@
+mod asset;
+mod block;
 mod editor;
+mod fixture;
+pub(crate) mod git;
+pub(crate) mod history;
+mod import;
+pub(crate) mod journal;
+mod lifecycle;
+pub(crate) mod lock;
 pub(crate) mod mutation;
 mod page;
+mod repair;
 mod summary;
 mod sync;
+mod watch;
 
+pub(crate) use asset::asset_entries;
+pub use asset::{
+    add_asset, delete_asset, list_assets, move_asset, plan_add_asset, plan_delete_asset,
+    plan_move_asset, unreferenced_assets,
+};
+pub use block::{
+    delete_block, insert_block, move_block, page_blocks, plan_delete_block, plan_insert_block,
+    plan_move_block, plan_replace_block, replace_block,
+};
+pub(crate) use editor::editor_page_entries;
 pub use editor::{
-    editor_page_detail, list_editor_pages, set_page_title, update_editor_page, update_page_body,
+    editor_page_detail, list_editor_pages, plan_set_page_title, plan_update_editor_page,
+    plan_update_page_body, set_page_title, update_editor_page, update_page_body,
+};
+pub use fixture::{generate_fixture_project, plan_generate_fixture_project};
+pub use git::{
+    git_auto_commit_enabled, page_blame, page_git_history, plan_set_git_auto_commit,
+    set_git_auto_commit,
+};
+pub use history::{
+    disable_history, enable_history, history_diff, history_enabled, history_entry, history_log,
+    plan_undo_history, undo_history,
+};
+pub use import::{import_pages, plan_import_pages, preflight_import_pages};
+pub use lifecycle::{
+    demote_page, plan_demote_page, plan_promote_note, preflight_demote_page,
+    preflight_promote_note, promote_note,
 };
+pub use lock::{mutation_lock_owner, unlock_project};
+pub use mutation::MutationPlan;
 pub use page::{
     create_directory, create_page, delete_directory, delete_page, export_page, extract_page_text,
-    import_markdown, init_project, init_project_at, load_project_manifest, new_page,
-    preflight_delete_page, preflight_rename_page, read_page_source, rename_page, write_page_source,
+    import_markdown, init_project, init_project_at, load_project_manifest, new_page, page_section,
+    plan_create_directory, plan_create_page, plan_delete_directory, plan_delete_page,
+    plan_export_page, plan_import_markdown, plan_init_project, plan_init_project_at, plan_new_page,
+    plan_rename_page, plan_write_page_source, preflight_delete_page, preflight_rename_page,
+    read_page_source, rename_page, write_page_source,
 };
+pub(crate) use page::{plan_page_reference_rewrites, push_planned_page_rewrites};
+pub use repair::{apply_repair_suggestions, plan_apply_repair_suggestions, repair_suggestions};
 pub use summary::project_summary;
-pub use sync::sync_project;
+pub use sync::{plan_sync_project, sync_project};
+pub use watch::{ProjectWatcher, WatchBackend, WatchOptions};
@@ -1,21 +1,186 @@
-use crate::io::fs::atomic_write;
-use crate::project::constants::WORKSPACE_DIR;
-use crate::types::{OperationEvent, OperationReport};
+use crate::index::plan_build_index;
+use crate::io::diff::unified_diff;
+use crate::io::hash::content_hash;
+use crate::ops::git::{commit_operation, git_auto_commit_enabled};
+use crate::ops::history::{append_history_entry, history_enabled, store_history_objects};
+use crate::ops::journal::{has_interrupted_transaction, recover_interrupted_transaction, Journal};
+use crate::ops::lock::{configured_lock_timeout, ProjectLock};
+use crate::project::paths::resolve_existing_page;
+use crate::types::{FileChange, FileDiff, MutationPreview, OperationEvent, OperationReport};
 use crate::{FractalError, Result};
-use std::fs::{self, OpenOptions};
-use std::io::Write;
+use std::collections::BTreeMap;
+use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-pub(crate) const MUTATION_LOCK_FILE: &str = "mutation.lock";
-
-#[derive(Debug, Default)]
-pub(crate) struct MutationPlan {
+#[derive(Debug)]
+pub struct MutationPlan {
+    root: PathBuf,
     steps: Vec<MutationStep>,
+    expected_revisions: Vec<(PathBuf, Option<String>)>,
+    rebuilds_generated_data: bool,
+    undoes: Option<u64>,
+    lock_timeout: Option<Duration>,
 }
 
 impl MutationPlan {
-    pub(crate) fn new() -> Self {
-        Self::default()
+    pub(crate) fn new(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            steps: Vec::new(),
+            expected_revisions: Vec::new(),
+            rebuilds_generated_data: false,
+            undoes: None,
+            lock_timeout: None,
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    pub fn rebuilds_generated_data(&self) -> bool {
+        self.rebuilds_generated_data
+    }
+
+    pub fn with_lock_timeout(mut self, timeout: Duration) -> Self {
+        self.lock_timeout = Some(timeout);
+        self
+    }
+
+    pub fn expect_revision(
+        mut self,
+        page: impl AsRef<Path>,
+        revision: impl Into<String>,
+    ) -> Result<Self> {
+        let page = resolve_existing_page(&self.root, page.as_ref())?;
+        self.require_revision(page, revision)?;
+        Ok(self)
+    }
+
+    pub(crate) fn require_revision(
+        &mut self,
+        path: impl Into<PathBuf>,
+        revision: impl Into<String>,
+    ) -> Result<()> {
+        let path = path.into();
+        let revision = Some(revision.into());
+        ensure_revision(&path, revision.as_deref())?;
+        self.expected_revisions.push((path, revision));
+        Ok(())
+    }
+
+    pub(crate) fn require_optional_revision(
+        &mut self,
+        path: impl Into<PathBuf>,
+        revision: Option<&str>,
+    ) -> Result<()> {
+        match revision {
+            Some(revision) => self.require_revision(path, revision),
+            None => Ok(()),
+        }
+    }
+
+    pub(crate) fn require_absent(&mut self, path: impl Into<PathBuf>) -> Result<()> {
+        let path = path.into();
+        ensure_revision(&path, None)?;
+        self.expected_revisions.push((path, None));
+        Ok(())
+    }
+
+    pub(crate) fn record_undo_of(&mut self, id: u64) {
+        self.undoes = Some(id);
+    }
+
+    pub fn preview(&self) -> Result<MutationPreview> {
+        let (report, files) = self.simulate()?;
+        let report = report.relative_to(&self.root);
+        Ok(MutationPreview {
+            summary: report.summary(),
+            report,
+            diffs: files
+                .changes()?
+                .into_iter()
+                .map(|change| change.diff(&self.root))
+                .collect(),
+        })
+    }
+
+    fn simulate(&self) -> Result<(OperationReport, PreviewFiles)> {
+        let mut files = PreviewFiles::default();
+        let mut report = OperationReport::new();
+
+        for step in &self.steps {
+            match step {
+                MutationStep::Event(event) => report.push(event.clone()),
+                MutationStep::CreateDir { event, .. } => report.push(event.clone()),
+                MutationStep::EnsureDir { .. } => {}
+                MutationStep::WriteFile {
+                    path,
+                    contents,
+                    event,
+                } => {
+                    let changed = files.read(path)?.as_deref() != Some(contents.as_slice());
+                    files.set(path, Some(contents.clone()));
+                    match event {
+                        WriteEvent::Silent => {}
+                        WriteEvent::Always(event) => report.push(event.clone()),
+                        WriteEvent::IfChanged(event) if changed => report.push(event.clone()),
+                        WriteEvent::IfChanged(_) => {}
+                    }
+                }
+                MutationStep::MoveFile { from, to, event } => {
+                    let contents = files.read(from)?;
+                    files.set(from, None);
+                    files.set(to, contents);
+                    files.moves.insert(to.clone(), from.clone());
+                    report.push(event.clone());
+                }
+                MutationStep::RemoveFile { path, event } => {
+                    files.set(path, None);
+                    report.push(event.clone());
+                }
+                MutationStep::RemoveDir { path, event, .. } => {
+                    for file in files_under(path)? {
+                        files.set(&file, None);
+                    }
+                    report.push(event.clone());
+                }
+            }
+        }
+
+        Ok((report, files))
+    }
+
+    /// Applies the steps, rebuilds generated data and auto-commits under one
+    /// project lock, so no other mutation can land between them.
+    pub fn apply(self) -> Result<OperationReport> {
+        if self.steps.is_empty() && !self.rebuilds_generated_data {
+            return Ok(OperationReport::new());
+        }
+
+        let root = self.root.clone();
+        let rebuilds_generated_data = self.rebuilds_generated_data;
+        let _lock = ProjectLock::acquire(&root, self.lock_timeout())?;
+        let mut report = self.apply_locked()?;
+        if rebuilds_generated_data {
+            report.extend(plan_build_index(&root)?.apply_locked()?);
+        }
+        let mut report = report.relative_to(&root);
+        if git_auto_commit_enabled(&root) {
+            if let Some(event) = commit_operation(&root, &report) {
+                report.push(event);
+            }
+        }
+        Ok(report)
+    }
+
+    pub(crate) fn rebuild_generated_data(&mut self) {
+        self.rebuilds_generated_data = true;
     }
 
     pub(crate) fn event(&mut self, event: OperationEvent) {
@@ -101,34 +266,75 @@ impl MutationPlan {
         });
     }
 
-    pub(crate) fn apply(self, root: &Path) -> Result<OperationReport> {
+    pub(crate) fn apply_steps(self) -> Result<OperationReport> {
         if self.steps.is_empty() {
             return Ok(OperationReport::new());
         }
 
-        let _lock = ProjectLock::acquire(root)?;
-        self.apply_unlocked()
+        let _lock = ProjectLock::acquire(&self.root, self.lock_timeout())?;
+        self.apply_locked()
+    }
+
+    fn lock_timeout(&self) -> Duration {
+        self.lock_timeout
+            .unwrap_or_else(|| configured_lock_timeout(&self.root))
+    }
+
+    fn apply_locked(self) -> Result<OperationReport> {
+        if self.steps.is_empty() {
+            return Ok(OperationReport::new());
+        }
+
+        let root = self.root.clone();
+        recover_interrupted_transaction(&root)?;
+        for (path, revision) in &self.expected_revisions {
+            ensure_revision(path, revision.as_deref())?;
+        }
+        let history = if history_enabled(&root) {
+            Some(store_history_objects(&root, self.simulate()?.1.changes()?)?)
+        } else {
+            None
+        };
+        let undoes = self.undoes;
+
+        let mut journal = Journal::begin(&root)?;
+        match self.apply_journaled(&mut journal) {
+            Ok(report) => {
+                journal.commit()?;
+                if let Some(files) = history {
+                    append_history_entry(&root, files, undoes, &report)?;
+                }
+                Ok(report)
+            }
+            Err(error) => match journal.rollback() {
+                Ok(_) => Err(error),
+                Err(rollback_error) => Err(FractalError::new(
+                    error.code,
+                    format!("{error}; rollback failed: {rollback_error}"),
+                )),
+            },
+        }
     }
 
-    fn apply_unlocked(self) -> Result<OperationReport> {
+    fn apply_journaled(self, journal: &mut Journal) -> Result<OperationReport> {
         let mut report = OperationReport::new();
 
         for step in self.steps {
             match step {
                 MutationStep::Event(event) => report.push(event),
                 MutationStep::CreateDir { path, event } => {
-                    fs::create_dir(&path)?;
+                    journal.create_dir(&path)?;
                     report.push(event);
                 }
                 MutationStep::EnsureDir { path } => {
-                    fs::create_dir_all(path)?;
+                    journal.ensure_dir(&path)?;
                 }
                 MutationStep::WriteFile {
                     path,
                     contents,
                     event,
                 } => {
-                    let changed = atomic_write(&path, contents)?;
+                    let changed = journal.write_file(&path, &contents)?;
                     match event {
                         WriteEvent::Silent => {}
                         WriteEvent::Always(event) => report.push(event),
@@ -137,11 +343,11 @@ impl MutationPlan {
                     }
                 }
                 MutationStep::MoveFile { from, to, event } => {
-                    fs::rename(from, to)?;
+                    journal.move_file(&from, &to)?;
                     report.push(event);
                 }
                 MutationStep::RemoveFile { path, event } => {
-                    fs::remove_file(path)?;
+                    journal.remove_file(&path)?;
                     report.push(event);
                 }
                 MutationStep::RemoveDir {
@@ -149,11 +355,7 @@ impl MutationPlan {
                     recursive,
                     event,
                 } => {
-                    if recursive {
-                        fs::remove_dir_all(path)?;
-                    } else {
-                        fs::remove_dir(path)?;
-                    }
+                    journal.remove_dir(&path, recursive)?;
                     report.push(event);
                 }
             }
@@ -163,43 +365,200 @@ impl MutationPlan {
     }
 }
 
-struct ProjectLock {
+pub(crate) fn recover_interrupted_mutation(root: &Path) -> Result<()> {
+    if !has_interrupted_transaction(root) {
+        return Ok(());
+    }
+
+    let _lock = ProjectLock::acquire(root, configured_lock_timeout(root))?;
+    recover_interrupted_transaction(root)
+}
+
+#[derive(Debug, Default)]
+struct PreviewFiles {
+    contents: BTreeMap<PathBuf, Option<Vec<u8>>>,
+    touched: Vec<PathBuf>,
+    moves: BTreeMap<PathBuf, PathBuf>,
+}
+
+impl PreviewFiles {
+    fn read(&self, path: &Path) -> Result<Option<Vec<u8>>> {
+        match self.contents.get(path) {
+            Some(contents) => Ok(contents.clone()),
+            None => read_existing_file(path),
+        }
+    }
+
+    fn set(&mut self, path: &Path, contents: Option<Vec<u8>>) {
+        if !self.contents.contains_key(path) {
+            self.touched.push(path.to_path_buf());
+        }
+        self.contents.insert(path.to_path_buf(), contents);
+    }
+
+    fn changes(&self) -> Result<Vec<PlannedFileChange>> {
+        let mut moved_from = BTreeMap::new();
+        for (to, from) in &self.moves {
+            let source_removed = matches!(self.contents.get(from), Some(None));
+            if source_removed && read_existing_file(to)?.is_none() {
+                moved_from.insert(to.clone(), from.clone());
+            }
+        }
+
+        let mut changes = Vec::new();
+        for path in &self.touched {
+            if moved_from.values().any(|from| from == path) {
+                continue;
+            }
+
+            let after = self.contents.get(path).cloned().flatten();
+            if let Some(from) = moved_from.get(path) {
+                changes.push(PlannedFileChange {
+                    path: path.clone(),
+                    change: FileChange::Moved,
+                    from: Some(from.clone()),
+                    before: read_existing_file(from)?,
+                    after,
+                });
+                continue;
+            }
+
+            let before = read_existing_file(path)?;
+            let change = match (&before, &after) {
+                (None, None) => continue,
+                (Some(before), Some(after)) if before == after => continue,
+                (None, Some(_)) => FileChange::Created,
+                (Some(_), None) => FileChange::Deleted,
+                (Some(_), Some(_)) => FileChange::Modified,
+            };
+            changes.push(PlannedFileChange {
+                path: path.clone(),
+                change,
+                from: None,
+                before,
+                after,
+            });
+        }
+
+        Ok(changes)
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct PlannedFileChange {
+    pub(crate) path: PathBuf,
+    pub(crate) change: FileChange,
+    pub(crate) from: Option<PathBuf>,
+    pub(crate) before: Option<Vec<u8>>,
+    pub(crate) after: Option<Vec<u8>>,
+}
+
+impl PlannedFileChange {
+    fn diff(&self, root: &Path) -> FileDiff {
+        file_diff(
+            relative_diff_path(root, &self.path),
+            self.change,
+            self.from
+                .as_deref()
+                .map(|from| relative_diff_path(root, from)),
+            self.before.as_deref(),
+            self.after.as_deref(),
+        )
+    }
+}
+
+pub(crate) fn file_diff(
     path: PathBuf,
+    change: FileChange,
+    from: Option<PathBuf>,
+    before: Option<&[u8]>,
+    after: Option<&[u8]>,
+) -> FileDiff {
+    let label = diff_label(&path);
+    let old_label = match (&from, before) {
+        (Some(from), _) => format!("a/{}", diff_label(from)),
+        (None, Some(_)) => format!("a/{label}"),
+        (None, None) => "/dev/null".to_string(),
+    };
+    let new_label = if after.is_some() {
+        format!("b/{label}")
+    } else {
+        "/dev/null".to_string()
+    };
+    FileDiff {
+        diff: unified_diff(&old_label, &new_label, before, after),
+        path,
+        change,
+        from,
+    }
 }
 
-impl ProjectLock {
-    fn acquire(root: &Path) -> Result<Self> {
-        let path = root.join(WORKSPACE_DIR).join(MUTATION_LOCK_FILE);
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent)?;
-        }
-
-        let mut file = OpenOptions::new()
-            .write(true)
-            .create_new(true)
-            .open(&path)
-            .map_err(|error| {
-                if error.kind() == std::io::ErrorKind::AlreadyExists {
-                    FractalError::project_locked(format!(
-                        "project is locked for mutation: {}",
-                        path.display()
-                    ))
-                } else {
-                    error.into()
-                }
-            })?;
+/// Reads a text file once and returns its contents with the revision of exactly
+/// those bytes, so callers never pair a revision with a later read.
+pub(crate) fn read_revisioned(path: &Path) -> Result<(String, String)> {
+    let bytes = fs::read(path)?;
+    let revision = content_hash(&bytes);
+    Ok((String::from_utf8(bytes)?, revision))
+}
 
-        writeln!(file, "pid={}", std::process::id())?;
-        file.sync_all()?;
+fn ensure_revision(path: &Path, expected: Option<&str>) -> Result<()> {
+    let current = read_existing_file(path)?.map(|contents| content_hash(&contents));
+    match (expected, current) {
+        (Some(expected), Some(current)) if current != expected => Err(FractalError::conflict(
+            format!(
+                "{} changed since revision {expected}; current revision is {current}",
+                path.display()
+            ),
+            current,
+        )),
+        (Some(expected), None) => Err(FractalError::not_found(format!(
+            "{} no longer exists; expected revision {expected}",
+            path.display()
+        ))),
+        (None, Some(current)) => Err(FractalError::conflict(
+            format!("{} already exists with revision {current}", path.display()),
+            current,
+        )),
+        _ => Ok(()),
+    }
+}
 
-        Ok(Self { path })
+fn read_existing_file(path: &Path) -> Result<Option<Vec<u8>>> {
+    if path.is_file() {
+        Ok(Some(fs::read(path)?))
+    } else {
+        Ok(None)
     }
 }
 
-impl Drop for ProjectLock {
-    fn drop(&mut self) {
-        let _ = fs::remove_file(&self.path);
+fn files_under(path: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    if !path.is_dir() {
+        return Ok(files);
     }
+
+    let mut entries = fs::read_dir(path)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    entries.sort();
+    for entry in entries {
+        if entry.is_dir() {
+            files.extend(files_under(&entry)?);
+        } else {
+            files.push(entry);
+        }
+    }
+    Ok(files)
+}
+
+fn relative_diff_path(root: &Path, path: &Path) -> PathBuf {
+    path.strip_prefix(root)
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn diff_label(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
 }
 
 #[derive(Debug)]
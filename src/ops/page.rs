@@ -1,25 +1,29 @@
+use crate::document::page::extracted_text;
 use crate::document::render::{default_stylesheet, render_page_document, stylesheet_href};
 use crate::document::PageDocument;
 use crate::graph::build_project_graph;
 use crate::graph::links::{normalize_link_label, page_label_from_path, relative_href};
 use crate::index::ensure_page_labels_available_for;
-use crate::index::{build_index, build_project_index, ensure_page_labels_available};
-use crate::io::markdown::{html_to_markdown, markdown_to_html};
-use crate::ops::mutation::MutationPlan;
+use crate::index::{build_project_index, ensure_page_labels_available};
+use crate::io::import::markdown_page;
+use crate::io::markdown::html_to_markdown;
+use crate::ops::import::plan_imported_pages;
+use crate::ops::mutation::{read_revisioned, MutationPlan};
 use crate::project::constants::{
     MANIFEST_FILE, MANIFEST_VERSION, PAGES_DIR, STYLE_FILE, WORKSPACE_DIR,
 };
 use crate::project::paths::{
     collect_page_paths, is_html_path, load_manifest, page_destination_from_title,
     page_destination_in_directory, page_relative_path, resolve_directory_destination,
-    resolve_existing_page, resolve_page_destination,
+    resolve_existing_page, resolve_page_destination, split_section_selector,
 };
 use crate::types::{
     OperationEvent, OperationReport, PageCreate, PageDeletePreflight, PageRename,
-    PageRenamePreflight, PageSource, ProjectManifest, Theme,
+    PageRenamePreflight, PageSection, PageSource, ProjectManifest, Theme,
 };
 use crate::validation::validate_page_html_for_project;
 use crate::{FractalError, Result};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -27,10 +31,21 @@ pub fn init_project(project_name: &str) -> Result<OperationReport> {
     init_project_at(project_name, project_name)
 }
 
+pub fn plan_init_project(project_name: &str) -> Result<MutationPlan> {
+    plan_init_project_at(project_name, project_name)
+}
+
 pub fn init_project_at(
     root: impl AsRef<Path>,
     project_name: impl AsRef<str>,
 ) -> Result<OperationReport> {
+    plan_init_project_at(root, project_name)?.apply()
+}
+
+pub fn plan_init_project_at(
+    root: impl AsRef<Path>,
+    project_name: impl AsRef<str>,
+) -> Result<MutationPlan> {
     let root = root.as_ref();
     let project_name = project_name.as_ref();
     let workspace_dir = root.join(WORKSPACE_DIR);
@@ -49,9 +64,12 @@ pub fn init_project_at(
         version: MANIFEST_VERSION,
         default_page: String::new(),
         theme: Theme::default(),
+        git_auto_commit: false,
+        lock_timeout_ms: 0,
+        lint: BTreeMap::new(),
     };
 
-    let mut plan = MutationPlan::new();
+    let mut plan = MutationPlan::new(root);
     plan.ensure_dir(pages_dir);
     plan.ensure_dir(workspace_dir.clone());
     plan.write_silent(
@@ -66,7 +84,7 @@ pub fn init_project_at(
         path: root.to_path_buf(),
     });
 
-    Ok(plan.apply(root)?.relative_to(root))
+    Ok(plan)
 }
 
 pub fn load_project_manifest(root: impl AsRef<Path>) -> Result<ProjectManifest> {
@@ -78,6 +96,14 @@ pub fn create_directory(
     parent: impl AsRef<Path>,
     name: impl AsRef<str>,
 ) -> Result<OperationReport> {
+    plan_create_directory(root, parent, name)?.apply()
+}
+
+pub fn plan_create_directory(
+    root: impl AsRef<Path>,
+    parent: impl AsRef<Path>,
+    name: impl AsRef<str>,
+) -> Result<MutationPlan> {
     let root = root.as_ref();
     load_manifest(root)?;
 
@@ -104,15 +130,19 @@ pub fn create_directory(
         )));
     }
 
-    let mut plan = MutationPlan::new();
+    let mut plan = MutationPlan::new(root);
     plan.create_dir(
         destination.clone(),
         OperationEvent::DirectoryCreated { path: destination },
     );
-    Ok(plan.apply(root)?.relative_to(root))
+    Ok(plan)
 }
 
 pub fn create_page(root: impl AsRef<Path>, page: PageCreate) -> Result<OperationReport> {
+    plan_create_page(root, page)?.apply()
+}
+
+pub fn plan_create_page(root: impl AsRef<Path>, page: PageCreate) -> Result<MutationPlan> {
     let root = root.as_ref();
     let mut manifest = load_manifest(root)?;
     let title = normalize_page_title(&page.title)?;
@@ -138,7 +168,7 @@ pub fn create_page(root: impl AsRef<Path>, page: PageCreate) -> Result<Operation
     let relative_page_string = relative_page.to_string_lossy().replace('\\', "/");
     ensure_page_labels_available(root, &relative_page_string, &title)?;
 
-    let mut plan = MutationPlan::new();
+    let mut plan = MutationPlan::new(root);
     plan.write_always(
         destination.clone(),
         render_page_document(&title, "", manifest.theme, stylesheet_href(relative_page))
@@ -157,12 +187,15 @@ pub fn create_page(root: impl AsRef<Path>, page: PageCreate) -> Result<Operation
         );
     }
 
-    let mut report = plan.apply(root)?;
-    report.extend(build_index(root)?);
-    Ok(report.relative_to(root))
+    plan.rebuild_generated_data();
+    Ok(plan)
 }
 
 pub fn new_page(root: impl AsRef<Path>, page: impl AsRef<Path>) -> Result<OperationReport> {
+    plan_new_page(root, page)?.apply()
+}
+
+pub fn plan_new_page(root: impl AsRef<Path>, page: impl AsRef<Path>) -> Result<MutationPlan> {
     let page = page.as_ref();
     let directory = page
         .parent()
@@ -172,7 +205,7 @@ pub fn new_page(root: impl AsRef<Path>, page: impl AsRef<Path>) -> Result<Operat
         .and_then(|stem| stem.to_str())
         .map(str::to_string)
         .unwrap_or_else(|| page.to_string_lossy().into_owned());
-    create_page(
+    plan_create_page(
         root,
         PageCreate {
             directory: directory.map(Path::to_path_buf),
@@ -268,8 +301,21 @@ pub fn rename_page(
     page: impl AsRef<Path>,
     rename: PageRename,
 ) -> Result<OperationReport> {
+    plan_rename_page(root, page, rename)?.apply()
+}
+
+pub fn plan_rename_page(
+    root: impl AsRef<Path>,
+    page: impl AsRef<Path>,
+    rename: PageRename,
+) -> Result<MutationPlan> {
     let root = root.as_ref();
     let mut manifest = load_manifest(root)?;
+    let mut plan = MutationPlan::new(root);
+    if let Some(expected_revision) = rename.expected_revision.clone() {
+        let source = resolve_existing_page(root, page.as_ref())?;
+        plan.require_revision(source, expected_revision)?;
+    }
     let preflight = preflight_rename_page(root, page, rename)?;
     let html = fs::read_to_string(&preflight.source_path)?;
     let document = PageDocument::parse(&html);
@@ -281,6 +327,9 @@ pub fn rename_page(
         document.rewrite_relative_page_hrefs_for_move(
             &preflight.source_page,
             &preflight.destination_page,
+        ) + document.rewrite_relative_image_srcs_for_move(
+            &preflight.source_page,
+            &preflight.destination_page,
         )
     } else {
         0
@@ -304,7 +353,6 @@ pub fn rename_page(
         title_changed,
     )?;
 
-    let mut plan = MutationPlan::new();
     if let Some(parent) = preflight.destination_path.parent() {
         plan.ensure_dir(parent.to_path_buf());
     }
@@ -336,25 +384,22 @@ pub fn rename_page(
         });
     }
 
-    let mut report = plan.apply(root)?;
-    report.extend(apply_planned_page_rewrites(root, renamed_link_rewrites)?);
+    push_planned_page_rewrites(&mut plan, renamed_link_rewrites);
 
     if preflight.updates_default_page {
         manifest.default_page = format!("{PAGES_DIR}/{}", preflight.destination_page);
         let manifest_path = root.join(MANIFEST_FILE);
-        let mut manifest_plan = MutationPlan::new();
-        manifest_plan.write_always(
+        plan.write_always(
             manifest_path.clone(),
             serde_json::to_string_pretty(&manifest)?.into_bytes(),
             OperationEvent::ManifestUpdated {
                 path: manifest_path,
             },
         );
-        report.extend(manifest_plan.apply(root)?);
     }
 
-    report.extend(build_index(root)?);
-    Ok(report.relative_to(root))
+    plan.rebuild_generated_data();
+    Ok(plan)
 }
 
 pub fn preflight_delete_page(
@@ -396,13 +441,26 @@ pub fn preflight_delete_page(
     })
 }
 
-pub fn delete_page(root: impl AsRef<Path>, page: impl AsRef<Path>) -> Result<OperationReport> {
+pub fn delete_page(
+    root: impl AsRef<Path>,
+    page: impl AsRef<Path>,
+    expected_revision: Option<&str>,
+) -> Result<OperationReport> {
+    plan_delete_page(root, page, expected_revision)?.apply()
+}
+
+pub fn plan_delete_page(
+    root: impl AsRef<Path>,
+    page: impl AsRef<Path>,
+    expected_revision: Option<&str>,
+) -> Result<MutationPlan> {
     let root = root.as_ref();
     let mut manifest = load_manifest(root)?;
     let preflight = preflight_delete_page(root, page)?;
     let deleted_link_rewrites =
         plan_unwrap_deleted_page_links(root, std::slice::from_ref(&preflight.page))?;
-    let mut plan = MutationPlan::new();
+    let mut plan = MutationPlan::new(root);
+    plan.require_optional_revision(&preflight.path, expected_revision)?;
     plan.event(OperationEvent::PageLinkImpact {
         page: preflight.page.clone(),
         backlinks: preflight.backlinks,
@@ -415,25 +473,22 @@ pub fn delete_page(root: impl AsRef<Path>, page: impl AsRef<Path>) -> Result<Ope
         },
     );
 
-    let mut report = plan.apply(root)?;
-    report.extend(apply_planned_page_rewrites(root, deleted_link_rewrites)?);
+    push_planned_page_rewrites(&mut plan, deleted_link_rewrites);
 
     if let Some(default_page) = preflight.replacement_default_page {
         manifest.default_page = format!("{PAGES_DIR}/{default_page}");
         let manifest_path = root.join(MANIFEST_FILE);
-        let mut manifest_plan = MutationPlan::new();
-        manifest_plan.write_always(
+        plan.write_always(
             manifest_path.clone(),
             serde_json::to_string_pretty(&manifest)?.into_bytes(),
             OperationEvent::ManifestUpdated {
                 path: manifest_path,
             },
         );
-        report.extend(manifest_plan.apply(root)?);
     }
 
-    report.extend(build_index(root)?);
-    Ok(report.relative_to(root))
+    plan.rebuild_generated_data();
+    Ok(plan)
 }
 
 pub fn delete_directory(
@@ -441,6 +496,14 @@ pub fn delete_directory(
     directory: impl AsRef<Path>,
     recursive: bool,
 ) -> Result<OperationReport> {
+    plan_delete_directory(root, directory, recursive)?.apply()
+}
+
+pub fn plan_delete_directory(
+    root: impl AsRef<Path>,
+    directory: impl AsRef<Path>,
+    recursive: bool,
+) -> Result<MutationPlan> {
     let root = root.as_ref();
     let mut manifest = load_manifest(root)?;
     let relative_directory =
@@ -485,7 +548,7 @@ pub fn delete_directory(
         None
     };
 
-    let mut plan = MutationPlan::new();
+    let mut plan = MutationPlan::new(root);
     plan.remove_dir(
         directory_path.clone(),
         recursive,
@@ -499,85 +562,41 @@ pub fn delete_directory(
         });
     }
 
-    let mut report = plan.apply(root)?;
-    report.extend(apply_planned_page_rewrites(root, deleted_link_rewrites)?);
+    push_planned_page_rewrites(&mut plan, deleted_link_rewrites);
 
     if default_was_deleted {
         manifest.default_page = replacement_default_page
             .map(|path| format!("{PAGES_DIR}/{path}"))
             .unwrap_or_default();
         let manifest_path = root.join(MANIFEST_FILE);
-        let mut manifest_plan = MutationPlan::new();
-        manifest_plan.write_always(
+        plan.write_always(
             manifest_path.clone(),
             serde_json::to_string_pretty(&manifest)?.into_bytes(),
             OperationEvent::ManifestUpdated {
                 path: manifest_path,
             },
         );
-        report.extend(manifest_plan.apply(root)?);
     }
 
-    report.extend(build_index(root)?);
-    Ok(report.relative_to(root))
+    plan.rebuild_generated_data();
+    Ok(plan)
 }
 
 pub fn import_markdown(
     root: impl AsRef<Path>,
     source: impl AsRef<Path>,
 ) -> Result<OperationReport> {
-    let root = root.as_ref();
-    let manifest = load_manifest(root)?;
-
-    let source = source.as_ref();
-    if source.extension().and_then(|ext| ext.to_str()) != Some("md") {
-        return Err(FractalError::invalid_input(format!(
-            "expected a markdown file: {}",
-            source.display()
-        )));
-    }
-
-    let markdown = fs::read_to_string(source)?;
-    let stem = source
-        .file_stem()
-        .and_then(|stem| stem.to_str())
-        .ok_or_else(|| {
-            FractalError::invalid_input("could not derive page name from source file")
-        })?;
-    let destination = resolve_page_destination(root, Path::new(stem))?;
-    if destination.exists() {
-        return Err(FractalError::already_exists(format!(
-            "page already exists: {}",
-            destination.display()
-        )));
-    }
-
-    let (title, body) = markdown_to_html(stem, &markdown);
-    let relative_page = page_relative_path(root, &destination)?;
-    let relative_page_string = relative_page.to_string_lossy().replace('\\', "/");
-    ensure_page_labels_available(root, &relative_page_string, &title)?;
+    plan_import_markdown(root, source)?.apply()
+}
 
-    let mut plan = MutationPlan::new();
-    if let Some(parent) = destination.parent() {
-        plan.ensure_dir(parent.to_path_buf());
-    }
-    plan.write_always(
-        destination.clone(),
-        render_page_document(
-            &title,
-            &body,
-            manifest.theme,
-            stylesheet_href(&relative_page),
-        )
-        .into_bytes(),
-        OperationEvent::PageImported {
-            source: source.to_path_buf(),
-            destination,
-        },
-    );
-    let mut report = plan.apply(root)?;
-    report.extend(build_index(root)?);
-    Ok(report.relative_to(root))
+pub fn plan_import_markdown(
+    root: impl AsRef<Path>,
+    source: impl AsRef<Path>,
+) -> Result<MutationPlan> {
+    let root = root.as_ref();
+    load_manifest(root)?;
+    let page = markdown_page(source.as_ref())?;
+    plan_imported_pages(root, vec![page])
 }
 
 pub fn export_page(
@@ -585,13 +604,21 @@ pub fn export_page(
     page: impl AsRef<Path>,
     output: impl AsRef<Path>,
 ) -> Result<OperationReport> {
+    plan_export_page(root, page, output)?.apply()
+}
+
+pub fn plan_export_page(
+    root: impl AsRef<Path>,
+    page: impl AsRef<Path>,
+    output: impl AsRef<Path>,
+) -> Result<MutationPlan> {
     let root = root.as_ref();
     load_manifest(root)?;
 
     let page = resolve_existing_page(root, page.as_ref())?;
 
     let output = output.as_ref();
-    let mut plan = MutationPlan::new();
+    let mut plan = MutationPlan::new(root);
     if let Some(parent) = output
         .parent()
         .filter(|parent| !parent.as_os_str().is_empty())
@@ -599,16 +626,23 @@ pub fn export_page(
         plan.ensure_dir(parent.to_path_buf());
     }
 
-    let html = fs::read_to_string(&page)?;
+    let page_path = page_relative_path(root, &page)?
+        .to_string_lossy()
+        .replace('\\', "/");
+    let pages_dir = root.join(PAGES_DIR);
+    let document = PageDocument::from_path(&page)?;
+    document.refresh_embeds(&page_path, |source_page| {
+        fs::read_to_string(pages_dir.join(source_page)).ok()
+    })?;
     plan.write_if_changed(
         output.to_path_buf(),
-        html_to_markdown(&html).into_bytes(),
+        html_to_markdown(&document.to_html()?).into_bytes(),
         OperationEvent::PageExported {
             page,
             output: output.to_path_buf(),
         },
     );
-    Ok(plan.apply(root)?.relative_to(root))
+    Ok(plan)
 }
 
 pub fn read_page_source(root: impl AsRef<Path>, page: impl AsRef<Path>) -> Result<PageSource> {
@@ -618,16 +652,30 @@ pub fn read_page_source(root: impl AsRef<Path>, page: impl AsRef<Path>) -> Resul
     let page = resolve_existing_page(root, page.as_ref())?;
     let relative_page = page_relative_path(root, &page)?;
     let path = relative_page.to_string_lossy().replace('\\', "/");
-    let html = fs::read_to_string(page)?;
+    let (html, revision) = read_revisioned(&page)?;
 
-    Ok(PageSource { path, html })
+    Ok(PageSource {
+        path,
+        html,
+        revision,
+    })
 }
 
 pub fn write_page_source(
     root: impl AsRef<Path>,
     page: impl AsRef<Path>,
     html: impl AsRef<str>,
+    expected_revision: Option<&str>,
 ) -> Result<OperationReport> {
+    plan_write_page_source(root, page, html, expected_revision)?.apply()
+}
+
+pub fn plan_write_page_source(
+    root: impl AsRef<Path>,
+    page: impl AsRef<Path>,
+    html: impl AsRef<str>,
+    expected_revision: Option<&str>,
+) -> Result<MutationPlan> {
     let root = root.as_ref();
     load_manifest(root)?;
 
@@ -635,26 +683,59 @@ pub fn write_page_source(
     let relative_page = page_relative_path(root, &page)?;
     let page_path = relative_page.to_string_lossy().replace('\\', "/");
     validate_page_html_for_project(root, &page_path, html.as_ref())?;
-    let mut plan = MutationPlan::new();
+    let mut plan = MutationPlan::new(root);
+    plan.require_optional_revision(&page, expected_revision)?;
     plan.write_if_changed(
         page.clone(),
         html.as_ref().as_bytes().to_vec(),
         OperationEvent::PageSourceUpdated { page },
     );
 
-    let mut report = plan.apply(root)?;
-    report.extend(build_index(root)?);
-    Ok(report.relative_to(root))
+    plan.rebuild_generated_data();
+    Ok(plan)
 }
 
 pub fn extract_page_text(root: impl AsRef<Path>, page: impl AsRef<Path>) -> Result<String> {
     let root = root.as_ref();
     load_manifest(root)?;
-    let page_path = resolve_existing_page(root, page.as_ref())?;
-    PageDocument::from_path(&page_path)?.main_text()
+    let (page, anchor) = split_section_selector(page.as_ref())?;
+    let page_path = resolve_existing_page(root, &page)?;
+    let document = PageDocument::from_path(&page_path)?;
+    match anchor {
+        Some(anchor) => document.section_text(&anchor),
+        None => document.main_text(),
+    }
+}
+
+pub fn page_section(root: impl AsRef<Path>, selector: impl AsRef<Path>) -> Result<PageSection> {
+    let root = root.as_ref();
+    load_manifest(root)?;
+    let (page, anchor) = split_section_selector(selector.as_ref())?;
+    let anchor = anchor.ok_or_else(|| {
+        FractalError::invalid_input(format!(
+            "section selector must name a heading anchor: {}#<anchor>",
+            page.display()
+        ))
+    })?;
+    let page_path = resolve_existing_page(root, &page)?;
+    let document = PageDocument::from_path(&page_path)?;
+    let (heading, nodes) = document.section(&anchor)?;
+    let mut html = Vec::new();
+    for node in &nodes {
+        node.serialize(&mut html)?;
+    }
+
+    Ok(PageSection {
+        page: page_relative_path(root, &page_path)?
+            .to_string_lossy()
+            .replace('\\', "/"),
+        heading,
+        html: String::from_utf8(html)?,
+        text: extracted_text(&nodes),
+    })
 }
 
-fn normalize_page_title(title: &str) -> Result<String> {
+pub(crate) fn normalize_page_title(title: &str) -> Result<String> {
     let title = normalize_link_label(title);
     if title.is_empty() {
         return Err(FractalError::invalid_input("page title cannot be empty"));
@@ -662,16 +743,19 @@ fn normalize_page_title(title: &str) -> Result<String> {
     Ok(title)
 }
 
-fn manifest_default_page_path(root: &Path, manifest: &ProjectManifest) -> Result<String> {
+pub(crate) fn manifest_default_page_path(
+    root: &Path,
+    manifest: &ProjectManifest,
+) -> Result<String> {
     Ok(page_relative_path(root, Path::new(&manifest.default_page))?
         .to_string_lossy()
         .replace('\\', "/"))
 }
 
-struct PlannedPageRewrite {
-    page: PathBuf,
-    html: String,
-    count: usize,
+pub(crate) struct PlannedPageRewrite {
+    pub(crate) page: PathBuf,
+    pub(crate) html: String,
+    pub(crate) count: usize,
 }
 
 fn plan_unwrap_deleted_page_links(
@@ -694,7 +778,10 @@ fn plan_unwrap_deleted_page_links(
         let document = PageDocument::parse(&html);
         let updated = deleted_paths
             .iter()
-            .map(|deleted_path| document.unwrap_generated_page_hrefs(&page_path, deleted_path))
+            .map(|deleted_path| {
+                document.unwrap_page_embeds(&page_path, deleted_path)
+                    + document.unwrap_generated_page_hrefs(&page_path, deleted_path)
+            })
             .sum();
         if updated == 0 {
             continue;
@@ -710,6 +797,39 @@ fn plan_unwrap_deleted_page_links(
     Ok(planned)
 }
 
+pub(crate) fn plan_page_reference_rewrites(
+    root: &Path,
+    skipped_paths: &[&str],
+    mut rewrite: impl FnMut(&str, &PageDocument) -> usize,
+) -> Result<Vec<PlannedPageRewrite>> {
+    let pages_dir = root.join(PAGES_DIR);
+    let mut page_paths = Vec::new();
+    collect_page_paths(&pages_dir, &pages_dir, &mut page_paths)?;
+    page_paths.sort();
+
+    let mut planned = Vec::new();
+    for page_path in page_paths.into_iter().filter(|path| is_html_path(path)) {
+        if skipped_paths.contains(&page_path.as_str()) {
+            continue;
+        }
+
+        let page = pages_dir.join(&page_path);
+        let document = PageDocument::from_path(&page)?;
+        let count = rewrite(&page_path, &document);
+        if count == 0 {
+            continue;
+        }
+
+        planned.push(PlannedPageRewrite {
+            page,
+            html: document.to_html()?,
+            count,
+        });
+    }
+
+    Ok(planned)
+}
+
 fn plan_renamed_page_link_rewrites(
     root: &Path,
     source_path: &str,
@@ -761,12 +881,10 @@ fn plan_renamed_page_link_rewrites(
     Ok(planned)
 }
 
-fn apply_planned_page_rewrites(
-    root: &Path,
+pub(crate) fn push_planned_page_rewrites(
+    plan: &mut MutationPlan,
     rewrites: Vec<PlannedPageRewrite>,
-) -> Result<OperationReport> {
-    let mut plan = MutationPlan::new();
-
+) {
     for rewrite in rewrites {
         plan.write_if_changed(
             rewrite.page.clone(),
@@ -777,6 +895,4 @@ fn apply_planned_page_rewrites(
             },
         );
     }
-
-    plan.apply(root)
 }
@@ -0,0 +1,294 @@
+use crate::document::notes::{is_valid_note_id, note_id_from_trigger};
+use crate::document::page::is_element_named;
+use crate::document::sanitize::{has_only_inline_content, rename_element, unwrap_element};
+use crate::document::PageDocument;
+use crate::ops::mutation::{file_diff, read_revisioned, MutationPlan};
+use crate::ops::{plan_page_reference_rewrites, push_planned_page_rewrites};
+use crate::project::constants::PAGES_DIR;
+use crate::types::{Diagnostic, FileChange, OperationEvent, OperationReport, RepairSuggestion};
+use crate::validation::{
+    node_at_span, validation_report, CONTENT_DIRECT_CHILD, CONTENT_ELEMENT, CONTENT_EXTRA_H1,
+    NOTE_DUPLICATE_ID, NOTE_MALFORMED_ID,
+};
+use crate::{FractalError, Result};
+use brik::NodeRef;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RepairFix {
+    Unwrap,
+    DemoteHeading,
+    RenameNote { rewrite_links: bool },
+}
+
+/// The action taken by a fix, plus the old and new id of a note whose references must follow it.
+type AppliedFix = (String, Option<(String, String)>);
+
+pub fn repair_suggestions(root: impl AsRef<Path>) -> Result<Vec<RepairSuggestion>> {
+    let root = root.as_ref();
+    let mut suggestions = Vec::new();
+    for (page, diagnostics) in suggestible_diagnostics(root)? {
+        let source = fs::read_to_string(root.join(PAGES_DIR).join(&page))?;
+        for (id, diagnostic, fix) in diagnostics {
+            let document = PageDocument::parse(&source);
+            let Some(node) = diagnostic_node(&source, &document, &diagnostic) else {
+                continue;
+            };
+            let (action, _) = apply_fix(&page, &document, &node, fix)?;
+            let after = document.to_html()?;
+            suggestions.push(RepairSuggestion {
+                id,
+                diff: file_diff(
+                    Path::new(PAGES_DIR).join(&page),
+                    FileChange::Modified,
+                    None,
+                    Some(source.as_bytes()),
+                    Some(after.as_bytes()),
+                ),
+                diagnostic,
+                action,
+            });
+        }
+    }
+    Ok(suggestions)
+}
+
+pub fn apply_repair_suggestions(
+    root: impl AsRef<Path>,
+    ids: &[impl AsRef<str>],
+) -> Result<OperationReport> {
+    plan_apply_repair_suggestions(root, ids)?.apply()
+}
+
+pub fn plan_apply_repair_suggestions(
+    root: impl AsRef<Path>,
+    ids: &[impl AsRef<str>],
+) -> Result<MutationPlan> {
+    let root = root.as_ref();
+    if ids.is_empty() {
+        return Err(FractalError::invalid_input(
+            "no repair suggestions selected; pass suggestion IDs from `fractal project repair --suggest`",
+        ));
+    }
+
+    let mut accepted = ids
+        .iter()
+        .map(|id| id.as_ref().to_string())
+        .collect::<BTreeSet<_>>();
+    let mut selected = BTreeMap::new();
+    for (page, diagnostics) in suggestible_diagnostics(root)? {
+        let diagnostics = diagnostics
+            .into_iter()
+            .filter(|(id, _, _)| accepted.remove(id))
+            .collect::<Vec<_>>();
+        if !diagnostics.is_empty() {
+            selected.insert(page, diagnostics);
+        }
+    }
+    if let Some(id) = accepted.into_iter().next() {
+        return Err(FractalError::not_found(format!(
+            "unknown repair suggestion: {id}"
+        )));
+    }
+
+    let mut plan = MutationPlan::new(root);
+    let mut repaired = Vec::new();
+    let mut renamed_notes = Vec::new();
+    for (page, diagnostics) in selected {
+        let path = root.join(PAGES_DIR).join(&page);
+        let (source, revision) = read_revisioned(&path)?;
+        let document = PageDocument::parse(&source);
+        let targets = diagnostics
+            .into_iter()
+            .filter_map(|(id, diagnostic, fix)| {
+                diagnostic_node(&source, &document, &diagnostic).map(|node| (id, node, fix))
+            })
+            .collect::<Vec<_>>();
+
+        let mut events = Vec::new();
+        for (id, node, fix) in targets {
+            let (action, renamed) = apply_fix(&page, &document, &node, fix)?;
+            if let Some((old_id, new_id)) = renamed {
+                renamed_notes.push((page.clone(), old_id, new_id));
+            }
+            events.push(OperationEvent::RepairSuggestionApplied {
+                page: path.clone(),
+                id,
+                action,
+            });
+        }
+
+        plan.require_revision(path.clone(), revision)?;
+        repaired.push((page, path, document, events));
+    }
+
+    let rewrite_note_references = |page_path: &str, document: &PageDocument| {
+        renamed_notes
+            .iter()
+            .filter(|(host_page, _, _)| host_page != page_path)
+            .map(|(host_page, old_id, new_id)| {
+                document.rewrite_note_hrefs(page_path, host_page, old_id, new_id)
+            })
+            .sum::<usize>()
+    };
+    let reference_rewrites = if renamed_notes.is_empty() {
+        Vec::new()
+    } else {
+        let skipped = repaired
+            .iter()
+            .map(|(page, _, _, _)| page.as_str())
+            .collect::<Vec<_>>();
+        plan_page_reference_rewrites(root, &skipped, rewrite_note_references)?
+    };
+    for (page, path, document, events) in repaired {
+        let rewritten = rewrite_note_references(&page, &document);
+        plan.write_silent(path.clone(), document.to_html()?.into_bytes());
+        for event in events {
+            plan.event(event);
+        }
+        if rewritten > 0 {
+            plan.event(OperationEvent::PageLinksRewritten {
+                page: path,
+                count: rewritten,
+            });
+        }
+    }
+    push_planned_page_rewrites(&mut plan, reference_rewrites);
+    plan.rebuild_generated_data();
+    Ok(plan)
+}
+
+type PageSuggestions = BTreeMap<String, Vec<(String, Diagnostic, RepairFix)>>;
+
+fn suggestible_diagnostics(root: &Path) -> Result<PageSuggestions> {
+    let mut pages = PageSuggestions::new();
+    for diagnostic in validation_report(root)?.diagnostics {
+        let (Some(page), Some(span)) = (diagnostic.page.clone(), diagnostic.span) else {
+            continue;
+        };
+        let Some(fix) = suggested_fix(&diagnostic) else {
+            continue;
+        };
+        let id = format!("{}:{page}:{}:{}", diagnostic.rule, span.line, span.column);
+        pages.entry(page).or_default().push((id, diagnostic, fix));
+    }
+    Ok(pages)
+}
+
+fn suggested_fix(diagnostic: &Diagnostic) -> Option<RepairFix> {
+    let name = diagnostic_element_name(diagnostic)?;
+    let rule = diagnostic.rule.as_str();
+    if rule == CONTENT_EXTRA_H1.id {
+        Some(RepairFix::DemoteHeading)
+    } else if (rule == CONTENT_ELEMENT.id || rule == CONTENT_DIRECT_CHILD.id)
+        && matches!(name, "div" | "span")
+    {
+        Some(RepairFix::Unwrap)
+    } else if rule == NOTE_MALFORMED_ID.id {
+        Some(RepairFix::RenameNote {
+            rewrite_links: true,
+        })
+    } else if rule == NOTE_DUPLICATE_ID.id {
+        Some(RepairFix::RenameNote {
+            rewrite_links: false,
+        })
+    } else {
+        None
+    }
+}
+
+fn diagnostic_node(
+    source: &str,
+    document: &PageDocument,
+    diagnostic: &Diagnostic,
+) -> Option<NodeRef> {
+    let name = diagnostic_element_name(diagnostic)?;
+    node_at_span(source, document, name, diagnostic.span?.start)
+}
+
+fn diagnostic_element_name(diagnostic: &Diagnostic) -> Option<&str> {
+    let tag = diagnostic.node.as_deref()?.strip_prefix('<')?;
+    let end = tag
+        .find(|character: char| character.is_whitespace() || matches!(character, '/' | '>'))
+        .unwrap_or(tag.len());
+    Some(&tag[..end]).filter(|name| !name.is_empty())
+}
+
+fn apply_fix(
+    page: &str,
+    document: &PageDocument,
+    node: &NodeRef,
+    fix: RepairFix,
+) -> Result<AppliedFix> {
+    match fix {
+        RepairFix::DemoteHeading => {
+            rename_element(node, "h2")?;
+            Ok(("demote <h1> to <h2>".to_string(), None))
+        }
+        RepairFix::Unwrap if is_element_named(node, "div") && has_only_inline_content(node) => {
+            rename_element(node, "p")?;
+            Ok(("turn <div> with inline content into <p>".to_string(), None))
+        }
+        RepairFix::Unwrap => {
+            let name = node
+                .as_element()
+                .map(|element| element.name.local.to_string())
+                .unwrap_or_default();
+            unwrap_element(node);
+            Ok((format!("unwrap <{name}> and keep its content"), None))
+        }
+        RepairFix::RenameNote { rewrite_links } => rename_note(page, document, node, rewrite_links),
+    }
+}
+
+fn rename_note(
+    page: &str,
+    document: &PageDocument,
+    node: &NodeRef,
+    rewrite_links: bool,
+) -> Result<AppliedFix> {
+    let element = node
+        .as_element()
+        .ok_or_else(|| FractalError::invalid_input("note is not an element"))?;
+    let old_id = element
+        .attributes
+        .borrow()
+        .get("id")
+        .unwrap_or_default()
+        .to_string();
+    let taken = document
+        .notes()
+        .into_iter()
+        .map(|note| note.id)
+        .collect::<BTreeSet<_>>();
+
+    let base = if is_valid_note_id(&old_id) {
+        old_id.clone()
+    } else {
+        let lowered = old_id.to_lowercase();
+        let stem = ["note-", "note_", "note "]
+            .iter()
+            .find_map(|prefix| lowered.strip_prefix(prefix))
+            .unwrap_or(&lowered);
+        note_id_from_trigger(stem).unwrap_or_else(|_| "note-untitled".to_string())
+    };
+    let new_id = std::iter::once(base.clone())
+        .chain((2..).map(|suffix| format!("{base}-{suffix}")))
+        .find(|candidate| !taken.contains(candidate))
+        .unwrap_or(base);
+    element.attributes.borrow_mut().insert("id", new_id.clone());
+
+    if !rewrite_links {
+        return Ok((
+            format!("rename duplicate note id `{old_id}` to `{new_id}`"),
+            None,
+        ));
+    }
+    let links = document.rewrite_note_hrefs(page, page, &old_id, &new_id);
+    Ok((
+        format!("rename note id `{old_id}` to `{new_id}` and rewrite {links} note link(s)"),
+        Some((old_id, new_id)),
+    ))
+}
@@ -1,5 +1,6 @@
 use crate::graph::build_project_graph;
 use crate::index::build_project_index;
+use crate::ops::asset_entries;
 use crate::project::constants::{GRAPH_FILE, INDEX_FILE, MANIFEST_FILE, WORKSPACE_DIR};
 use crate::project::paths::load_manifest;
 use crate::types::ProjectSummary;
@@ -41,6 +42,15 @@ pub fn project_summary(root: impl AsRef<Path>) -> Result<ProjectSummary> {
                 .count()
         })
         .unwrap_or(0);
+    let unreferenced_asset_count = index
+        .as_ref()
+        .map(|index| {
+            asset_entries(index)
+                .iter()
+                .filter(|asset| asset.referenced_by.is_empty())
+                .count()
+        })
+        .unwrap_or(0);
     let note_count = index
         .as_ref()
         .map(|index| index.pages.iter().map(|page| page.notes.len()).sum())
@@ -74,6 +84,7 @@ pub fn project_summary(root: impl AsRef<Path>) -> Result<ProjectSummary> {
         file_count,
         page_count,
         asset_count,
+        unreferenced_asset_count,
         note_count,
         link_count,
         graph_node_count,
@@ -1,68 +1,87 @@
-use crate::document::html::{escape_html, escape_html_attribute, find_case_insensitive};
+use crate::document::embeds::{is_embed, is_inside_embed};
+use crate::document::html::{
+    escape_html, escape_html_attribute, find_case_insensitive, is_inline_formatting_element,
+};
 use crate::document::PageDocument;
 use crate::graph::links::{
     is_linkable_label, link_label_key, normalize_link_label, page_link_labels, relative_href,
 };
-use crate::index::{build_project_index, write_generated_project_data};
+use crate::index::build_project_index;
 use crate::ops::mutation::MutationPlan;
 use crate::project::constants::PAGES_DIR;
 use crate::types::{OperationEvent, OperationReport, ProjectIndex};
 use crate::{FractalError, Result};
 use brik::NodeRef;
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
 use std::path::Path;
 
 pub fn sync_project(root: impl AsRef<Path>) -> Result<OperationReport> {
+    plan_sync_project(root)?.apply()
+}
+
+pub fn plan_sync_project(root: impl AsRef<Path>) -> Result<MutationPlan> {
     let root = root.as_ref();
     let initial_index = build_project_index(root)?;
     let pages_dir = root.join(PAGES_DIR);
-    let mut planned_rewrites = Vec::new();
+    let mut synced_pages = BTreeMap::new();
 
     for page in &initial_index.pages {
-        let path = pages_dir.join(&page.path);
-        let html = fs::read_to_string(&path)?;
+        let html = fs::read_to_string(pages_dir.join(&page.path))?;
         let updated = sync_page_links(&html, &page.path, &initial_index)?;
-        if updated.html != html {
+        synced_pages.insert(page.path.clone(), (html, updated));
+    }
+
+    let mut planned_rewrites = Vec::new();
+    for (page_path, (html, updated)) in &synced_pages {
+        let document = PageDocument::parse(&updated.html);
+        let embeds_refreshed = document.refresh_embeds(page_path, |source_page| {
+            synced_pages
+                .get(source_page)
+                .map(|(_, source)| source.html.clone())
+        })?;
+        let synced_html = if embeds_refreshed > 0 {
+            document.to_html()?
+        } else {
+            updated.html.clone()
+        };
+        if synced_html != *html {
             planned_rewrites.push(PlannedSyncRewrite {
-                path,
-                html: updated.html,
-                links_written: updated.links_written,
+                path: pages_dir.join(page_path),
+                html: synced_html,
+                links_written: (updated.html != *html).then_some(updated.links_written),
+                embeds_refreshed,
             });
         }
     }
 
-    let mut plan = MutationPlan::new();
+    let mut plan = MutationPlan::new(root);
+    let pages_updated = planned_rewrites.len();
     for rewrite in planned_rewrites {
-        plan.write_if_changed(
-            rewrite.path.clone(),
-            rewrite.html.into_bytes(),
-            OperationEvent::PageLinksRewritten {
+        plan.write_silent(rewrite.path.clone(), rewrite.html.into_bytes());
+        if let Some(count) = rewrite.links_written {
+            plan.event(OperationEvent::PageLinksRewritten {
+                page: rewrite.path.clone(),
+                count,
+            });
+        }
+        if rewrite.embeds_refreshed > 0 {
+            plan.event(OperationEvent::EmbedsRefreshed {
                 page: rewrite.path,
-                count: rewrite.links_written,
-            },
-        );
+                count: rewrite.embeds_refreshed,
+            });
+        }
     }
-
-    let mut report = plan.apply(root)?;
-    let synced = report
-        .events
-        .iter()
-        .filter(|event| matches!(event, OperationEvent::PageLinksRewritten { .. }))
-        .count();
-
-    let final_index = build_project_index(root)?;
-    report.extend(write_generated_project_data(root, &final_index)?);
-    report.push(OperationEvent::SyncCompleted {
-        pages_updated: synced,
-    });
-    Ok(report.relative_to(root))
+    plan.event(OperationEvent::SyncCompleted { pages_updated });
+    plan.rebuild_generated_data();
+    Ok(plan)
 }
 
 struct PlannedSyncRewrite {
     path: std::path::PathBuf,
     html: String,
-    links_written: usize,
+    links_written: Option<usize>,
+    embeds_refreshed: usize,
 }
 
 pub(crate) fn sync_page_links(
@@ -71,13 +90,8 @@ pub(crate) fn sync_page_links(
     index: &ProjectIndex,
 ) -> Result<SyncPageLinks> {
     let document = PageDocument::parse(html);
-    let main = document
-        .document
-        .select_first("main")
-        .map_err(|_| FractalError::invalid_project("missing main section in page"))?
-        .as_node()
-        .clone();
-    let note_candidates = note_link_candidates(html);
+    let main = main_node(&document)?;
+    let note_candidates = note_link_candidates(&document);
     let project_candidates = project_link_candidates(index, page_path);
 
     unwrap_generated_links(&main);
@@ -95,8 +109,25 @@ pub(crate) struct SyncPageLinks {
     pub(crate) links_written: usize,
 }
 
-fn note_link_candidates(html: &str) -> Vec<LinkCandidate> {
-    let document = PageDocument::parse(html);
+/// Links note triggers in `<main>` to their notes, as `sync` would, without touching page links.
+pub(crate) fn link_note_triggers(document: &PageDocument) -> Result<usize> {
+    let main = main_node(document)?;
+    Ok(link_candidates_in_node(
+        &main,
+        &note_link_candidates(document),
+    ))
+}
+
+fn main_node(document: &PageDocument) -> Result<NodeRef> {
+    Ok(document
+        .document
+        .select_first("main")
+        .map_err(|_| FractalError::invalid_project("missing main section in page"))?
+        .as_node()
+        .clone())
+}
+
+fn note_link_candidates(document: &PageDocument) -> Vec<LinkCandidate> {
     let mut candidates = document
         .notes()
         .into_iter()
@@ -154,9 +185,17 @@ fn sort_link_candidates(candidates: &mut [LinkCandidate]) {
 
 fn unwrap_generated_links(root: &NodeRef) {
     let links = root
-        .select("a[data-fractal-link]")
+        .select("a[data-fractal-link=page], a[data-fractal-link=note]")
         .expect("static selector should parse")
+        .filter(|element| {
+            let attributes = element.attributes.borrow();
+            attributes.get("data-fractal-link") == Some("page")
+                || attributes
+                    .get("href")
+                    .is_some_and(|href| href.starts_with('#'))
+        })
         .map(|element| element.as_node().clone())
+        .filter(|link| !is_inside_embed(link))
         .collect::<Vec<_>>();
 
     for link in links {
@@ -173,61 +212,169 @@ fn link_candidates_in_node(root: &NodeRef, candidates: &[LinkCandidate]) -> usiz
         return 0;
     }
 
-    let text_nodes = root
-        .descendants()
-        .filter(|node| node.as_text().is_some() && !has_skipped_ancestor(node, root))
-        .collect::<Vec<_>>();
+    let mut runs = Vec::new();
+    let mut current = Vec::new();
+    collect_inline_runs(root, &mut runs, &mut current);
+    flush_inline_run(&mut runs, &mut current);
 
-    text_nodes
-        .into_iter()
-        .map(|text_node| link_text_node(&text_node, candidates))
+    runs.into_iter()
+        .map(|run| link_inline_run(&run, candidates))
         .sum()
 }
 
-fn has_skipped_ancestor(node: &NodeRef, root: &NodeRef) -> bool {
-    node.ancestors()
-        .take_while(|ancestor| ancestor != root)
-        .any(|ancestor| {
-            let Some(element) = ancestor.as_element() else {
-                return false;
-            };
-            matches!(
-                element.name.local.to_string().as_str(),
-                "a" | "code" | "pre" | "script" | "style" | "textarea"
-            )
-        })
+fn collect_inline_runs(node: &NodeRef, runs: &mut Vec<Vec<NodeRef>>, current: &mut Vec<NodeRef>) {
+    for child in node.children() {
+        if child.as_text().is_some() {
+            current.push(child);
+            continue;
+        }
+        let Some(element) = child.as_element() else {
+            continue;
+        };
+        let name = element.name.local.to_string();
+        if is_inline_formatting_element(&name) {
+            collect_inline_runs(&child, runs, current);
+            continue;
+        }
+
+        flush_inline_run(runs, current);
+        if !is_skipped_element(&name) && !is_embed(&child) {
+            collect_inline_runs(&child, runs, current);
+            flush_inline_run(runs, current);
+        }
+    }
 }
 
-fn link_text_node(text_node: &NodeRef, candidates: &[LinkCandidate]) -> usize {
-    let Some(text) = text_node.as_text() else {
-        return 0;
-    };
-    let text = text.borrow().clone();
+fn flush_inline_run(runs: &mut Vec<Vec<NodeRef>>, current: &mut Vec<NodeRef>) {
+    if !current.is_empty() {
+        runs.push(std::mem::take(current));
+    }
+}
+
+fn is_skipped_element(name: &str) -> bool {
+    matches!(
+        name,
+        "a" | "br" | "code" | "pre" | "script" | "style" | "textarea"
+    )
+}
+
+fn link_inline_run(run: &[NodeRef], candidates: &[LinkCandidate]) -> usize {
+    let texts = run
+        .iter()
+        .map(|node| {
+            node.as_text()
+                .map(|text| text.borrow().clone())
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>();
+    let text = texts.concat();
     let ranges = link_ranges(&text, candidates);
     if ranges.is_empty() {
         return 0;
     }
 
-    let links_written = ranges.len();
-    let mut offset = 0;
-    for range in ranges {
-        if range.start > offset {
-            text_node.insert_before(NodeRef::new_text(&text[offset..range.start]));
+    let pieces = split_run_at_ranges(run, &texts, &ranges);
+    ranges
+        .iter()
+        .filter(|range| {
+            let covered = pieces
+                .iter()
+                .filter(|(start, end, _)| *start >= range.start && *end <= range.end)
+                .map(|(_, _, node)| node.clone())
+                .collect::<Vec<_>>();
+            wrap_link_range(&covered, &range.candidate)
+        })
+        .count()
+}
+
+fn split_run_at_ranges(
+    run: &[NodeRef],
+    texts: &[String],
+    ranges: &[LinkRange],
+) -> Vec<(usize, usize, NodeRef)> {
+    let mut pieces = Vec::new();
+    let mut node_start = 0;
+
+    for (node, text) in run.iter().zip(texts) {
+        let node_end = node_start + text.len();
+        let mut cuts = ranges
+            .iter()
+            .flat_map(|range| [range.start, range.end])
+            .filter(|offset| *offset > node_start && *offset < node_end)
+            .map(|offset| offset - node_start)
+            .collect::<Vec<_>>();
+        cuts.sort_unstable();
+        cuts.dedup();
+
+        if cuts.is_empty() {
+            pieces.push((node_start, node_end, node.clone()));
+            node_start = node_end;
+            continue;
         }
 
-        text_node.insert_before(render_link_node(
-            &range.candidate.href,
-            range.candidate.scope,
-            &text[range.start..range.end],
-        ));
-        offset = range.end;
+        let mut offset = 0;
+        for cut in cuts.into_iter().chain([text.len()]) {
+            let piece = NodeRef::new_text(&text[offset..cut]);
+            node.insert_before(piece.clone());
+            pieces.push((node_start + offset, node_start + cut, piece));
+            offset = cut;
+        }
+        node.detach();
+        node_start = node_end;
+    }
+
+    pieces
+}
+
+fn wrap_link_range(covered: &[NodeRef], candidate: &LinkCandidate) -> bool {
+    let (Some(first), Some(last)) = (covered.first(), covered.last()) else {
+        return false;
+    };
+    let Some(parent) = first
+        .ancestors()
+        .find(|ancestor| last.ancestors().any(|other| other == *ancestor))
+    else {
+        return false;
+    };
+    let (Some(start), Some(end)) = (
+        child_containing(&parent, first),
+        child_containing(&parent, last),
+    ) else {
+        return false;
+    };
+    if first_text_descendant(&start).as_ref() != Some(first)
+        || last_text_descendant(&end).as_ref() != Some(last)
+    {
+        return false;
     }
 
-    if offset < text.len() {
-        text_node.insert_before(NodeRef::new_text(&text[offset..]));
+    let link = render_link_node(&candidate.href, candidate.scope, "");
+    start.insert_before(link.clone());
+    let mut next = Some(start);
+    while let Some(node) = next {
+        next = (node != end).then(|| node.next_sibling()).flatten();
+        link.append(node);
     }
-    text_node.detach();
-    links_written
+    true
+}
+
+fn child_containing(parent: &NodeRef, node: &NodeRef) -> Option<NodeRef> {
+    node.inclusive_ancestors()
+        .find(|ancestor| ancestor.parent().as_ref() == Some(parent))
+}
+
+fn first_text_descendant(node: &NodeRef) -> Option<NodeRef> {
+    node.inclusive_descendants().find(is_non_empty_text)
+}
+
+fn last_text_descendant(node: &NodeRef) -> Option<NodeRef> {
+    node.inclusive_descendants()
+        .filter(is_non_empty_text)
+        .last()
+}
+
+fn is_non_empty_text(node: &NodeRef) -> bool {
+    node.as_text().is_some_and(|text| !text.borrow().is_empty())
 }
 
 fn link_ranges(text: &str, candidates: &[LinkCandidate]) -> Vec<LinkRange> {
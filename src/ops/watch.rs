@@ -0,0 +1,359 @@
+use crate::index::{build_project_index, plan_generated_project_data, update_project_index};
+use crate::io::hash::content_hash;
+use crate::project::constants::{MANIFEST_FILE, PAGES_DIR};
+use crate::project::paths::{collect_page_paths, is_html_path, load_manifest};
+use crate::types::{OperationEvent, OperationReport, ProjectIndex};
+use crate::validation::validate_page_html_for_project;
+use crate::Result;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::ops::ControlFlow;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+const NOTIFY_TICK: Duration = Duration::from_millis(20);
+/// Coarsest mtime resolution we expect from a filesystem. A file modified this
+/// recently can be rewritten with the same length without its mtime moving.
+const MTIME_GRANULARITY: Duration = Duration::from_secs(2);
+
+type Snapshot = BTreeMap<PathBuf, FileStamp>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FileStamp {
+    len: u64,
+    modified: Option<SystemTime>,
+    /// Content hash, kept only while `(len, modified)` cannot be trusted alone.
+    revision: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WatchOptions {
+    pub debounce: Duration,
+    pub poll_interval: Duration,
+    pub validate: bool,
+    pub polling: bool,
+}
+
+impl Default for WatchOptions {
+    fn default() -> Self {
+        Self {
+            debounce: Duration::from_millis(200),
+            poll_interval: Duration::from_millis(500),
+            validate: false,
+            polling: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchBackend {
+    Inotify,
+    Polling,
+}
+
+pub struct ProjectWatcher {
+    root: PathBuf,
+    options: WatchOptions,
+    notifier: Option<Notifier>,
+    snapshot: Snapshot,
+    index: Option<ProjectIndex>,
+}
+
+impl ProjectWatcher {
+    pub fn new(root: impl AsRef<Path>, options: WatchOptions) -> Result<Self> {
+        let root = root.as_ref().to_path_buf();
+        load_manifest(&root)?;
+        let notifier = if options.polling {
+            None
+        } else {
+            Notifier::new(&root)
+        };
+        let snapshot = snapshot(&root, &Snapshot::new())?;
+
+        Ok(Self {
+            root,
+            options,
+            notifier,
+            snapshot,
+            index: None,
+        })
+    }
+
+    pub fn backend(&self) -> WatchBackend {
+        if self.notifier.is_some() {
+            WatchBackend::Inotify
+        } else {
+            WatchBackend::Polling
+        }
+    }
+
+    pub fn wait_for_changes(&mut self, timeout: Option<Duration>) -> Result<Vec<PathBuf>> {
+        let started = Instant::now();
+        let tick = if self.notifier.is_some() {
+            NOTIFY_TICK
+        } else {
+            self.options.poll_interval
+        };
+        let mut changed = BTreeSet::new();
+        let mut last_change = None;
+
+        loop {
+            let signalled = match &mut self.notifier {
+                Some(notifier) => notifier.signalled(&self.root),
+                None => true,
+            };
+            if signalled {
+                if let Ok(current) = snapshot(&self.root, &self.snapshot) {
+                    let paths = changed_paths(&self.snapshot, &current);
+                    if !paths.is_empty() {
+                        changed.extend(paths);
+                        last_change = Some(Instant::now());
+                    }
+                    self.snapshot = current;
+                }
+            }
+
+            match last_change {
+                Some(at) if at.elapsed() >= self.options.debounce => {
+                    return Ok(changed.into_iter().collect());
+                }
+                None if timeout.is_some_and(|timeout| started.elapsed() >= timeout) => {
+                    return Ok(Vec::new());
+                }
+                _ => thread::sleep(tick),
+            }
+        }
+    }
+
+    pub fn rescan(&mut self) -> Result<()> {
+        if let Some(notifier) = &mut self.notifier {
+            notifier.signalled(&self.root);
+        }
+        self.snapshot = snapshot(&self.root, &Snapshot::new())?;
+        self.index = None;
+        Ok(())
+    }
+
+    pub fn refresh(&mut self, changed: &[PathBuf]) -> OperationReport {
+        let mut report = OperationReport::new();
+        if !changed.is_empty() {
+            report.push(OperationEvent::FilesChanged {
+                paths: changed.to_vec(),
+            });
+        }
+
+        match self.rebuild(changed) {
+            Ok(rebuilt) => report.extend(rebuilt),
+            Err(error) => {
+                self.index = None;
+                report.push(OperationEvent::Warning {
+                    message: error.message,
+                });
+            }
+        }
+
+        if self.options.validate {
+            let pages_dir = self.root.join(PAGES_DIR);
+            for page in changed_pages(changed)
+                .into_iter()
+                .filter(|page| is_html_path(page))
+            {
+                let Ok(html) = fs::read_to_string(pages_dir.join(&page)) else {
+                    continue;
+                };
+                if let Err(error) = validate_page_html_for_project(&self.root, &page, &html) {
+                    report.push(OperationEvent::Warning {
+                        message: error.message,
+                    });
+                }
+            }
+        }
+
+        report.relative_to(&self.root)
+    }
+
+    pub fn run(
+        &mut self,
+        mut on_event: impl FnMut(OperationEvent) -> ControlFlow<()>,
+    ) -> Result<()> {
+        self.index = None;
+        let mut report = self.refresh(&[]);
+        loop {
+            for event in report.events {
+                if on_event(event).is_break() {
+                    return Ok(());
+                }
+            }
+            let changed = self.wait_for_changes(None)?;
+            report = self.refresh(&changed);
+        }
+    }
+
+    fn rebuild(&mut self, changed: &[PathBuf]) -> Result<OperationReport> {
+        let manifest_changed = changed.iter().any(|path| path == Path::new(MANIFEST_FILE));
+        let index = match &self.index {
+            Some(previous) if !manifest_changed => {
+                update_project_index(&self.root, previous, &changed_pages(changed))?
+            }
+            _ => build_project_index(&self.root)?,
+        };
+        let report = plan_generated_project_data(&self.root, &index)?.apply_steps()?;
+        self.index = Some(index);
+        Ok(report)
+    }
+}
+
+/// Stats every page and the manifest. Files modified within
+/// [`MTIME_GRANULARITY`] of the scan are also hashed, and a file hashed by the
+/// previous scan is hashed again while its `(len, mtime)` is unchanged, so a
+/// same-length rewrite inside one mtime tick still shows up as a change. Once a
+/// file's mtime is older than that window, `(len, mtime)` alone decides.
+fn snapshot(root: &Path, previous: &Snapshot) -> Result<Snapshot> {
+    let pages_dir = root.join(PAGES_DIR);
+    let mut pages = Vec::new();
+    if pages_dir.is_dir() {
+        collect_page_paths(&pages_dir, &pages_dir, &mut pages)?;
+    }
+
+    let now = SystemTime::now();
+    let mut snapshot = Snapshot::new();
+    for path in pages
+        .iter()
+        .map(|page| Path::new(PAGES_DIR).join(page))
+        .chain([PathBuf::from(MANIFEST_FILE)])
+    {
+        let full_path = root.join(&path);
+        let Ok(metadata) = fs::metadata(&full_path) else {
+            continue;
+        };
+        let len = metadata.len();
+        let modified = metadata.modified().ok();
+        let recent = modified.is_none_or(|modified| {
+            now.duration_since(modified)
+                .map_or(true, |age| age < MTIME_GRANULARITY)
+        });
+        let earlier = previous
+            .get(&path)
+            .filter(|stamp| stamp.len == len && stamp.modified == modified)
+            .and_then(|stamp| stamp.revision.as_deref());
+        let revision = if recent || earlier.is_some() {
+            let Ok(contents) = fs::read(&full_path) else {
+                continue;
+            };
+            let revision = content_hash(&contents);
+            (recent || earlier != Some(revision.as_str())).then_some(revision)
+        } else {
+            None
+        };
+        snapshot.insert(
+            path,
+            FileStamp {
+                len,
+                modified,
+                revision,
+            },
+        );
+    }
+    Ok(snapshot)
+}
+
+fn changed_paths(before: &Snapshot, after: &Snapshot) -> Vec<PathBuf> {
+    before
+        .keys()
+        .chain(after.keys())
+        .filter(|path| match (before.get(*path), after.get(*path)) {
+            (Some(before), Some(after)) => {
+                before.len != after.len
+                    || before.modified != after.modified
+                    || matches!(
+                        (&before.revision, &after.revision),
+                        (Some(before), Some(after)) if before != after
+                    )
+            }
+            (before, after) => before.is_some() != after.is_some(),
+        })
+        .cloned()
+        .collect()
+}
+
+fn changed_pages(changed: &[PathBuf]) -> BTreeSet<String> {
+    changed
+        .iter()
+        .filter_map(|path| path.strip_prefix(PAGES_DIR).ok())
+        .map(|path| path.to_string_lossy().replace('\\', "/"))
+        .filter(|path| !path.is_empty())
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+struct Notifier {
+    inotify: inotify::Inotify,
+    buffer: Vec<u8>,
+}
+
+#[cfg(target_os = "linux")]
+impl Notifier {
+    fn new(root: &Path) -> Option<Self> {
+        let mut notifier = Self {
+            inotify: inotify::Inotify::init().ok()?,
+            buffer: vec![0; 4096],
+        };
+        notifier.watch(root).ok()?;
+        Some(notifier)
+    }
+
+    fn watch(&mut self, root: &Path) -> std::io::Result<()> {
+        use inotify::WatchMask;
+
+        let mask = WatchMask::CREATE
+            | WatchMask::MODIFY
+            | WatchMask::CLOSE_WRITE
+            | WatchMask::ATTRIB
+            | WatchMask::DELETE
+            | WatchMask::MOVED_FROM
+            | WatchMask::MOVED_TO;
+        let mut watches = self.inotify.watches();
+        watches.add(root, mask)?;
+
+        let mut directories = vec![root.join(PAGES_DIR)];
+        while let Some(directory) = directories.pop() {
+            watches.add(&directory, mask)?;
+            for entry in fs::read_dir(&directory)? {
+                let entry = entry?;
+                if entry.file_type()?.is_dir() {
+                    directories.push(entry.path());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn signalled(&mut self, root: &Path) -> bool {
+        let mut signalled = false;
+        while let Ok(mut events) = self.inotify.read_events(&mut self.buffer) {
+            if events.next().is_none() {
+                break;
+            }
+            signalled = true;
+        }
+        if signalled {
+            let _ = self.watch(root);
+        }
+        signalled
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+struct Notifier;
+
+#[cfg(not(target_os = "linux"))]
+impl Notifier {
+    fn new(_root: &Path) -> Option<Self> {
+        None
+    }
+
+    fn signalled(&mut self, _root: &Path) -> bool {
+        true
+    }
+}
@@ -5,9 +5,11 @@ pub(crate) const GRAPH_FILE: &str = "graph.json";
 pub(crate) const STYLE_FILE: &str = "style.css";
 pub(crate) const PAGES_DIR: &str = "pages";
 pub(crate) const INDEX_PAGE: &str = "index.html";
-pub(crate) const DEFAULT_VERSION: &str = "0.1";
+pub(crate) const DEFAULT_VERSION: &str = "0.2";
+pub(crate) const LEGACY_VERSION: &str = "0.1";
 pub(crate) const DEFAULT_SUMMARY: &str = "";
 pub(crate) const DEFAULT_TAGS: &str = "";
 pub(crate) const MANIFEST_VERSION: u32 = 1;
 pub(crate) const INDEX_VERSION: u32 = 1;
 pub(crate) const GRAPH_VERSION: u32 = 1;
+pub(crate) const COMMAND_RESULT_SCHEMA: &str = "fractal.command_result.v1";
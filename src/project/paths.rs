@@ -13,6 +13,33 @@ pub(crate) fn file_kind(path: &str) -> &'static str {
     }
 }
 
+pub(crate) fn asset_mime_type(path: &str) -> &'static str {
+    let extension = Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase);
+    match extension.as_deref() {
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("svg") => "image/svg+xml",
+        Some("webp") => "image/webp",
+        Some("avif") => "image/avif",
+        Some("ico") => "image/x-icon",
+        Some("pdf") => "application/pdf",
+        Some("json") => "application/json",
+        Some("txt") => "text/plain",
+        Some("md") => "text/markdown",
+        Some("csv") => "text/csv",
+        Some("css") => "text/css",
+        Some("mp3") => "audio/mpeg",
+        Some("wav") => "audio/wav",
+        Some("mp4") => "video/mp4",
+        Some("webm") => "video/webm",
+        _ => "application/octet-stream",
+    }
+}
+
 pub(crate) fn is_html_path(path: &str) -> bool {
     Path::new(path).extension().and_then(|ext| ext.to_str()) == Some("html")
 }
@@ -104,6 +131,19 @@ pub(crate) fn resolve_existing_page(root: &Path, page: &Path) -> Result<PathBuf>
     Ok(destination)
 }
 
+pub(crate) fn split_section_selector(page: &Path) -> Result<(PathBuf, Option<String>)> {
+    let Some((path, anchor)) = page.to_str().and_then(|page| page.rsplit_once('#')) else {
+        return Ok((page.to_path_buf(), None));
+    };
+    if anchor.is_empty() {
+        return Err(FractalError::invalid_input(format!(
+            "section selector is missing a heading anchor: {}",
+            page.display()
+        )));
+    }
+    Ok((PathBuf::from(path), Some(anchor.to_string())))
+}
+
 pub(crate) fn page_relative_path(root: &Path, page: &Path) -> Result<PathBuf> {
     let resolved = resolve_page_reference(root, page)?;
     Ok(resolved.strip_prefix(root.join(PAGES_DIR))?.to_path_buf())
@@ -123,6 +163,83 @@ fn resolve_page_reference(root: &Path, page: &Path) -> Result<PathBuf> {
     Ok(root.join(PAGES_DIR).join(relative))
 }
 
+pub(crate) fn resolve_asset_destination(root: &Path, asset: &Path) -> Result<PathBuf> {
+    let relative = normalize_asset_relative_path(root, asset)?;
+    Ok(root.join(PAGES_DIR).join(relative))
+}
+
+pub(crate) fn resolve_existing_asset(root: &Path, asset: &Path) -> Result<PathBuf> {
+    let destination = resolve_asset_destination(root, asset)?;
+    if !destination.is_file() {
+        return Err(FractalError::not_found(format!(
+            "asset does not exist: {}",
+            destination.display()
+        )));
+    }
+    Ok(destination)
+}
+
+pub(crate) fn asset_relative_path(root: &Path, asset: &Path) -> Result<String> {
+    Ok(normalize_asset_relative_path(root, asset)?
+        .to_string_lossy()
+        .replace('\\', "/"))
+}
+
+fn normalize_asset_relative_path(root: &Path, asset: &Path) -> Result<PathBuf> {
+    let asset = if asset.is_absolute() {
+        asset
+            .strip_prefix(root.join(PAGES_DIR))
+            .map_err(|_| FractalError::invalid_input("asset path must be inside pages/"))?
+    } else {
+        asset
+    };
+
+    let mut components = asset.components().peekable();
+    if matches!(
+        components.peek(),
+        Some(Component::Normal(prefix)) if prefix.to_str() == Some(PAGES_DIR)
+    ) {
+        components.next();
+    }
+
+    let mut relative = PathBuf::new();
+    for component in components {
+        match component {
+            Component::Normal(part) => {
+                if part.to_str().is_none() {
+                    return Err(FractalError::invalid_input(
+                        "asset path components must be valid UTF-8",
+                    ));
+                }
+                relative.push(part);
+            }
+            Component::CurDir => {}
+            Component::ParentDir => {
+                return Err(FractalError::invalid_input(
+                    "asset path cannot contain `..`",
+                ));
+            }
+            Component::RootDir | Component::Prefix(_) => {
+                return Err(FractalError::invalid_input(
+                    "asset path must be relative to pages/",
+                ));
+            }
+        }
+    }
+
+    if relative.as_os_str().is_empty() {
+        return Err(FractalError::invalid_input("asset path cannot be empty"));
+    }
+
+    if is_html_path(&relative.to_string_lossy()) {
+        return Err(FractalError::invalid_input(
+            "asset path cannot end in .html; use page commands for pages",
+        ));
+    }
+
+    Ok(relative)
+}
+
 pub(crate) fn normalize_page_directory_path(directory: &Path) -> Result<PathBuf> {
     if directory.is_absolute() {
         return Err(FractalError::invalid_input(
@@ -0,0 +1,779 @@
+use crate::document::notes::{plan_add_note, plan_patch_note, plan_remove_note, plan_rename_note};
+use crate::graph::{
+    build_project_graph, external_links_in, graph_page_in, neighbor_pages_in,
+    normalize_graph_page_path, orphan_pages_in, page_notes_in, related_pages_for,
+    shortest_page_path_in,
+};
+use crate::index::search::search_index;
+use crate::index::{build_project_index, plan_build_index};
+use crate::lint::{lint_project_in, LINT_RULES};
+use crate::ops::{
+    asset_entries, disable_history, editor_page_detail, editor_page_entries, enable_history,
+    history_diff, history_entry, history_log, page_blocks, page_section, plan_add_asset,
+    plan_delete_asset, plan_delete_block, plan_delete_page, plan_demote_page, plan_import_markdown,
+    plan_import_pages, plan_insert_block, plan_move_asset, plan_move_block, plan_new_page,
+    plan_promote_note, plan_rename_page, plan_replace_block, plan_sync_project, plan_undo_history,
+    plan_update_editor_page, project_summary, read_page_source, MutationPlan, ProjectWatcher,
+};
+use crate::project::paths::{load_manifest, split_section_selector};
+use crate::types::{
+    BlockPosition, CommandResult, ContextPageData, EditorPageUpdate, ImportFormat, OperationReport,
+    PageRename, ProjectGraph, ProjectIndex, ReportData,
+};
+use crate::validation::{validate_project, validation_report};
+use crate::{FractalError, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+pub const FRACTAL_ERROR: i64 = -32000;
+pub const CHANGED_NOTIFICATION: &str = "fractal.changed";
+
+const WATCH_TICK: Duration = Duration::from_millis(50);
+
+pub const RPC_METHODS: &[&str] = &[
+    "server.methods",
+    "project.summary",
+    "project.validate",
+    "project.sync",
+    "lint",
+    "index.build",
+    "page.list",
+    "page.read",
+    "page.source",
+    "page.create",
+    "page.set",
+    "page.move",
+    "page.delete",
+    "page.demote",
+    "page.block.list",
+    "page.block.insert",
+    "page.block.replace",
+    "page.block.move",
+    "page.block.delete",
+    "note.add",
+    "note.remove",
+    "note.set",
+    "note.promote",
+    "note.rename",
+    "asset.list",
+    "asset.add",
+    "asset.move",
+    "asset.delete",
+    "history.enable",
+    "history.disable",
+    "history.log",
+    "history.show",
+    "history.diff",
+    "history.undo",
+    "import.markdown",
+    "import.notion",
+    "import.logseq",
+    "search.text",
+    "graph.page",
+    "graph.backlinks",
+    "graph.outlinks",
+    "graph.related",
+    "graph.neighbors",
+    "graph.path",
+    "graph.notes",
+    "graph.orphans",
+    "link.external",
+    "context.page",
+];
+
+pub struct RpcServer {
+    root: PathBuf,
+    cache: Option<(ProjectIndex, ProjectGraph)>,
+    notifications: Vec<Value>,
+}
+
+#[derive(Debug)]
+pub(crate) struct RpcError {
+    pub(crate) code: i64,
+    pub(crate) message: String,
+    pub(crate) data: Option<FractalError>,
+}
+
+impl From<FractalError> for RpcError {
+    fn from(error: FractalError) -> Self {
+        Self {
+            code: FRACTAL_ERROR,
+            message: error.message.clone(),
+            data: Some(error),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct DryRunParams {
+    #[serde(default)]
+    dry_run: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct ValidateParams {
+    #[serde(default)]
+    fail_fast: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct PageParams {
+    page: PathBuf,
+    #[serde(default)]
+    dry_run: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct LintParams {
+    #[serde(default)]
+    list_rules: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct PageDeleteParams {
+    page: PathBuf,
+    expected_revision: Option<String>,
+    #[serde(default)]
+    dry_run: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct NeighborParams {
+    page: PathBuf,
+    #[serde(default = "default_depth")]
+    depth: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct PathParams {
+    from: PathBuf,
+    to: PathBuf,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContextParams {
+    page: PathBuf,
+    budget: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchParams {
+    query: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PageSetParams {
+    page: PathBuf,
+    #[serde(flatten)]
+    update: EditorPageUpdate,
+    #[serde(default)]
+    dry_run: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct PageMoveParams {
+    page: PathBuf,
+    #[serde(flatten)]
+    rename: PageRename,
+    #[serde(default)]
+    dry_run: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct PageDemoteParams {
+    page: PathBuf,
+    into: PathBuf,
+    expected_revision: Option<String>,
+    #[serde(default)]
+    dry_run: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct BlockParams {
+    page: PathBuf,
+    block: Option<String>,
+    html: Option<String>,
+    before: Option<String>,
+    after: Option<String>,
+    #[serde(default)]
+    start: bool,
+    expected_revision: Option<String>,
+    #[serde(default)]
+    dry_run: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct NoteParams {
+    page: PathBuf,
+    trigger: String,
+    content: Option<String>,
+    expected_revision: Option<String>,
+    #[serde(default)]
+    dry_run: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct AssetListParams {
+    #[serde(default)]
+    unreferenced: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct AssetAddParams {
+    source: PathBuf,
+    to: Option<PathBuf>,
+    #[serde(default)]
+    dry_run: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct AssetMoveParams {
+    asset: PathBuf,
+    to: PathBuf,
+    #[serde(default)]
+    dry_run: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct AssetDeleteParams {
+    asset: PathBuf,
+    #[serde(default)]
+    force: bool,
+    #[serde(default)]
+    dry_run: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct HistoryLogParams {
+    page: Option<PathBuf>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HistoryEntryParams {
+    id: u64,
+    #[serde(default)]
+    dry_run: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct ImportParams {
+    source: PathBuf,
+    #[serde(default)]
+    dry_run: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct NotePromoteParams {
+    page: PathBuf,
+    note: String,
+    title: String,
+    expected_revision: Option<String>,
+    #[serde(default)]
+    dry_run: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct NoteRenameParams {
+    page: PathBuf,
+    note: String,
+    to: String,
+    expected_revision: Option<String>,
+    #[serde(default)]
+    dry_run: bool,
+}
+
+fn default_depth() -> usize {
+    1
+}
+
+impl RpcServer {
+    pub fn new(root: impl AsRef<Path>) -> Result<Self> {
+        let root = root.as_ref().to_path_buf();
+        load_manifest(&root)?;
+        Ok(Self {
+            root,
+            cache: None,
+            notifications: Vec::new(),
+        })
+    }
+
+    pub fn handle(&mut self, message: &str) -> Option<Value> {
+        let request = match serde_json::from_str::<Value>(message) {
+            Ok(request) => request,
+            Err(error) => {
+                return Some(error_response(
+                    Value::Null,
+                    RpcError {
+                        code: PARSE_ERROR,
+                        message: error.to_string(),
+                        data: None,
+                    },
+                ));
+            }
+        };
+        let id = request.get("id").cloned();
+        let Some(method) = request.get("method").and_then(Value::as_str) else {
+            return Some(error_response(
+                id.unwrap_or(Value::Null),
+                RpcError {
+                    code: INVALID_REQUEST,
+                    message: "request is missing a method".to_string(),
+                    data: None,
+                },
+            ));
+        };
+        let params = request.get("params").cloned().unwrap_or_else(|| json!({}));
+
+        let result = self.dispatch(method, params);
+        let id = id?;
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(error) => error_response(id, error),
+        })
+    }
+
+    pub fn take_notifications(&mut self) -> Vec<Value> {
+        std::mem::take(&mut self.notifications)
+    }
+
+    pub fn serve(
+        &mut self,
+        input: impl BufRead + Send + 'static,
+        mut output: impl Write,
+        mut watcher: Option<ProjectWatcher>,
+    ) -> Result<()> {
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            for line in input.lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        loop {
+            match receiver.recv_timeout(WATCH_TICK) {
+                Ok(line) => {
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    if let Some(response) = self.handle(&line) {
+                        write_message(&mut output, &response)?;
+                    }
+                    if !self.notifications.is_empty() {
+                        if let Some(watcher) = &mut watcher {
+                            watcher.rescan()?;
+                        }
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    if let Some(watcher) = &mut watcher {
+                        let changed = watcher.wait_for_changes(Some(Duration::ZERO))?;
+                        if !changed.is_empty() {
+                            let report = watcher.refresh(&changed);
+                            self.push_change("watch", report);
+                        }
+                    }
+                }
+                Err(RecvTimeoutError::Disconnected) => return Ok(()),
+            }
+
+            for notification in self.take_notifications() {
+                write_message(&mut output, &notification)?;
+            }
+        }
+    }
+
+    pub(crate) fn dispatch(
+        &mut self,
+        method: &str,
+        params: Value,
+    ) -> std::result::Result<Value, RpcError> {
+        let root = self.root.clone();
+        let value = match method {
+            "server.methods" => data(RPC_METHODS)?,
+            "project.summary" => data(project_summary(&root)?)?,
+            "project.validate" => {
+                let params: ValidateParams = parse_params(params)?;
+                if params.fail_fast {
+                    data(ReportData::new(validate_project(&root)?))?
+                } else {
+                    data(validation_report(&root)?)?
+                }
+            }
+            "lint" => {
+                let params: LintParams = parse_params(params)?;
+                if params.list_rules {
+                    data(LINT_RULES)?
+                } else {
+                    let manifest = load_manifest(&root)?;
+                    let (index, graph) = self.cache()?;
+                    data(lint_project_in(&root, &manifest, index, graph)?)?
+                }
+            }
+            "project.sync" => {
+                let params: DryRunParams = parse_params(params)?;
+                self.apply(method, plan_sync_project(&root)?, params.dry_run)?
+            }
+            "index.build" => {
+                let params: DryRunParams = parse_params(params)?;
+                self.apply(method, plan_build_index(&root)?, params.dry_run)?
+            }
+            "page.list" => {
+                let (index, graph) = self.cache()?;
+                data(editor_page_entries(index, graph)?)?
+            }
+            "page.read" => {
+                let params: PageParams = parse_params(params)?;
+                data(editor_page_detail(&root, &params.page)?)?
+            }
+            "page.source" => {
+                let params: PageParams = parse_params(params)?;
+                data(read_page_source(&root, &params.page)?)?
+            }
+            "page.create" => {
+                let params: PageParams = parse_params(params)?;
+                self.apply(method, plan_new_page(&root, &params.page)?, params.dry_run)?
+            }
+            "page.set" => {
+                let params: PageSetParams = parse_params(params)?;
+                let plan = plan_update_editor_page(&root, &params.page, params.update)?;
+                self.apply(method, plan, params.dry_run)?
+            }
+            "page.move" => {
+                let params: PageMoveParams = parse_params(params)?;
+                let plan = plan_rename_page(&root, &params.page, params.rename)?;
+                self.apply(method, plan, params.dry_run)?
+            }
+            "page.delete" => {
+                let params: PageDeleteParams = parse_params(params)?;
+                let plan =
+                    plan_delete_page(&root, &params.page, params.expected_revision.as_deref())?;
+                self.apply(method, plan, params.dry_run)?
+            }
+            "page.demote" => {
+                let params: PageDemoteParams = parse_params(params)?;
+                let plan = plan_demote_page(
+                    &root,
+                    &params.page,
+                    &params.into,
+                    params.expected_revision.as_deref(),
+                )?;
+                self.apply(method, plan, params.dry_run)?
+            }
+            "page.block.list" => {
+                let params: PageParams = parse_params(params)?;
+                data(page_blocks(&root, &params.page)?)?
+            }
+            "page.block.insert" | "page.block.replace" | "page.block.move"
+            | "page.block.delete" => {
+                let params: BlockParams = parse_params(params)?;
+                let required = |value: Option<String>, name: &str| {
+                    value.ok_or_else(|| RpcError {
+                        code: INVALID_PARAMS,
+                        message: format!("{method} requires `{name}`"),
+                        data: None,
+                    })
+                };
+                let expected_revision = params.expected_revision.as_deref();
+                let plan = match method {
+                    "page.block.insert" => plan_insert_block(
+                        &root,
+                        &params.page,
+                        BlockPosition::from_flags(params.before, params.after, params.start)?,
+                        &required(params.html, "html")?,
+                        expected_revision,
+                    )?,
+                    "page.block.replace" => plan_replace_block(
+                        &root,
+                        &params.page,
+                        &required(params.block, "block")?,
+                        &required(params.html, "html")?,
+                        expected_revision,
+                    )?,
+                    "page.block.move" => plan_move_block(
+                        &root,
+                        &params.page,
+                        &required(params.block, "block")?,
+                        BlockPosition::from_flags(params.before, params.after, params.start)?,
+                        expected_revision,
+                    )?,
+                    _ => plan_delete_block(
+                        &root,
+                        &params.page,
+                        &required(params.block, "block")?,
+                        expected_revision,
+                    )?,
+                };
+                self.apply(method, plan, params.dry_run)?
+            }
+            "note.add" | "note.set" => {
+                let params: NoteParams = parse_params(params)?;
+                let content = params.content.ok_or_else(|| RpcError {
+                    code: INVALID_PARAMS,
+                    message: format!("{method} requires `content`"),
+                    data: None,
+                })?;
+                let expected_revision = params.expected_revision.as_deref();
+                let plan = if method == "note.add" {
+                    plan_add_note(
+                        &root,
+                        &params.page,
+                        &params.trigger,
+                        &content,
+                        expected_revision,
+                    )?
+                } else {
+                    plan_patch_note(
+                        &root,
+                        &params.page,
+                        &params.trigger,
+                        &content,
+                        expected_revision,
+                    )?
+                };
+                self.apply(method, plan, params.dry_run)?
+            }
+            "note.remove" => {
+                let params: NoteParams = parse_params(params)?;
+                let plan = plan_remove_note(
+                    &root,
+                    &params.page,
+                    &params.trigger,
+                    params.expected_revision.as_deref(),
+                )?;
+                self.apply(method, plan, params.dry_run)?
+            }
+            "note.promote" => {
+                let params: NotePromoteParams = parse_params(params)?;
+                let plan = plan_promote_note(
+                    &root,
+                    &params.page,
+                    &params.note,
+                    &params.title,
+                    params.expected_revision.as_deref(),
+                )?;
+                self.apply(method, plan, params.dry_run)?
+            }
+            "note.rename" => {
+                let params: NoteRenameParams = parse_params(params)?;
+                let plan = plan_rename_note(
+                    &root,
+                    &params.page,
+                    &params.note,
+                    &params.to,
+                    params.expected_revision.as_deref(),
+                )?;
+                self.apply(method, plan, params.dry_run)?
+            }
+            "asset.list" => {
+                let params: AssetListParams = parse_params(params)?;
+                let (index, _) = self.cache()?;
+                let mut assets = asset_entries(index);
+                if params.unreferenced {
+                    assets.retain(|asset| asset.referenced_by.is_empty());
+                }
+                data(assets)?
+            }
+            "asset.add" => {
+                let params: AssetAddParams = parse_params(params)?;
+                let destination = match params.to {
+                    Some(to) => to,
+                    None => params
+                        .source
+                        .file_name()
+                        .map(PathBuf::from)
+                        .ok_or_else(|| {
+                            FractalError::invalid_input("asset source must name a file")
+                        })?,
+                };
+                let plan = plan_add_asset(&root, &params.source, &destination)?;
+                self.apply(method, plan, params.dry_run)?
+            }
+            "asset.move" => {
+                let params: AssetMoveParams = parse_params(params)?;
+                let plan = plan_move_asset(&root, &params.asset, &params.to)?;
+                self.apply(method, plan, params.dry_run)?
+            }
+            "asset.delete" => {
+                let params: AssetDeleteParams = parse_params(params)?;
+                let plan = plan_delete_asset(&root, &params.asset, params.force)?;
+                self.apply(method, plan, params.dry_run)?
+            }
+            "history.enable" => data(ReportData::new(enable_history(&root)?))?,
+            "history.disable" => data(ReportData::new(disable_history(&root)?))?,
+            "history.log" => {
+                let params: HistoryLogParams = parse_params(params)?;
+                data(history_log(&root, params.page.as_deref())?)?
+            }
+            "history.show" => {
+                let params: HistoryEntryParams = parse_params(params)?;
+                data(history_entry(&root, params.id)?)?
+            }
+            "history.diff" => {
+                let params: HistoryEntryParams = parse_params(params)?;
+                data(history_diff(&root, params.id)?)?
+            }
+            "history.undo" => {
+                let params: HistoryEntryParams = parse_params(params)?;
+                let plan = plan_undo_history(&root, params.id)?;
+                self.apply(method, plan, params.dry_run)?
+            }
+            "import.markdown" | "import.notion" | "import.logseq" => {
+                let params: ImportParams = parse_params(params)?;
+                let plan = match method {
+                    "import.notion" => {
+                        plan_import_pages(&root, &params.source, ImportFormat::Notion)?
+                    }
+                    "import.logseq" => {
+                        plan_import_pages(&root, &params.source, ImportFormat::Logseq)?
+                    }
+                    _ => plan_import_markdown(&root, &params.source)?,
+                };
+                self.apply(method, plan, params.dry_run)?
+            }
+            "search.text" => {
+                let params: SearchParams = parse_params(params)?;
+                let (index, _) = self.cache()?;
+                data(search_index(&root, index, &params.query)?)?
+            }
+            "graph.page" | "graph.backlinks" | "graph.outlinks" | "graph.related" => {
+                let params: PageParams = parse_params(params)?;
+                let page = normalize_graph_page_path(&root, &params.page)?;
+                let (_, graph) = self.cache()?;
+                let entry = graph_page_in(graph, &page)?;
+                match method {
+                    "graph.backlinks" => data(entry.backlinks)?,
+                    "graph.outlinks" => data(entry.outlinks)?,
+                    "graph.related" => data(related_pages_for(entry))?,
+                    _ => data(entry)?,
+                }
+            }
+            "graph.neighbors" => {
+                let params: NeighborParams = parse_params(params)?;
+                let page = normalize_graph_page_path(&root, &params.page)?;
+                let (_, graph) = self.cache()?;
+                data(neighbor_pages_in(graph, &page, params.depth)?)?
+            }
+            "graph.path" => {
+                let params: PathParams = parse_params(params)?;
+                let from = normalize_graph_page_path(&root, &params.from)?;
+                let to = normalize_graph_page_path(&root, &params.to)?;
+                let (_, graph) = self.cache()?;
+                data(shortest_page_path_in(graph, &from, &to)?)?
+            }
+            "graph.notes" => {
+                let params: PageParams = parse_params(params)?;
+                let page = normalize_graph_page_path(&root, &params.page)?;
+                let (_, graph) = self.cache()?;
+                data(page_notes_in(graph, &page))?
+            }
+            "graph.orphans" => {
+                let (_, graph) = self.cache()?;
+                data(orphan_pages_in(graph))?
+            }
+            "link.external" => {
+                let (_, graph) = self.cache()?;
+                data(external_links_in(graph))?
+            }
+            "context.page" => {
+                let params: ContextParams = parse_params(params)?;
+                let (page_path, anchor) = split_section_selector(&params.page)?;
+                let page = editor_page_detail(&root, &page_path)?;
+                let section = anchor
+                    .map(|_| page_section(&root, &params.page))
+                    .transpose()?;
+                let path = normalize_graph_page_path(&root, &page_path)?;
+                let (_, graph) = self.cache()?;
+                data(ContextPageData {
+                    page,
+                    neighbors: neighbor_pages_in(graph, &path, 1)?,
+                    section,
+                    budget: params.budget,
+                })?
+            }
+            _ => {
+                return Err(RpcError {
+                    code: METHOD_NOT_FOUND,
+                    message: format!("unknown method: {method}"),
+                    data: None,
+                });
+            }
+        };
+
+        Ok(data(CommandResult::new(method, &root, value))?)
+    }
+
+    fn cache(&mut self) -> Result<&(ProjectIndex, ProjectGraph)> {
+        let cache = match self.cache.take() {
+            Some(cache) => cache,
+            None => {
+                let index = build_project_index(&self.root)?;
+                let graph = build_project_graph(&index);
+                (index, graph)
+            }
+        };
+        Ok(self.cache.insert(cache))
+    }
+
+    fn apply(&mut self, command: &str, plan: MutationPlan, dry_run: bool) -> Result<Value> {
+        if dry_run {
+            return data(plan.preview()?);
+        }
+
+        let report = plan.apply()?;
+        if !report.summary().noop {
+            self.push_change(command, report.clone());
+        }
+        data(ReportData::new(report))
+    }
+
+    fn push_change(&mut self, command: &str, report: OperationReport) {
+        self.cache = None;
+        let params = CommandResult::new(command, &self.root, ReportData::new(report));
+        self.notifications.push(json!({
+            "jsonrpc": "2.0",
+            "method": CHANGED_NOTIFICATION,
+            "params": params,
+        }));
+    }
+}
+
+fn parse_params<T: DeserializeOwned>(params: Value) -> std::result::Result<T, RpcError> {
+    serde_json::from_value(params).map_err(|error| RpcError {
+        code: INVALID_PARAMS,
+        message: error.to_string(),
+        data: None,
+    })
+}
+
+fn data<T: Serialize>(value: T) -> Result<Value> {
+    Ok(serde_json::to_value(value)?)
+}
+
+pub(crate) fn error_response(id: Value, error: RpcError) -> Value {
+    let mut body = json!({ "code": error.code, "message": error.message });
+    if let Some(data) = error.data {
+        body["data"] = json!(data);
+    }
+    json!({ "jsonrpc": "2.0", "id": id, "error": body })
+}
+
+fn write_message(output: &mut impl Write, message: &Value) -> Result<()> {
+    writeln!(output, "{}", serde_json::to_string(message)?)?;
+    output.flush()?;
+    Ok(())
+}
@@ -7,29 +7,44 @@ use crate::graph::{
     orphan_pages,
 };
 use crate::io::markdown::{html_to_markdown, markdown_to_html};
+#[cfg(feature = "cli")]
+use crate::mcp::McpServer;
 use crate::project::constants::{GRAPH_VERSION, INDEX_VERSION, MANIFEST_VERSION};
 use crate::project::paths::{collect_page_paths, resolve_page_destination};
-use crate::validation::validate_page_metadata;
+use crate::rpc::{self, RpcServer};
+use crate::validation::{node_at_span, source_span, validate_page_metadata};
 use crate::FractalErrorCode;
 use crate::{
-    add_note, build_index, create_directory, create_page, delete_directory, delete_page,
-    editor_page_detail, export_page, extract_page_text, graph_backlinks_report, graph_notes_report,
-    graph_outlinks_report, graph_related_report, import_markdown, init_project_at,
-    list_editor_pages, load_project_index, load_project_manifest, new_page, page_backlinks,
-    page_metadata, page_metadata_report, page_notes, page_outlinks, patch_note,
-    preflight_delete_page, preflight_rename_page, preflight_repair_project, project_summary,
-    read_page_source, related_pages, remove_note, rename_page, repair_project, reset_page_metadata,
-    search_project, search_report, set_page_summary, set_page_tags, set_page_title, sync_project,
-    update_editor_page, update_page_body, validate_project, write_page_source, EditorLinkDetail,
-    EditorNoteDetail, EditorPageListEntry, EditorPageUpdate, FileEntry, GraphEdge,
-    GraphNeighborPage, GraphNode, GraphNoteLink, GraphPageLink, GraphRelatedPage, LinkEntry,
-    NoteEntry, OperationEvent, PageCreate, PageEntry, PageGraphEntry, PageRename, ProjectGraph,
-    ProjectIndex, ProjectManifest, SearchMatch, SearchResult, Theme,
+    add_asset, add_note, apply_repair_suggestions, build_index, create_directory, create_page,
+    delete_asset, delete_block, delete_directory, delete_page, demote_page, disable_history,
+    editor_page_detail, enable_history, export_page, external_links, extract_page_text,
+    generate_fixture_project, graph_backlinks_report, graph_notes_report, graph_outlinks_report,
+    graph_related_report, history_diff, history_entry, history_log, import_markdown, import_pages,
+    init_project_at, insert_block, lint_project, list_assets, list_editor_pages,
+    load_project_index, load_project_manifest, merge_page_edit, move_asset, move_block,
+    mutation_lock_owner, new_page, page_backlinks, page_blame, page_blocks, page_git_history,
+    page_metadata, page_metadata_report, page_notes, page_outlinks, page_section, page_workers,
+    patch_note, plan_add_note, plan_create_page, plan_delete_page, plan_rename_page,
+    plan_set_page_tags, plan_update_page_body, preflight_delete_page, preflight_demote_page,
+    preflight_import_pages, preflight_promote_note, preflight_rename_page,
+    preflight_repair_project, project_summary, promote_note, read_page_source, related_pages,
+    remove_note, rename_note, rename_page, repair_project, repair_suggestions, replace_block,
+    reset_page_metadata, search_project, search_report, set_git_auto_commit, set_page_summary,
+    set_page_tags, set_page_title, set_page_workers, sync_project, undo_history, unlock_project,
+    unreferenced_assets, update_editor_page, update_page_body, validate_project, validation_report,
+    write_page_source, AssetEntry, BlameRegion, BlockPosition, DiagnosticSeverity,
+    EditorLinkDetail, EditorNoteDetail, EditorPageListEntry, EditorPageUpdate, ExternalLinkEntry,
+    ExternalLinkSource, FileChange, FileEntry, GraphEdge, GraphNeighborPage, GraphNode,
+    GraphNoteLink, GraphPageLink, GraphRelatedPage, HeadingEntry, ImageEntry, ImportFormat,
+    LinkEntry, MergeRegion, MutationLockOwner, NoteEntry, OperationEvent, PageCreate, PageEntry,
+    PageGraphEntry, PageRename, ProjectGraph, ProjectIndex, ProjectManifest, ProjectWatcher,
+    SearchMatch, SearchResult, Theme, WatchBackend, WatchOptions,
 };
 use std::collections::BTreeMap;
 use std::fs;
-use std::ops::Deref;
+use std::ops::{ControlFlow, Deref};
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 use tempfile::{Builder, TempDir};
 
 struct TestDir {
@@ -119,7 +134,7 @@ fn required_meta() -> BTreeMap<String, String> {
     [
         ("fractal:summary".to_string(), "".to_string()),
         ("fractal:tags".to_string(), "".to_string()),
-        ("fractal:version".to_string(), "0.1".to_string()),
+        ("fractal:version".to_string(), "0.2".to_string()),
     ]
     .into_iter()
     .collect()
@@ -133,6 +148,9 @@ fn write_test_manifest(root: &Path) {
             version: MANIFEST_VERSION,
             default_page: "pages/index.html".to_string(),
             theme: Theme::Dark,
+            git_auto_commit: false,
+            lock_timeout_ms: 0,
+            lint: BTreeMap::new(),
         })
         .expect("serialize manifest"),
     )
@@ -215,7 +233,7 @@ fn page_renderer_includes_required_meta_tags() {
         "../.fractal/style.css".to_string(),
     );
 
-    assert!(html.contains("<meta name=\"fractal:version\" content=\"0.1\" />"));
+    assert!(html.contains("<meta name=\"fractal:version\" content=\"0.2\" />"));
     assert!(html.contains("<meta name=\"fractal:summary\" content=\"\" />"));
     assert!(html.contains("<meta name=\"fractal:tags\" content=\"\" />"));
     assert!(html.contains("<link rel=\"stylesheet\" href=\"../.fractal/style.css\">"));
@@ -284,7 +302,7 @@ fn html_export_reads_ordinary_html_formatting() {
 
     assert_eq!(
         html_to_markdown(html),
-        "# Flexible Title\n\nIntro link & more\n\n## Nested Section\n\nBody text"
+        "# Flexible *Title*\n\nIntro link & more\n\n## Nested Section\n\n**Body** text"
     );
 }
 
@@ -302,6 +320,9 @@ fn import_and_export_markdown_files() {
             version: MANIFEST_VERSION,
             default_page: "pages/index.html".to_string(),
             theme: Theme::Dark,
+            git_auto_commit: false,
+            lock_timeout_ms: 0,
+            lint: BTreeMap::new(),
         })
         .expect("serialize manifest"),
     )
@@ -323,6 +344,78 @@ fn import_and_export_markdown_files() {
     );
 }
 
+#[test]
+fn markdown_inline_formatting_round_trips() {
+    let (_, body) = markdown_to_html(
+        "fallback",
+        "Some **bold**, *em*, `a*b`, ~~gone~~, ==hot==, H~2~O and x^2^ snake_case_name\\\nnext line  \nlast",
+    );
+    assert_eq!(
+        body,
+        "<p>Some <strong>bold</strong>, <em>em</em>, <code>a*b</code>, <s>gone</s>, <mark>hot</mark>, H<sub>2</sub>O and x<sup>2</sup> snake_case_name<br>next line<br>last</p>"
+    );
+
+    let html = render_page_document(
+        "Title",
+        &body,
+        Theme::Dark,
+        "../.fractal/style.css".to_string(),
+    );
+    assert_eq!(
+        html_to_markdown(&html),
+        "# Title\n\nSome **bold**, *em*, `a*b`, ~~gone~~, ==hot==, H~2~O and x^2^ snake_case_name\\\nnext line\\\nlast"
+    );
+    assert_eq!(
+        markdown_to_html("fallback", "2 * 3 * 4").1,
+        "<p>2 * 3 * 4</p>"
+    );
+}
+
+#[test]
+fn markdown_tables_and_figures_round_trip() {
+    let markdown = "| Name | Notes |\n| --- | :---: |\n| Alpha | **bold** a\\|b |\n| Beta |\n\n![Flow diagram](media/flow.png \"Data flow\")\n\n![Icon](icon.svg)\n\nSee ![logo](logo.png) here";
+    let (_, body) = markdown_to_html("fallback", markdown);
+    assert_eq!(
+        body,
+        "<table><thead><tr><th>Name</th><th>Notes</th></tr></thead><tbody><tr><td>Alpha</td><td><strong>bold</strong> a|b</td></tr><tr><td>Beta</td><td></td></tr></tbody></table>\n      <figure><img src=\"media/flow.png\" alt=\"Flow diagram\"><figcaption>Data flow</figcaption></figure>\n      <figure><img src=\"icon.svg\" alt=\"Icon\"></figure>\n      <p>See <img src=\"logo.png\" alt=\"logo\"> here</p>"
+    );
+
+    let html = render_page_document(
+        "Title",
+        &body,
+        Theme::Dark,
+        "../.fractal/style.css".to_string(),
+    );
+    assert_eq!(
+        html_to_markdown(&html),
+        "# Title\n\n| Name | Notes |\n| --- | --- |\n| Alpha | **bold** a\\|b |\n| Beta |  |\n\n![Flow diagram](media/flow.png \"Data flow\")\n\n![Icon](icon.svg)\n\nSee ![logo](logo.png) here"
+    );
+}
+
+#[test]
+fn markdown_external_links_round_trip() {
+    let (title, body) = markdown_to_html(
+        "fallback",
+        "# Read [the **docs**](https://example.com/docs)\n\nSee [the **docs**](https://example.com/docs \"Docs\"), <https://rust-lang.org>, [mail](mailto:team@example.org) and [local](other.md).",
+    );
+    assert_eq!(title, "Read the docs");
+    assert_eq!(
+        body,
+        "<p>See <a href=\"https://example.com/docs\" data-fractal-link=\"external\">the <strong>docs</strong></a>, <a href=\"https://rust-lang.org\" data-fractal-link=\"external\">https://rust-lang.org</a>, <a href=\"mailto:team@example.org\" data-fractal-link=\"external\">mail</a> and [local](other.md).</p>"
+    );
+
+    let html = render_page_document(
+        "Title",
+        &body,
+        Theme::Dark,
+        "../.fractal/style.css".to_string(),
+    );
+    assert_eq!(
+        html_to_markdown(&html),
+        "# Title\n\nSee [the **docs**](https://example.com/docs), <https://rust-lang.org>, [mail](mailto:team@example.org) and [local](other.md)."
+    );
+}
+
 #[test]
 fn markdown_import_rejects_existing_destination() {
     let project = TestProject::new("markdown-overwrite");
@@ -346,6 +439,195 @@ fn markdown_import_rejects_existing_destination() {
         .contains("existing"));
 }
 
+#[test]
+fn notion_import_strips_ids_and_maps_folders_and_properties() {
+    let project = TestProject::new("notion-import");
+    let export = project.root().join("export");
+    let nested = export.join("Projects 0123456789abcdef0123456789abcdef");
+    fs::create_dir_all(&nested).expect("create notion export");
+    fs::write(
+        export.join("Projects 0123456789abcdef0123456789abcdef.md"),
+        "# Projects\n\nAll projects.",
+    )
+    .expect("write parent page");
+    fs::write(
+        nested.join("Alpha Launch fedcba9876543210fedcba9876543210.md"),
+        "# Alpha Launch\n\nStatus: Active\nTags: launch, Q3\n\nShip it.",
+    )
+    .expect("write child page");
+    fs::write(
+        export.join("Projects 0123456789abcdef0123456789abcdef.csv"),
+        "\u{feff}Name,Status,Summary\n\"Alpha Launch\",Active,\"Launch plan, phase one\"\n",
+    )
+    .expect("write database csv");
+
+    import_pages(project.root(), &export, ImportFormat::Notion).expect("import notion");
+
+    let parent =
+        fs::read_to_string(project.pages_dir().join("projects.html")).expect("read parent page");
+    assert!(parent.contains("<h1>Projects</h1>"));
+    let child =
+        page_metadata(project.root(), "projects/alpha-launch.html").expect("read child metadata");
+    assert_eq!(child.title, "Alpha Launch");
+    assert_eq!(child.summary.as_deref(), Some("Launch plan, phase one"));
+    assert_eq!(child.tags, vec!["launch".to_string(), "Q3".to_string()]);
+    let html = fs::read_to_string(project.pages_dir().join("projects/alpha-launch.html"))
+        .expect("read child page");
+    assert!(html.contains("<p>Ship it.</p>"));
+    assert!(!html.contains("Status: Active"));
+    project.write_page(
+        "index.html",
+        render_page_document(
+            "Home",
+            "<p>home</p>",
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        ),
+    );
+    validate_project(project.root()).expect("imported notion pages validate");
+}
+
+#[test]
+fn logseq_import_maps_outline_properties_and_block_refs() {
+    let project = TestProject::new("logseq-import");
+    let graph = project.root().join("graph");
+    fs::create_dir_all(graph.join("pages")).expect("create logseq pages");
+    fs::create_dir_all(graph.join("logseq")).expect("create logseq config dir");
+    fs::write(graph.join("logseq/config.md"), "- ignored").expect("write config");
+    fs::write(
+        graph.join("pages/Research___Sources.md"),
+        "summary:: Where facts come from\ntags:: [[reading]], #archive\n\n- Primary sources matter\n  id:: 64f1c2aa-0000-4000-8000-000000000001\n- Secondary notes\n\t- nested detail\n- ...\n  id:: 64f1c2aa-0000-4000-8000-000000000002\n",
+    )
+    .expect("write source page");
+    fs::write(
+        graph.join("pages/Essay.md"),
+        "- ## Draft\n- See ((64f1c2aa-0000-4000-8000-000000000001)) for context\n- Broken ((missing-block))\n- Pause {{embed ((64f1c2aa-0000-4000-8000-000000000002))}} here\n",
+    )
+    .expect("write essay page");
+
+    let report = import_pages(project.root(), &graph, ImportFormat::Logseq).expect("import logseq");
+
+    assert!(report.events.iter().any(|event| matches!(
+        event,
+        OperationEvent::Warning { message } if message.contains("missing-block")
+    )));
+    assert!(report.events.iter().any(|event| matches!(
+        event,
+        OperationEvent::Warning { message }
+            if message.starts_with("dropped Logseq block reference")
+                && message.contains("000000000002")
+    )));
+    let sources =
+        page_metadata(project.root(), "research/sources.html").expect("read namespaced metadata");
+    assert_eq!(sources.title, "Research/Sources");
+    assert_eq!(sources.summary.as_deref(), Some("Where facts come from"));
+    assert_eq!(
+        sources.tags,
+        vec!["reading".to_string(), "archive".to_string()]
+    );
+    let sources_html = fs::read_to_string(project.pages_dir().join("research/sources.html"))
+        .expect("read namespaced page");
+    assert!(sources_html.contains("<p>Secondary notes</p>\n      <ul><li>nested detail</li></ul>"));
+    assert!(!sources_html.contains("id::"));
+
+    let essay = fs::read_to_string(project.pages_dir().join("essay.html")).expect("read essay");
+    assert!(essay.contains("<h2>Draft</h2>"));
+    assert!(essay.contains("id=\"note-primary-sources-matter\""));
+    let essay_document = PageDocument::parse(&essay);
+    let note_links = essay_document
+        .document
+        .select("main a[data-fractal-link=note]")
+        .expect("note link selector")
+        .map(|link| {
+            (
+                link.attributes
+                    .borrow()
+                    .get("href")
+                    .unwrap_or_default()
+                    .to_string(),
+                link.text_contents(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        note_links,
+        vec![(
+            "#note-primary-sources-matter".to_string(),
+            "Primary sources matter".to_string()
+        )]
+    );
+    assert_eq!(
+        page_notes(project.root(), "essay").expect("essay notes")[0].id,
+        "note-primary-sources-matter"
+    );
+    assert!(!project.pages_dir().join("config.html").exists());
+    project.write_page(
+        "index.html",
+        render_page_document(
+            "Home",
+            "<p>home</p>",
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        ),
+    );
+    validate_project(project.root()).expect("imported logseq pages validate");
+    sync_project(project.root()).expect("sync imported pages");
+    assert!(fs::read_to_string(project.pages_dir().join("essay.html"))
+        .expect("read synced essay")
+        .contains("href=\"#note-primary-sources-matter\""));
+}
+
+#[test]
+fn import_preflight_reports_plan_without_writing() {
+    let project = TestProject::new("import-preflight");
+    let export = project.root().join("export");
+    fs::create_dir_all(&export).expect("create export");
+    fs::write(
+        export.join("Ideas 0123456789abcdef0123456789abcdef.md"),
+        "# Ideas\n\nTags: brainstorm\n\nBody",
+    )
+    .expect("write notion page");
+
+    let preflight = preflight_import_pages(project.root(), &export, ImportFormat::Notion)
+        .expect("preflight import");
+
+    assert_eq!(preflight.len(), 1);
+    assert_eq!(preflight[0].destination_page, "ideas.html");
+    assert_eq!(preflight[0].tags, vec!["brainstorm".to_string()]);
+    assert!(!project.pages_dir().join("ideas.html").exists());
+
+    project.write_page(
+        "other.html",
+        render_page_document(
+            "Ideas",
+            "<p>taken</p>",
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        ),
+    );
+    build_index(project.root()).expect("build index");
+    let error = import_pages(project.root(), &export, ImportFormat::Notion)
+        .expect_err("duplicate title should fail");
+    assert_eq!(error.code, FractalErrorCode::InvalidProject);
+    assert!(!project.pages_dir().join("ideas.html").exists());
+
+    let notes = project.root().join("notes");
+    fs::create_dir_all(notes.join("archive")).expect("create notes");
+    fs::write(notes.join("plan.md"), "# Plan\n\nCurrent").expect("write plan");
+    fs::write(notes.join("archive").join("plan.md"), "# Old Plan\n\nOld").expect("write old plan");
+    let error = preflight_import_pages(project.root(), &notes, ImportFormat::Markdown)
+        .expect_err("colliding file stems should fail");
+    assert_eq!(error.code, FractalErrorCode::AlreadyExists);
+    assert!(error
+        .message
+        .contains(&notes.join("plan.md").display().to_string()));
+    assert!(error
+        .message
+        .contains(&notes.join("archive").join("plan.md").display().to_string()));
+    assert!(error.message.contains("plan.html"));
+    assert!(!project.pages_dir().join("plan.html").exists());
+}
+
 #[test]
 fn export_page_accepts_optional_pages_prefix() {
     let project = TestProject::new("export-prefix");
@@ -407,1103 +689,4322 @@ fn extract_page_text_returns_compact_main_text() {
 }
 
 #[test]
-fn stylesheet_href_reaches_workspace_from_nested_pages() {
-    assert_eq!(
-        stylesheet_href(Path::new("index.html")),
-        "../.fractal/style.css"
+fn tables_images_and_figures_are_indexed_graphed_and_extracted() {
+    let project = TestProject::new("embedded-content");
+    project.write_page(
+        "index.html",
+        render_page_document(
+            "Home",
+            "<table><thead><tr><th>Name</th><th>Size</th></tr></thead><tbody><tr><td>Alpha</td><td><strong>3</strong></td></tr></tbody></table>\n      <figure><img src=\"media/diagram.png\" alt=\"Flow diagram\"><figcaption>Data <em>flow</em></figcaption></figure>\n      <p>Inline <img src=\"media/icon.svg\" alt=\"icon\"> image</p>",
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        ),
     );
+    fs::create_dir_all(project.pages_dir().join("media")).expect("create media dir");
+    fs::write(project.pages_dir().join("media/diagram.png"), "png").expect("write diagram");
+    fs::write(project.pages_dir().join("media/icon.svg"), "svg").expect("write icon");
+
+    validate_project(project.root()).expect("embedded content validates");
+    build_index(project.root()).expect("build index");
+
+    let index = load_project_index(project.root()).expect("load index");
     assert_eq!(
-        stylesheet_href(Path::new("folder/subpage.html")),
-        "../../.fractal/style.css"
+        index.pages[0].images,
+        vec![
+            ImageEntry {
+                src: "media/diagram.png".to_string(),
+                alt: "Flow diagram".to_string(),
+            },
+            ImageEntry {
+                src: "media/icon.svg".to_string(),
+                alt: "icon".to_string(),
+            },
+        ]
     );
-}
-
-#[test]
-fn validate_page_metadata_rejects_missing_notes_section() {
-    let root = temp_dir("validate-notes");
-    fs::create_dir_all(root.path()).expect("create temp dir");
-    let page = root.join("page.html");
-    let html = render_page_document(
-        "page",
-        "<p>body</p>",
-        Theme::Dark,
-        "../.fractal/style.css".to_string(),
+    let graph: ProjectGraph = serde_json::from_str(
+        &fs::read_to_string(project.workspace_dir().join("graph.json")).expect("read graph"),
     )
-    .replace("    <section data-fractal-notes>\n    </section>\n", "");
-    fs::write(&page, html).expect("write page");
+    .expect("parse graph");
+    assert!(graph.nodes.contains(&GraphNode {
+        id: "asset:media/diagram.png".to_string(),
+        kind: "asset".to_string(),
+        label: "media/diagram.png".to_string(),
+        path: Some("media/diagram.png".to_string()),
+    }));
+    assert!(graph.edges.contains(&GraphEdge {
+        from: "page:index.html".to_string(),
+        to: "asset:media/diagram.png".to_string(),
+        kind: "embeds_asset".to_string(),
+        text: Some("Flow diagram".to_string()),
+        href: Some("media/diagram.png".to_string()),
+    }));
 
-    let error = validate_page_metadata(&page).expect_err("missing notes section should fail");
-    assert!(error.to_string().contains("missing notes section"));
+    assert_eq!(
+        extract_page_text(project.root(), Path::new("index")).expect("extract text"),
+        "Home Name Size Alpha 3 Flow diagram Data flow Inline icon image"
+    );
 }
 
 #[test]
-fn validate_page_metadata_rejects_duplicate_notes_sections() {
-    let root = temp_dir("validate-duplicate-notes");
-    fs::create_dir_all(root.path()).expect("create temp dir");
-    let page = root.join("page.html");
-    let html = render_page_document(
-        "page",
-        "<p>body</p>",
-        Theme::Dark,
-        "../.fractal/style.css".to_string(),
-    )
-    .replace(
-        "    <section data-fractal-notes>\n    </section>",
-        "    <section data-fractal-notes>\n    </section>\n    <section data-fractal-notes></section>",
+fn assets_are_added_listed_and_moved_with_reference_rewrites() {
+    let project = TestProject::new("asset-move");
+    project.write_page(
+        "index.html",
+        render_page_document(
+            "Home",
+            "<figure><img src=\"diagram.png\" alt=\"Diagram\"></figure>",
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        ),
     );
-    fs::write(&page, html).expect("write page");
+    project.write_page(
+        "folder/topic.html",
+        render_page_document(
+            "Topic",
+            "<p>See <img src=\"../diagram.png\" alt=\"diagram\"> here.</p>",
+            Theme::Dark,
+            "../../.fractal/style.css".to_string(),
+        ),
+    );
+    fs::write(project.pages_dir().join("notes.txt"), "notes").expect("write notes");
+    let source = temp_dir("asset-source");
+    fs::write(source.join("diagram.png"), "png").expect("write source asset");
 
-    let error = validate_page_metadata(&page).expect_err("duplicate notes sections should fail");
-    assert!(error.to_string().contains("duplicate notes section"));
-}
+    let report =
+        add_asset(project.root(), source.join("diagram.png"), "diagram.png").expect("add asset");
+    assert!(report.events.contains(&OperationEvent::AssetAdded {
+        source: source.join("diagram.png"),
+        path: PathBuf::from("pages/diagram.png"),
+    }));
+    validate_project(project.root()).expect("added asset validates");
 
-#[test]
-fn validate_page_metadata_rejects_malformed_note_ids() {
-    let root = temp_dir("validate-note-id");
-    fs::create_dir_all(root.path()).expect("create temp dir");
-    let page = root.join("page.html");
-    let html = render_page_document(
-        "page",
-        "<p>body</p>",
-        Theme::Dark,
-        "../.fractal/style.css".to_string(),
-    )
-    .replace(
-        "    <section data-fractal-notes>\n    </section>",
-        "    <section data-fractal-notes>\n      <aside id=\"Rust\" data-fractal-note><p>bad</p></aside>\n    </section>",
+    let assets = list_assets(project.root()).expect("list assets");
+    assert_eq!(
+        assets[0],
+        AssetEntry {
+            path: "diagram.png".to_string(),
+            mime_type: "image/png".to_string(),
+            content_hash: "sha256:8f8cbb7dcf46e0bc7d53265749a6c17d116093a6ba95e442764060c76fd4a86c"
+                .to_string(),
+            referenced_by: vec!["folder/topic.html".to_string(), "index.html".to_string()],
+        }
+    );
+    assert_eq!(
+        unreferenced_assets(project.root())
+            .expect("unreferenced assets")
+            .into_iter()
+            .map(|asset| asset.path)
+            .collect::<Vec<_>>(),
+        vec!["notes.txt".to_string()]
     );
-    fs::write(&page, html).expect("write page");
 
-    let error = validate_page_metadata(&page).expect_err("malformed note id should fail");
-    assert!(error.to_string().contains("malformed note id"));
+    let report =
+        move_asset(project.root(), "diagram.png", "media/diagram.png").expect("move asset");
+    assert!(report.events.contains(&OperationEvent::AssetMoved {
+        from: PathBuf::from("pages/diagram.png"),
+        to: PathBuf::from("pages/media/diagram.png"),
+    }));
+    assert_eq!(report.summary().links_rewritten_count, 2);
+    assert!(project.pages_dir().join("media/diagram.png").is_file());
+    assert!(!project.pages_dir().join("diagram.png").exists());
+
+    let home = fs::read_to_string(project.pages_dir().join("index.html")).expect("read home");
+    assert!(home.contains("<img src=\"media/diagram.png\" alt=\"Diagram\">"));
+    let topic =
+        fs::read_to_string(project.pages_dir().join("folder/topic.html")).expect("read topic");
+    assert!(topic.contains("<img src=\"../media/diagram.png\" alt=\"diagram\">"));
+    validate_project(project.root()).expect("moved asset validates");
+
+    let error = move_asset(project.root(), "media/diagram.png", "notes.txt")
+        .expect_err("occupied destination should fail");
+    assert_eq!(error.code, FractalErrorCode::AlreadyExists);
+    let error = add_asset(project.root(), source.join("diagram.png"), "page.html")
+        .expect_err("html destination should fail");
+    assert_eq!(error.code, FractalErrorCode::InvalidInput);
 }
 
 #[test]
-fn validate_page_metadata_allows_custom_summary_and_tags() {
-    let root = temp_dir("validate-custom-meta");
-    fs::create_dir_all(root.path()).expect("create temp dir");
-    let page = root.join("page.html");
-    let html = render_page_document(
-        "page",
-        "<p>body</p>",
-        Theme::Dark,
-        "../.fractal/style.css".to_string(),
-    )
-    .replace(
-        "content=\"Short page summary here.\"",
-        "content=\"A custom project summary.\"",
-    )
-    .replace(
-        "content=\"rust, graphs, parsing\"",
-        "content=\"personal, custom\"",
+fn delete_asset_refuses_referenced_assets_unless_forced() {
+    let project = TestProject::new("asset-delete");
+    project.write_page(
+        "index.html",
+        render_page_document(
+            "Home",
+            "<p><img src=\"logo.png\" alt=\"Logo\"></p>",
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        ),
     );
-    fs::write(&page, html).expect("write page");
+    fs::write(project.pages_dir().join("logo.png"), "png").expect("write logo");
+    fs::write(project.pages_dir().join("unused.pdf"), "pdf").expect("write unused");
 
-    validate_page_metadata(&page).expect("custom metadata should validate");
-}
+    let error = delete_asset(project.root(), "logo.png", false)
+        .expect_err("referenced asset delete should fail");
+    assert_eq!(error.code, FractalErrorCode::InvalidInput);
+    assert!(error.message.contains("referenced by index.html"));
+    assert!(project.pages_dir().join("logo.png").is_file());
 
-#[test]
-fn validate_project_rejects_extra_fractal_meta_tags() {
-    let project = TestProject::new("validate-extra-fractal-meta");
-    let html = render_page_document(
-        "Home",
-        "<p>body</p>",
-        Theme::Dark,
-        "../.fractal/style.css".to_string(),
-    )
-    .replace(
-        "    <link rel=\"stylesheet\" href=\"../.fractal/style.css\">",
-        "    <meta name=\"fractal:custom\" content=\"nope\">\n    <link rel=\"stylesheet\" href=\"../.fractal/style.css\">",
-    );
-    project.write_page("index.html", html);
-
-    let error = validate_project(project.root()).expect_err("extra fractal meta should fail");
-    assert_eq!(error.code, FractalErrorCode::InvalidProject);
-    assert!(error.to_string().contains("unsupported Fractal meta tag"));
-}
-
-#[test]
-fn validate_project_rejects_title_heading_mismatch() {
-    let project = TestProject::new("validate-title-heading-mismatch");
-    let html = render_page_document(
-        "Home",
-        "<p>body</p>",
-        Theme::Dark,
-        "../.fractal/style.css".to_string(),
-    )
-    .replace("<h1>Home</h1>", "<h1>Other</h1>");
-    project.write_page("index.html", html);
+    let report = delete_asset(project.root(), "pages/unused.pdf", false).expect("delete unused");
+    assert!(report.events.contains(&OperationEvent::AssetDeleted {
+        path: PathBuf::from("pages/unused.pdf"),
+    }));
+    assert!(report.summary().warnings.is_empty());
 
-    let error = validate_project(project.root()).expect_err("mismatched title should fail");
-    assert!(error.to_string().contains("title and main heading differ"));
+    let report = delete_asset(project.root(), "logo.png", true).expect("force delete");
+    assert_eq!(
+        report.summary().warnings,
+        vec!["index.html still references deleted asset logo.png".to_string()]
+    );
+    assert!(!project.pages_dir().join("logo.png").exists());
 }
 
 #[test]
-fn validate_project_rejects_unsupported_body_elements_and_manual_links() {
-    let project = TestProject::new("validate-body-contract");
+fn rename_page_rewrites_relative_image_sources() {
+    let project = TestProject::new("rename-images");
     project.write_page(
         "index.html",
         render_page_document(
             "Home",
-            "<p><span>bad</span></p>",
+            "<p><img src=\"media/chart.png\" alt=\"Chart\"></p>",
             Theme::Dark,
             "../.fractal/style.css".to_string(),
         ),
     );
-
-    let error = validate_project(project.root()).expect_err("unsupported span should fail");
-    assert_eq!(error.code, FractalErrorCode::InvalidProject);
-    assert!(error
-        .to_string()
-        .contains("unsupported Fractal body element"));
-
     project.write_page(
-        "index.html",
+        "other.html",
         render_page_document(
-            "Home",
-            "<p><a href=\"missing.html\">Manual</a></p>",
+            "Other",
+            "<p>body</p>",
             Theme::Dark,
             "../.fractal/style.css".to_string(),
         ),
     );
+    fs::create_dir_all(project.pages_dir().join("media")).expect("create media dir");
+    fs::write(project.pages_dir().join("media/chart.png"), "png").expect("write chart");
 
-    let error = validate_project(project.root()).expect_err("manual link should fail");
-    assert!(error
-        .to_string()
-        .contains("manual link is not valid Fractal"));
-}
-
-#[test]
-fn validate_project_rejects_broken_generated_links() {
-    let project = TestProject::new("validate-broken-generated-link");
-    project.write_page(
+    rename_page(
+        project.root(),
         "index.html",
-        render_page_document(
-            "Home",
-            "<p><a href=\"missing.html\" data-fractal-link=\"page\">Missing</a></p>",
-            Theme::Dark,
-            "../.fractal/style.css".to_string(),
-        ),
-    );
+        PageRename {
+            path: Some(PathBuf::from("archive/home.html")),
+            title: None,
+            expected_revision: None,
+        },
+    )
+    .expect("rename page");
 
-    let error = validate_project(project.root()).expect_err("missing generated target should fail");
-    assert!(error
-        .to_string()
-        .contains("generated page link target is missing"));
+    let moved =
+        fs::read_to_string(project.pages_dir().join("archive/home.html")).expect("read moved");
+    assert!(moved.contains("<img src=\"../media/chart.png\" alt=\"Chart\">"));
+    validate_project(project.root()).expect("moved page validates");
 }
 
 #[test]
-fn validate_project_rejects_page_links_whose_text_does_not_match_the_target() {
-    let project = TestProject::new("validate-mismatched-page-link-text");
+fn external_links_are_validated_indexed_graphed_and_inventoried() {
+    let project = TestProject::new("external-links");
     project.write_page(
         "index.html",
         render_page_document(
             "Home",
-            "<p><a href=\"rust.html\" data-fractal-link=\"page\">the language</a></p>",
+            "<p>Read <a href=\"https://Docs.Example.com:8443/guide?x=1\" data-fractal-link=\"external\">the guide</a> or <a href=\"mailto:team@example.org\" data-fractal-link=\"external\">mail the team</a>.</p>",
             Theme::Dark,
             "../.fractal/style.css".to_string(),
         ),
     );
     project.write_page(
-        "rust.html",
+        "notes.html",
         render_page_document(
-            "Rust",
-            "<p>body</p>",
+            "Notes",
+            "<p>Also <a href=\"https://Docs.Example.com:8443/guide?x=1\" data-fractal-link=\"external\">the Home guide</a>.</p>",
             Theme::Dark,
             "../.fractal/style.css".to_string(),
         ),
     );
 
-    let error =
-        validate_project(project.root()).expect_err("mismatched generated link text should fail");
-    assert!(error
-        .to_string()
-        .contains("generated page link text does not identify its target"));
-    assert!(error.to_string().contains("expected `Rust`"));
+    validate_project(project.root()).expect("external links validate");
+    sync_project(project.root()).expect("sync keeps external links");
+
+    let index = load_project_index(project.root()).expect("load index");
+    assert!(index.pages[0].links.contains(&LinkEntry {
+        href: "mailto:team@example.org".to_string(),
+        text: "mail the team".to_string(),
+        scope: "external".to_string(),
+    }));
+    let notes = fs::read_to_string(project.pages_dir().join("notes.html")).expect("read notes");
+    assert!(notes.contains("data-fractal-link=\"external\">the Home guide</a>"));
+
+    let graph: ProjectGraph = serde_json::from_str(
+        &fs::read_to_string(project.workspace_dir().join("graph.json")).expect("read graph"),
+    )
+    .expect("parse graph");
+    assert!(graph.nodes.contains(&GraphNode {
+        id: "domain:docs.example.com".to_string(),
+        kind: "domain".to_string(),
+        label: "docs.example.com".to_string(),
+        path: None,
+    }));
+    assert!(graph.edges.contains(&GraphEdge {
+        from: "url:https://Docs.Example.com:8443/guide?x=1".to_string(),
+        to: "domain:docs.example.com".to_string(),
+        kind: "hosted_on".to_string(),
+        text: None,
+        href: None,
+    }));
+    assert!(graph
+        .pages
+        .iter()
+        .all(|page| page.outlinks.is_empty() && page.backlinks.is_empty()));
+
+    let links = external_links(project.root()).expect("external links");
+    assert_eq!(
+        links,
+        vec![
+            ExternalLinkEntry {
+                url: "https://Docs.Example.com:8443/guide?x=1".to_string(),
+                scheme: "https".to_string(),
+                domain: "docs.example.com".to_string(),
+                sources: vec![
+                    ExternalLinkSource {
+                        page: "index.html".to_string(),
+                        text: "the guide".to_string(),
+                    },
+                    ExternalLinkSource {
+                        page: "notes.html".to_string(),
+                        text: "the Home guide".to_string(),
+                    },
+                ],
+            },
+            ExternalLinkEntry {
+                url: "mailto:team@example.org".to_string(),
+                scheme: "mailto".to_string(),
+                domain: "example.org".to_string(),
+                sources: vec![ExternalLinkSource {
+                    page: "index.html".to_string(),
+                    text: "mail the team".to_string(),
+                }],
+            },
+        ]
+    );
+}
+
+#[test]
+fn external_link_schemes_are_validated_and_manual_links_repaired() {
+    for href in [
+        "javascript:alert(1)",
+        "ftp://example.com/file",
+        "https://",
+        "mailto:team",
+    ] {
+        let project = TestProject::new("external-link-schemes");
+        project.write_page(
+            "index.html",
+            render_page_document(
+                "Home",
+                &format!("<p><a href=\"{href}\" data-fractal-link=\"external\">bad</a></p>"),
+                Theme::Dark,
+                "../.fractal/style.css".to_string(),
+            ),
+        );
+
+        let error = validate_project(project.root()).expect_err("bad scheme should fail");
+        assert!(
+            error
+                .message
+                .contains("external link must be an http, https, or mailto URL"),
+            "{href}: {}",
+            error.message
+        );
+    }
+
+    let project = TestProject::new("external-link-repair");
+    let legacy = render_page_document(
+        "Home",
+        "<p>See <a href=\"https://example.com\">example</a> and <a href=\"javascript:void(0)\" data-fractal-link=\"external\">bad</a>.</p>",
+        Theme::Dark,
+        "../.fractal/style.css".to_string(),
+    )
+    .replace(
+        "<meta name=\"fractal:version\" content=\"0.2\">",
+        "<meta name=\"fractal:version\" content=\"0.1\">",
+    );
+    project.write_page("index.html", legacy);
+    let error = validate_project(project.root()).expect_err("manual link should fail");
+    assert!(error.message.contains("manual link is not valid Fractal"));
+
+    repair_project(project.root()).expect("repair project");
+    let repaired = fs::read_to_string(project.pages_dir().join("index.html")).expect("read page");
+    assert!(repaired.contains(
+        "<a href=\"https://example.com\" data-fractal-link=\"external\">example</a> and bad."
+    ));
+    assert!(repaired.contains("<meta name=\"fractal:version\" content=\"0.2\">"));
+    validate_project(project.root()).expect("repaired page validates");
+}
+
+#[test]
+fn validate_project_rejects_invalid_images_tables_and_figures() {
+    let project = TestProject::new("embedded-content-invalid");
+    fs::write(project.pages_dir().join("chart.png"), "png").expect("write asset");
+    let cases = [
+        (
+            "<p><img src=\"missing.png\" alt=\"Missing\"></p>",
+            "image asset is missing",
+        ),
+        (
+            "<p><img src=\"index.html\" alt=\"Page\"></p>",
+            "image asset is missing",
+        ),
+        (
+            "<p><img src=\"https://example.com/a.png\" alt=\"Remote\"></p>",
+            "image src must be a relative path",
+        ),
+        ("<p><img src=\"chart.png\"></p>", "image is missing alt text"),
+        (
+            "<figure><img src=\"chart.png\" alt=\"Chart\"><figcaption>One</figcaption><p>after</p><figcaption>Two</figcaption></figure>",
+            "figure may contain only one figcaption",
+        ),
+        (
+            "<figure><figcaption>Only</figcaption></figure>",
+            "figure must contain content besides its caption",
+        ),
+        (
+            "<table><tbody><tr><td><span>x</span></td></tr></tbody></table>",
+            "unsupported Fractal body element",
+        ),
+    ];
+
+    for (body, expected) in cases {
+        project.write_page(
+            "index.html",
+            render_page_document(
+                "Home",
+                body,
+                Theme::Dark,
+                "../.fractal/style.css".to_string(),
+            ),
+        );
+        let error = validate_project(project.root()).expect_err(expected);
+        assert!(error.to_string().contains(expected), "{body}: {error}");
+    }
 
     project.write_page(
         "index.html",
         render_page_document(
             "Home",
-            "<p><a href=\"rust.html\">the language</a></p>",
+            "<table><tbody><tr><td>x</td></tr></tbody></table>",
             Theme::Dark,
             "../.fractal/style.css".to_string(),
-        ),
+        )
+        .replace("content=\"0.2\"", "content=\"0.1\""),
     );
-
-    let error = validate_project(project.root()).expect_err("mismatched manual link should fail");
+    let error = validate_project(project.root()).expect_err("legacy tables fail");
     assert!(error
         .to_string()
-        .contains("manual page link text does not identify its target"));
+        .contains("tables, images, and figures require fractal:version 0.2"));
 }
 
 #[test]
-fn validate_fix_rewrites_mismatched_internal_links_to_visible_target_labels() {
-    let project = TestProject::new("validate-fix-mismatched-page-links");
-    project.write_page(
-        "index.html",
-        render_page_document(
-            "Home",
-            r#"<p><a href="rust.html">the language</a></p>
-      <p><a href="rust.html" data-fractal-link="page">systems language</a></p>"#,
-            Theme::Dark,
-            "../.fractal/style.css".to_string(),
-        ),
+fn stylesheet_href_reaches_workspace_from_nested_pages() {
+    assert_eq!(
+        stylesheet_href(Path::new("index.html")),
+        "../.fractal/style.css"
     );
-    project.write_page(
-        "rust.html",
-        render_page_document(
-            "Rust",
-            "<p>body</p>",
-            Theme::Dark,
-            "../.fractal/style.css".to_string(),
-        ),
+    assert_eq!(
+        stylesheet_href(Path::new("folder/subpage.html")),
+        "../../.fractal/style.css"
     );
-
-    repair_project(project.root()).expect("fix mismatched internal links");
-
-    let fixed =
-        fs::read_to_string(project.pages_dir().join("index.html")).expect("read fixed index page");
-    assert!(fixed.contains("<a href=\"rust.html\" data-fractal-link=\"page\">Rust</a>"));
-    assert!(!fixed.contains("the language (Rust)"));
-    assert!(!fixed.contains("systems language (Rust)"));
 }
 
 #[test]
-fn validate_fix_repairs_simple_contract_drift_and_unwraps_manual_links() {
-    let project = TestProject::new("validate-fix-contract-drift");
+fn validate_page_metadata_rejects_missing_notes_section() {
+    let root = temp_dir("validate-notes");
+    fs::create_dir_all(root.path()).expect("create temp dir");
+    let page = root.join("page.html");
     let html = render_page_document(
-        "Home",
-        "<p><a href=\"missing.html\">Manual</a></p>",
-        Theme::Light,
-        "wrong.css".to_string(),
+        "page",
+        "<p>body</p>",
+        Theme::Dark,
+        "../.fractal/style.css".to_string(),
     )
-    .replace("    <title>Home</title>\n", "");
-    project.write_page("index.html", html);
-
-    let preflight = preflight_repair_project(project.root()).expect("preflight simple drift");
-    assert!(preflight.events.iter().any(|event| matches!(
-        event,
-        OperationEvent::ProjectRepaired { path, applied: false } if path == Path::new("pages/index.html")
-    )));
-    let preflight_summary = preflight.summary();
-    assert!(!preflight_summary.source_changed);
-    assert_eq!(preflight_summary.repaired_paths.len(), 1);
-
-    let preview =
-        fs::read_to_string(project.pages_dir().join("index.html")).expect("read preview page");
-    assert!(!preview.contains("<title>Home</title>"));
-    assert!(preview.contains("href=\"wrong.css\""));
-
-    let repair = repair_project(project.root()).expect("fix simple drift");
-    let repair_summary = repair.summary();
-    assert!(repair_summary.source_changed);
-    assert_eq!(repair_summary.repaired_paths.len(), 1);
+    .replace("    <section data-fractal-notes>\n    </section>\n", "");
+    fs::write(&page, html).expect("write page");
 
-    let fixed =
-        fs::read_to_string(project.pages_dir().join("index.html")).expect("read fixed page");
-    assert!(fixed.contains("<title>Home</title>"));
-    assert!(fixed.contains("href=\"../.fractal/style.css\""));
-    assert!(fixed.contains("data-fractal-theme=\"dark\""));
-    assert!(!fixed.contains("<a href=\"missing.html\""));
-    assert!(fixed.contains("Manual"));
+    let error = validate_page_metadata(&page).expect_err("missing notes section should fail");
+    assert!(error.to_string().contains("missing notes section"));
 }
 
 #[test]
-fn write_page_source_rejects_invalid_fractal_html_before_saving() {
-    let project = TestProject::new("write-source-validates");
-    project.write_page(
-        "index.html",
-        render_page_document(
-            "Home",
-            "<p>original</p>",
-            Theme::Dark,
-            "../.fractal/style.css".to_string(),
-        ),
-    );
-    let invalid = render_page_document(
-        "Home",
-        "<p><span>invalid</span></p>",
+fn validate_page_metadata_rejects_duplicate_notes_sections() {
+    let root = temp_dir("validate-duplicate-notes");
+    fs::create_dir_all(root.path()).expect("create temp dir");
+    let page = root.join("page.html");
+    let html = render_page_document(
+        "page",
+        "<p>body</p>",
         Theme::Dark,
         "../.fractal/style.css".to_string(),
+    )
+    .replace(
+        "    <section data-fractal-notes>\n    </section>",
+        "    <section data-fractal-notes>\n    </section>\n    <section data-fractal-notes></section>",
     );
+    fs::write(&page, html).expect("write page");
 
-    let error = write_page_source(project.root(), Path::new("index"), invalid)
-        .expect_err("invalid source should fail");
-    assert!(error
-        .to_string()
-        .contains("unsupported Fractal body element"));
-    assert!(fs::read_to_string(project.pages_dir().join("index.html"))
-        .expect("read original page")
-        .contains("original"));
-}
+    let error = validate_page_metadata(&page).expect_err("duplicate notes sections should fail");
+    assert!(error.to_string().contains("duplicate notes section"));
+}
 
 #[test]
-fn validate_rejects_unsupported_manifest_version() {
-    let root = temp_dir("manifest-version");
+fn validate_page_metadata_rejects_malformed_note_ids() {
+    let root = temp_dir("validate-note-id");
     fs::create_dir_all(root.path()).expect("create temp dir");
-    fs::write(
-        root.join("fractal.json"),
-        serde_json::to_string_pretty(&ProjectManifest {
-            project_name: "test".to_string(),
-            version: MANIFEST_VERSION + 1,
-            default_page: "pages/index.html".to_string(),
-            theme: Theme::Dark,
-        })
-        .expect("serialize manifest"),
+    let page = root.join("page.html");
+    let html = render_page_document(
+        "page",
+        "<p>body</p>",
+        Theme::Dark,
+        "../.fractal/style.css".to_string(),
     )
-    .expect("write manifest");
+    .replace(
+        "    <section data-fractal-notes>\n    </section>",
+        "    <section data-fractal-notes>\n      <aside id=\"Rust\" data-fractal-note><p>bad</p></aside>\n    </section>",
+    );
+    fs::write(&page, html).expect("write page");
 
-    let error = validate_project(root.path()).expect_err("unsupported manifest should fail");
-    assert!(error.to_string().contains("unsupported manifest version"));
+    let error = validate_page_metadata(&page).expect_err("malformed note id should fail");
+    assert!(error.to_string().contains("malformed note id"));
 }
 
 #[test]
-fn validate_fix_repairs_missing_project_scaffold_and_page_markers() {
-    let root = temp_dir("validate-fix");
-    let pages_dir = root.join("pages");
-    fs::create_dir_all(&pages_dir).expect("create pages dir");
-    fs::write(
-        root.join("fractal.json"),
-        serde_json::to_string_pretty(&ProjectManifest {
-            project_name: "test".to_string(),
-            version: MANIFEST_VERSION,
-            default_page: "pages/index.html".to_string(),
-            theme: Theme::Dark,
-        })
-        .expect("serialize manifest"),
+fn validate_page_metadata_allows_custom_summary_and_tags() {
+    let root = temp_dir("validate-custom-meta");
+    fs::create_dir_all(root.path()).expect("create temp dir");
+    let page = root.join("page.html");
+    let html = render_page_document(
+        "page",
+        "<p>body</p>",
+        Theme::Dark,
+        "../.fractal/style.css".to_string(),
     )
-    .expect("write manifest");
-    fs::write(
-            pages_dir.join("index.html"),
-            "<!doctype html>\n<html lang=\"en\">\n  <head>\n    <meta charset=\"utf-8\">\n    <title>index</title>\n  </head>\n  <body>\n    <main>\n      <h1>index</h1>\n      <p>body</p>\n    </main>\n  </body>\n</html>\n",
+    .replace(
+        "content=\"Short page summary here.\"",
+        "content=\"A custom project summary.\"",
+    )
+    .replace(
+        "content=\"rust, graphs, parsing\"",
+        "content=\"personal, custom\"",
+    );
+    fs::write(&page, html).expect("write page");
+
+    validate_page_metadata(&page).expect("custom metadata should validate");
+}
+
+#[test]
+fn validation_report_collects_every_diagnostic_with_rule_and_location() {
+    let project = TestProject::new("validation-report");
+    let page = |title: &str, body: &str| {
+        render_page_document(
+            title,
+            body,
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
         )
-        .expect("write page");
+    };
+    project.write_page("index.html", page("Home", "<p>Fine.</p>"));
+    project.write_page(
+        "no-summary.html",
+        page("No Summary", "<p>Fine.</p>")
+            .replace("<meta name=\"fractal:summary\" content=\"\" />", ""),
+    );
+    let structural = page(
+        "Structural",
+        "<div>Boxed.</div>\n<p>Then <h1>Extra</h1></p>",
+    );
+    project.write_page("structural.html", &structural);
 
-    repair_project(root.path()).expect("fix and validate project");
+    let error = validate_project(project.root()).expect_err("fail-fast validation");
+    assert!(error.message.contains("no-summary.html"));
 
-    assert!(root.join(".fractal/style.css").is_file());
-    let html = fs::read_to_string(pages_dir.join("index.html")).expect("read page");
-    let document = PageDocument::parse(&html);
-    assert_eq!(document.fractal_meta(), required_meta());
-    assert!(document
-        .document
-        .select_first("link[rel=\"stylesheet\"][href=\"../.fractal/style.css\"]")
-        .is_ok());
-    assert!(html.contains("<body data-fractal-theme=\"dark\">"));
-    assert_eq!(document.notes_section_count(), 1);
+    let report = validation_report(project.root()).expect("validation report");
+    assert_eq!(report.pages_checked, 3);
+    assert!(!report.is_valid());
+    assert!(report
+        .diagnostics
+        .iter()
+        .all(|diagnostic| diagnostic.page.as_deref() != Some("index.html")));
+
+    let missing_summary = report
+        .diagnostics
+        .iter()
+        .find(|diagnostic| diagnostic.rule == "FR-HEAD-002")
+        .expect("missing summary diagnostic");
+    assert_eq!(missing_summary.page.as_deref(), Some("no-summary.html"));
+    assert_eq!(missing_summary.severity, DiagnosticSeverity::Error);
+    assert!(missing_summary.message.ends_with("fractal:summary"));
+    assert!(missing_summary.fixable);
+
+    let boxed = report
+        .diagnostics
+        .iter()
+        .find(|diagnostic| diagnostic.rule == "FR-CONTENT-004")
+        .expect("div diagnostic");
+    let offset = structural.find("<div>").expect("div offset");
+    let span = boxed.span.expect("div span");
+    assert_eq!((span.start, span.end), (offset, offset + "<div>".len()));
+    assert_eq!(span.line, structural[..offset].matches('\n').count() + 1);
+    assert_eq!(boxed.node.as_deref(), Some("<div>"));
+    assert!(!boxed.fixable);
+    assert!(report
+        .diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.rule == "FR-CONTENT-001"
+            && diagnostic.node.as_deref() == Some("<h1>")));
+    assert_eq!(
+        report.error_count(),
+        report
+            .diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.page.is_some())
+            .count()
+    );
+
+    repair_project(project.root()).expect_err("structural page still fails after repair");
+    let repaired = validation_report(project.root()).expect("report after repair");
+    assert!(repaired
+        .diagnostics
+        .iter()
+        .all(|diagnostic| diagnostic.page.as_deref() == Some("structural.html")));
 }
 
 #[test]
-fn validate_fix_merges_duplicate_notes_sections() {
-    let root = temp_dir("validate-fix-duplicate-notes");
-    let pages_dir = root.join("pages");
-    fs::create_dir_all(&pages_dir).expect("create pages dir");
-    write_test_manifest(root.path());
-    fs::write(
-        pages_dir.join("index.html"),
-        r#"<!doctype html>
-<html lang="en">
-  <head>
-    <meta charset="utf-8">
-    <title>index</title>
-    <meta name="fractal:version" content="0.1">
-    <meta name="fractal:summary" content="">
-    <meta name="fractal:tags" content="">
-  </head>
-  <body>
-    <main><h1>index</h1></main>
-    <section data-fractal-notes>
-      <aside id="note-alpha" data-fractal-note><p>alpha</p></aside>
-    </section>
-    <section data-fractal-notes>
-      <aside id="note-beta" data-fractal-note><p>beta</p></aside>
-    </section>
-  </body>
-</html>
-"#,
-    )
-    .expect("write page");
+fn source_spans_only_pair_elements_with_start_tags_written_in_the_source() {
+    let source = "<main><!-- <p>draft</p> --><script>let tag = \"<p>\";</script>\
+                  <p title=\"a > <p>\">One</p><table><tr><td>Cell</td></tr></table>\
+                  <table><tbody><tr><td>Two</td></tr></tbody></table><p>Three</p></main>";
+    let document = PageDocument::parse(source);
+    let nodes = |selector: &str| {
+        document
+            .document
+            .select(selector)
+            .expect("selector")
+            .map(|element| element.as_node().clone())
+            .collect::<Vec<_>>()
+    };
+
+    let paragraphs = nodes("p");
+    assert_eq!(paragraphs.len(), 2);
+    let first = source.find("<p title").expect("first paragraph");
+    let span = source_span(source, &document, &paragraphs[0]).expect("first span");
+    assert_eq!(
+        (span.start, span.end),
+        (first, first + "<p title=\"a > <p>\">".len())
+    );
+    let last = source.find("<p>Three").expect("last paragraph");
+    let span = source_span(source, &document, &paragraphs[1]).expect("last span");
+    assert_eq!((span.start, span.end), (last, last + "<p>".len()));
+    assert_eq!(
+        node_at_span(source, &document, "p", last).as_ref(),
+        Some(&paragraphs[1])
+    );
+    assert!(node_at_span(source, &document, "p", source.find("<p>draft").unwrap()).is_none());
 
-    repair_project(root.path()).expect("fix and validate project");
+    let cells = nodes("td");
+    let second = source.find("<td>Two").expect("second cell");
+    let span = source_span(source, &document, &cells[1]).expect("cell span");
+    assert_eq!(span.start, second);
 
-    let html = fs::read_to_string(pages_dir.join("index.html")).expect("read page");
-    let document = PageDocument::parse(&html);
-    assert_eq!(document.notes_section_count(), 1);
+    for implied in nodes("tbody").iter().chain(&nodes("body")) {
+        assert_eq!(source_span(source, &document, implied), None);
+    }
+}
+
+#[test]
+fn repair_suggestions_propose_patches_and_apply_only_accepted_ids() {
+    let project = TestProject::new("repair-suggestions");
+    let body = "<div>Boxed text.</div>\n<div><p>Inside.</p></div>\n<h1>Extra</h1>\n<p>Some <span>inner</span> text and a <a href=\"#Bad_Note\" data-fractal-link=\"note\">note</a>.</p>";
+    let html = insert_note_into_document(
+        &render_page_document(
+            "Home",
+            body,
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        ),
+        &render_note_aside("Bad_Note", "Explains the note."),
+    )
+    .expect("insert note");
+    project.write_page("index.html", &html);
+    let citing = render_page_document(
+        "Topic",
+        "<p>See the <a href=\"../index.html#Bad_Note\" data-fractal-link=\"note\">boxed note</a>.</p>\
+         <section data-fractal-embed=\"../index.html#Bad_Note\"></section>",
+        Theme::Dark,
+        "../../.fractal/style.css".to_string(),
+    );
+    project.write_page("topics/topic.html", &citing);
+    assert!(!validation_report(project.root())
+        .expect("report")
+        .is_valid());
+
+    let suggestions = repair_suggestions(project.root()).expect("suggestions");
+    let actions = suggestions
+        .iter()
+        .map(|suggestion| suggestion.action.as_str())
+        .collect::<Vec<_>>();
     assert_eq!(
-        document.notes(),
+        actions,
         vec![
-            NoteEntry {
-                id: "note-alpha".to_string(),
-                label: "alpha".to_string(),
-            },
-            NoteEntry {
-                id: "note-beta".to_string(),
-                label: "beta".to_string(),
-            },
+            "turn <div> with inline content into <p>",
+            "unwrap <div> and keep its content",
+            "demote <h1> to <h2>",
+            "unwrap <span> and keep its content",
+            "rename note id `Bad_Note` to `note-bad-note` and rewrite 1 note link(s)",
         ]
     );
+    let demote = &suggestions[2];
+    assert_eq!(demote.diagnostic.rule, "FR-CONTENT-001");
+    assert!(demote.id.starts_with("FR-CONTENT-001:index.html:"));
+    assert!(demote.diff.diff.contains("+<h2>Extra</h2>"));
+    assert_eq!(
+        fs::read_to_string(project.pages_dir().join("index.html")).expect("read page"),
+        html
+    );
+
+    let error = apply_repair_suggestions(project.root(), &["FR-CONTENT-001:index.html:1:1"])
+        .expect_err("unknown suggestion");
+    assert_eq!(error.code, FractalErrorCode::NotFound);
+
+    let report = apply_repair_suggestions(project.root(), &[demote.id.as_str()])
+        .expect("apply one suggestion");
+    assert!(report.events.iter().any(|event| matches!(
+        event,
+        OperationEvent::RepairSuggestionApplied { action, .. } if action == "demote <h1> to <h2>"
+    )));
+    let page = fs::read_to_string(project.pages_dir().join("index.html")).expect("read page");
+    assert!(page.contains("<h2>Extra</h2>"));
+    assert!(page.contains("<span>inner</span>"));
+
+    let remaining = repair_suggestions(project.root()).expect("remaining suggestions");
+    assert_eq!(remaining.len(), 4);
+    let ids = remaining
+        .iter()
+        .map(|suggestion| suggestion.id.as_str())
+        .collect::<Vec<_>>();
+
+    let report =
+        apply_repair_suggestions(project.root(), &ids).expect("apply remaining suggestions");
+    assert!(report.events.contains(&OperationEvent::PageLinksRewritten {
+        page: PathBuf::from("pages/topics/topic.html"),
+        count: 2,
+    }));
+    let topic =
+        fs::read_to_string(project.pages_dir().join("topics/topic.html")).expect("read topic");
+    assert!(topic.contains("href=\"../index.html#note-bad-note\""));
+    assert!(topic.contains("data-fractal-embed=\"../index.html#note-bad-note\""));
+
+    let page = fs::read_to_string(project.pages_dir().join("index.html")).expect("read page");
+    assert!(page.contains("<p>Boxed text.</p>"));
+    assert!(page.contains("<p>Inside.</p>"));
+    assert!(page.contains("Some inner text"));
+    assert!(page.contains("href=\"#note-bad-note\""));
+    assert!(page.contains("id=\"note-bad-note\""));
+    validate_project(project.root()).expect("repaired page validates");
 }
 
 #[test]
-fn validate_ignores_non_html_files_under_pages() {
-    let root = temp_dir("validate-assets");
-    let pages_dir = root.join("pages");
-    let workspace_dir = root.join(".fractal");
-    fs::create_dir_all(&pages_dir).expect("create pages dir");
-    fs::create_dir_all(&workspace_dir).expect("create workspace dir");
-    fs::write(workspace_dir.join("style.css"), "").expect("write stylesheet");
-    fs::write(
-        root.join("fractal.json"),
-        serde_json::to_string_pretty(&ProjectManifest {
-            project_name: "test".to_string(),
-            version: MANIFEST_VERSION,
-            default_page: "pages/index.html".to_string(),
-            theme: Theme::Dark,
-        })
-        .expect("serialize manifest"),
-    )
-    .expect("write manifest");
-    fs::write(
-        pages_dir.join("index.html"),
+fn lint_project_reports_configured_hygiene_rules() {
+    let project = TestProject::new("lint");
+    let page = |title: &str, body: &str, summary: &str, tags: &str| {
         render_page_document(
-            "index",
-            "<p>body</p>",
+            title,
+            body,
             Theme::Dark,
             "../.fractal/style.css".to_string(),
+        )
+        .replace(
+            "<meta name=\"fractal:summary\" content=\"\" />",
+            &format!("<meta name=\"fractal:summary\" content=\"{summary}\" />"),
+        )
+        .replace(
+            "<meta name=\"fractal:tags\" content=\"\" />",
+            &format!("<meta name=\"fractal:tags\" content=\"{tags}\" />"),
+        )
+    };
+    project.write_page(
+        "index.html",
+        page("Home", "<p>Welcome.</p>", "Start here", "guide, graphs"),
+    );
+    let theory = insert_note_into_document(
+        &page(
+            "Graph Theory",
+            "<h2>Basics</h2>\n<h4>Edges</h4>\n<p>one two three four five six seven</p>",
+            "",
+            "graphs",
         ),
+        &render_note_aside("note-edges", "Edges join nodes."),
     )
-    .expect("write index page");
-    fs::write(pages_dir.join("asset.txt"), "asset").expect("write asset");
+    .expect("insert note");
+    project.write_page("graph-theory.html", &theory);
+    project.write_page(
+        "graph-theroy.html",
+        page("Graph Theroy", "<p>Short.</p>", "Typo", "graphs, solo"),
+    );
 
-    validate_project(root.path()).expect("validate project");
+    let mut manifest = load_project_manifest(project.root()).expect("load manifest");
+    manifest.lint = serde_json::from_value(serde_json::json!({
+        "missing-tags": { "enabled": false },
+        "FR-LINT-003": { "severity": "error" },
+        "long-page": { "max_words": 8 },
+    }))
+    .expect("lint config");
+    fs::write(
+        project.root().join("fractal.json"),
+        serde_json::to_string_pretty(&manifest).expect("serialize manifest"),
+    )
+    .expect("write manifest");
+
+    let report = lint_project(project.root()).expect("lint project");
+    assert_eq!(report.pages_checked, 3);
+    let found = report
+        .diagnostics
+        .iter()
+        .map(|diagnostic| {
+            (
+                diagnostic.rule.as_str(),
+                diagnostic.page.as_deref().unwrap_or_default(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        found,
+        vec![
+            ("FR-LINT-001", "graph-theory.html"),
+            ("FR-LINT-004", "graph-theory.html"),
+            ("FR-LINT-005", "graph-theory.html"),
+            ("FR-LINT-006", "graph-theory.html"),
+            ("FR-LINT-003", "graph-theory.html"),
+            ("FR-LINT-003", "graph-theroy.html"),
+            ("FR-LINT-007", "graph-theroy.html"),
+            ("FR-LINT-008", "graph-theroy.html"),
+            ("FR-LINT-007", "index.html"),
+        ]
+    );
+    assert_eq!(report.error_count(), 2);
+    assert!(!report.is_valid());
+
+    let skip = &report.diagnostics[2];
+    assert_eq!(skip.node.as_deref(), Some("<h4>"));
+    let offset = theory.find("<h4>").expect("h4 offset");
+    assert_eq!(skip.span.expect("h4 span").start, offset);
+    assert_eq!(skip.severity, DiagnosticSeverity::Warning);
+    assert!(report.diagnostics[6].message.contains("`solo`"));
+
+    manifest.lint = serde_json::from_value(serde_json::json!({ "no-such-rule": {} }))
+        .expect("unknown lint config");
+    fs::write(
+        project.root().join("fractal.json"),
+        serde_json::to_string_pretty(&manifest).expect("serialize manifest"),
+    )
+    .expect("write manifest");
+    let error = lint_project(project.root()).expect_err("unknown rule");
+    assert_eq!(error.code, FractalErrorCode::InvalidProject);
 }
 
 #[test]
-fn page_destination_is_relative_to_pages_root() {
-    let destination = resolve_page_destination(Path::new("."), Path::new("nested/secondpage"))
-        .expect("resolve destination");
+fn parallel_page_work_matches_sequential_results() {
+    let dir = temp_dir("fixture");
+    let root = dir.path().join("fixture");
+    let report = generate_fixture_project(&root, 24).expect("generate fixture");
+    assert_eq!(
+        report
+            .events
+            .iter()
+            .filter(|event| matches!(event, OperationEvent::PageCreated { .. }))
+            .count(),
+        24
+    );
 
-    assert_eq!(destination, PathBuf::from("./pages/nested/secondpage.html"));
+    set_page_workers(1);
+    validate_project(&root).expect("sequential validation");
+    let sequential_index = load_project_index(&root).expect("sequential index");
+    let sequential_report = validation_report(&root).expect("sequential report");
+
+    set_page_workers(4);
+    assert_eq!(page_workers(), 4);
+    validate_project(&root).expect("parallel validation");
+    build_index(&root).expect("parallel index build");
+    assert_eq!(
+        load_project_index(&root).expect("parallel index"),
+        sequential_index
+    );
+    assert_eq!(
+        validation_report(&root).expect("parallel report"),
+        sequential_report
+    );
+    assert!(sequential_report.diagnostics.is_empty());
+    assert_eq!(sequential_report.pages_checked, 24);
+
+    fs::write(root.join("pages/topic-07.html"), "<html></html>").expect("break page");
+    fs::write(root.join("pages/topic-19.html"), "<html></html>").expect("break page");
+    let error = validate_project(&root).expect_err("broken fixture");
+    assert!(error.message.contains("topic-07.html"));
+    let broken = validation_report(&root).expect("broken report");
+    let mut pages = broken
+        .diagnostics
+        .iter()
+        .filter_map(|diagnostic| diagnostic.page.as_deref())
+        .collect::<Vec<_>>();
+    pages.dedup();
+    assert_eq!(pages, vec!["topic-07.html", "topic-19.html"]);
+    set_page_workers(0);
+
+    let error = generate_fixture_project(dir.path().join("empty"), 0).expect_err("zero pages");
+    assert_eq!(error.code, FractalErrorCode::InvalidInput);
+}
+
+#[test]
+fn validate_project_rejects_extra_fractal_meta_tags() {
+    let project = TestProject::new("validate-extra-fractal-meta");
+    let html = render_page_document(
+        "Home",
+        "<p>body</p>",
+        Theme::Dark,
+        "../.fractal/style.css".to_string(),
+    )
+    .replace(
+        "    <link rel=\"stylesheet\" href=\"../.fractal/style.css\">",
+        "    <meta name=\"fractal:custom\" content=\"nope\">\n    <link rel=\"stylesheet\" href=\"../.fractal/style.css\">",
+    );
+    project.write_page("index.html", html);
+
+    let error = validate_project(project.root()).expect_err("extra fractal meta should fail");
+    assert_eq!(error.code, FractalErrorCode::InvalidProject);
+    assert!(error.to_string().contains("unsupported Fractal meta tag"));
+}
+
+#[test]
+fn validate_project_rejects_title_heading_mismatch() {
+    let project = TestProject::new("validate-title-heading-mismatch");
+    let html = render_page_document(
+        "Home",
+        "<p>body</p>",
+        Theme::Dark,
+        "../.fractal/style.css".to_string(),
+    )
+    .replace("<h1>Home</h1>", "<h1>Other</h1>");
+    project.write_page("index.html", html);
+
+    let error = validate_project(project.root()).expect_err("mismatched title should fail");
+    assert!(error.to_string().contains("title and main heading differ"));
+}
+
+#[test]
+fn validate_project_rejects_unsupported_body_elements_and_manual_links() {
+    let project = TestProject::new("validate-body-contract");
+    project.write_page(
+        "index.html",
+        render_page_document(
+            "Home",
+            "<p><span>bad</span></p>",
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        ),
+    );
+
+    let error = validate_project(project.root()).expect_err("unsupported span should fail");
+    assert_eq!(error.code, FractalErrorCode::InvalidProject);
+    assert!(error
+        .to_string()
+        .contains("unsupported Fractal body element"));
+
+    project.write_page(
+        "index.html",
+        render_page_document(
+            "Home",
+            "<p><a href=\"missing.html\">Manual</a></p>",
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        ),
+    );
+
+    let error = validate_project(project.root()).expect_err("manual link should fail");
+    assert!(error
+        .to_string()
+        .contains("manual link is not valid Fractal"));
+}
+
+#[test]
+fn validate_project_accepts_inline_formatting_only_in_current_format_version() {
+    let project = TestProject::new("validate-inline-formatting");
+    let body = "<p><strong>Bold</strong>, <em>em</em>, H<sub>2</sub>O, x<sup>2</sup>,<br><mark>marked</mark> <s>old</s> <code>code</code></p>";
+    project.write_page(
+        "index.html",
+        render_page_document(
+            "Home",
+            body,
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        )
+        .replace("<section data-fractal-notes>\n", "<section data-fractal-notes>\n      <aside id=\"note-x\" data-fractal-note><p><em>note</em></p></aside>\n"),
+    );
+    validate_project(project.root()).expect("inline formatting is valid in current pages");
+
+    let legacy = render_page_document(
+        "Home",
+        body,
+        Theme::Dark,
+        "../.fractal/style.css".to_string(),
+    )
+    .replace("content=\"0.2\"", "content=\"0.1\"");
+    project.write_page("index.html", &legacy);
+    let error =
+        validate_project(project.root()).expect_err("legacy pages reject inline formatting");
+    assert!(error
+        .to_string()
+        .contains("inline formatting requires fractal:version 0.2"));
+
+    project.write_page("index.html", legacy.replace(body, "<p>plain</p>"));
+    validate_project(project.root()).expect("legacy plain pages stay valid");
+
+    project.write_page(
+        "index.html",
+        legacy.replace("content=\"0.1\"", "content=\"0.3\""),
+    );
+    let error = validate_project(project.root()).expect_err("unknown versions fail");
+    assert!(error
+        .to_string()
+        .contains("unsupported page format version"));
+}
+
+#[test]
+fn update_page_body_upgrades_legacy_pages_for_inline_formatting() {
+    let project = TestProject::new("update-body-inline");
+    project.write_page(
+        "index.html",
+        render_page_document(
+            "Home",
+            "<p>plain</p>",
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        )
+        .replace("content=\"0.2\"", "content=\"0.1\""),
+    );
+
+    update_page_body(
+        project.root(),
+        "index.html",
+        "<p>Now <strong>bold</strong><br>and <em>emphasized</em></p>",
+        None,
+    )
+    .expect("update body with inline formatting");
+
+    let html = fs::read_to_string(project.pages_dir().join("index.html")).expect("read page");
+    assert!(html.contains("content=\"0.2\""));
+    assert!(html.contains("<p>Now <strong>bold</strong><br>and <em>emphasized</em></p>"));
+    validate_project(project.root()).expect("upgraded page validates");
+}
+
+#[test]
+fn validate_project_rejects_broken_generated_links() {
+    let project = TestProject::new("validate-broken-generated-link");
+    project.write_page(
+        "index.html",
+        render_page_document(
+            "Home",
+            "<p><a href=\"missing.html\" data-fractal-link=\"page\">Missing</a></p>",
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        ),
+    );
+
+    let error = validate_project(project.root()).expect_err("missing generated target should fail");
+    assert!(error
+        .to_string()
+        .contains("generated page link target is missing"));
+}
+
+#[test]
+fn validate_project_rejects_page_links_whose_text_does_not_match_the_target() {
+    let project = TestProject::new("validate-mismatched-page-link-text");
+    project.write_page(
+        "index.html",
+        render_page_document(
+            "Home",
+            "<p><a href=\"rust.html\" data-fractal-link=\"page\">the language</a></p>",
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        ),
+    );
+    project.write_page(
+        "rust.html",
+        render_page_document(
+            "Rust",
+            "<p>body</p>",
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        ),
+    );
+
+    let error =
+        validate_project(project.root()).expect_err("mismatched generated link text should fail");
+    assert!(error
+        .to_string()
+        .contains("generated page link text does not identify its target"));
+    assert!(error.to_string().contains("expected `Rust`"));
+
+    project.write_page(
+        "index.html",
+        render_page_document(
+            "Home",
+            "<p><a href=\"rust.html\">the language</a></p>",
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        ),
+    );
+
+    let error = validate_project(project.root()).expect_err("mismatched manual link should fail");
+    assert!(error
+        .to_string()
+        .contains("manual page link text does not identify its target"));
+}
+
+#[test]
+fn validate_fix_rewrites_mismatched_internal_links_to_visible_target_labels() {
+    let project = TestProject::new("validate-fix-mismatched-page-links");
+    project.write_page(
+        "index.html",
+        render_page_document(
+            "Home",
+            r#"<p><a href="rust.html">the language</a></p>
+      <p><a href="rust.html" data-fractal-link="page">systems language</a></p>"#,
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        ),
+    );
+    project.write_page(
+        "rust.html",
+        render_page_document(
+            "Rust",
+            "<p>body</p>",
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        ),
+    );
+
+    repair_project(project.root()).expect("fix mismatched internal links");
+
+    let fixed =
+        fs::read_to_string(project.pages_dir().join("index.html")).expect("read fixed index page");
+    assert!(fixed.contains("<a href=\"rust.html\" data-fractal-link=\"page\">Rust</a>"));
+    assert!(!fixed.contains("the language (Rust)"));
+    assert!(!fixed.contains("systems language (Rust)"));
+}
+
+#[test]
+fn validate_fix_repairs_simple_contract_drift_and_unwraps_manual_links() {
+    let project = TestProject::new("validate-fix-contract-drift");
+    let html = render_page_document(
+        "Home",
+        "<p><a href=\"missing.html\">Manual</a></p>",
+        Theme::Light,
+        "wrong.css".to_string(),
+    )
+    .replace("    <title>Home</title>\n", "");
+    project.write_page("index.html", html);
+
+    let preflight = preflight_repair_project(project.root()).expect("preflight simple drift");
+    assert!(preflight.events.iter().any(|event| matches!(
+        event,
+        OperationEvent::ProjectRepaired { path, applied: false } if path == Path::new("pages/index.html")
+    )));
+    let preflight_summary = preflight.summary();
+    assert!(!preflight_summary.source_changed);
+    assert_eq!(preflight_summary.repaired_paths.len(), 1);
+
+    let preview =
+        fs::read_to_string(project.pages_dir().join("index.html")).expect("read preview page");
+    assert!(!preview.contains("<title>Home</title>"));
+    assert!(preview.contains("href=\"wrong.css\""));
+
+    let repair = repair_project(project.root()).expect("fix simple drift");
+    let repair_summary = repair.summary();
+    assert!(repair_summary.source_changed);
+    assert_eq!(repair_summary.repaired_paths.len(), 1);
+
+    let fixed =
+        fs::read_to_string(project.pages_dir().join("index.html")).expect("read fixed page");
+    assert!(fixed.contains("<title>Home</title>"));
+    assert!(fixed.contains("href=\"../.fractal/style.css\""));
+    assert!(fixed.contains("data-fractal-theme=\"dark\""));
+    assert!(!fixed.contains("<a href=\"missing.html\""));
+    assert!(fixed.contains("Manual"));
+}
+
+#[test]
+fn write_page_source_rejects_invalid_fractal_html_before_saving() {
+    let project = TestProject::new("write-source-validates");
+    project.write_page(
+        "index.html",
+        render_page_document(
+            "Home",
+            "<p>original</p>",
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        ),
+    );
+    let invalid = render_page_document(
+        "Home",
+        "<p><span>invalid</span></p>",
+        Theme::Dark,
+        "../.fractal/style.css".to_string(),
+    );
+
+    let error = write_page_source(project.root(), Path::new("index"), invalid, None)
+        .expect_err("invalid source should fail");
+    assert!(error
+        .to_string()
+        .contains("unsupported Fractal body element"));
+    assert!(fs::read_to_string(project.pages_dir().join("index.html"))
+        .expect("read original page")
+        .contains("original"));
+}
+
+#[test]
+fn validate_rejects_unsupported_manifest_version() {
+    let root = temp_dir("manifest-version");
+    fs::create_dir_all(root.path()).expect("create temp dir");
+    fs::write(
+        root.join("fractal.json"),
+        serde_json::to_string_pretty(&ProjectManifest {
+            project_name: "test".to_string(),
+            version: MANIFEST_VERSION + 1,
+            default_page: "pages/index.html".to_string(),
+            theme: Theme::Dark,
+            git_auto_commit: false,
+            lock_timeout_ms: 0,
+            lint: BTreeMap::new(),
+        })
+        .expect("serialize manifest"),
+    )
+    .expect("write manifest");
+
+    let error = validate_project(root.path()).expect_err("unsupported manifest should fail");
+    assert!(error.to_string().contains("unsupported manifest version"));
+}
+
+#[test]
+fn validate_fix_repairs_missing_project_scaffold_and_page_markers() {
+    let root = temp_dir("validate-fix");
+    let pages_dir = root.join("pages");
+    fs::create_dir_all(&pages_dir).expect("create pages dir");
+    fs::write(
+        root.join("fractal.json"),
+        serde_json::to_string_pretty(&ProjectManifest {
+            project_name: "test".to_string(),
+            version: MANIFEST_VERSION,
+            default_page: "pages/index.html".to_string(),
+            theme: Theme::Dark,
+            git_auto_commit: false,
+            lock_timeout_ms: 0,
+            lint: BTreeMap::new(),
+        })
+        .expect("serialize manifest"),
+    )
+    .expect("write manifest");
+    fs::write(
+            pages_dir.join("index.html"),
+            "<!doctype html>\n<html lang=\"en\">\n  <head>\n    <meta charset=\"utf-8\">\n    <title>index</title>\n  </head>\n  <body>\n    <main>\n      <h1>index</h1>\n      <p>body</p>\n    </main>\n  </body>\n</html>\n",
+        )
+        .expect("write page");
+
+    repair_project(root.path()).expect("fix and validate project");
+
+    assert!(root.join(".fractal/style.css").is_file());
+    let html = fs::read_to_string(pages_dir.join("index.html")).expect("read page");
+    let document = PageDocument::parse(&html);
+    assert_eq!(document.fractal_meta(), required_meta());
+    assert!(document
+        .document
+        .select_first("link[rel=\"stylesheet\"][href=\"../.fractal/style.css\"]")
+        .is_ok());
+    assert!(html.contains("<body data-fractal-theme=\"dark\">"));
+    assert_eq!(document.notes_section_count(), 1);
+}
+
+#[test]
+fn validate_fix_merges_duplicate_notes_sections() {
+    let root = temp_dir("validate-fix-duplicate-notes");
+    let pages_dir = root.join("pages");
+    fs::create_dir_all(&pages_dir).expect("create pages dir");
+    write_test_manifest(root.path());
+    fs::write(
+        pages_dir.join("index.html"),
+        r#"<!doctype html>
+<html lang="en">
+  <head>
+    <meta charset="utf-8">
+    <title>index</title>
+    <meta name="fractal:version" content="0.1">
+    <meta name="fractal:summary" content="">
+    <meta name="fractal:tags" content="">
+  </head>
+  <body>
+    <main><h1>index</h1></main>
+    <section data-fractal-notes>
+      <aside id="note-alpha" data-fractal-note><p>alpha</p></aside>
+    </section>
+    <section data-fractal-notes>
+      <aside id="note-beta" data-fractal-note><p>beta</p></aside>
+    </section>
+  </body>
+</html>
+"#,
+    )
+    .expect("write page");
+
+    repair_project(root.path()).expect("fix and validate project");
+
+    let html = fs::read_to_string(pages_dir.join("index.html")).expect("read page");
+    let document = PageDocument::parse(&html);
+    assert_eq!(document.notes_section_count(), 1);
+    assert_eq!(
+        document.notes(),
+        vec![
+            NoteEntry {
+                id: "note-alpha".to_string(),
+                label: "alpha".to_string(),
+            },
+            NoteEntry {
+                id: "note-beta".to_string(),
+                label: "beta".to_string(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn validate_ignores_non_html_files_under_pages() {
+    let root = temp_dir("validate-assets");
+    let pages_dir = root.join("pages");
+    let workspace_dir = root.join(".fractal");
+    fs::create_dir_all(&pages_dir).expect("create pages dir");
+    fs::create_dir_all(&workspace_dir).expect("create workspace dir");
+    fs::write(workspace_dir.join("style.css"), "").expect("write stylesheet");
+    fs::write(
+        root.join("fractal.json"),
+        serde_json::to_string_pretty(&ProjectManifest {
+            project_name: "test".to_string(),
+            version: MANIFEST_VERSION,
+            default_page: "pages/index.html".to_string(),
+            theme: Theme::Dark,
+            git_auto_commit: false,
+            lock_timeout_ms: 0,
+            lint: BTreeMap::new(),
+        })
+        .expect("serialize manifest"),
+    )
+    .expect("write manifest");
+    fs::write(
+        pages_dir.join("index.html"),
+        render_page_document(
+            "index",
+            "<p>body</p>",
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        ),
+    )
+    .expect("write index page");
+    fs::write(pages_dir.join("asset.txt"), "asset").expect("write asset");
+
+    validate_project(root.path()).expect("validate project");
+}
+
+#[test]
+fn page_destination_is_relative_to_pages_root() {
+    let destination = resolve_page_destination(Path::new("."), Path::new("nested/secondpage"))
+        .expect("resolve destination");
+
+    assert_eq!(destination, PathBuf::from("./pages/nested/secondpage.html"));
+}
+
+#[test]
+fn page_destination_accepts_optional_pages_prefix() {
+    let destination = resolve_page_destination(Path::new("."), Path::new("pages/nested/topic"))
+        .expect("resolve destination");
+
+    assert_eq!(destination, PathBuf::from("./pages/nested/topic.html"));
+}
+
+#[test]
+fn page_destination_rejects_parent_traversal() {
+    let error = resolve_page_destination(Path::new("."), Path::new("../escape"))
+        .expect_err("parent traversal should be rejected");
+
+    assert_eq!(error.code, FractalErrorCode::InvalidInput);
+    assert_eq!(error.to_string(), "page path cannot contain `..`");
+}
+
+#[test]
+fn page_destination_accepts_lowercase_unicode_and_underscores() {
+    let destination = resolve_page_destination(Path::new("."), Path::new("göteborg/my_new_page"))
+        .expect("resolve destination");
+
+    assert_eq!(
+        destination,
+        PathBuf::from("./pages/göteborg/my_new_page.html")
+    );
+}
+
+#[test]
+fn page_destination_rejects_uppercase_unicode() {
+    let error = resolve_page_destination(Path::new("."), Path::new("Göteborg"))
+        .expect_err("uppercase page path should be rejected");
+
+    assert_eq!(error.code, FractalErrorCode::InvalidInput);
+    assert_eq!(
+        error.to_string(),
+        "page path component must be a lowercase page slug: Göteborg"
+    );
+}
+
+#[test]
+fn note_id_normalizes_trigger_text() {
+    assert_eq!(
+        note_id_from_trigger("Andromeda Galaxy").expect("normalize trigger"),
+        "note-andromeda-galaxy"
+    );
+    assert_eq!(
+        note_id_from_trigger("JAVA").expect("normalize trigger"),
+        "note-java"
+    );
+}
+
+#[test]
+fn new_page_rebuilds_index() {
+    let project = TestProject::new("new-page-index");
+    project.write_page(
+        "index.html",
+        render_page_document(
+            "index",
+            "<p>body</p>",
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        ),
+    );
+
+    let report = new_page(project.root(), Path::new("secondpage")).expect("create new page");
+    let created_page = PathBuf::from("pages/secondpage.html");
+    assert_eq!(
+        report.events.first(),
+        Some(&OperationEvent::PageCreated {
+            path: created_page.clone()
+        })
+    );
+    let summary = report.summary();
+    assert!(summary.source_changed);
+    assert!(summary.generated_changed);
+    assert_eq!(summary.created_paths, vec![created_page.clone()]);
+    assert_eq!(summary.pages_changed, vec![created_page]);
+
+    let index: ProjectIndex = serde_json::from_str(
+        &fs::read_to_string(project.workspace_dir().join("index.json")).expect("read index"),
+    )
+    .expect("parse index");
+
+    assert_eq!(index.version, INDEX_VERSION);
+    assert_eq!(
+        index.files,
+        vec![
+            FileEntry {
+                path: "index.html".to_string(),
+                kind: "page".to_string(),
+                content_hash: None,
+                mime_type: None,
+            },
+            FileEntry {
+                path: "secondpage.html".to_string(),
+                kind: "page".to_string(),
+                content_hash: None,
+                mime_type: None,
+            },
+        ]
+    );
+    assert_eq!(
+        index.pages,
+        vec![
+            PageEntry {
+                path: "index.html".to_string(),
+                title: "index".to_string(),
+                meta: required_meta(),
+                notes: vec![],
+                links: vec![],
+                images: vec![],
+                headings: vec![],
+                embeds: vec![],
+            },
+            PageEntry {
+                path: "secondpage.html".to_string(),
+                title: "secondpage".to_string(),
+                meta: required_meta(),
+                notes: vec![],
+                links: vec![],
+                images: vec![],
+                headings: vec![],
+                embeds: vec![],
+            }
+        ]
+    );
+}
+
+#[test]
+fn create_directory_then_create_page_in_directory() {
+    let project = TestProject::new("directory-page-create");
+    project.write_page(
+        "index.html",
+        render_page_document(
+            "index",
+            "<p>body</p>",
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        ),
+    );
+
+    let report =
+        create_directory(project.root(), Path::new(""), "research").expect("create directory");
+    assert_eq!(
+        report.events,
+        vec![OperationEvent::DirectoryCreated {
+            path: PathBuf::from("pages/research")
+        }]
+    );
+
+    create_page(
+        project.root(),
+        PageCreate {
+            directory: Some(PathBuf::from("research")),
+            title: "Rust Ownership".to_string(),
+        },
+    )
+    .expect("create page in existing directory");
+
+    assert!(project
+        .pages_dir()
+        .join("research/rust-ownership.html")
+        .is_file());
+}
+
+#[test]
+fn create_page_does_not_create_missing_parent_directories() {
+    let project = TestProject::new("page-missing-directory");
+    project.write_page(
+        "index.html",
+        render_page_document(
+            "index",
+            "<p>body</p>",
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        ),
+    );
+
+    let error = create_page(
+        project.root(),
+        PageCreate {
+            directory: Some(PathBuf::from("missing")),
+            title: "Rust Ownership".to_string(),
+        },
+    )
+    .expect_err("missing directory should be rejected");
+
+    assert_eq!(error.code, FractalErrorCode::NotFound);
+    assert!(!project.pages_dir().join("missing").exists());
+}
+
+#[test]
+fn page_source_round_trip_rebuilds_index_and_serializes_report() {
+    let project = TestProject::new("page-source");
+    project.write_page(
+        "index.html",
+        render_page_document(
+            "index",
+            "<p>body</p>",
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        ),
+    );
+
+    let source =
+        read_page_source(project.root(), Path::new("pages/index")).expect("read page source");
+    assert_eq!(source.path, "index.html");
+    assert!(source.html.contains("<p>body</p>"));
+
+    let updated = source.html.replace("<p>body</p>", "<p>updated</p>");
+    let report = write_page_source(project.root(), Path::new("index"), updated, None)
+        .expect("write page source");
+
+    assert!(fs::read_to_string(project.pages_dir().join("index.html"))
+        .expect("read updated page")
+        .contains("<p>updated</p>"));
+    assert_eq!(
+        load_project_index(project.root())
+            .expect("load generated index")
+            .pages[0]
+            .title,
+        "index"
+    );
+
+    let report_json = serde_json::to_value(&report).expect("serialize report");
+    assert_eq!(report_json["events"][0]["type"], "page_source_updated");
+    assert_eq!(report_json["events"][0]["page"], "pages/index.html");
+
+    let summary = report.summary();
+    assert!(!summary.noop);
+    assert!(summary.source_changed);
+    assert!(summary.source_files_changed);
+    assert!(summary.user_content_changed);
+    assert!(summary.generated_changed);
+    assert!(summary.generated_files_changed);
+    assert_eq!(
+        summary.pages_changed,
+        vec![PathBuf::from("pages/index.html")]
+    );
+    assert_eq!(
+        summary.source_paths_changed,
+        vec![PathBuf::from("pages/index.html")]
+    );
+    assert_eq!(
+        summary.generated_paths_changed,
+        vec![
+            PathBuf::from(".fractal/index.json"),
+            PathBuf::from(".fractal/graph.json")
+        ]
+    );
+
+    let summary_json = serde_json::to_value(summary).expect("serialize summary");
+    assert_eq!(summary_json["noop"], false);
+    assert_eq!(summary_json["user_content_changed"], true);
+    assert_eq!(summary_json["source_paths_changed"][0], "pages/index.html");
+    assert_eq!(
+        summary_json["generated_paths_changed"],
+        serde_json::json!([".fractal/index.json", ".fractal/graph.json"])
+    );
+}
+
+#[test]
+fn same_content_source_write_reports_noop_when_generated_data_is_fresh() {
+    let project = TestProject::new("same-content-source-write");
+    project.write_page(
+        "index.html",
+        render_page_document(
+            "index",
+            "<p>body</p>",
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        ),
+    );
+    build_index(project.root()).expect("build initial generated data");
+    let generated_report = build_index(project.root()).expect("rebuild unchanged generated data");
+    assert_eq!(generated_report.events, Vec::<OperationEvent>::new());
+    assert!(generated_report.summary().noop);
+
+    let source = read_page_source(project.root(), Path::new("index")).expect("read page source");
+    let report = write_page_source(project.root(), Path::new("index"), source.html, None)
+        .expect("write same page source");
+
+    assert_eq!(report.events, Vec::<OperationEvent>::new());
+    let summary = report.summary();
+    assert!(summary.noop);
+    assert!(!summary.source_files_changed);
+    assert!(!summary.user_content_changed);
+    assert!(!summary.generated_files_changed);
+    assert_eq!(summary.changed_paths, Vec::<PathBuf>::new());
+
+    let summary_json = serde_json::to_value(summary).expect("serialize no-op summary");
+    assert_eq!(summary_json["noop"], true);
+    assert_eq!(summary_json["changed_paths"], serde_json::json!([]));
+}
+
+#[test]
+fn editor_page_api_lists_pages_and_returns_detail_data() {
+    let project = TestProject::new("editor-page-api");
+    let home = render_page_document(
+        "Home",
+        "<p><a href=\"rust.html\" data-fractal-link=\"page\">Rust</a> and <a href=\"#note-java\" data-fractal-link=\"note\">Java</a>.</p>",
+        Theme::Dark,
+        "../.fractal/style.css".to_string(),
+    );
+    let home = insert_note_into_document(&home, &render_note_aside("note-java", "note body"))
+        .expect("insert note");
+    project.write_page("index.html", home);
+    project.write_page(
+        "rust.html",
+        render_page_document(
+            "Rust",
+            "<p><a href=\"index.html\" data-fractal-link=\"page\">Home</a></p>",
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        ),
+    );
+
+    let pages = list_editor_pages(project.root()).expect("list editor pages");
+    assert_eq!(
+        pages,
+        vec![
+            EditorPageListEntry {
+                path: "index.html".to_string(),
+                title: "Home".to_string(),
+                summary: None,
+                tags: vec![],
+                backlink_count: 1,
+                outlink_count: 1,
+            },
+            EditorPageListEntry {
+                path: "rust.html".to_string(),
+                title: "Rust".to_string(),
+                summary: None,
+                tags: vec![],
+                backlink_count: 1,
+                outlink_count: 1,
+            },
+        ]
+    );
+
+    let detail = editor_page_detail(project.root(), Path::new("index")).expect("page detail");
+    assert_eq!(detail.source.path, "index.html");
+    assert!(detail.source.html.contains("href=\"rust.html\""));
+    assert!(detail.source.html.contains("data-fractal-link=\"page\""));
+    assert!(detail.body_html.contains("Java"));
+    assert_eq!(detail.metadata.title, "Home");
+    assert_eq!(
+        detail.notes,
+        vec![EditorNoteDetail {
+            id: "note-java".to_string(),
+            label: "java".to_string(),
+            text: "note body".to_string(),
+        }]
+    );
+    assert_eq!(
+        detail.links,
+        vec![
+            EditorLinkDetail {
+                href: "rust.html".to_string(),
+                text: "Rust".to_string(),
+                scope: "page".to_string(),
+                target_page: Some("rust.html".to_string()),
+                target_note: None,
+                target_section: None,
+            },
+            EditorLinkDetail {
+                href: "#note-java".to_string(),
+                text: "Java".to_string(),
+                scope: "note".to_string(),
+                target_page: None,
+                target_note: Some("note-java".to_string()),
+                target_section: None,
+            },
+        ]
+    );
+    assert_eq!(
+        detail.outlinks,
+        vec![GraphPageLink {
+            page: "rust.html".to_string(),
+            text: "Rust".to_string(),
+        }]
+    );
+    assert_eq!(
+        detail.backlinks,
+        vec![GraphPageLink {
+            page: "rust.html".to_string(),
+            text: "Home".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn project_summary_reports_validation_counts_and_generated_freshness() {
+    let project = TestProject::new("project-summary");
+    let home = render_page_document(
+        "Home",
+        "<p><a href=\"rust.html\" data-fractal-link=\"page\">Rust</a></p>",
+        Theme::Dark,
+        "../.fractal/style.css".to_string(),
+    );
+    let home = insert_note_into_document(&home, &render_note_aside("note-java", "note body"))
+        .expect("insert note");
+    project.write_page("index.html", home);
+    project.write_page(
+        "rust.html",
+        render_page_document(
+            "Rust",
+            "<p>body</p>",
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        ),
+    );
+    fs::write(project.pages_dir().join("asset.txt"), "asset").expect("write asset");
+    build_index(project.root()).expect("build generated data");
+
+    let summary = project_summary(project.root()).expect("project summary");
+
+    assert_eq!(summary.project_name, "test");
+    assert_eq!(summary.default_page, "pages/index.html");
+    assert!(summary.valid);
+    assert_eq!(summary.validation_error, None);
+    assert_eq!(summary.file_count, 3);
+    assert_eq!(summary.page_count, 2);
+    assert_eq!(summary.asset_count, 1);
+    assert_eq!(summary.unreferenced_asset_count, 1);
+    assert_eq!(summary.note_count, 1);
+    assert_eq!(summary.link_count, 1);
+    assert_eq!(summary.graph_node_count, 3);
+    assert_eq!(summary.graph_edge_count, 2);
+    assert_eq!(summary.orphan_page_count, 1);
+    assert!(summary.generated_index_exists);
+    assert!(summary.generated_graph_exists);
+    assert!(summary.generated_index_fresh);
+    assert!(summary.generated_graph_fresh);
+
+    project.write_page(
+        "java.html",
+        render_page_document(
+            "Java",
+            "<p>body</p>",
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        ),
+    );
+
+    let stale_summary = project_summary(project.root()).expect("stale project summary");
+
+    assert_eq!(stale_summary.page_count, 3);
+    assert!(!stale_summary.generated_index_fresh);
+    assert!(!stale_summary.generated_graph_fresh);
+}
+
+#[test]
+fn safe_editor_page_update_mutates_owned_fields_and_rebuilds_index() {
+    let project = TestProject::new("safe-editor-update");
+    project.write_page(
+        "index.html",
+        render_page_document(
+            "Home",
+            "<p>Original body.</p>",
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        ),
+    );
+    project.write_page(
+        "rust.html",
+        render_page_document(
+            "Rust",
+            "<p>Target page.</p>",
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        ),
+    );
+
+    let report = update_editor_page(
+        project.root(),
+        Path::new("index"),
+        EditorPageUpdate {
+            title: Some("Home Base".to_string()),
+            body_html: Some(
+                "<p><a href=\"rust.html\" data-fractal-link=\"page\">Rust</a> updated.</p>"
+                    .to_string(),
+            ),
+            summary: Some("Local project home".to_string()),
+            tags: Some(vec![
+                "Projects".to_string(),
+                "rust, projects".to_string(),
+                " ".to_string(),
+            ]),
+            expected_revision: None,
+        },
+    )
+    .expect("update editor page");
+
+    assert!(report.events.iter().any(|event| {
+        matches!(
+            event,
+            OperationEvent::PageTitleUpdated { title, .. } if title == "Home Base"
+        )
+    }));
+    assert!(report
+        .events
+        .iter()
+        .any(|event| matches!(event, OperationEvent::PageContentUpdated { .. })));
+    assert!(report.events.iter().any(|event| {
+        matches!(
+            event,
+            OperationEvent::PageMetadataUpdated { name, content, .. }
+                if name == "fractal:summary" && content == "Local project home"
+        )
+    }));
+    assert!(report
+        .events
+        .iter()
+        .any(|event| matches!(event, OperationEvent::GeneratedIndexBuilt { .. })));
+
+    let detail = editor_page_detail(project.root(), Path::new("index")).expect("page detail");
+    assert_eq!(detail.metadata.title, "Home Base");
+    assert_eq!(
+        detail.metadata.summary,
+        Some("Local project home".to_string())
+    );
+    assert_eq!(
+        detail.metadata.tags,
+        vec!["Projects".to_string(), "rust".to_string()]
+    );
+    assert!(detail.source.html.contains("<title>Home Base</title>"));
+    assert!(detail.source.html.contains("<h1>Home Base</h1>"));
+    assert!(detail.body_html.contains("Rust"));
+    assert_eq!(
+        detail.links,
+        vec![EditorLinkDetail {
+            href: "rust.html".to_string(),
+            text: "Rust".to_string(),
+            scope: "page".to_string(),
+            target_page: Some("rust.html".to_string()),
+            target_note: None,
+            target_section: None,
+        }]
+    );
+}
+
+#[test]
+fn safe_editor_page_update_repairs_editor_html_links_before_validating() {
+    let project = TestProject::new("safe-editor-repairs-links");
+    project.write_page(
+        "index.html",
+        render_page_document(
+            "Home",
+            "<p>Original body.</p>",
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        ),
+    );
+    project.write_page(
+        "rust.html",
+        render_page_document(
+            "Rust",
+            "<p>Target page.</p>",
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        ),
+    );
+
+    let report = update_editor_page(
+        project.root(),
+        Path::new("index"),
+        EditorPageUpdate {
+            body_html: Some("<p><a href=\"rust.html\">Rust</a> stayed readable.</p>".to_string()),
+            ..EditorPageUpdate::default()
+        },
+    )
+    .expect("repair editor body links");
+
+    assert!(report.events.iter().any(|event| {
+        matches!(
+            event,
+            OperationEvent::PageLinksRewritten { count, .. } if *count == 1
+        )
+    }));
+
+    let detail = editor_page_detail(project.root(), Path::new("index")).expect("page detail");
+    assert!(detail.body_html.contains("Rust stayed readable."));
+    assert!(detail.links.is_empty());
+
+    sync_project(project.root()).expect("sync generated links");
+    let detail = editor_page_detail(project.root(), Path::new("index")).expect("synced detail");
+    assert_eq!(
+        detail.links,
+        vec![EditorLinkDetail {
+            href: "rust.html".to_string(),
+            text: "Rust".to_string(),
+            scope: "page".to_string(),
+            target_page: Some("rust.html".to_string()),
+            target_note: None,
+            target_section: None,
+        }]
+    );
+}
+
+#[test]
+fn safe_editor_page_update_sanitizes_common_editor_html() {
+    let project = TestProject::new("safe-editor-sanitizes");
+    project.write_page(
+        "index.html",
+        render_page_document(
+            "Home",
+            "<p>Original body.</p>",
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        ),
+    );
+
+    let report = update_page_body(
+        project.root(),
+        Path::new("index"),
+        "Loose <i>intro</i><div><b>Bold</b> line<br></div><div><br></div>\
+         <h1>Section</h1><div><div>Nested</div><p>Block</p></div>\
+         <ul><li>One</li><ul><li>Child</li></ul>Two</ul><br><p><span>Plain</span><br></p>",
+        None,
+    )
+    .expect("sanitize editor body");
+
+    let changes = report
+        .events
+        .iter()
+        .filter_map(|event| match event {
+            OperationEvent::PageHtmlSanitized { change, count, .. } => {
+                Some((change.as_str(), *count))
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        changes,
+        vec![
+            ("renamed <i> to <em>", 1),
+            ("renamed <b> to <strong>", 1),
+            ("unwrapped <span>", 1),
+            ("turned <div> into <p>", 2),
+            ("unwrapped <div>", 1),
+            ("removed empty <div>", 1),
+            ("demoted <h1> to <h2>", 1),
+            ("wrapped loose inline content in <p>", 1),
+            ("moved nested list into <li>", 1),
+            ("wrapped list content in <li>", 1),
+            ("removed stray <br>", 3),
+        ]
+    );
+    assert!(report.summary().user_content_changed);
+
+    let detail = editor_page_detail(project.root(), Path::new("index")).expect("page detail");
+    assert_eq!(
+        detail.body_html.split_whitespace().collect::<String>(),
+        "<p>Loose<em>intro</em></p><p><strong>Bold</strong>line</p><h2id=\"section\">Section</h2>\
+         <p>Nested</p><p>Block</p><ul><li>One<ul><li>Child</li></ul></li><li>Two</li></ul>\
+         <p>Plain</p>"
+    );
+    validate_project(project.root()).expect("sanitized page validates");
+
+    let error = update_page_body(
+        project.root(),
+        Path::new("index"),
+        "<p>Fine</p><script>alert(1)</script>",
+        None,
+    )
+    .expect_err("irrecoverable editor html");
+    assert_eq!(error.code, FractalErrorCode::InvalidProject);
+    assert!(error.message.contains("<script>"));
+}
+
+#[test]
+fn block_api_edits_single_body_blocks_by_derived_id() {
+    let project = TestProject::new("block-api");
+    project.write_page(
+        "index.html",
+        render_page_document(
+            "Home",
+            "<p>First.</p><p>Second.</p><p>Second.</p>",
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        ),
+    );
+    let page_text = || {
+        page_blocks(project.root(), Path::new("index"))
+            .expect("blocks")
+            .into_iter()
+            .map(|block| block.text)
+            .collect::<Vec<_>>()
+    };
+
+    let blocks = page_blocks(project.root(), Path::new("index")).expect("blocks");
+    assert_eq!(blocks.len(), 3);
+    assert_eq!(blocks[0].kind, "p");
+    assert_eq!(blocks[0].html, "<p>First.</p>");
+    assert!(blocks[1].id.starts_with("block-"));
+    assert_eq!(blocks[2].id, format!("{}-2", blocks[1].id));
+    assert_eq!(
+        page_blocks(project.root(), Path::new("index")).expect("blocks again"),
+        blocks
+    );
+
+    let report = insert_block(
+        project.root(),
+        Path::new("index"),
+        BlockPosition::After(blocks[0].id.clone()),
+        "<div><b>Inserted</b></div>",
+        None,
+    )
+    .expect("insert block");
+    let inserted = report
+        .events
+        .iter()
+        .find_map(|event| match event {
+            OperationEvent::BlockInserted { block_id, .. } => Some(block_id.clone()),
+            _ => None,
+        })
+        .expect("inserted block id");
+    assert!(report
+        .events
+        .iter()
+        .any(|event| matches!(event, OperationEvent::PageHtmlSanitized { .. })));
+    assert_eq!(page_text(), ["First.", "Inserted", "Second.", "Second."]);
+    let after_insert = page_blocks(project.root(), Path::new("index")).expect("blocks");
+    assert_eq!(after_insert[1].id, inserted);
+    assert_eq!(after_insert[1].html, "<p><strong>Inserted</strong></p>");
+    assert_eq!(after_insert[0].id, blocks[0].id);
+
+    let report = replace_block(
+        project.root(),
+        Path::new("index"),
+        &blocks[0].id,
+        "<h2>Heading</h2>",
+        None,
+    )
+    .expect("replace block");
+    assert!(report.events.iter().any(|event| matches!(
+        event,
+        OperationEvent::BlockReplaced { block_id, .. } if *block_id == blocks[0].id
+    )));
+    assert_eq!(page_text(), ["Heading", "Inserted", "Second.", "Second."]);
+
+    move_block(
+        project.root(),
+        Path::new("index"),
+        &inserted,
+        BlockPosition::Start,
+        None,
+    )
+    .expect("move block");
+    assert_eq!(page_text(), ["Inserted", "Heading", "Second.", "Second."]);
+    move_block(
+        project.root(),
+        Path::new("index"),
+        &inserted,
+        BlockPosition::End,
+        None,
+    )
+    .expect("move block to end");
+    assert_eq!(page_text(), ["Heading", "Second.", "Second.", "Inserted"]);
+
+    delete_block(project.root(), Path::new("index"), &blocks[2].id, None).expect("delete block");
+    assert_eq!(page_text(), ["Heading", "Second.", "Inserted"]);
+    validate_project(project.root()).expect("edited page validates");
+
+    let error = delete_block(project.root(), Path::new("index"), &blocks[0].id, None)
+        .expect_err("stale block id");
+    assert_eq!(error.code, FractalErrorCode::NotFound);
+    let error = insert_block(
+        project.root(),
+        Path::new("index"),
+        BlockPosition::End,
+        "<p>One</p><p>Two</p>",
+        None,
+    )
+    .expect_err("two blocks");
+    assert_eq!(error.code, FractalErrorCode::InvalidInput);
+    let error = move_block(
+        project.root(),
+        Path::new("index"),
+        &inserted,
+        BlockPosition::Before(inserted.clone()),
+        None,
+    )
+    .expect_err("move relative to itself");
+    assert_eq!(error.code, FractalErrorCode::InvalidInput);
+    let error = insert_block(
+        project.root(),
+        Path::new("index"),
+        BlockPosition::End,
+        "<script>alert(1)</script>",
+        None,
+    )
+    .expect_err("invalid block");
+    assert_eq!(error.code, FractalErrorCode::InvalidProject);
+    assert_eq!(page_text(), ["Heading", "Second.", "Inserted"]);
+}
+
+#[test]
+fn heading_sections_are_indexed_selectable_and_linkable() {
+    let project = TestProject::new("heading-sections");
+    project.write_page(
+        "guide.html",
+        render_page_document(
+            "Guide",
+            "<h2>Setup</h2><p>Install it.</p><h3>Options</h3><p>Flags.</p>\
+             <h2>Usage</h2><p>Run it.</p><h2>Setup</h2><p>Again.</p>",
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        ),
+    );
+    project.write_page(
+        "index.html",
+        render_page_document(
+            "Home",
+            "<p>See <a href=\"guide.html#usage\" data-fractal-link=\"section\">usage</a>.</p>",
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        ),
+    );
+    validate_project(project.root()).expect("section links validate");
+    build_index(project.root()).expect("build index");
+
+    let index = load_project_index(project.root()).expect("index");
+    let guide = index
+        .pages
+        .iter()
+        .find(|page| page.path == "guide.html")
+        .expect("guide entry");
+    let heading = |level, text: &str, anchor: &str| HeadingEntry {
+        level,
+        text: text.to_string(),
+        anchor: anchor.to_string(),
+    };
+    assert_eq!(
+        guide.headings,
+        vec![
+            heading(2, "Setup", "setup"),
+            heading(3, "Options", "options"),
+            heading(2, "Usage", "usage"),
+            heading(2, "Setup", "setup-2"),
+        ]
+    );
+
+    assert_eq!(
+        extract_page_text(project.root(), Path::new("guide#setup")).expect("section text"),
+        "Setup Install it. Options Flags."
+    );
+    assert_eq!(
+        extract_page_text(project.root(), Path::new("guide#setup-2")).expect("section text"),
+        "Setup Again."
+    );
+    let section = page_section(project.root(), Path::new("guide#options")).expect("section");
+    assert_eq!(section.page, "guide.html");
+    assert_eq!(section.heading, heading(3, "Options", "options"));
+    assert_eq!(section.html, "<h3>Options</h3><p>Flags.</p>");
+    assert_eq!(
+        extract_page_text(project.root(), Path::new("guide#missing"))
+            .expect_err("missing section")
+            .code,
+        FractalErrorCode::NotFound
+    );
+
+    assert_eq!(
+        page_backlinks(project.root(), Path::new("guide")).expect("backlinks"),
+        vec![GraphPageLink {
+            page: "index.html".to_string(),
+            text: "usage".to_string(),
+        }]
+    );
+    let detail = editor_page_detail(project.root(), Path::new("index")).expect("detail");
+    assert_eq!(detail.links[0].target_page.as_deref(), Some("guide.html"));
+    assert_eq!(detail.links[0].target_section.as_deref(), Some("usage"));
+
+    update_page_body(
+        project.root(),
+        Path::new("guide"),
+        "<h2>Setup</h2><p>Install it.</p><h2>Running</h2><p>Run it.</p>",
+        None,
+    )
+    .expect("update guide");
+    let guide_source = read_page_source(project.root(), Path::new("guide")).expect("source");
+    assert!(guide_source.html.contains("<h2 id=\"setup\">Setup</h2>"));
+    assert!(guide_source
+        .html
+        .contains("<h2 id=\"running\">Running</h2>"));
+    let error = write_page_source(
+        project.root(),
+        Path::new("guide"),
+        guide_source.html.replace("id=\"running\"", "id=\"usage\""),
+        None,
+    )
+    .expect_err("stale heading anchor");
+    assert!(error.message.contains("expected #running"));
+
+    project.write_page(
+        "index.html",
+        render_page_document(
+            "Home",
+            "<p><a href=\"guide.html#usage\" data-fractal-link=\"section\">usage</a>, \
+             <a href=\"guide.html#run\" data-fractal-link=\"section\">Running</a>, and \
+             <a href=\"guide.html#setup\" data-fractal-link=\"section\">setup steps</a>.</p>",
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        ),
+    );
+    let rules = validation_report(project.root())
+        .expect("report")
+        .diagnostics
+        .into_iter()
+        .map(|diagnostic| diagnostic.rule)
+        .collect::<Vec<_>>();
+    assert_eq!(rules, ["FR-LINK-011", "FR-LINK-011", "FR-LINK-012"]);
+
+    repair_project(project.root()).expect("repair section links");
+    validate_project(project.root()).expect("repaired section links validate");
+    let detail = editor_page_detail(project.root(), Path::new("index")).expect("detail");
+    assert_eq!(
+        detail
+            .links
+            .iter()
+            .map(|link| (link.href.as_str(), link.text.as_str()))
+            .collect::<Vec<_>>(),
+        [
+            ("guide.html#running", "Running"),
+            ("guide.html#setup", "Setup")
+        ]
+    );
+    assert!(detail.body_html.contains("<p>usage, "));
+}
+
+#[test]
+fn page_embeds_are_refreshed_exported_and_follow_renames_and_deletes() {
+    let project = TestProject::new("page-embeds");
+    let glossary = |terms: &str| {
+        insert_note_into_document(
+            &render_page_document(
+                "Glossary",
+                &format!("<h2>Terms</h2><p>{terms}</p><h2>Sources</h2><p>Books.</p>"),
+                Theme::Dark,
+                "../.fractal/style.css".to_string(),
+            ),
+            &render_note_aside("note-vertex", "A point in a graph."),
+        )
+        .expect("insert note")
+    };
+    project.write_page(
+        "index.html",
+        render_page_document(
+            "Home",
+            "<p>Welcome.</p>",
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        ),
+    );
+    project.write_page("glossary.html", glossary("An edge joins points."));
+    project.write_page(
+        "topics/usage.html",
+        render_page_document(
+            "Usage",
+            "<p>Intro.</p><section data-fractal-embed=\"../glossary.html#note-vertex\"></section>\
+             <section data-fractal-embed=\"../glossary.html#terms\"></section>",
+            Theme::Dark,
+            "../../.fractal/style.css".to_string(),
+        ),
+    );
+    validate_project(project.root()).expect("embeds validate");
+
+    let report = sync_project(project.root()).expect("sync embeds");
+    assert!(report.events.contains(&OperationEvent::EmbedsRefreshed {
+        page: PathBuf::from("pages/topics/usage.html"),
+        count: 2,
+    }));
+    let usage = read_page_source(project.root(), Path::new("topics/usage")).expect("source");
+    assert!(usage.html.contains("<p>A point in a graph.</p>"));
+    assert!(usage.html.contains(
+        "<section data-fractal-embed=\"../glossary.html#terms\"><h2>Terms</h2><p>An edge joins points.</p></section>"
+    ));
+    let report = sync_project(project.root()).expect("sync again");
+    assert!(!report
+        .events
+        .iter()
+        .any(|event| matches!(event, OperationEvent::EmbedsRefreshed { .. })));
+    validate_project(project.root()).expect("refreshed embeds validate");
+
+    let index = load_project_index(project.root()).expect("index");
+    let usage = index
+        .pages
+        .iter()
+        .find(|page| page.path == "topics/usage.html")
+        .expect("usage entry");
+    assert!(usage.headings.is_empty());
+    assert_eq!(
+        usage
+            .embeds
+            .iter()
+            .map(|embed| embed.target.as_str())
+            .collect::<Vec<_>>(),
+        ["../glossary.html#note-vertex", "../glossary.html#terms"]
+    );
+    let graph: ProjectGraph = serde_json::from_str(
+        &fs::read_to_string(project.workspace_dir().join("graph.json")).expect("read graph"),
+    )
+    .expect("parse graph");
+    assert_eq!(
+        graph
+            .edges
+            .iter()
+            .filter(|edge| edge.kind == "embeds")
+            .map(|edge| (edge.from.as_str(), edge.to.as_str()))
+            .collect::<Vec<_>>(),
+        [
+            ("page:topics/usage.html", "note:glossary.html#note-vertex"),
+            ("page:topics/usage.html", "page:glossary.html"),
+        ]
+    );
+
+    project.write_page("glossary.html", glossary("An edge joins two points."));
+    let output = project.root().join("usage.md");
+    export_page(project.root(), Path::new("topics/usage"), &output).expect("export");
+    let markdown = fs::read_to_string(&output).expect("read export");
+    assert!(markdown.contains("A point in a graph."));
+    assert!(markdown.contains("## Terms\n\nAn edge joins two points."));
+
+    rename_page(
+        project.root(),
+        Path::new("glossary"),
+        PageRename {
+            path: Some(PathBuf::from("reference/glossary.html")),
+            title: None,
+            expected_revision: None,
+        },
+    )
+    .expect("rename glossary");
+    let usage = read_page_source(project.root(), Path::new("topics/usage")).expect("source");
+    assert!(usage
+        .html
+        .contains("data-fractal-embed=\"../reference/glossary.html#note-vertex\""));
+    validate_project(project.root()).expect("renamed embeds validate");
+
+    project.write_page(
+        "broken.html",
+        render_page_document(
+            "Broken",
+            "<section data-fractal-embed=\"reference/glossary.html#missing\"><p>Old.</p></section>\
+             <blockquote><section data-fractal-embed=\"reference/glossary.html#terms\"></section></blockquote>",
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        ),
+    );
+    let rules = validation_report(project.root())
+        .expect("report")
+        .diagnostics
+        .into_iter()
+        .map(|diagnostic| diagnostic.rule)
+        .collect::<Vec<_>>();
+    assert_eq!(rules, ["FR-EMBED-001", "FR-EMBED-002"]);
+    delete_page(project.root(), Path::new("broken"), None).expect("delete broken");
+
+    delete_page(project.root(), Path::new("reference/glossary"), None).expect("delete glossary");
+    let usage = read_page_source(project.root(), Path::new("topics/usage")).expect("source");
+    assert!(!usage.html.contains("data-fractal-embed"));
+    assert!(usage.html.contains("<p>A point in a graph.</p>"));
+    validate_project(project.root()).expect("unwrapped embeds validate");
+}
+
+#[test]
+fn notes_promote_to_pages_and_pages_demote_to_notes() {
+    let project = TestProject::new("note-lifecycle");
+    let page = |title: &str, body: &str| {
+        render_page_document(
+            title,
+            body,
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        )
+    };
+    project.write_page(
+        "index.html",
+        page(
+            "Home",
+            "<p>See <a href=\"edge.html\" data-fractal-link=\"page\">Edge</a> and \
+             <a href=\"glossary.html#note-vertex\" data-fractal-link=\"note\">vertices</a>.</p>",
+        ),
+    );
+    project.write_page(
+        "glossary.html",
+        insert_note_into_document(
+            &page(
+                "Glossary",
+                "<p>A <a href=\"#note-vertex\" data-fractal-link=\"note\">vertex</a> meets an \
+                 <a href=\"edge.html\" data-fractal-link=\"page\">edge</a> by \
+                 <a href=\"edge.html#weight\" data-fractal-link=\"section\">Weight</a>.</p>",
+            ),
+            &render_note_aside("note-vertex", "A point in a graph."),
+        )
+        .expect("insert note"),
+    );
+    project.write_page(
+        "other.html",
+        page(
+            "Other",
+            "<section data-fractal-embed=\"glossary.html#note-vertex\"></section>",
+        ),
+    );
+    project.write_page(
+        "edge.html",
+        page(
+            "Edge",
+            "<p>Joins two points.</p><h2>Weight</h2><p>Cost.</p>",
+        ),
+    );
+    sync_project(project.root()).expect("sync");
+    validate_project(project.root()).expect("fixture validates");
+
+    let preflight =
+        preflight_promote_note(project.root(), Path::new("glossary"), "vertex", "Vertex")
+            .expect("preflight promote");
+    assert_eq!(preflight.note_id, "note-vertex");
+    assert_eq!(preflight.destination_page, "vertex.html");
+    assert_eq!(preflight.note_links, 1);
+    assert_eq!(preflight.cited_by, ["index.html"]);
+    assert_eq!(preflight.embedded_by, ["other.html"]);
+    assert!(!project.pages_dir().join("vertex.html").exists());
+    assert_eq!(
+        promote_note(
+            project.root(),
+            Path::new("glossary"),
+            "note-missing",
+            "Missing",
+            None,
+        )
+        .expect_err("missing note")
+        .code,
+        FractalErrorCode::NotFound
+    );
+
+    promote_note(
+        project.root(),
+        Path::new("glossary"),
+        "note-vertex",
+        "Vertex",
+        None,
+    )
+    .expect("promote note");
+    validate_project(project.root()).expect("promoted note validates");
+    assert_eq!(
+        extract_page_text(project.root(), Path::new("vertex")).expect("vertex text"),
+        "Vertex A point in a graph."
+    );
+    let glossary = editor_page_detail(project.root(), Path::new("glossary")).expect("detail");
+    assert!(glossary.notes.is_empty());
+    assert_eq!(
+        (
+            glossary.links[0].href.as_str(),
+            glossary.links[0].scope.as_str()
+        ),
+        ("vertex.html", "page")
+    );
+    let index = editor_page_detail(project.root(), Path::new("index")).expect("index");
+    assert_eq!(
+        (index.links[1].href.as_str(), index.links[1].text.as_str()),
+        ("vertex.html", "Vertex")
+    );
+    let other = read_page_source(project.root(), Path::new("other")).expect("other");
+    assert!(!other.html.contains("data-fractal-embed"));
+    assert!(other.html.contains("<p>A point in a graph.</p>"));
+
+    let preflight = preflight_demote_page(project.root(), Path::new("edge"), Path::new("glossary"))
+        .expect("preflight demote");
+    assert_eq!(preflight.note_id, "note-edge");
+    assert_eq!(
+        preflight.backlinks,
+        vec![
+            GraphPageLink {
+                page: "glossary.html".to_string(),
+                text: "Weight".to_string(),
+            },
+            GraphPageLink {
+                page: "glossary.html".to_string(),
+                text: "edge".to_string(),
+            },
+            GraphPageLink {
+                page: "index.html".to_string(),
+                text: "Edge".to_string(),
+            },
+        ]
+    );
+    assert_eq!(
+        demote_page(project.root(), Path::new("edge"), Path::new("edge"), None)
+            .expect_err("self demotion")
+            .code,
+        FractalErrorCode::InvalidInput
+    );
+
+    demote_page(
+        project.root(),
+        Path::new("edge"),
+        Path::new("glossary"),
+        None,
+    )
+    .expect("demote page");
+    assert!(!project.pages_dir().join("edge.html").exists());
+    validate_project(project.root()).expect("demoted page validates");
+    let glossary = editor_page_detail(project.root(), Path::new("glossary")).expect("detail");
+    assert_eq!(glossary.notes.len(), 1);
+    assert_eq!(glossary.notes[0].id, "note-edge");
+    assert_eq!(
+        glossary
+            .links
+            .iter()
+            .map(|link| (link.href.as_str(), link.scope.as_str()))
+            .collect::<Vec<_>>(),
+        [
+            ("vertex.html", "page"),
+            ("#note-edge", "note"),
+            ("#note-edge", "note")
+        ]
+    );
+    let index = editor_page_detail(project.root(), Path::new("index")).expect("index");
+    assert_eq!(
+        (
+            index.links[0].href.as_str(),
+            index.links[0].scope.as_str(),
+            index.links[0].target_note.as_deref()
+        ),
+        ("glossary.html#note-edge", "note", Some("note-edge"))
+    );
+}
+
+#[test]
+fn cross_page_note_links_validate_graph_and_follow_renames_and_deletes() {
+    let project = TestProject::new("cross-page-notes");
+    let page = |title: &str, body: &str, stylesheet: &str| {
+        render_page_document(title, body, Theme::Dark, stylesheet.to_string())
+    };
+    project.write_page(
+        "index.html",
+        page(
+            "Home",
+            "<p>A <a href=\"glossary.html#note-vertex\" data-fractal-link=\"note\">vertex</a> \
+             has a <a href=\"glossary.html#note-degree\" data-fractal-link=\"note\">degree</a>.</p>",
+            "../.fractal/style.css",
+        ),
+    );
+    project.write_page(
+        "topics/usage.html",
+        page(
+            "Usage",
+            "<p>Count each <a href=\"../glossary.html#note-vertex\" data-fractal-link=\"note\">Vertex</a>.</p>",
+            "../../.fractal/style.css",
+        ),
+    );
+    let glossary = insert_note_into_document(
+        &page("Glossary", "<p>Terms.</p>", "../.fractal/style.css"),
+        &render_note_aside("note-vertex", "A point in a graph."),
+    )
+    .expect("insert vertex");
+    project.write_page(
+        "glossary.html",
+        insert_note_into_document(
+            &glossary,
+            &render_note_aside("note-degree", "Edges at a vertex."),
+        )
+        .expect("insert degree"),
+    );
+    sync_project(project.root()).expect("sync");
+    validate_project(project.root()).expect("cross-page note links validate");
+
+    let index = editor_page_detail(project.root(), Path::new("index")).expect("index");
+    assert_eq!(
+        (
+            index.links[0].href.as_str(),
+            index.links[0].target_page.as_deref(),
+            index.links[0].target_note.as_deref()
+        ),
+        (
+            "glossary.html#note-vertex",
+            Some("glossary.html"),
+            Some("note-vertex")
+        )
+    );
+    let notes = page_notes(project.root(), Path::new("glossary")).expect("notes");
+    assert_eq!(notes[1].id, "note-vertex");
+    assert_eq!(
+        notes[1].backlinks,
+        vec![
+            GraphPageLink {
+                page: "index.html".to_string(),
+                text: "vertex".to_string(),
+            },
+            GraphPageLink {
+                page: "topics/usage.html".to_string(),
+                text: "Vertex".to_string(),
+            },
+        ]
+    );
+    assert!(graph_notes_report(project.root(), Path::new("glossary"))
+        .expect("notes report")
+        .contains("  - note-vertex (vertex)\n      <- index.html (vertex)\n"));
+    assert!(page_backlinks(project.root(), Path::new("glossary"))
+        .expect("backlinks")
+        .iter()
+        .any(|link| link.page == "topics/usage.html"));
+
+    let source = read_page_source(project.root(), Path::new("index")).expect("index");
+    let error = write_page_source(
+        project.root(),
+        Path::new("index"),
+        source.html.replace("#note-degree", "#note-missing"),
+        None,
+    )
+    .expect_err("missing cross-page note");
+    assert!(error
+        .message
+        .contains("cross-page note link target is missing"));
+
+    rename_note(
+        project.root(),
+        Path::new("glossary"),
+        "vertex",
+        "Node",
+        None,
+    )
+    .expect("rename note");
+    validate_project(project.root()).expect("renamed note validates");
+    let usage = read_page_source(project.root(), Path::new("topics/usage")).expect("usage");
+    assert!(usage.html.contains("href=\"../glossary.html#note-node\""));
+    assert_eq!(
+        rename_note(
+            project.root(),
+            Path::new("glossary"),
+            "note-node",
+            "Degree",
+            None
+        )
+        .expect_err("taken note id")
+        .code,
+        FractalErrorCode::AlreadyExists
+    );
+
+    rename_page(
+        project.root(),
+        Path::new("glossary"),
+        PageRename {
+            path: Some(PathBuf::from("terms")),
+            title: Some("Terms".to_string()),
+            expected_revision: None,
+        },
+    )
+    .expect("rename host");
+    validate_project(project.root()).expect("renamed host validates");
+    let index = read_page_source(project.root(), Path::new("index")).expect("index");
+    assert!(index.html.contains("href=\"terms.html#note-node\""));
+    assert!(index.html.contains("href=\"terms.html#note-degree\""));
+
+    remove_note(project.root(), Path::new("terms"), "Node", None).expect("remove note");
+    validate_project(project.root()).expect("removed note validates");
+    let usage = read_page_source(project.root(), Path::new("topics/usage")).expect("usage");
+    assert!(usage.html.contains("<p>Count each Vertex.</p>"));
+
+    project.write_page(
+        "topics/usage.html",
+        page(
+            "Usage",
+            "<p>Count each <a href=\"../terms.html#note-gone\" data-fractal-link=\"note\">Vertex</a>.</p>",
+            "../../.fractal/style.css",
+        ),
+    );
+    let rules = validation_report(project.root())
+        .expect("report")
+        .diagnostics
+        .into_iter()
+        .map(|diagnostic| diagnostic.rule)
+        .collect::<Vec<_>>();
+    assert_eq!(rules, ["FR-LINK-013"]);
+    repair_project(project.root()).expect("repair note links");
+    validate_project(project.root()).expect("repaired note links validate");
+
+    delete_page(project.root(), Path::new("terms"), None).expect("delete host");
+    validate_project(project.root()).expect("deleted host validates");
+    let index = read_page_source(project.root(), Path::new("index")).expect("index");
+    assert!(index.html.contains("<p>A vertex has a degree.</p>"));
+}
+
+#[test]
+fn mutation_lock_blocks_writes_and_successful_mutations_clean_up_lock() {
+    let project = TestProject::new("mutation-lock");
+    project.write_page(
+        "index.html",
+        render_page_document(
+            "Home",
+            "<p>body</p>",
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        ),
+    );
+    let lock_path = project
+        .workspace_dir()
+        .join(crate::ops::lock::MUTATION_LOCK_FILE);
+
+    update_page_body(project.root(), Path::new("index"), "<p>updated</p>", None)
+        .expect("update page with free lock");
+    assert!(!lock_path.exists());
+
+    fs::write(&lock_path, "held by another process").expect("create lock");
+    let error = update_page_body(project.root(), Path::new("index"), "<p>blocked</p>", None)
+        .expect_err("locked project should reject writes");
+
+    assert_eq!(error.code, FractalErrorCode::ProjectLocked);
+    assert!(lock_path.exists());
+    assert!(fs::read_to_string(project.pages_dir().join("index.html"))
+        .expect("read page")
+        .contains("<p>updated</p>"));
+    assert!(!fs::read_to_string(project.pages_dir().join("index.html"))
+        .expect("read page")
+        .contains("<p>blocked</p>"));
 }
 
 #[test]
-fn page_destination_accepts_optional_pages_prefix() {
-    let destination = resolve_page_destination(Path::new("."), Path::new("pages/nested/topic"))
-        .expect("resolve destination");
+fn mutation_locks_record_owner_and_recover_when_the_owner_is_gone() {
+    let project = TestProject::new("mutation-stale-lock");
+    project.write_page(
+        "index.html",
+        render_page_document(
+            "Home",
+            "<p>body</p>",
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        ),
+    );
+    let lock_path = project
+        .workspace_dir()
+        .join(crate::ops::lock::MUTATION_LOCK_FILE);
+
+    let lock = crate::ops::lock::ProjectLock::acquire(project.root(), std::time::Duration::ZERO)
+        .expect("acquire lock");
+    let owner = mutation_lock_owner(project.root())
+        .expect("read lock owner")
+        .expect("lock owner");
+    assert_eq!(owner.pid, Some(std::process::id()));
+    assert!(owner.hostname.is_some());
+    assert!(owner.created_at.is_some());
+    drop(lock);
+    assert_eq!(
+        mutation_lock_owner(project.root()).expect("read lock owner"),
+        None
+    );
 
-    assert_eq!(destination, PathBuf::from("./pages/nested/topic.html"));
-}
+    fs::write(&lock_path, "pid=4294967295\ncreated_at=0\n").expect("write stale lock");
+    update_page_body(project.root(), Path::new("index"), "<p>recovered</p>", None)
+        .expect("stale lock should be recovered");
+    assert!(!lock_path.exists());
 
-#[test]
-fn page_destination_rejects_parent_traversal() {
-    let error = resolve_page_destination(Path::new("."), Path::new("../escape"))
-        .expect_err("parent traversal should be rejected");
+    fs::write(&lock_path, format!("pid={}\n", std::process::id())).expect("write live lock");
+    let error = update_page_body(project.root(), Path::new("index"), "<p>blocked</p>", None)
+        .expect_err("live lock should block writes");
+    assert_eq!(error.code, FractalErrorCode::ProjectLocked);
+    assert!(error
+        .message
+        .contains(&format!("pid {}", std::process::id())));
 
-    assert_eq!(error.code, FractalErrorCode::InvalidInput);
-    assert_eq!(error.to_string(), "page path cannot contain `..`");
+    let error = unlock_project(project.root(), false).expect_err("live lock needs force");
+    assert_eq!(error.code, FractalErrorCode::ProjectLocked);
+    let report = unlock_project(project.root(), true).expect("force unlock");
+    assert_eq!(
+        report.events,
+        vec![OperationEvent::ProjectUnlocked {
+            path: PathBuf::from(".fractal/mutation.lock"),
+            owner: MutationLockOwner {
+                pid: Some(std::process::id()),
+                hostname: None,
+                created_at: None,
+            },
+            stale: false,
+        }]
+    );
+    assert!(!lock_path.exists());
+    assert!(unlock_project(project.root(), false)
+        .expect("unlock without lock")
+        .events
+        .is_empty());
 }
 
 #[test]
-fn page_destination_accepts_lowercase_unicode_and_underscores() {
-    let destination = resolve_page_destination(Path::new("."), Path::new("göteborg/my_new_page"))
-        .expect("resolve destination");
+fn mutations_wait_for_the_lock_up_to_the_configured_timeout() {
+    let project = TestProject::new("mutation-lock-wait");
+    project.write_page(
+        "index.html",
+        render_page_document(
+            "Home",
+            "<p>body</p>",
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        ),
+    );
+    let lock_path = project
+        .workspace_dir()
+        .join(crate::ops::lock::MUTATION_LOCK_FILE);
+    fs::write(&lock_path, format!("pid={}\n", std::process::id())).expect("write live lock");
+
+    let release_path = lock_path.clone();
+    let release = std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        fs::remove_file(release_path).expect("release lock");
+    });
+
+    let result = plan_update_page_body(project.root(), Path::new("index"), "<p>waited</p>", None)
+        .expect("plan update")
+        .with_lock_timeout(std::time::Duration::from_secs(5))
+        .apply();
+    release.join().expect("join release thread");
+
+    result.expect("mutation should wait for the lock");
+    assert!(fs::read_to_string(project.pages_dir().join("index.html"))
+        .expect("read page")
+        .contains("<p>waited</p>"));
 
-    assert_eq!(
-        destination,
-        PathBuf::from("./pages/göteborg/my_new_page.html")
+    let mut manifest = load_project_manifest(project.root()).expect("manifest");
+    manifest.lock_timeout_ms = 5000;
+    fs::write(
+        project.root().join("fractal.json"),
+        serde_json::to_string_pretty(&manifest).expect("serialize manifest"),
+    )
+    .expect("write manifest");
+    fs::write(&lock_path, format!("pid={}\n", std::process::id())).expect("write live lock");
+    let release_path = lock_path.clone();
+    let release = std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        fs::remove_file(release_path).expect("release lock");
+    });
+    let result = update_page_body(
+        project.root(),
+        Path::new("index"),
+        "<p>configured</p>",
+        None,
     );
+    release.join().expect("join release thread");
+    result.expect("mutation should wait for the configured timeout");
 }
 
 #[test]
-fn page_destination_rejects_uppercase_unicode() {
-    let error = resolve_page_destination(Path::new("."), Path::new("Göteborg"))
-        .expect_err("uppercase page path should be rejected");
+fn contenders_for_a_stale_lock_never_hold_it_together() {
+    let project = TestProject::new("mutation-stale-lock-race");
+    let lock_path = project
+        .workspace_dir()
+        .join(crate::ops::lock::MUTATION_LOCK_FILE);
+    fs::create_dir_all(project.workspace_dir()).expect("create workspace");
+    let stale = "pid=4294967295\ncreated_at=0\n";
+
+    let fresh = crate::ops::lock::ProjectLock::acquire(project.root(), std::time::Duration::ZERO)
+        .expect("acquire fresh lock");
+    let fresh_contents = fs::read_to_string(&lock_path).expect("read fresh lock");
+    assert!(!crate::ops::lock::steal_stale_lock(&lock_path, stale).expect("late steal"));
+    assert_eq!(
+        fs::read_to_string(&lock_path).expect("fresh lock survives a late steal"),
+        fresh_contents
+    );
+    drop(fresh);
+    assert!(!lock_path.exists());
 
-    assert_eq!(error.code, FractalErrorCode::InvalidInput);
+    fs::write(&lock_path, stale).expect("write stale lock");
+    let guard = crate::ops::lock::lock_steal_guard(&lock_path).expect("first stealer guard");
+    let second = {
+        let lock_path = lock_path.clone();
+        std::thread::spawn(move || crate::ops::lock::steal_stale_lock(&lock_path, stale))
+    };
+    std::thread::sleep(std::time::Duration::from_millis(50));
+    fs::remove_file(&lock_path).expect("first stealer removes the stale lock");
+    let live = crate::ops::lock::ProjectLock::acquire(project.root(), std::time::Duration::ZERO)
+        .expect("first stealer takes the lock");
+    let live_contents = fs::read_to_string(&lock_path).expect("read live lock");
+    drop(guard);
+    assert!(!second
+        .join()
+        .expect("join second stealer")
+        .expect("second steal"));
     assert_eq!(
-        error.to_string(),
-        "page path component must be a lowercase page slug: Göteborg"
+        fs::read_to_string(&lock_path).expect("live lock survives the second stealer"),
+        live_contents
     );
+    drop(live);
+    assert!(!lock_path.exists());
+
+    for _ in 0..20 {
+        fs::write(&lock_path, stale).expect("write stale lock");
+        let holders = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let overlaps = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let barrier = std::sync::Arc::new(std::sync::Barrier::new(8));
+        let contenders = (0..8)
+            .map(|_| {
+                let root = project.root().to_path_buf();
+                let holders = holders.clone();
+                let overlaps = overlaps.clone();
+                let barrier = barrier.clone();
+                std::thread::spawn(move || {
+                    barrier.wait();
+                    let lock = crate::ops::lock::ProjectLock::acquire(
+                        &root,
+                        std::time::Duration::from_secs(10),
+                    )
+                    .expect("acquire lock");
+                    if holders.fetch_add(1, std::sync::atomic::Ordering::SeqCst) > 0 {
+                        overlaps.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                    }
+                    std::thread::sleep(std::time::Duration::from_millis(5));
+                    holders.fetch_sub(1, std::sync::atomic::Ordering::SeqCst);
+                    drop(lock);
+                })
+            })
+            .collect::<Vec<_>>();
+        for contender in contenders {
+            contender.join().expect("join contender");
+        }
+
+        assert_eq!(overlaps.load(std::sync::atomic::Ordering::SeqCst), 0);
+        assert!(!lock_path.exists());
+    }
+    let leftovers = fs::read_dir(project.workspace_dir())
+        .expect("read workspace")
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_string_lossy()
+                .starts_with(crate::ops::lock::MUTATION_LOCK_FILE)
+        })
+        .count();
+    assert_eq!(leftovers, 0);
 }
 
 #[test]
-fn note_id_normalizes_trigger_text() {
+fn failed_mutation_plans_roll_back_every_applied_step() {
+    let project = TestProject::new("mutation-rollback");
+    let pages = project.pages_dir();
+    project.write_page("keep.html", "original");
+    project.write_page("move.html", "moved");
+    project.write_page("remove.html", "removed");
+    project.write_page("existing/page.html", "nested");
+
+    let mut plan = crate::ops::mutation::MutationPlan::new(project.root());
+    plan.write_silent(pages.join("keep.html"), "rewritten");
+    plan.ensure_dir(pages.join("fresh/deeper"));
+    plan.write_silent(pages.join("fresh/deeper/new.html"), "created");
+    plan.move_file(
+        pages.join("move.html"),
+        pages.join("moved.html"),
+        OperationEvent::Warning {
+            message: "moved".to_string(),
+        },
+    );
+    plan.remove_file(
+        pages.join("remove.html"),
+        OperationEvent::Warning {
+            message: "removed".to_string(),
+        },
+    );
+    plan.remove_dir(
+        pages.join("existing"),
+        true,
+        OperationEvent::Warning {
+            message: "removed dir".to_string(),
+        },
+    );
+    plan.create_dir(
+        pages.join("fresh"),
+        OperationEvent::Warning {
+            message: "duplicate".to_string(),
+        },
+    );
+
+    let error = plan
+        .apply()
+        .expect_err("creating an existing directory should fail");
+
+    assert_eq!(error.code, FractalErrorCode::AlreadyExists);
     assert_eq!(
-        note_id_from_trigger("Andromeda Galaxy").expect("normalize trigger"),
-        "note-andromeda-galaxy"
+        fs::read_to_string(pages.join("keep.html")).expect("read kept page"),
+        "original"
     );
+    assert!(!pages.join("fresh").exists());
+    assert!(pages.join("move.html").is_file());
+    assert!(!pages.join("moved.html").exists());
+    assert!(pages.join("remove.html").is_file());
     assert_eq!(
-        note_id_from_trigger("JAVA").expect("normalize trigger"),
-        "note-java"
+        fs::read_to_string(pages.join("existing/page.html")).expect("read nested page"),
+        "nested"
     );
+    assert!(!project
+        .workspace_dir()
+        .join(crate::ops::journal::TRANSACTION_DIR)
+        .exists());
+    assert!(!project
+        .workspace_dir()
+        .join(crate::ops::lock::MUTATION_LOCK_FILE)
+        .exists());
 }
 
 #[test]
-fn new_page_rebuilds_index() {
-    let project = TestProject::new("new-page-index");
+fn interrupted_transactions_are_rolled_back_on_next_mutation_or_validation() {
+    let project = TestProject::new("mutation-recovery");
     project.write_page(
         "index.html",
         render_page_document(
-            "index",
+            "Home",
             "<p>body</p>",
             Theme::Dark,
             "../.fractal/style.css".to_string(),
         ),
     );
+    let index_page = project.pages_dir().join("index.html");
+    let original = fs::read_to_string(&index_page).expect("read page");
+    let transaction_dir = project
+        .workspace_dir()
+        .join(crate::ops::journal::TRANSACTION_DIR);
 
-    let report = new_page(project.root(), Path::new("secondpage")).expect("create new page");
-    let created_page = PathBuf::from("pages/secondpage.html");
-    assert_eq!(
-        report.events.first(),
-        Some(&OperationEvent::PageCreated {
-            path: created_page.clone()
-        })
-    );
-    let summary = report.summary();
-    assert!(summary.source_changed);
-    assert!(summary.generated_changed);
-    assert_eq!(summary.created_paths, vec![created_page.clone()]);
-    assert_eq!(summary.pages_changed, vec![created_page]);
-
-    let index: ProjectIndex = serde_json::from_str(
-        &fs::read_to_string(project.workspace_dir().join("index.json")).expect("read index"),
-    )
-    .expect("parse index");
+    let mut journal = crate::ops::journal::Journal::begin(project.root()).expect("begin journal");
+    journal
+        .write_file(&index_page, b"half-written")
+        .expect("write page");
+    journal
+        .write_file(&project.pages_dir().join("partial.html"), b"partial")
+        .expect("write partial page");
+    drop(journal);
 
-    assert_eq!(index.version, INDEX_VERSION);
+    let error = validate_project(project.root()).expect_err("validation should recover first");
+    assert_eq!(error.code, FractalErrorCode::TransactionRecovered);
     assert_eq!(
-        index.files,
-        vec![
-            FileEntry {
-                path: "index.html".to_string(),
-                kind: "page".to_string(),
-            },
-            FileEntry {
-                path: "secondpage.html".to_string(),
-                kind: "page".to_string(),
-            },
-        ]
+        fs::read_to_string(&index_page).expect("read page"),
+        original
+    );
+    assert!(!project.pages_dir().join("partial.html").exists());
+    assert!(!transaction_dir.exists());
+    validate_project(project.root()).expect("validate recovered project");
+
+    let archive_page = project.pages_dir().join("archive.html");
+    fs::write(&archive_page, "archive").expect("write archive page");
+    let mut journal = crate::ops::journal::Journal::begin(project.root()).expect("begin journal");
+    journal
+        .remove_file(&archive_page)
+        .expect("remove page inside journal");
+    drop(journal);
+    assert!(!archive_page.exists());
+
+    let error = update_page_body(project.root(), Path::new("index"), "<p>updated</p>", None)
+        .expect_err("mutation should recover first");
+    assert_eq!(error.code, FractalErrorCode::TransactionRecovered);
+    assert_eq!(
+        fs::read_to_string(&index_page).expect("read page"),
+        original
     );
     assert_eq!(
-        index.pages,
-        vec![
-            PageEntry {
-                path: "index.html".to_string(),
-                title: "index".to_string(),
-                meta: required_meta(),
-                notes: vec![],
-                links: vec![],
-            },
-            PageEntry {
-                path: "secondpage.html".to_string(),
-                title: "secondpage".to_string(),
-                meta: required_meta(),
-                notes: vec![],
-                links: vec![],
-            }
-        ]
+        fs::read_to_string(&archive_page).expect("read archive page"),
+        "archive"
     );
+    assert!(!transaction_dir.exists());
+
+    update_page_body(project.root(), Path::new("index"), "<p>updated</p>", None)
+        .expect("retry mutation after recovery");
+    assert!(fs::read_to_string(&index_page)
+        .expect("read page")
+        .contains("<p>updated</p>"));
 }
 
 #[test]
-fn create_directory_then_create_page_in_directory() {
-    let project = TestProject::new("directory-page-create");
+fn plan_only_operations_preview_reports_and_diffs_without_touching_disk() {
+    let project = TestProject::new("mutation-preview");
     project.write_page(
         "index.html",
         render_page_document(
-            "index",
+            "Home",
             "<p>body</p>",
             Theme::Dark,
             "../.fractal/style.css".to_string(),
         ),
     );
-
-    let report =
-        create_directory(project.root(), Path::new(""), "research").expect("create directory");
+    project.write_page(
+        "other.html",
+        render_page_document(
+            "Other",
+            "<p>other</p>",
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        ),
+    );
+    build_index(project.root()).expect("build index");
+    let index_page = project.pages_dir().join("index.html");
+    let original = fs::read_to_string(&index_page).expect("read page");
+
+    let plan =
+        plan_set_page_tags(project.root(), "index", ["rust", "notes"], None).expect("plan tags");
+    assert_eq!(plan.root(), project.root());
+    assert!(plan.rebuilds_generated_data());
+    let preview = plan.preview().expect("preview tags");
     assert_eq!(
-        report.events,
-        vec![OperationEvent::DirectoryCreated {
-            path: PathBuf::from("pages/research")
+        preview.report.events,
+        vec![OperationEvent::PageMetadataUpdated {
+            page: PathBuf::from("pages/index.html"),
+            name: "fractal:tags".to_string(),
+            content: "rust, notes".to_string(),
         }]
     );
+    assert!(preview.summary.source_files_changed);
+    assert_eq!(preview.diffs.len(), 1);
+    assert_eq!(preview.diffs[0].path, PathBuf::from("pages/index.html"));
+    assert_eq!(preview.diffs[0].change, FileChange::Modified);
+    assert!(preview.diffs[0]
+        .diff
+        .starts_with("--- a/pages/index.html\n+++ b/pages/index.html\n@@ -"));
+    assert!(preview.diffs[0]
+        .diff
+        .contains("+    <meta name=\"fractal:tags\" content=\"rust, notes\">"));
+    assert_eq!(
+        fs::read_to_string(&index_page).expect("read page"),
+        original
+    );
 
-    create_page(
+    let report = plan.apply().expect("apply planned tags");
+    assert!(report.events.iter().any(|event| matches!(
+        event,
+        OperationEvent::PageMetadataUpdated { page, .. } if page == Path::new("pages/index.html")
+    )));
+    assert!(fs::read_to_string(&index_page)
+        .expect("read page")
+        .contains("content=\"rust, notes\""));
+
+    let create = plan_create_page(
         project.root(),
         PageCreate {
-            directory: Some(PathBuf::from("research")),
-            title: "Rust Ownership".to_string(),
+            directory: None,
+            title: "Fresh".to_string(),
         },
     )
-    .expect("create page in existing directory");
-
-    assert!(project
-        .pages_dir()
-        .join("research/rust-ownership.html")
-        .is_file());
+    .expect("plan create")
+    .preview()
+    .expect("preview create");
+    assert_eq!(create.diffs.len(), 1);
+    assert_eq!(create.diffs[0].change, FileChange::Created);
+    assert!(create.diffs[0]
+        .diff
+        .starts_with("--- /dev/null\n+++ b/pages/fresh.html\n@@ -0,0 +1,"));
+    assert!(!project.pages_dir().join("fresh.html").exists());
+
+    let rename = plan_rename_page(
+        project.root(),
+        "other",
+        PageRename {
+            path: Some(PathBuf::from("archive/other.html")),
+            title: None,
+            expected_revision: None,
+        },
+    )
+    .expect("plan rename")
+    .preview()
+    .expect("preview rename");
+    let moved = rename
+        .diffs
+        .iter()
+        .find(|diff| diff.change == FileChange::Moved)
+        .expect("moved diff");
+    assert_eq!(moved.path, PathBuf::from("pages/archive/other.html"));
+    assert_eq!(moved.from, Some(PathBuf::from("pages/other.html")));
+    assert!(moved
+        .diff
+        .contains("-    <link rel=\"stylesheet\" href=\"../.fractal/style.css\">"));
+    assert!(project.pages_dir().join("other.html").is_file());
+    assert!(!project.pages_dir().join("archive").exists());
+
+    let delete = plan_delete_page(project.root(), "other", None)
+        .expect("plan delete")
+        .preview()
+        .expect("preview delete");
+    assert!(delete
+        .diffs
+        .iter()
+        .any(|diff| { diff.change == FileChange::Deleted && diff.diff.contains("+++ /dev/null") }));
+    assert!(project.pages_dir().join("other.html").is_file());
+    assert!(!project
+        .workspace_dir()
+        .join(crate::ops::journal::TRANSACTION_DIR)
+        .exists());
 }
 
 #[test]
-fn create_page_does_not_create_missing_parent_directories() {
-    let project = TestProject::new("page-missing-directory");
+fn stale_expected_revisions_fail_with_conflict_and_leave_pages_untouched() {
+    let project = TestProject::new("page-revisions");
     project.write_page(
         "index.html",
         render_page_document(
-            "index",
-            "<p>body</p>",
+            "Home",
+            "<p>java body</p>",
             Theme::Dark,
             "../.fractal/style.css".to_string(),
         ),
     );
+    build_index(project.root()).expect("build index");
+    let index_page = project.pages_dir().join("index.html");
 
-    let error = create_page(
+    let source = read_page_source(project.root(), "index").expect("read source");
+    let detail = editor_page_detail(project.root(), "index").expect("read detail");
+    assert!(source.revision.starts_with("sha256:"));
+    assert_eq!(detail.source.revision, source.revision);
+
+    set_page_summary(project.root(), "index", "Changed elsewhere", None).expect("concurrent edit");
+    let current = read_page_source(project.root(), "index").expect("reread source");
+    assert_ne!(current.revision, source.revision);
+
+    let error = update_editor_page(
         project.root(),
-        PageCreate {
-            directory: Some(PathBuf::from("missing")),
-            title: "Rust Ownership".to_string(),
+        "index",
+        EditorPageUpdate {
+            title: Some("Stale".to_string()),
+            expected_revision: Some(source.revision.clone()),
+            ..EditorPageUpdate::default()
         },
     )
-    .expect_err("missing directory should be rejected");
+    .expect_err("stale update conflicts");
+    assert_eq!(error.code, FractalErrorCode::Conflict);
+    assert_eq!(
+        error.current_revision.as_deref(),
+        Some(current.revision.as_str())
+    );
+    assert_eq!(
+        fs::read_to_string(&index_page).expect("read page"),
+        current.html
+    );
 
-    assert_eq!(error.code, FractalErrorCode::NotFound);
-    assert!(!project.pages_dir().join("missing").exists());
+    let error = rename_page(
+        project.root(),
+        "index",
+        PageRename {
+            path: Some(PathBuf::from("home")),
+            title: None,
+            expected_revision: Some(source.revision.clone()),
+        },
+    )
+    .expect_err("stale rename conflicts");
+    assert_eq!(error.code, FractalErrorCode::Conflict);
+    assert!(index_page.exists());
+
+    let error = plan_add_note(project.root(), "index", "java", "pending note", None)
+        .expect("plan note")
+        .expect_revision("index", source.revision.clone())
+        .expect_err("stale note conflicts");
+    assert_eq!(error.code, FractalErrorCode::Conflict);
+
+    let plan = plan_add_note(project.root(), "index", "java", "pending note", None)
+        .expect("plan note")
+        .expect_revision("index", current.revision.clone())
+        .expect("matching revision");
+    set_page_tags(project.root(), "index", ["late"], None).expect("edit after planning");
+    let error = plan.apply().expect_err("apply rechecks revision");
+    assert_eq!(error.code, FractalErrorCode::Conflict);
+    assert!(!fs::read_to_string(&index_page)
+        .expect("read page")
+        .contains("pending note"));
+
+    let latest = read_page_source(project.root(), "index").expect("read latest");
+    let stale = Some(source.revision.as_str());
+    let root = project.root();
+    for error in [
+        add_note(root, "index", "java", "stale note", stale).unwrap_err(),
+        set_page_tags(root, "index", ["stale"], stale).unwrap_err(),
+        set_page_title(root, "index", "Stale", stale).unwrap_err(),
+        update_page_body(root, "index", "<p>stale</p>", stale).unwrap_err(),
+        write_page_source(root, "index", &latest.html, stale).unwrap_err(),
+        insert_block(root, "index", BlockPosition::End, "<p>stale</p>", stale).unwrap_err(),
+    ] {
+        assert_eq!(error.code, FractalErrorCode::Conflict, "{}", error.message);
+    }
+    assert_eq!(
+        fs::read_to_string(&index_page).expect("read page"),
+        latest.html
+    );
+
+    update_editor_page(
+        project.root(),
+        "index",
+        EditorPageUpdate {
+            title: Some("Fresh".to_string()),
+            expected_revision: Some(latest.revision),
+            ..EditorPageUpdate::default()
+        },
+    )
+    .expect("matching revision updates");
+    assert_eq!(
+        editor_page_detail(project.root(), "index")
+            .expect("read detail")
+            .metadata
+            .title,
+        "Fresh"
+    );
 }
 
 #[test]
-fn page_source_round_trip_rebuilds_index_and_serializes_report() {
-    let project = TestProject::new("page-source");
-    project.write_page(
-        "index.html",
+fn stale_expected_revisions_guard_note_and_lifecycle_mutations() {
+    let project = TestProject::new("note-revisions");
+    let page = |title: &str, body: &str| {
         render_page_document(
-            "index",
-            "<p>body</p>",
+            title,
+            body,
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        )
+    };
+    project.write_page("index.html", page("Home", "<p>See the vertex.</p>"));
+    project.write_page("edge.html", page("Edge", "<p>Joins two vertices.</p>"));
+    build_index(project.root()).expect("build index");
+    add_note(project.root(), "index", "vertex", "A point.", None).expect("add note");
+    let index_page = project.pages_dir().join("index.html");
+    let edge_page = project.pages_dir().join("edge.html");
+
+    let index = read_page_source(project.root(), "index").expect("read index");
+    let edge = read_page_source(project.root(), "edge").expect("read edge");
+    set_page_summary(project.root(), "index", "Changed elsewhere", None).expect("edit index");
+    set_page_summary(project.root(), "edge", "Changed elsewhere", None).expect("edit edge");
+    let latest_index = fs::read_to_string(&index_page).expect("read index");
+    let latest_edge = fs::read_to_string(&edge_page).expect("read edge");
+
+    let root = project.root();
+    let stale = Some(index.revision.as_str());
+    for error in [
+        remove_note(root, "index", "vertex", stale).unwrap_err(),
+        rename_note(root, "index", "note-vertex", "Link", stale).unwrap_err(),
+        promote_note(root, "index", "note-vertex", "Link", stale).unwrap_err(),
+        reset_page_metadata(root, "index", stale).unwrap_err(),
+        demote_page(root, "edge", "index", Some(edge.revision.as_str())).unwrap_err(),
+    ] {
+        assert_eq!(error.code, FractalErrorCode::Conflict, "{}", error.message);
+    }
+    assert_eq!(
+        fs::read_to_string(&index_page).expect("read index"),
+        latest_index
+    );
+    assert_eq!(
+        fs::read_to_string(&edge_page).expect("read edge"),
+        latest_edge
+    );
+    assert!(!project.pages_dir().join("link.html").exists());
+
+    let mut server = RpcServer::new(project.root()).expect("start server");
+    let request = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "note.remove",
+        "params": { "page": "index", "trigger": "vertex", "expected_revision": index.revision },
+    });
+    let response = server
+        .handle(&request.to_string())
+        .expect("note.remove response");
+    assert_eq!(response["error"]["data"]["code"], "conflict");
+    assert!(response["error"]["data"]["current_revision"].is_string());
+    assert!(page_notes(project.root(), "index")
+        .expect("notes")
+        .iter()
+        .any(|note| note.id == "note-vertex"));
+
+    let current = read_page_source(project.root(), "index").expect("read current");
+    rename_note(
+        project.root(),
+        "index",
+        "note-vertex",
+        "Link",
+        Some(current.revision.as_str()),
+    )
+    .expect("matching revision renames note");
+}
+
+#[test]
+fn page_edits_merge_at_block_level_and_report_overlapping_conflicts() {
+    let page = |title: &str, body: &str, summary: &str, notes: &[(&str, &str)]| {
+        let document = PageDocument::parse(&render_page_document(
+            title,
+            body,
             Theme::Dark,
             "../.fractal/style.css".to_string(),
-        ),
+        ));
+        document
+            .set_meta_tag("fractal:summary", summary)
+            .expect("set summary");
+        let mut html = document.to_html().expect("serialize page");
+        for (id, content) in notes {
+            html = insert_note_into_document(&html, &render_note_aside(id, content))
+                .expect("insert note");
+        }
+        html
+    };
+    let base = page(
+        "Home",
+        "<p>alpha</p><h2>Section</h2><p>beta</p><ul><li>one</li><li>two</li></ul><p>gamma</p>",
+        "",
+        &[("note-a", "first")],
+    );
+    let ours = page(
+        "Home",
+        "<p>alpha ours</p><h2>Section</h2><p>beta</p><ul><li>one ours</li><li>two</li></ul><p>gamma</p>",
+        "Ours summary",
+        &[("note-a", "first ours")],
+    );
+    let theirs = page(
+        "Home Base",
+        "<p>alpha</p><h2>Section</h2><p>beta</p><ul><li>one</li><li>two theirs</li></ul><p>gamma theirs</p>",
+        "",
+        &[("note-a", "first"), ("note-b", "second")],
     );
 
-    let source =
-        read_page_source(project.root(), Path::new("pages/index")).expect("read page source");
-    assert_eq!(source.path, "index.html");
-    assert!(source.html.contains("<p>body</p>"));
-
-    let updated = source.html.replace("<p>body</p>", "<p>updated</p>");
-    let report =
-        write_page_source(project.root(), Path::new("index"), updated).expect("write page source");
-
-    assert!(fs::read_to_string(project.pages_dir().join("index.html"))
-        .expect("read updated page")
-        .contains("<p>updated</p>"));
+    let merge = merge_page_edit(&base, &ours, &theirs).expect("merge page");
+    assert_eq!(merge.conflicts, Vec::new());
+    let merged = PageDocument::parse(&merge.html);
+    assert_eq!(merged.title().as_deref(), Some("Home Base"));
     assert_eq!(
-        load_project_index(project.root())
-            .expect("load generated index")
-            .pages[0]
-            .title,
-        "index"
+        merged.main_body_html().expect("merged body"),
+        "\n      <p>alpha ours</p>\n      <h2>Section</h2>\n      <p>beta</p>\n      <ul>\n        <li>one ours</li>\n        <li>two theirs</li>\n      </ul>\n      <p>gamma theirs</p>\n    "
     );
-
-    let report_json = serde_json::to_value(&report).expect("serialize report");
-    assert_eq!(report_json["events"][0]["type"], "page_source_updated");
-    assert_eq!(report_json["events"][0]["page"], "pages/index.html");
-
-    let summary = report.summary();
-    assert!(!summary.noop);
-    assert!(summary.source_changed);
-    assert!(summary.source_files_changed);
-    assert!(summary.user_content_changed);
-    assert!(summary.generated_changed);
-    assert!(summary.generated_files_changed);
     assert_eq!(
-        summary.pages_changed,
-        vec![PathBuf::from("pages/index.html")]
+        merged
+            .fractal_meta()
+            .get("fractal:summary")
+            .map(String::as_str),
+        Some("Ours summary")
     );
     assert_eq!(
-        summary.source_paths_changed,
-        vec![PathBuf::from("pages/index.html")]
+        merged
+            .notes()
+            .into_iter()
+            .map(|note| note.id)
+            .collect::<Vec<_>>(),
+        vec!["note-a".to_string(), "note-b".to_string()]
+    );
+    assert!(merge.html.contains("first ours"));
+
+    let theirs = page(
+        "Home",
+        "<p>alpha</p><h2>Section</h2><p>beta theirs</p><ul><li>one theirs</li><li>two</li></ul><p>gamma</p>",
+        "Their summary",
+        &[("note-a", "first theirs")],
+    );
+    let ours = page(
+        "Home",
+        "<p>alpha</p><h2>Section</h2><p>beta ours</p><ul><li>one ours</li><li>two</li></ul><p>gamma</p>",
+        "Ours summary",
+        &[("note-a", "first")],
     );
+    let merge = merge_page_edit(&base, &ours, &theirs).expect("merge conflicting page");
     assert_eq!(
-        summary.generated_paths_changed,
+        merge
+            .conflicts
+            .iter()
+            .map(|conflict| (conflict.region, conflict.key.as_str()))
+            .collect::<Vec<_>>(),
         vec![
-            PathBuf::from(".fractal/index.json"),
-            PathBuf::from(".fractal/graph.json")
+            (MergeRegion::Block, "main[2]"),
+            (MergeRegion::ListItem, "main[3]/li[0]"),
+            (MergeRegion::Metadata, "fractal:summary"),
         ]
     );
-
-    let summary_json = serde_json::to_value(summary).expect("serialize summary");
-    assert_eq!(summary_json["noop"], false);
-    assert_eq!(summary_json["user_content_changed"], true);
-    assert_eq!(summary_json["source_paths_changed"][0], "pages/index.html");
+    assert_eq!(merge.conflicts[0].base.as_deref(), Some("<p>beta</p>"));
+    assert_eq!(merge.conflicts[0].ours.as_deref(), Some("<p>beta ours</p>"));
     assert_eq!(
-        summary_json["generated_paths_changed"],
-        serde_json::json!([".fractal/index.json", ".fractal/graph.json"])
-    );
+        merge.conflicts[0].theirs.as_deref(),
+        Some("<p>beta theirs</p>")
+    );
+    assert!(merge.html.contains("<p>beta ours</p>"));
+    assert!(merge.html.contains("<li>one ours</li>"));
+    assert!(merge.html.contains("first theirs"));
+
+    let without_summary = |html: &str| {
+        let document = PageDocument::parse(html);
+        assert!(document.remove_meta_tag("fractal:summary"));
+        document.to_html().expect("serialize page")
+    };
+    let base = page("Home", "<p>alpha</p>", "Base summary", &[]);
+    let edited = page("Home", "<p>alpha edited</p>", "Base summary", &[]);
+    for (ours, theirs) in [
+        (without_summary(&base), edited.clone()),
+        (edited.clone(), without_summary(&base)),
+    ] {
+        let merge = merge_page_edit(&base, &ours, &theirs).expect("merge summary removal");
+        assert_eq!(merge.conflicts, Vec::new());
+        let merged = PageDocument::parse(&merge.html);
+        assert_eq!(merged.fractal_meta().get("fractal:summary"), None);
+        assert!(merge.html.contains("<p>alpha edited</p>"));
+    }
 }
 
 #[test]
-fn same_content_source_write_reports_noop_when_generated_data_is_fresh() {
-    let project = TestProject::new("same-content-source-write");
+fn history_records_applied_plans_and_undoes_them_as_new_operations() {
+    let project = TestProject::new("history-store");
     project.write_page(
         "index.html",
         render_page_document(
-            "index",
+            "Home",
             "<p>body</p>",
             Theme::Dark,
             "../.fractal/style.css".to_string(),
         ),
     );
-    build_index(project.root()).expect("build initial generated data");
-    let generated_report = build_index(project.root()).expect("rebuild unchanged generated data");
-    assert_eq!(generated_report.events, Vec::<OperationEvent>::new());
-    assert!(generated_report.summary().noop);
-
-    let source = read_page_source(project.root(), Path::new("index")).expect("read page source");
-    let report = write_page_source(project.root(), Path::new("index"), source.html)
-        .expect("write same page source");
-
-    assert_eq!(report.events, Vec::<OperationEvent>::new());
-    let summary = report.summary();
-    assert!(summary.noop);
-    assert!(!summary.source_files_changed);
-    assert!(!summary.user_content_changed);
-    assert!(!summary.generated_files_changed);
-    assert_eq!(summary.changed_paths, Vec::<PathBuf>::new());
+    build_index(project.root()).expect("build index");
+    let index_page = project.pages_dir().join("index.html");
 
-    let summary_json = serde_json::to_value(summary).expect("serialize no-op summary");
-    assert_eq!(summary_json["noop"], true);
-    assert_eq!(summary_json["changed_paths"], serde_json::json!([]));
-}
+    let error = history_log(project.root(), None).expect_err("history is opt-in");
+    assert_eq!(error.code, FractalErrorCode::NotFound);
+    set_page_summary(project.root(), "index", "Before history", None).expect("untracked edit");
+    let untracked = fs::read(&index_page).expect("read page");
 
-#[test]
-fn editor_page_api_lists_pages_and_returns_detail_data() {
-    let project = TestProject::new("editor-page-api");
-    let home = render_page_document(
-        "Home",
-        "<p><a href=\"rust.html\" data-fractal-link=\"page\">Rust</a> and <a href=\"#note-java\" data-fractal-link=\"note\">Java</a>.</p>",
-        Theme::Dark,
-        "../.fractal/style.css".to_string(),
-    );
-    let home = insert_note_into_document(&home, &render_note_aside("note-java", "note body"))
-        .expect("insert note");
-    project.write_page("index.html", home);
-    project.write_page(
-        "rust.html",
-        render_page_document(
-            "Rust",
-            "<p><a href=\"index.html\" data-fractal-link=\"page\">Home</a></p>",
-            Theme::Dark,
-            "../.fractal/style.css".to_string(),
-        ),
+    let report = enable_history(project.root()).expect("enable history");
+    assert_eq!(
+        report.events,
+        vec![OperationEvent::HistoryEnabled {
+            path: PathBuf::from(".fractal/history"),
+        }]
     );
+    assert!(history_log(project.root(), None)
+        .expect("empty log")
+        .is_empty());
 
-    let pages = list_editor_pages(project.root()).expect("list editor pages");
+    set_page_summary(project.root(), "index", "Tracked", None).expect("tracked edit");
+    create_page(
+        project.root(),
+        PageCreate {
+            directory: None,
+            title: "Fresh".to_string(),
+        },
+    )
+    .expect("create page");
+    rename_page(
+        project.root(),
+        "fresh",
+        PageRename {
+            path: Some(PathBuf::from("moved")),
+            title: None,
+            expected_revision: None,
+        },
+    )
+    .expect("move page");
+
+    let log = history_log(project.root(), None).expect("read log");
     assert_eq!(
-        pages,
+        log.iter()
+            .map(|entry| (entry.id, entry.files[0].change))
+            .collect::<Vec<_>>(),
         vec![
-            EditorPageListEntry {
-                path: "index.html".to_string(),
-                title: "Home".to_string(),
-                summary: None,
-                tags: vec![],
-                backlink_count: 1,
-                outlink_count: 1,
-            },
-            EditorPageListEntry {
-                path: "rust.html".to_string(),
-                title: "Rust".to_string(),
-                summary: None,
-                tags: vec![],
-                backlink_count: 1,
-                outlink_count: 1,
-            },
+            (1, FileChange::Modified),
+            (2, FileChange::Created),
+            (3, FileChange::Moved),
         ]
     );
-
-    let detail = editor_page_detail(project.root(), Path::new("index")).expect("page detail");
-    assert_eq!(detail.source.path, "index.html");
-    assert!(detail.source.html.contains("href=\"rust.html\""));
-    assert!(detail.source.html.contains("data-fractal-link=\"page\""));
-    assert!(detail.body_html.contains("Java"));
-    assert_eq!(detail.metadata.title, "Home");
-    assert_eq!(
-        detail.notes,
-        vec![EditorNoteDetail {
-            id: "note-java".to_string(),
-            label: "java".to_string(),
-            text: "note body".to_string(),
-        }]
-    );
+    assert!(log.iter().all(|entry| entry
+        .files
+        .iter()
+        .all(|file| !file.path.starts_with(".fractal"))));
+    assert_eq!(log[0].files[0].path, PathBuf::from("pages/index.html"));
     assert_eq!(
-        detail.links,
-        vec![
-            EditorLinkDetail {
-                href: "rust.html".to_string(),
-                text: "Rust".to_string(),
-                scope: "page".to_string(),
-                target_page: Some("rust.html".to_string()),
-                target_note: None,
-            },
-            EditorLinkDetail {
-                href: "#note-java".to_string(),
-                text: "Java".to_string(),
-                scope: "note".to_string(),
-                target_page: None,
-                target_note: Some("note-java".to_string()),
-            },
-        ]
+        log[0].files[0].before.as_deref(),
+        Some(crate::io::hash::content_hash(&untracked).as_str())
     );
     assert_eq!(
-        detail.outlinks,
-        vec![GraphPageLink {
-            page: "rust.html".to_string(),
-            text: "Rust".to_string(),
-        }]
+        log[2].files[0].from,
+        Some(PathBuf::from("pages/fresh.html"))
     );
     assert_eq!(
-        detail.backlinks,
-        vec![GraphPageLink {
-            page: "rust.html".to_string(),
-            text: "Home".to_string(),
-        }]
+        history_log(project.root(), Some(Path::new("index")))
+            .expect("page log")
+            .into_iter()
+            .map(|entry| entry.id)
+            .collect::<Vec<_>>(),
+        vec![1]
     );
+    let diffs = history_diff(project.root(), 1).expect("diff entry");
+    assert!(diffs[0]
+        .diff
+        .starts_with("--- a/pages/index.html\n+++ b/pages/index.html\n@@ -"));
+    assert!(diffs[0].diff.contains("content=\"Tracked\""));
+
+    let report = undo_history(project.root(), 3).expect("undo move");
+    assert!(report
+        .events
+        .contains(&OperationEvent::HistoryUndone { id: 3 }));
+    assert!(project.pages_dir().join("fresh.html").is_file());
+    assert!(!project.pages_dir().join("moved.html").exists());
+
+    undo_history(project.root(), 1).expect("undo summary");
+    assert_eq!(fs::read(&index_page).expect("read page"), untracked);
+    let entry = history_entry(project.root(), 5).expect("undo entry");
+    assert_eq!(entry.undoes, Some(1));
+
+    let error = undo_history(project.root(), 1).expect_err("stale undo conflicts");
+    assert_eq!(error.code, FractalErrorCode::Conflict);
+    undo_history(project.root(), 5).expect("redo by undoing the undo");
+    assert!(fs::read_to_string(&index_page)
+        .expect("read page")
+        .contains("content=\"Tracked\""));
+
+    disable_history(project.root()).expect("disable history");
+    assert!(!project.root().join(".fractal/history").exists());
 }
 
 #[test]
-fn project_summary_reports_validation_counts_and_generated_freshness() {
-    let project = TestProject::new("project-summary");
-    let home = render_page_document(
-        "Home",
-        "<p><a href=\"rust.html\" data-fractal-link=\"page\">Rust</a></p>",
-        Theme::Dark,
-        "../.fractal/style.css".to_string(),
-    );
-    let home = insert_note_into_document(&home, &render_note_aside("note-java", "note body"))
-        .expect("insert note");
-    project.write_page("index.html", home);
+fn git_auto_commit_records_operations_and_attributes_page_blocks() {
+    let project = TestProject::new("git-provenance");
     project.write_page(
-        "rust.html",
+        "index.html",
         render_page_document(
-            "Rust",
-            "<p>body</p>",
+            "Home",
+            "<p>first</p><p>second</p>",
             Theme::Dark,
             "../.fractal/style.css".to_string(),
         ),
     );
-    fs::write(project.pages_dir().join("asset.txt"), "asset").expect("write asset");
-    build_index(project.root()).expect("build generated data");
+    build_index(project.root()).expect("build index");
 
-    let summary = project_summary(project.root()).expect("project summary");
+    let error = set_git_auto_commit(project.root(), true).expect_err("requires a git work tree");
+    assert_eq!(error.code, FractalErrorCode::InvalidProject);
+    let error = page_git_history(project.root(), "index").expect_err("requires a git work tree");
+    assert_eq!(error.code, FractalErrorCode::InvalidProject);
 
-    assert_eq!(summary.project_name, "test");
-    assert_eq!(summary.default_page, "pages/index.html");
-    assert!(summary.valid);
-    assert_eq!(summary.validation_error, None);
-    assert_eq!(summary.file_count, 3);
-    assert_eq!(summary.page_count, 2);
-    assert_eq!(summary.asset_count, 1);
-    assert_eq!(summary.note_count, 1);
-    assert_eq!(summary.link_count, 1);
-    assert_eq!(summary.graph_node_count, 3);
-    assert_eq!(summary.graph_edge_count, 2);
-    assert_eq!(summary.orphan_page_count, 1);
-    assert!(summary.generated_index_exists);
-    assert!(summary.generated_graph_exists);
-    assert!(summary.generated_index_fresh);
-    assert!(summary.generated_graph_fresh);
+    run_git(project.root(), &["init", "-q"]);
+    run_git(project.root(), &["config", "user.name", "Fractal Test"]);
+    run_git(
+        project.root(),
+        &["config", "user.email", "fractal@example.com"],
+    );
+    run_git(project.root(), &["add", "-A"]);
+    run_git(project.root(), &["commit", "-q", "-m", "initial"]);
+    let initial_commit = run_git(project.root(), &["rev-parse", "HEAD"])
+        .trim()
+        .to_string();
+    fs::write(project.root().join("scratch.txt"), "unrelated").expect("write scratch");
+
+    let report = set_git_auto_commit(project.root(), true).expect("enable auto-commit");
+    assert!(
+        load_project_manifest(project.root())
+            .expect("manifest")
+            .git_auto_commit
+    );
+    assert!(report.events.iter().any(|event| matches!(
+        event,
+        OperationEvent::GitCommitted { message, .. } if message == "fractal: update fractal.json"
+    )));
+
+    let report =
+        add_note(project.root(), "index", "second", "Explains second", None).expect("add note");
+    let OperationEvent::GitCommitted {
+        commit: note_commit,
+        message,
+    } = report.events.last().expect("commit event").clone()
+    else {
+        panic!("expected a git commit: {:?}", report.events);
+    };
+    assert_eq!(message, "fractal: update pages/index.html");
+    assert_eq!(
+        run_git(project.root(), &["status", "--porcelain"]),
+        "?? scratch.txt\n"
+    );
+
+    rename_page(
+        project.root(),
+        "index",
+        PageRename {
+            path: Some(PathBuf::from("home")),
+            title: None,
+            expected_revision: None,
+        },
+    )
+    .expect("move page");
+    assert_eq!(
+        run_git(project.root(), &["log", "-1", "--format=%s"]).trim(),
+        "fractal: move pages/index.html -> pages/home.html and 1 more"
+    );
+
+    let history = page_git_history(project.root(), "home").expect("page history");
+    assert_eq!(
+        history
+            .iter()
+            .map(|commit| commit.subject.as_str())
+            .collect::<Vec<_>>(),
+        vec![
+            "fractal: move pages/index.html -> pages/home.html and 1 more",
+            "fractal: update pages/index.html",
+            "initial",
+        ]
+    );
+    assert!(history.iter().all(|commit| commit.author == "Fractal Test"));
+
+    let home_page = project.pages_dir().join("home.html");
+    let source = fs::read_to_string(&home_page).expect("read page");
+    fs::write(
+        &home_page,
+        source.replace("<p>first</p>", "<p>first, revised</p>"),
+    )
+    .expect("edit page outside fractal");
 
-    project.write_page(
-        "java.html",
-        render_page_document(
-            "Java",
-            "<p>body</p>",
-            Theme::Dark,
-            "../.fractal/style.css".to_string(),
-        ),
+    let blame = page_blame(project.root(), "home").expect("page blame");
+    assert_eq!(blame.page, "pages/home.html");
+    assert_eq!(
+        blame
+            .entries
+            .iter()
+            .map(|entry| (
+                entry.region,
+                entry.key.as_str(),
+                entry.commit.as_ref().map(|commit| commit.id.as_str())
+            ))
+            .collect::<Vec<_>>(),
+        vec![
+            (BlameRegion::Block, "main[0]", None),
+            (BlameRegion::Block, "main[1]", Some(initial_commit.as_str())),
+            (
+                BlameRegion::Note,
+                note_id_from_trigger("second").expect("note id").as_str(),
+                Some(note_commit.as_str())
+            ),
+        ]
     );
 
-    let stale_summary = project_summary(project.root()).expect("stale project summary");
+    set_git_auto_commit(project.root(), false).expect("disable auto-commit");
+    set_page_summary(project.root(), "home", "Not committed", None).expect("edit page");
+    assert_eq!(
+        page_git_history(project.root(), "home")
+            .expect("history")
+            .len(),
+        3
+    );
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let hook = project.root().join(".git/hooks/pre-commit");
+        let install_hook = |script: &str| {
+            fs::write(&hook, script).expect("write hook");
+            fs::set_permissions(&hook, fs::Permissions::from_mode(0o755)).expect("chmod hook");
+        };
+        set_git_auto_commit(project.root(), true).expect("enable auto-commit");
+        install_hook("#!/bin/sh\ntest -f .fractal/mutation.lock\n");
+        let report = set_page_summary(project.root(), "home", "Committed under lock", None)
+            .expect("edit page");
+        assert!(
+            matches!(
+                report.events.last(),
+                Some(OperationEvent::GitCommitted { .. })
+            ),
+            "auto-commit should run while the mutation lock is held: {:?}",
+            report.events
+        );
 
-    assert_eq!(stale_summary.page_count, 3);
-    assert!(!stale_summary.generated_index_fresh);
-    assert!(!stale_summary.generated_graph_fresh);
+        install_hook("#!/bin/sh\nexit 1\n");
+        let report = set_page_summary(project.root(), "home", "Rejected", None).expect("edit page");
+        assert!(matches!(
+            report.events.last(),
+            Some(OperationEvent::GitCommitFailed { .. })
+        ));
+        assert!(report
+            .summary()
+            .warnings
+            .iter()
+            .any(|warning| warning.starts_with("git auto-commit failed")));
+        assert!(mutation_lock_owner(project.root())
+            .expect("lock owner")
+            .is_none());
+    }
 }
 
 #[test]
-fn safe_editor_page_update_mutates_owned_fields_and_rebuilds_index() {
-    let project = TestProject::new("safe-editor-update");
-    project.write_page(
-        "index.html",
-        render_page_document(
-            "Home",
-            "<p>Original body.</p>",
-            Theme::Dark,
-            "../.fractal/style.css".to_string(),
-        ),
-    );
-    project.write_page(
-        "rust.html",
+fn project_watcher_rebuilds_generated_data_after_page_changes() {
+    let project = TestProject::new("watcher");
+    let page = |title: &str, body: &str| {
         render_page_document(
-            "Rust",
-            "<p>Target page.</p>",
+            title,
+            body,
             Theme::Dark,
             "../.fractal/style.css".to_string(),
-        ),
-    );
+        )
+    };
+    project.write_page("index.html", page("Home", "<p>body</p>"));
+    build_index(project.root()).expect("build index");
 
-    let report = update_editor_page(
+    let mut watcher = ProjectWatcher::new(
         project.root(),
-        Path::new("index"),
-        EditorPageUpdate {
-            title: Some("Home Base".to_string()),
-            body_html: Some(
-                "<p><a href=\"rust.html\" data-fractal-link=\"page\">Rust</a> updated.</p>"
-                    .to_string(),
-            ),
-            summary: Some("Local project home".to_string()),
-            tags: Some(vec![
-                "Projects".to_string(),
-                "rust, projects".to_string(),
-                " ".to_string(),
-            ]),
+        WatchOptions {
+            debounce: Duration::from_millis(50),
+            validate: true,
+            ..WatchOptions::default()
         },
     )
-    .expect("update editor page");
+    .expect("start watcher");
+    #[cfg(target_os = "linux")]
+    assert_eq!(watcher.backend(), WatchBackend::Inotify);
+    assert!(watcher
+        .wait_for_changes(Some(Duration::from_millis(100)))
+        .expect("idle wait")
+        .is_empty());
+
+    project.write_page("second.html", page("Second", "<p>second</p>"));
+    project.write_page("index.html", page("Home", "<p>edited body</p>"));
+    let changed = watcher
+        .wait_for_changes(Some(Duration::from_secs(5)))
+        .expect("wait for changes");
+    assert_eq!(
+        changed,
+        vec![
+            PathBuf::from("pages/index.html"),
+            PathBuf::from("pages/second.html")
+        ]
+    );
+    assert!(
+        !project_summary(project.root())
+            .expect("summary")
+            .generated_index_fresh
+    );
 
-    assert!(report.events.iter().any(|event| {
-        matches!(
-            event,
-            OperationEvent::PageTitleUpdated { title, .. } if title == "Home Base"
-        )
-    }));
-    assert!(report
-        .events
-        .iter()
-        .any(|event| matches!(event, OperationEvent::PageContentUpdated { .. })));
-    assert!(report.events.iter().any(|event| {
-        matches!(
-            event,
-            OperationEvent::PageMetadataUpdated { name, content, .. }
-                if name == "fractal:summary" && content == "Local project home"
-        )
-    }));
+    let report = watcher.refresh(&changed);
+    assert_eq!(
+        report.events[0],
+        OperationEvent::FilesChanged {
+            paths: changed.clone()
+        }
+    );
     assert!(report
         .events
-        .iter()
-        .any(|event| matches!(event, OperationEvent::GeneratedIndexBuilt { .. })));
-
-    let detail = editor_page_detail(project.root(), Path::new("index")).expect("page detail");
-    assert_eq!(detail.metadata.title, "Home Base");
-    assert_eq!(
-        detail.metadata.summary,
-        Some("Local project home".to_string())
+        .contains(&OperationEvent::GeneratedIndexBuilt {
+            path: PathBuf::from(".fractal/index.json"),
+        }));
+    assert!(report.summary().warnings.is_empty());
+    assert!(
+        project_summary(project.root())
+            .expect("summary")
+            .generated_index_fresh
     );
     assert_eq!(
-        detail.metadata.tags,
-        vec!["Projects".to_string(), "rust".to_string()]
+        load_project_index(project.root())
+            .expect("index")
+            .pages
+            .len(),
+        2
     );
-    assert!(detail.source.html.contains("<title>Home Base</title>"));
-    assert!(detail.source.html.contains("<h1>Home Base</h1>"));
-    assert!(detail.body_html.contains("Rust"));
+
+    project.write_page("second.html", "<p>not a page</p>");
+    let changed = watcher
+        .wait_for_changes(Some(Duration::from_secs(5)))
+        .expect("wait for broken page");
+    let report = watcher.refresh(&changed);
+    assert_eq!(report.summary().warnings.len(), 1);
+    assert!(report.summary().warnings[0].contains("second.html"));
+
+    let mut polling = ProjectWatcher::new(
+        project.root(),
+        WatchOptions {
+            debounce: Duration::from_millis(20),
+            poll_interval: Duration::from_millis(20),
+            polling: true,
+            ..WatchOptions::default()
+        },
+    )
+    .expect("start polling watcher");
+    assert_eq!(polling.backend(), WatchBackend::Polling);
+    fs::remove_file(project.pages_dir().join("second.html")).expect("remove page");
     assert_eq!(
-        detail.links,
-        vec![EditorLinkDetail {
-            href: "rust.html".to_string(),
-            text: "Rust".to_string(),
-            scope: "page".to_string(),
-            target_page: Some("rust.html".to_string()),
-            target_note: None,
-        }]
+        polling
+            .wait_for_changes(Some(Duration::from_secs(5)))
+            .expect("poll for changes"),
+        vec![PathBuf::from("pages/second.html")]
+    );
+
+    let mut events = Vec::new();
+    polling
+        .run(|event| {
+            let built = matches!(event, OperationEvent::GeneratedGraphBuilt { .. });
+            events.push(event);
+            if built {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        })
+        .expect("run watcher");
+    assert!(events.contains(&OperationEvent::GeneratedIndexBuilt {
+        path: PathBuf::from(".fractal/index.json"),
+    }));
+    assert_eq!(
+        load_project_index(project.root())
+            .expect("index")
+            .pages
+            .len(),
+        1
+    );
+
+    let index_page = project.pages_dir().join("index.html");
+    project.write_page("index.html", page("Home", "<p>body</p>"));
+    polling
+        .wait_for_changes(Some(Duration::from_millis(200)))
+        .expect("settle rewritten page");
+    let modified = fs::metadata(&index_page)
+        .and_then(|metadata| metadata.modified())
+        .expect("page mtime");
+    project.write_page("index.html", page("Home", "<p>BODY</p>"));
+    fs::File::options()
+        .write(true)
+        .open(&index_page)
+        .and_then(|file| file.set_modified(modified))
+        .expect("restore page mtime");
+    assert_eq!(
+        polling
+            .wait_for_changes(Some(Duration::from_secs(5)))
+            .expect("poll for same-length edit"),
+        vec![PathBuf::from("pages/index.html")]
     );
 }
 
 #[test]
-fn safe_editor_page_update_repairs_editor_html_links_before_validating() {
-    let project = TestProject::new("safe-editor-repairs-links");
+fn rpc_server_answers_requests_and_pushes_change_notifications() {
+    let project = TestProject::new("rpc-server");
     project.write_page(
         "index.html",
         render_page_document(
             "Home",
-            "<p>Original body.</p>",
+            "<p>Welcome home.</p>",
             Theme::Dark,
             "../.fractal/style.css".to_string(),
         ),
     );
+    build_index(project.root()).expect("build index");
+    let mut server = RpcServer::new(project.root()).expect("start server");
+
+    let response = server
+        .handle(r#"{"jsonrpc":"2.0","id":1,"method":"page.list"}"#)
+        .expect("page.list response");
+    assert_eq!(response["id"], 1);
+    assert_eq!(response["result"]["schema"], "fractal.command_result.v1");
+    assert_eq!(response["result"]["command"], "page.list");
+    assert_eq!(response["result"]["data"][0]["title"], "Home");
+
+    let preview = server
+        .handle(
+            r#"{"jsonrpc":"2.0","id":2,"method":"page.set","params":{"page":"index","body_html":"<p>Fresh words.</p>","dry_run":true}}"#,
+        )
+        .expect("dry run response");
+    assert!(preview["result"]["data"]["diffs"].is_array());
+    assert!(server.take_notifications().is_empty());
+
+    server
+        .handle(
+            r#"{"jsonrpc":"2.0","id":3,"method":"page.set","params":{"page":"index","body_html":"<p>Fresh words.</p>"}}"#,
+        )
+        .expect("page.set response");
+    let notifications = server.take_notifications();
+    assert_eq!(notifications.len(), 1);
+    assert_eq!(notifications[0]["method"], "fractal.changed");
+    assert_eq!(notifications[0]["params"]["command"], "page.set");
+    assert!(notifications[0].get("id").is_none());
+
+    let search = server
+        .handle(r#"{"jsonrpc":"2.0","id":4,"method":"search.text","params":{"query":"fresh"}}"#)
+        .expect("search response");
+    assert_eq!(search["result"]["data"][0]["path"], "index.html");
+
+    let unknown = server
+        .handle(r#"{"jsonrpc":"2.0","id":5,"method":"page.teleport"}"#)
+        .expect("unknown method response");
+    assert_eq!(unknown["error"]["code"], rpc::METHOD_NOT_FOUND);
+
+    let missing = server
+        .handle(r#"{"jsonrpc":"2.0","id":6,"method":"page.read","params":{"page":"missing"}}"#)
+        .expect("missing page response");
+    assert_eq!(missing["error"]["code"], rpc::FRACTAL_ERROR);
+    assert_eq!(missing["error"]["data"]["code"], "not_found");
+    assert!(missing["error"]["data"].get("current_revision").is_none());
+
+    let invalid = server
+        .handle(r#"{"jsonrpc":"2.0","id":7,"method":"graph.neighbors","params":{"depth":2}}"#)
+        .expect("invalid params response");
+    assert_eq!(invalid["error"]["code"], rpc::INVALID_PARAMS);
+
+    let malformed = server.handle("{not json").expect("parse error response");
+    assert_eq!(malformed["error"]["code"], rpc::PARSE_ERROR);
+    assert!(malformed["id"].is_null());
+
+    assert!(server
+        .handle(r#"{"jsonrpc":"2.0","method":"project.summary"}"#)
+        .is_none());
+
+    let input = concat!(
+        r#"{"jsonrpc":"2.0","id":"a","method":"graph.orphans"}"#,
+        "\n\n",
+        r#"{"jsonrpc":"2.0","id":"b","method":"page.create","params":{"page":"second"}}"#,
+        "\n",
+    );
+    let mut output = Vec::new();
+    server
+        .serve(std::io::Cursor::new(input), &mut output, None)
+        .expect("serve stdio");
+    let messages = String::from_utf8(output)
+        .expect("utf8 output")
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).expect("json line"))
+        .collect::<Vec<_>>();
+    assert_eq!(messages.len(), 3);
+    assert_eq!(messages[0]["id"], "a");
+    assert_eq!(messages[1]["id"], "b");
+    assert_eq!(messages[2]["params"]["command"], "page.create");
+    assert!(project.pages_dir().join("second.html").is_file());
+}
+
+#[test]
+fn rpc_server_manages_assets_imports_history_and_lint() {
+    let project = TestProject::new("rpc-server-operations");
     project.write_page(
-        "rust.html",
+        "index.html",
         render_page_document(
-            "Rust",
-            "<p>Target page.</p>",
+            "Home",
+            "<p>Welcome home.</p>",
             Theme::Dark,
             "../.fractal/style.css".to_string(),
         ),
     );
-
-    let report = update_editor_page(
-        project.root(),
-        Path::new("index"),
-        EditorPageUpdate {
-            body_html: Some("<p><a href=\"rust.html\">Rust</a> stayed readable.</p>".to_string()),
-            ..EditorPageUpdate::default()
-        },
-    )
-    .expect("repair editor body links");
-
-    assert!(report.events.iter().any(|event| {
-        matches!(
-            event,
-            OperationEvent::PageLinksRewritten { count, .. } if *count == 1
-        )
-    }));
-
-    let detail = editor_page_detail(project.root(), Path::new("index")).expect("page detail");
-    assert!(detail.body_html.contains("Rust stayed readable."));
-    assert!(detail.links.is_empty());
-
-    sync_project(project.root()).expect("sync generated links");
-    let detail = editor_page_detail(project.root(), Path::new("index")).expect("synced detail");
+    build_index(project.root()).expect("build index");
+    let source = temp_dir("rpc-sources");
+    fs::write(source.join("diagram.png"), "png").expect("write asset source");
+    fs::write(source.join("plan.md"), "# Plan\n\nShip it.").expect("write markdown source");
+    let mut server = RpcServer::new(project.root()).expect("start server");
+    let mut call = |method: &str, params: serde_json::Value| {
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+        server.handle(&request.to_string()).expect("response")
+    };
+
+    let rules = call("lint", serde_json::json!({ "list_rules": true }));
+    assert!(rules["result"]["data"]
+        .as_array()
+        .is_some_and(|rules| !rules.is_empty()));
+    assert!(call("lint", serde_json::json!({}))["result"]["data"].is_object());
+
+    let enabled = call("history.enable", serde_json::json!({}));
+    assert_eq!(enabled["result"]["command"], "history.enable");
+
+    call(
+        "asset.add",
+        serde_json::json!({ "source": source.join("diagram.png") }),
+    );
+    assert!(project.pages_dir().join("diagram.png").is_file());
+    let unreferenced = call("asset.list", serde_json::json!({ "unreferenced": true }));
+    assert_eq!(unreferenced["result"]["data"][0]["path"], "diagram.png");
+    call(
+        "asset.move",
+        serde_json::json!({ "asset": "diagram.png", "to": "media/diagram.png" }),
+    );
+    assert!(project.pages_dir().join("media/diagram.png").is_file());
+
+    let preview = call(
+        "import.markdown",
+        serde_json::json!({ "source": source.join("plan.md"), "dry_run": true }),
+    );
+    assert!(preview["result"]["data"]["diffs"].is_array());
+    assert!(!project.pages_dir().join("plan.html").exists());
+    call(
+        "import.markdown",
+        serde_json::json!({ "source": source.join("plan.md") }),
+    );
+    assert!(project.pages_dir().join("plan.html").is_file());
+
+    let log = call("history.log", serde_json::json!({}));
+    let ids = log["result"]["data"]
+        .as_array()
+        .expect("history entries")
+        .iter()
+        .map(|entry| entry["id"].as_u64().expect("entry id"))
+        .collect::<Vec<_>>();
+    assert_eq!(ids, [1, 2, 3]);
+    let shown = call("history.show", serde_json::json!({ "id": 3 }));
+    assert_eq!(shown["result"]["data"]["id"], 3);
+    let diff = call("history.diff", serde_json::json!({ "id": 3 }));
+    assert!(diff["result"]["data"][0]["diff"]
+        .as_str()
+        .expect("unified diff")
+        .contains("Ship it."));
+    call("history.undo", serde_json::json!({ "id": 3 }));
+    assert!(!project.pages_dir().join("plan.html").exists());
+
+    call(
+        "asset.delete",
+        serde_json::json!({ "asset": "media/diagram.png" }),
+    );
+    assert!(!project.pages_dir().join("media/diagram.png").exists());
+    let notifications = server.take_notifications();
     assert_eq!(
-        detail.links,
-        vec![EditorLinkDetail {
-            href: "rust.html".to_string(),
-            text: "Rust".to_string(),
-            scope: "page".to_string(),
-            target_page: Some("rust.html".to_string()),
-            target_note: None,
-        }]
+        notifications
+            .iter()
+            .map(|notification| notification["params"]["command"].as_str().expect("command"))
+            .collect::<Vec<_>>(),
+        [
+            "asset.add",
+            "asset.move",
+            "import.markdown",
+            "history.undo",
+            "asset.delete"
+        ]
     );
 }
 
+#[cfg(feature = "cli")]
 #[test]
-fn mutation_lock_blocks_writes_and_successful_mutations_clean_up_lock() {
-    let project = TestProject::new("mutation-lock");
-    project.write_page(
-        "index.html",
+fn mcp_server_exposes_read_only_tools_and_page_resources() {
+    let project = TestProject::new("mcp-server");
+    let page = |title: &str, body: &str| {
         render_page_document(
-            "Home",
-            "<p>body</p>",
+            title,
+            body,
             Theme::Dark,
             "../.fractal/style.css".to_string(),
-        ),
+        )
+    };
+    project.write_page(
+        "index.html",
+        page("Home", r#"<p>See <a href="middle.html">Middle</a>.</p>"#),
     );
-    let lock_path = project
-        .workspace_dir()
-        .join(crate::ops::mutation::MUTATION_LOCK_FILE);
+    project.write_page(
+        "middle.html",
+        page("Middle", r#"<p>Go on to <a href="end.html">End</a>.</p>"#),
+    );
+    project.write_page("end.html", page("End", "<p>The end of the trail.</p>"));
+    build_index(project.root()).expect("build index");
 
-    update_page_body(project.root(), Path::new("index"), "<p>updated</p>")
-        .expect("update page with free lock");
-    assert!(!lock_path.exists());
+    let mut server = McpServer::new(project.root(), false).expect("start mcp server");
+    let call = |server: &mut McpServer, id: u64, method: &str, params: serde_json::Value| {
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": method,
+            "params": params,
+        });
+        server.handle(&request.to_string())
+    };
+
+    let init = call(
+        &mut server,
+        1,
+        "initialize",
+        serde_json::json!({ "protocolVersion": "2025-03-26" }),
+    );
+    assert_eq!(init[0]["result"]["protocolVersion"], "2025-03-26");
+    assert_eq!(init[0]["result"]["serverInfo"]["name"], "fractal");
+    assert!(server
+        .handle(r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#)
+        .is_empty());
+
+    let tools = call(&mut server, 2, "tools/list", serde_json::json!({}));
+    let tools = tools[0]["result"]["tools"].as_array().expect("tools");
+    let names = tools
+        .iter()
+        .map(|tool| tool["name"].as_str().expect("tool name"))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        [
+            "search_text",
+            "context_page",
+            "graph_neighbors",
+            "graph_path",
+            "page_read",
+            "page_block_list",
+            "lint",
+            "asset_list",
+            "history_log",
+            "history_show",
+            "history_diff"
+        ]
+    );
+    let neighbors = &tools[2]["inputSchema"];
+    assert_eq!(neighbors["required"], serde_json::json!(["page"]));
+    assert_eq!(neighbors["properties"]["depth"]["type"], "integer");
+    assert_eq!(neighbors["properties"]["depth"]["default"], 1);
+    assert!(tools[4]["inputSchema"]["properties"].get("view").is_none());
 
-    fs::write(&lock_path, "held by another process").expect("create lock");
-    let error = update_page_body(project.root(), Path::new("index"), "<p>blocked</p>")
-        .expect_err("locked project should reject writes");
+    let path = call(
+        &mut server,
+        3,
+        "tools/call",
+        serde_json::json!({ "name": "graph_path", "arguments": { "from": "index", "to": "end" } }),
+    );
+    assert_eq!(path[0]["result"]["isError"], false);
+    assert_eq!(
+        path[0]["result"]["structuredContent"]["data"],
+        serde_json::json!(["index.html", "middle.html", "end.html"])
+    );
+
+    let missing = call(
+        &mut server,
+        4,
+        "tools/call",
+        serde_json::json!({ "name": "page_read", "arguments": { "page": "nowhere" } }),
+    );
+    assert_eq!(missing[0]["result"]["isError"], true);
+
+    let blocked = call(
+        &mut server,
+        5,
+        "tools/call",
+        serde_json::json!({
+            "name": "note_add",
+            "arguments": { "page": "index", "trigger": "Middle", "content": "A stop." },
+        }),
+    );
+    assert_eq!(blocked[0]["error"]["code"], rpc::INVALID_PARAMS);
+
+    let resources = call(&mut server, 6, "resources/list", serde_json::json!({}));
+    let resources = resources[0]["result"]["resources"]
+        .as_array()
+        .expect("resources");
+    assert_eq!(resources.len(), 3);
+    assert_eq!(resources[0]["uri"], "fractal:///pages/end.html");
+    assert_eq!(resources[0]["title"], "End");
+    let read = call(
+        &mut server,
+        7,
+        "resources/read",
+        serde_json::json!({ "uri": "fractal:///pages/end.html" }),
+    );
+    assert!(read[0]["result"]["contents"][0]["text"]
+        .as_str()
+        .expect("page html")
+        .contains("The end of the trail."));
+
+    let mut writer = McpServer::new(project.root(), true).expect("start writable server");
+    let added = call(
+        &mut writer,
+        8,
+        "tools/call",
+        serde_json::json!({
+            "name": "note_add",
+            "arguments": { "page": "index", "trigger": "Middle", "content": "A stop." },
+        }),
+    );
+    assert_eq!(added[0]["result"]["isError"], false);
+    assert_eq!(added[1]["method"], "notifications/resources/list_changed");
+
+    let tools = call(&mut writer, 9, "tools/list", serde_json::json!({}));
+    let hints = |name: &str| {
+        let tool = tools[0]["result"]["tools"]
+            .as_array()
+            .expect("tools")
+            .iter()
+            .find(|tool| tool["name"] == name)
+            .expect("tool listed")
+            .clone();
+        let annotations = &tool["annotations"];
+        (
+            annotations["readOnlyHint"] == true,
+            annotations["destructiveHint"] == true,
+            annotations["idempotentHint"] == true,
+        )
+    };
+    assert_eq!(hints("asset_delete"), (false, true, true));
+    assert_eq!(hints("page_set"), (false, true, true));
+    assert_eq!(hints("history_undo"), (false, true, false));
+    assert_eq!(hints("note_add"), (false, false, false));
+    assert_eq!(hints("graph_path"), (true, false, true));
+    assert_eq!(
+        page_notes(project.root(), "index").expect("notes")[0].id,
+        "note-middle"
+    );
+}
 
-    assert_eq!(error.code, FractalErrorCode::ProjectLocked);
-    assert!(lock_path.exists());
-    assert!(fs::read_to_string(project.pages_dir().join("index.html"))
-        .expect("read page")
-        .contains("<p>updated</p>"));
-    assert!(!fs::read_to_string(project.pages_dir().join("index.html"))
-        .expect("read page")
-        .contains("<p>blocked</p>"));
+fn run_git(root: &Path, args: &[&str]) -> String {
+    let output = std::process::Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(args)
+        .output()
+        .expect("run git");
+    assert!(
+        output.status.success(),
+        "git {args:?} failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).expect("git output is utf-8")
 }
 
 #[test]
@@ -1528,14 +5029,15 @@ fn safe_page_editing_rejects_duplicate_title_before_writing() {
         ),
     );
 
-    let error = set_page_title(project.root(), Path::new("index"), "rust")
+    let error = set_page_title(project.root(), Path::new("index"), "rust", None)
         .expect_err("duplicate title should fail");
     assert!(error.to_string().contains("duplicate page label"));
     assert!(fs::read_to_string(project.pages_dir().join("index.html"))
         .expect("read index")
         .contains("<title>Home</title>"));
 
-    update_page_body(project.root(), Path::new("index"), "<p>safe body</p>").expect("update body");
+    update_page_body(project.root(), Path::new("index"), "<p>safe body</p>", None)
+        .expect("update body");
     assert!(fs::read_to_string(project.pages_dir().join("index.html"))
         .expect("read updated index")
         .contains("<p>safe body</p>"));
@@ -1578,6 +5080,7 @@ fn preflight_rename_page_reports_affected_state_without_writing() {
         PageRename {
             path: Some(PathBuf::from("folder/home")),
             title: Some("Home Base".to_string()),
+            expected_revision: None,
         },
     )
     .expect("preflight rename");
@@ -1653,6 +5156,7 @@ fn rename_page_moves_page_updates_title_manifest_and_generated_data() {
         PageRename {
             path: Some(PathBuf::from("folder/home")),
             title: Some("Home Base".to_string()),
+            expected_revision: None,
         },
     )
     .expect("rename page");
@@ -1754,6 +5258,7 @@ fn rename_page_rejects_label_collision_before_writing() {
         PageRename {
             path: Some(PathBuf::from("folder/rust")),
             title: Some("Rust".to_string()),
+            expected_revision: None,
         },
     )
     .expect_err("duplicate future labels should fail");
@@ -1915,7 +5420,7 @@ fn delete_page_removes_file_rebuilds_generated_data_and_reports_links() {
         ),
     );
 
-    let report = delete_page(project.root(), Path::new("rust")).expect("delete page");
+    let report = delete_page(project.root(), Path::new("rust"), None).expect("delete page");
 
     assert!(!project.pages_dir().join("rust.html").exists());
     assert!(report.events.iter().any(|event| {
@@ -1986,7 +5491,8 @@ fn delete_page_updates_manifest_default_when_needed() {
         ),
     );
 
-    let report = delete_page(project.root(), Path::new("index")).expect("delete default page");
+    let report =
+        delete_page(project.root(), Path::new("index"), None).expect("delete default page");
 
     assert!(!project.pages_dir().join("index.html").exists());
     assert_eq!(
@@ -2015,8 +5521,8 @@ fn delete_page_rejects_removing_only_page() {
         ),
     );
 
-    let error =
-        delete_page(project.root(), Path::new("index")).expect_err("only page delete should fail");
+    let error = delete_page(project.root(), Path::new("index"), None)
+        .expect_err("only page delete should fail");
 
     assert!(error.to_string().contains("cannot delete the only page"));
     assert!(project.pages_dir().join("index.html").is_file());
@@ -2121,9 +5627,22 @@ fn add_note_creates_notes_section_in_requested_page() {
         ),
     );
 
-    add_note(project.root(), Path::new("index"), "java", "my note text").expect("add note");
-    let error = add_note(project.root(), Path::new("index"), "java", "duplicate")
-        .expect_err("duplicate note should fail");
+    add_note(
+        project.root(),
+        Path::new("index"),
+        "java",
+        "my note text",
+        None,
+    )
+    .expect("add note");
+    let error = add_note(
+        project.root(),
+        Path::new("index"),
+        "java",
+        "duplicate",
+        None,
+    )
+    .expect_err("duplicate note should fail");
     assert_eq!(error.code, FractalErrorCode::AlreadyExists);
 
     let html = fs::read_to_string(project.pages_dir().join("index.html")).expect("read page");
@@ -2154,7 +5673,7 @@ fn patch_note_updates_existing_note() {
     .expect("insert note");
     project.write_page("index.html", page);
 
-    patch_note(project.root(), Path::new("index"), "java", "new text").expect("patch note");
+    patch_note(project.root(), Path::new("index"), "java", "new text", None).expect("patch note");
 
     let html = fs::read_to_string(project.pages_dir().join("index.html")).expect("read page");
     assert!(html.contains("<p>new text</p>"));
@@ -2176,7 +5695,7 @@ fn remove_note_keeps_empty_note_section() {
     .expect("insert note");
     project.write_page("index.html", page);
 
-    remove_note(project.root(), Path::new("index"), "java").expect("remove note");
+    remove_note(project.root(), Path::new("index"), "java", None).expect("remove note");
 
     let html = fs::read_to_string(project.pages_dir().join("index.html")).expect("read page");
     let document = PageDocument::parse(&html);
@@ -2234,8 +5753,13 @@ fn set_page_metadata_updates_meta_tags_and_rebuilds_index() {
         ),
     );
 
-    let summary_report = set_page_summary(project.root(), Path::new("index"), "Local graph notes")
-        .expect("set summary");
+    let summary_report = set_page_summary(
+        project.root(),
+        Path::new("index"),
+        "Local graph notes",
+        None,
+    )
+    .expect("set summary");
     assert!(summary_report.events.iter().any(|event| matches!(
         event,
         OperationEvent::PageMetadataUpdated { name, content, .. }
@@ -2246,6 +5770,7 @@ fn set_page_metadata_updates_meta_tags_and_rebuilds_index() {
         project.root(),
         Path::new("index"),
         ["rust", "graphs, parsing", "rust"],
+        None,
     )
     .expect("set tags");
 
@@ -2286,10 +5811,11 @@ fn reset_page_metadata_restores_generated_defaults() {
             "../.fractal/style.css".to_string(),
         ),
     );
-    set_page_summary(project.root(), Path::new("index"), "Custom summary").expect("set summary");
-    set_page_tags(project.root(), Path::new("index"), ["custom"]).expect("set tags");
+    set_page_summary(project.root(), Path::new("index"), "Custom summary", None)
+        .expect("set summary");
+    set_page_tags(project.root(), Path::new("index"), ["custom"], None).expect("set tags");
 
-    reset_page_metadata(project.root(), Path::new("index")).expect("reset metadata");
+    reset_page_metadata(project.root(), Path::new("index"), None).expect("reset metadata");
 
     let metadata = page_metadata(project.root(), Path::new("index")).expect("read metadata");
     assert_eq!(metadata.summary, None);
@@ -2338,9 +5864,23 @@ fn note_mutation_handles_ordinary_html_formatting() {
 "#,
     );
 
-    add_note(project.root(), Path::new("index"), "Java", "java & <note>").expect("add note");
-    patch_note(project.root(), Path::new("index"), "rust", "patched <rust>").expect("patch note");
-    remove_note(project.root(), Path::new("index"), "Java").expect("remove note");
+    add_note(
+        project.root(),
+        Path::new("index"),
+        "Java",
+        "java & <note>",
+        None,
+    )
+    .expect("add note");
+    patch_note(
+        project.root(),
+        Path::new("index"),
+        "rust",
+        "patched <rust>",
+        None,
+    )
+    .expect("patch note");
+    remove_note(project.root(), Path::new("index"), "Java", None).expect("remove note");
 
     let html = fs::read_to_string(project.pages_dir().join("index.html")).expect("read page");
     let document = PageDocument::parse(&html);
@@ -2423,14 +5963,23 @@ fn sync_rebuilds_index_and_links_notes_before_project_pages() {
             FileEntry {
                 path: "asset.txt".to_string(),
                 kind: "asset".to_string(),
+                content_hash: Some(
+                    "sha256:d59386e0ae435e292fbe0ebcdb954b75ed5fb3922091277cb19f798fc5d50718"
+                        .to_string()
+                ),
+                mime_type: Some("text/plain".to_string()),
             },
             FileEntry {
                 path: "index.html".to_string(),
                 kind: "page".to_string(),
+                content_hash: None,
+                mime_type: None,
             },
             FileEntry {
                 path: "rust.html".to_string(),
                 kind: "page".to_string(),
+                content_hash: None,
+                mime_type: None,
             },
         ]
     );
@@ -2656,6 +6205,48 @@ fn sync_links_ordinary_html_without_touching_manual_or_code_links() {
     assert!(html.contains("<code>Rust</code>"));
 }
 
+#[test]
+fn sync_links_labels_across_inline_formatting_boundaries() {
+    let project = TestProject::new("sync-inline-formatting");
+    project.write_page(
+        "index.html",
+        render_page_document(
+            "Home",
+            "<p>Read Rust <strong>Book</strong> and <em>Rust</em> Book, not <strong>Big Rust</strong> Book.</p>\n      <p>Rust<br>Book and <mark>Rust Book</mark></p>",
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        ),
+    );
+    project.write_page(
+        "rust-book.html",
+        render_page_document(
+            "Rust Book",
+            "<p>body</p>",
+            Theme::Dark,
+            "../.fractal/style.css".to_string(),
+        ),
+    );
+
+    let report = sync_project(project.root()).expect("sync project");
+
+    let html = fs::read_to_string(project.pages_dir().join("index.html")).expect("read index page");
+    assert!(html.contains(
+        "Read <a href=\"rust-book.html\" data-fractal-link=\"page\">Rust <strong>Book</strong></a>"
+    ));
+    assert!(html
+        .contains("<a href=\"rust-book.html\" data-fractal-link=\"page\"><em>Rust</em> Book</a>"));
+    assert!(html.contains("<strong>Big Rust</strong> Book."));
+    assert!(html.contains("Rust<br>Book"));
+    assert!(html.contains(
+        "<mark><a href=\"rust-book.html\" data-fractal-link=\"page\">Rust Book</a></mark>"
+    ));
+    assert!(report.events.contains(&OperationEvent::PageLinksRewritten {
+        page: PathBuf::from("pages/index.html"),
+        count: 3,
+    }));
+    validate_project(project.root()).expect("synced inline links validate");
+}
+
 #[test]
 fn sync_prefers_page_local_notes_over_same_named_pages_case_insensitively() {
     let project = TestProject::new("sync-note-priority");
@@ -2864,6 +6455,7 @@ fn graph_focused_reports_show_basic_page_views() {
             id: "note-rust".to_string(),
             label: "Rust".to_string(),
             href: "#note-rust".to_string(),
+            backlinks: vec![],
         }]
     );
 
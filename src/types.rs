@@ -1,4 +1,5 @@
-use crate::FractalError;
+use crate::project::constants::COMMAND_RESULT_SCHEMA;
+use crate::{FractalError, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
@@ -137,12 +138,41 @@ impl OperationSummary {
                     push_unique_path(&mut summary.pages_changed, from);
                     push_unique_path(&mut summary.pages_changed, to);
                 }
+                OperationEvent::AssetAdded { path, .. } => {
+                    mark_user_content_changed(&mut summary);
+                    mark_source_file_changed(&mut summary, path);
+                    push_unique_path(&mut summary.created_paths, path);
+                    push_unique_path(&mut summary.changed_paths, path);
+                }
+                OperationEvent::AssetMoved { from, to } => {
+                    mark_user_content_changed(&mut summary);
+                    mark_source_file_changed(&mut summary, from);
+                    mark_source_file_changed(&mut summary, to);
+                    push_unique_move(&mut summary.moved_paths, from, to);
+                    push_unique_path(&mut summary.changed_paths, to);
+                }
+                OperationEvent::AssetDeleted { path } | OperationEvent::FileRemoved { path } => {
+                    mark_user_content_changed(&mut summary);
+                    mark_source_file_changed(&mut summary, path);
+                    push_unique_path(&mut summary.deleted_paths, path);
+                }
+                OperationEvent::FileRestored { path } => {
+                    mark_user_content_changed(&mut summary);
+                    mark_source_file_changed(&mut summary, path);
+                    push_unique_path(&mut summary.changed_paths, path);
+                }
                 OperationEvent::NoteAdded { page, .. }
                 | OperationEvent::NoteRemoved { page, .. }
                 | OperationEvent::NoteUpdated { page, .. }
+                | OperationEvent::NoteRenamed { page, .. }
                 | OperationEvent::PageContentUpdated { page }
                 | OperationEvent::PageTitleUpdated { page, .. }
                 | OperationEvent::PageMetadataUpdated { page, .. }
+                | OperationEvent::PageHtmlSanitized { page, .. }
+                | OperationEvent::BlockInserted { page, .. }
+                | OperationEvent::BlockReplaced { page, .. }
+                | OperationEvent::BlockMoved { page, .. }
+                | OperationEvent::BlockDeleted { page, .. }
                 | OperationEvent::PageSourceUpdated { page } => {
                     mark_user_page_changed(&mut summary, page);
                     push_unique_path(&mut summary.changed_paths, page);
@@ -153,6 +183,11 @@ impl OperationSummary {
                     push_unique_path(&mut summary.pages_changed, page);
                     summary.links_rewritten_count += count;
                 }
+                OperationEvent::EmbedsRefreshed { page, .. } => {
+                    mark_source_file_changed(&mut summary, page);
+                    push_unique_path(&mut summary.changed_paths, page);
+                    push_unique_path(&mut summary.pages_changed, page);
+                }
                 OperationEvent::ManifestUpdated { path } => {
                     mark_source_file_changed(&mut summary, path);
                     summary.manifest_changed = true;
@@ -166,6 +201,11 @@ impl OperationSummary {
                     }
                     push_unique_path(&mut summary.repaired_paths, path);
                 }
+                OperationEvent::RepairSuggestionApplied { page, .. } => {
+                    mark_user_page_changed(&mut summary, page);
+                    push_unique_path(&mut summary.changed_paths, page);
+                    push_unique_path(&mut summary.repaired_paths, page);
+                }
                 OperationEvent::GeneratedIndexBuilt { path }
                 | OperationEvent::GeneratedGraphBuilt { path } => {
                     summary.generated_changed = true;
@@ -176,10 +216,23 @@ impl OperationSummary {
                 OperationEvent::ProjectValidated { .. } => {
                     summary.validation_performed = true;
                 }
-                OperationEvent::PageLinkImpact { .. } | OperationEvent::SyncCompleted { .. } => {}
+                OperationEvent::PageLinkImpact { .. }
+                | OperationEvent::SyncCompleted { .. }
+                | OperationEvent::ProjectUnlocked { .. }
+                | OperationEvent::HistoryEnabled { .. }
+                | OperationEvent::HistoryDisabled { .. }
+                | OperationEvent::HistoryUndone { .. }
+                | OperationEvent::GitCommitted { .. }
+                | OperationEvent::FilesChanged { .. } => {}
                 OperationEvent::Warning { message } => {
                     push_unique_string(&mut summary.warnings, message);
                 }
+                OperationEvent::GitCommitFailed { message } => {
+                    push_unique_string(
+                        &mut summary.warnings,
+                        &format!("git auto-commit failed: {message}"),
+                    );
+                }
             }
         }
 
@@ -212,6 +265,86 @@ pub struct PathMove {
     pub to: PathBuf,
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MutationLockOwner {
+    pub pid: Option<u32>,
+    pub hostname: Option<String>,
+    pub created_at: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MutationPreview {
+    pub report: OperationReport,
+    pub summary: OperationSummary,
+    pub diffs: Vec<FileDiff>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FileDiff {
+    pub path: PathBuf,
+    pub change: FileChange,
+    #[serde(default)]
+    pub from: Option<PathBuf>,
+    pub diff: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FileChange {
+    Created,
+    Modified,
+    Deleted,
+    Moved,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct HistoryEntry {
+    pub id: u64,
+    pub created_at: u64,
+    #[serde(default)]
+    pub undoes: Option<u64>,
+    pub events: Vec<OperationEvent>,
+    pub files: Vec<HistoryFileChange>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct HistoryFileChange {
+    pub path: PathBuf,
+    pub change: FileChange,
+    #[serde(default)]
+    pub from: Option<PathBuf>,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GitCommit {
+    pub id: String,
+    pub author: String,
+    pub timestamp: u64,
+    pub subject: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PageBlame {
+    pub page: String,
+    pub entries: Vec<BlameEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BlameEntry {
+    pub region: BlameRegion,
+    pub key: String,
+    pub commit: Option<GitCommit>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BlameRegion {
+    Block,
+    Note,
+}
+
 fn push_unique_path(paths: &mut Vec<PathBuf>, path: &PathBuf) {
     if !paths.contains(path) {
         paths.push(path.clone());
@@ -266,6 +399,17 @@ pub enum OperationEvent {
         from: PathBuf,
         to: PathBuf,
     },
+    AssetAdded {
+        source: PathBuf,
+        path: PathBuf,
+    },
+    AssetMoved {
+        from: PathBuf,
+        to: PathBuf,
+    },
+    AssetDeleted {
+        path: PathBuf,
+    },
     NoteAdded {
         page: PathBuf,
         note_id: String,
@@ -278,6 +422,11 @@ pub enum OperationEvent {
         page: PathBuf,
         note_id: String,
     },
+    NoteRenamed {
+        page: PathBuf,
+        note_id: String,
+        new_note_id: String,
+    },
     PageContentUpdated {
         page: PathBuf,
     },
@@ -294,6 +443,32 @@ pub enum OperationEvent {
         page: PathBuf,
         count: usize,
     },
+    EmbedsRefreshed {
+        page: PathBuf,
+        count: usize,
+    },
+    PageHtmlSanitized {
+        page: PathBuf,
+        change: String,
+        count: usize,
+    },
+    BlockInserted {
+        page: PathBuf,
+        block_id: String,
+    },
+    BlockReplaced {
+        page: PathBuf,
+        block_id: String,
+        replacement_id: String,
+    },
+    BlockMoved {
+        page: PathBuf,
+        block_id: String,
+    },
+    BlockDeleted {
+        page: PathBuf,
+        block_id: String,
+    },
     PageSourceUpdated {
         page: PathBuf,
     },
@@ -309,6 +484,11 @@ pub enum OperationEvent {
         path: PathBuf,
         applied: bool,
     },
+    RepairSuggestionApplied {
+        page: PathBuf,
+        id: String,
+        action: String,
+    },
     GeneratedIndexBuilt {
         path: PathBuf,
     },
@@ -322,6 +502,36 @@ pub enum OperationEvent {
         project_name: String,
         manifest_path: PathBuf,
     },
+    ProjectUnlocked {
+        path: PathBuf,
+        owner: MutationLockOwner,
+        stale: bool,
+    },
+    HistoryEnabled {
+        path: PathBuf,
+    },
+    HistoryDisabled {
+        path: PathBuf,
+    },
+    HistoryUndone {
+        id: u64,
+    },
+    FileRestored {
+        path: PathBuf,
+    },
+    FileRemoved {
+        path: PathBuf,
+    },
+    GitCommitted {
+        commit: String,
+        message: String,
+    },
+    GitCommitFailed {
+        message: String,
+    },
+    FilesChanged {
+        paths: Vec<PathBuf>,
+    },
     Warning {
         message: String,
     },
@@ -335,18 +545,32 @@ impl OperationEvent {
             | OperationEvent::PageCreated { path }
             | OperationEvent::PageDeleted { path }
             | OperationEvent::DirectoryDeleted { path }
+            | OperationEvent::AssetDeleted { path }
             | OperationEvent::NoteAdded { page: path, .. }
             | OperationEvent::NoteRemoved { page: path, .. }
             | OperationEvent::NoteUpdated { page: path, .. }
+            | OperationEvent::NoteRenamed { page: path, .. }
             | OperationEvent::PageContentUpdated { page: path }
             | OperationEvent::PageTitleUpdated { page: path, .. }
             | OperationEvent::PageMetadataUpdated { page: path, .. }
             | OperationEvent::PageLinksRewritten { page: path, .. }
+            | OperationEvent::EmbedsRefreshed { page: path, .. }
+            | OperationEvent::PageHtmlSanitized { page: path, .. }
+            | OperationEvent::BlockInserted { page: path, .. }
+            | OperationEvent::BlockReplaced { page: path, .. }
+            | OperationEvent::BlockMoved { page: path, .. }
+            | OperationEvent::BlockDeleted { page: path, .. }
             | OperationEvent::PageSourceUpdated { page: path }
             | OperationEvent::ManifestUpdated { path }
             | OperationEvent::ProjectRepaired { path, .. }
+            | OperationEvent::RepairSuggestionApplied { page: path, .. }
             | OperationEvent::GeneratedIndexBuilt { path }
             | OperationEvent::GeneratedGraphBuilt { path }
+            | OperationEvent::ProjectUnlocked { path, .. }
+            | OperationEvent::HistoryEnabled { path }
+            | OperationEvent::HistoryDisabled { path }
+            | OperationEvent::FileRestored { path }
+            | OperationEvent::FileRemoved { path }
             | OperationEvent::ProjectValidated {
                 manifest_path: path,
                 ..
@@ -362,12 +586,24 @@ impl OperationEvent {
                 relativize_path(page, root);
                 relativize_path(output, root);
             }
-            OperationEvent::PageMoved { from, to } => {
+            OperationEvent::AssetAdded { source, path } => {
+                relativize_path(source, root);
+                relativize_path(path, root);
+            }
+            OperationEvent::PageMoved { from, to } | OperationEvent::AssetMoved { from, to } => {
                 relativize_path(from, root);
                 relativize_path(to, root);
             }
+            OperationEvent::FilesChanged { paths } => {
+                for path in paths {
+                    relativize_path(path, root);
+                }
+            }
             OperationEvent::PageLinkImpact { .. }
             | OperationEvent::SyncCompleted { .. }
+            | OperationEvent::HistoryUndone { .. }
+            | OperationEvent::GitCommitted { .. }
+            | OperationEvent::GitCommitFailed { .. }
             | OperationEvent::Warning { .. } => {}
         }
     }
@@ -411,6 +647,29 @@ pub struct ProjectManifest {
     pub default_page: String,
     #[serde(default)]
     pub theme: Theme,
+    #[serde(default)]
+    pub git_auto_commit: bool,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub lock_timeout_ms: u64,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub lint: BTreeMap<String, LintRuleConfig>,
+}
+
+fn is_zero(value: &u64) -> bool {
+    *value == 0
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct LintRuleConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<DiagnosticSeverity>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_words: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_distance: Option<usize>,
 }
 
 #[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
@@ -441,6 +700,18 @@ pub struct ProjectIndex {
 pub struct FileEntry {
     pub path: String,
     pub kind: String,
+    #[serde(default)]
+    pub content_hash: Option<String>,
+    #[serde(default)]
+    pub mime_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AssetEntry {
+    pub path: String,
+    pub mime_type: String,
+    pub content_hash: String,
+    pub referenced_by: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -450,6 +721,12 @@ pub struct PageEntry {
     pub meta: BTreeMap<String, String>,
     pub notes: Vec<NoteEntry>,
     pub links: Vec<LinkEntry>,
+    #[serde(default)]
+    pub images: Vec<ImageEntry>,
+    #[serde(default)]
+    pub headings: Vec<HeadingEntry>,
+    #[serde(default)]
+    pub embeds: Vec<EmbedEntry>,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
@@ -458,6 +735,18 @@ pub struct NoteEntry {
     pub label: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct HeadingEntry {
+    pub level: u8,
+    pub text: String,
+    pub anchor: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EmbedEntry {
+    pub target: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct LinkEntry {
     pub href: String,
@@ -465,10 +754,42 @@ pub struct LinkEntry {
     pub scope: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ImageEntry {
+    pub src: String,
+    pub alt: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct PageSource {
     pub path: String,
     pub html: String,
+    pub revision: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PageMerge {
+    pub html: String,
+    pub conflicts: Vec<MergeConflict>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MergeConflict {
+    pub region: MergeRegion,
+    pub key: String,
+    pub base: Option<String>,
+    pub ours: Option<String>,
+    pub theirs: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeRegion {
+    Title,
+    Metadata,
+    Block,
+    ListItem,
+    Note,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -515,6 +836,51 @@ pub struct EditorLinkDetail {
     pub scope: String,
     pub target_page: Option<String>,
     pub target_note: Option<String>,
+    pub target_section: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PageBlock {
+    pub id: String,
+    pub kind: String,
+    pub html: String,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PageSection {
+    pub page: String,
+    pub heading: HeadingEntry,
+    pub html: String,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockPosition {
+    Start,
+    #[default]
+    End,
+    Before(String),
+    After(String),
+}
+
+impl BlockPosition {
+    pub(crate) fn from_flags(
+        before: Option<String>,
+        after: Option<String>,
+        start: bool,
+    ) -> Result<Self> {
+        match (before, after, start) {
+            (None, None, false) => Ok(Self::End),
+            (None, None, true) => Ok(Self::Start),
+            (Some(block), None, false) => Ok(Self::Before(block)),
+            (None, Some(block), false) => Ok(Self::After(block)),
+            _ => Err(FractalError::invalid_input(
+                "choose at most one of `before`, `after`, and `start`",
+            )),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
@@ -523,12 +889,16 @@ pub struct EditorPageUpdate {
     pub body_html: Option<String>,
     pub summary: Option<String>,
     pub tags: Option<Vec<String>>,
+    #[serde(default)]
+    pub expected_revision: Option<String>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
 pub struct PageRename {
     pub path: Option<PathBuf>,
     pub title: Option<String>,
+    #[serde(default)]
+    pub expected_revision: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -555,6 +925,53 @@ pub struct PageRenamePreflight {
     pub outlinks: Vec<GraphPageLink>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct NotePromotePreflight {
+    pub host_page: String,
+    pub host_path: PathBuf,
+    pub note_id: String,
+    pub destination_page: String,
+    pub destination_path: PathBuf,
+    pub title: String,
+    pub note_links: usize,
+    pub cited_by: Vec<String>,
+    pub embedded_by: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PageDemotePreflight {
+    pub page: String,
+    pub path: PathBuf,
+    pub host_page: String,
+    pub host_path: PathBuf,
+    pub note_id: String,
+    pub deleting_default: bool,
+    pub backlinks: Vec<GraphPageLink>,
+    pub outlinks: Vec<GraphPageLink>,
+    pub embedded_by: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportFormat {
+    #[default]
+    Markdown,
+    Notion,
+    Logseq,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PageImportPreflight {
+    pub source: PathBuf,
+    pub destination_page: String,
+    pub destination_path: PathBuf,
+    pub title: String,
+    pub summary: Option<String>,
+    pub tags: Vec<String>,
+    pub note_ids: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct ProjectSummary {
     pub root: PathBuf,
@@ -568,6 +985,7 @@ pub struct ProjectSummary {
     pub file_count: usize,
     pub page_count: usize,
     pub asset_count: usize,
+    pub unreferenced_asset_count: usize,
     pub note_count: usize,
     pub link_count: usize,
     pub graph_node_count: usize,
@@ -622,6 +1040,8 @@ pub struct GraphNoteLink {
     pub id: String,
     pub label: String,
     pub href: String,
+    #[serde(default)]
+    pub backlinks: Vec<GraphPageLink>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
@@ -637,6 +1057,20 @@ pub struct GraphNeighborPage {
     pub distance: usize,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ExternalLinkEntry {
+    pub url: String,
+    pub scheme: String,
+    pub domain: String,
+    pub sources: Vec<ExternalLinkSource>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ExternalLinkSource {
+    pub page: String,
+    pub text: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct SearchResult {
     pub path: String,
@@ -649,3 +1083,116 @@ pub struct SearchMatch {
     pub field: String,
     pub text: String,
 }
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+    Info,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SourceSpan {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub rule: String,
+    pub severity: DiagnosticSeverity,
+    pub page: Option<String>,
+    pub message: String,
+    pub span: Option<SourceSpan>,
+    pub node: Option<String>,
+    pub fixable: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RepairSuggestion {
+    pub id: String,
+    pub diagnostic: Diagnostic,
+    pub action: String,
+    pub diff: FileDiff,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ValidationReport {
+    pub pages_checked: usize,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl ValidationReport {
+    pub fn error_count(&self) -> usize {
+        self.count(DiagnosticSeverity::Error)
+    }
+
+    pub fn warning_count(&self) -> usize {
+        self.count(DiagnosticSeverity::Warning)
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.error_count() == 0
+    }
+
+    fn count(&self, severity: DiagnosticSeverity) -> usize {
+        self.diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == severity)
+            .count()
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct CommandResult<'a, T: Serialize> {
+    pub(crate) ok: bool,
+    pub(crate) schema: &'static str,
+    pub(crate) command: &'a str,
+    pub(crate) project: ProjectRef,
+    pub(crate) data: T,
+}
+
+impl<'a, T: Serialize> CommandResult<'a, T> {
+    pub(crate) fn new(command: &'a str, root: &Path, data: T) -> Self {
+        Self {
+            ok: true,
+            schema: COMMAND_RESULT_SCHEMA,
+            command,
+            project: ProjectRef {
+                root: root.to_path_buf(),
+            },
+            data,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct ProjectRef {
+    pub(crate) root: PathBuf,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct ReportData {
+    pub(crate) report: OperationReport,
+    pub(crate) summary: OperationSummary,
+}
+
+impl ReportData {
+    pub(crate) fn new(report: OperationReport) -> Self {
+        Self {
+            summary: report.summary(),
+            report,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct ContextPageData {
+    pub(crate) page: EditorPageDetail,
+    pub(crate) neighbors: Vec<GraphNeighborPage>,
+    pub(crate) section: Option<PageSection>,
+    pub(crate) budget: Option<usize>,
+}
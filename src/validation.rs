@@ -1,60 +1,144 @@
+use crate::document::embeds::is_embed;
+use crate::document::html::is_inline_formatting_element;
+use crate::document::metadata::VERSION_META;
 use crate::document::notes::is_valid_note_id;
 use crate::document::render::{
     default_stylesheet, render_page_document, required_meta_tags, stylesheet_href,
 };
 use crate::document::PageDocument;
 use crate::graph::links::{
-    is_external_href, link_label_key, normalize_link_label, page_link_labels,
-    page_link_text_matches, resolve_page_href,
+    is_allowed_external_href, is_external_href, link_label_key, normalize_link_label,
+    page_link_labels, page_link_text_matches, resolve_asset_src, resolve_page_href,
+    resolve_section_href,
 };
 use crate::index::ensure_page_labels_available_for;
-use crate::ops::mutation::MutationPlan;
-use crate::project::constants::{INDEX_PAGE, MANIFEST_FILE, PAGES_DIR, STYLE_FILE, WORKSPACE_DIR};
-use crate::project::paths::{collect_page_paths, is_html_path, load_manifest};
-use crate::types::{OperationEvent, OperationReport, Theme};
+use crate::io::parallel::map_pages;
+use crate::ops::mutation::{recover_interrupted_mutation, MutationPlan};
+use crate::project::constants::{
+    DEFAULT_VERSION, INDEX_PAGE, LEGACY_VERSION, MANIFEST_FILE, PAGES_DIR, STYLE_FILE,
+    WORKSPACE_DIR,
+};
+use crate::project::paths::{collect_page_paths, file_kind, is_html_path, load_manifest};
+use crate::types::{
+    Diagnostic, DiagnosticSeverity, HeadingEntry, OperationEvent, OperationReport, SourceSpan,
+    Theme, ValidationReport,
+};
 use crate::{FractalError, Result};
 use brik::NodeRef;
 use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
 use std::path::Path;
 
 pub fn validate_project(root: impl AsRef<Path>) -> Result<OperationReport> {
     let root = root.as_ref();
-    let mut report = OperationReport::new();
-
     let manifest_path = root.join(MANIFEST_FILE);
+    let checked = check_project(root, false)?;
+
+    let mut report = warn_duplicate_page_labels(&checked.known_page_titles);
+    report.push(OperationEvent::ProjectValidated {
+        project_name: checked.project_name,
+        manifest_path,
+    });
+    Ok(report.relative_to(root))
+}
+
+pub fn validation_report(root: impl AsRef<Path>) -> Result<ValidationReport> {
+    let root = root.as_ref();
+    let mut checked = check_project(root, true)?;
+
+    for (page, message) in duplicate_page_labels(&checked.known_page_titles) {
+        checked.diagnostics.push(Diagnostic {
+            rule: PROJECT_DUPLICATE_LABEL.id.to_string(),
+            severity: DiagnosticSeverity::Warning,
+            page: Some(page),
+            message,
+            span: None,
+            node: None,
+            fixable: PROJECT_DUPLICATE_LABEL.fixable,
+        });
+    }
+
+    Ok(ValidationReport {
+        pages_checked: checked.pages_checked,
+        diagnostics: checked.diagnostics,
+    })
+}
+
+struct ProjectCheck {
+    project_name: String,
+    known_page_titles: BTreeMap<String, String>,
+    pages_checked: usize,
+    diagnostics: Vec<Diagnostic>,
+}
+
+fn check_project(root: &Path, collect: bool) -> Result<ProjectCheck> {
     let workspace_dir = root.join(WORKSPACE_DIR);
     let pages_dir = root.join(PAGES_DIR);
     let manifest = load_manifest(root)?;
+    recover_interrupted_mutation(root)?;
+
+    let mut checked = ProjectCheck {
+        project_name: manifest.project_name,
+        known_page_titles: BTreeMap::new(),
+        pages_checked: 0,
+        diagnostics: Vec::new(),
+    };
+    let shown = |path: &Path| {
+        if collect {
+            path.strip_prefix(root).unwrap_or(path).to_path_buf()
+        } else {
+            path.to_path_buf()
+        }
+    };
+    let mut fail = |rule: Rule, message: String| {
+        if !collect {
+            return Err(FractalError::invalid_project(message));
+        }
+        checked.diagnostics.push(Diagnostic {
+            rule: rule.id.to_string(),
+            severity: DiagnosticSeverity::Error,
+            page: None,
+            message,
+            span: None,
+            node: None,
+            fixable: rule.fixable,
+        });
+        Ok(())
+    };
 
     if !workspace_dir.is_dir() {
-        return Err(FractalError::invalid_project(format!(
-            "missing workspace directory: {}",
-            workspace_dir.display()
-        )));
+        fail(
+            PROJECT_WORKSPACE,
+            format!(
+                "missing workspace directory: {}",
+                shown(&workspace_dir).display()
+            ),
+        )?;
     }
 
     let stylesheet = workspace_dir.join(STYLE_FILE);
     if !stylesheet.is_file() {
-        return Err(FractalError::invalid_project(format!(
-            "missing stylesheet: {}",
-            stylesheet.display()
-        )));
+        fail(
+            PROJECT_STYLESHEET,
+            format!("missing stylesheet: {}", shown(&stylesheet).display()),
+        )?;
     }
 
     if !pages_dir.is_dir() {
-        return Err(FractalError::invalid_project(format!(
-            "missing pages directory: {}",
-            pages_dir.display()
-        )));
+        fail(
+            PROJECT_PAGES_DIR,
+            format!("missing pages directory: {}", shown(&pages_dir).display()),
+        )?;
+        return Ok(checked);
     }
 
     if !manifest.default_page.trim().is_empty() {
         let default_page = root.join(&manifest.default_page);
         if !default_page.is_file() {
-            return Err(FractalError::invalid_project(format!(
-                "missing default page: {}",
-                default_page.display()
-            )));
+            fail(
+                PROJECT_DEFAULT_PAGE,
+                format!("missing default page: {}", shown(&default_page).display()),
+            )?;
         }
     }
 
@@ -62,30 +146,39 @@ pub fn validate_project(root: impl AsRef<Path>) -> Result<OperationReport> {
     collect_page_paths(&pages_dir, &pages_dir, &mut page_paths)?;
     page_paths.sort();
     let known_page_paths = known_html_page_paths(&page_paths);
+    let known_asset_paths = known_asset_paths(&page_paths);
     let known_page_titles = known_page_titles(&pages_dir, &page_paths)?;
 
-    for page_path in &page_paths {
-        if !is_html_path(page_path) {
-            continue;
-        }
-
-        let page = pages_dir.join(page_path);
+    let html_page_paths = page_paths
+        .iter()
+        .filter(|path| is_html_path(path))
+        .collect::<Vec<_>>();
+    let page_diagnostics = map_pages(&html_page_paths, |page_path| -> Result<Vec<Diagnostic>> {
+        let page = if collect {
+            Path::new(PAGES_DIR).join(page_path)
+        } else {
+            pages_dir.join(page_path)
+        };
+        let source = fs::read_to_string(pages_dir.join(page_path))?;
+        let document = PageDocument::parse(&source);
+        let mut check = PageCheck::new(&page, page_path, &source, &document, collect);
         validate_project_page(
-            &page,
-            page_path,
+            &mut check,
             manifest.theme,
+            &pages_dir,
             &known_page_paths,
+            &known_asset_paths,
             &known_page_titles,
         )?;
+        Ok(check.diagnostics)
+    });
+    for diagnostics in page_diagnostics {
+        checked.diagnostics.extend(diagnostics?);
+        checked.pages_checked += 1;
     }
 
-    report.extend(warn_duplicate_page_labels(&known_page_titles));
-
-    report.push(OperationEvent::ProjectValidated {
-        project_name: manifest.project_name,
-        manifest_path,
-    });
-    Ok(report.relative_to(root))
+    checked.known_page_titles = known_page_titles;
+    Ok(checked)
 }
 
 pub fn repair_project(root: impl AsRef<Path>) -> Result<OperationReport> {
@@ -119,13 +212,13 @@ fn fix_project(root: &Path, mode: RepairMode) -> Result<OperationReport> {
     let mut report = OperationReport::new();
 
     if mode.writes() {
-        let mut setup_plan = MutationPlan::new();
+        let mut setup_plan = MutationPlan::new(root);
         setup_plan.ensure_dir(workspace_dir.clone());
         setup_plan.ensure_dir(pages_dir.clone());
-        report.extend(setup_plan.apply(root)?);
+        report.extend(setup_plan.apply_steps()?);
     }
 
-    let mut plan = MutationPlan::new();
+    let mut plan = MutationPlan::new(root);
 
     let stylesheet = workspace_dir.join(STYLE_FILE);
     if !stylesheet.is_file() {
@@ -188,7 +281,13 @@ fn fix_project(root: &Path, mode: RepairMode) -> Result<OperationReport> {
         }
 
         let page = pages_dir.join(&page_path);
-        if let Some(html) = fix_page(&page, &page_path, manifest.theme, &known_page_titles)? {
+        if let Some(html) = fix_page(
+            &page,
+            &pages_dir,
+            &page_path,
+            manifest.theme,
+            &known_page_titles,
+        )? {
             let event = OperationEvent::ProjectRepaired {
                 path: page.clone(),
                 applied: mode.writes(),
@@ -203,7 +302,7 @@ fn fix_project(root: &Path, mode: RepairMode) -> Result<OperationReport> {
     }
 
     if mode.writes() {
-        report.extend(plan.apply(root)?);
+        report.extend(plan.apply_steps()?);
     }
 
     Ok(report.relative_to(root))
@@ -213,8 +312,9 @@ fn fix_project(root: &Path, mode: RepairMode) -> Result<OperationReport> {
 pub(crate) fn validate_page_metadata(page: &Path) -> Result<()> {
     let html = std::fs::read_to_string(page)?;
     let document = PageDocument::parse(&html);
-    validate_page_structure(page, "page.html", Theme::Dark, &document)?;
-    validate_note_ids(page, &document)?;
+    let mut check = PageCheck::new(page, "page.html", &html, &document, false);
+    validate_page_structure(&mut check, Theme::Dark)?;
+    validate_note_ids(&mut check)?;
     Ok(())
 }
 
@@ -227,19 +327,23 @@ pub(crate) fn validate_page_html_for_project(
     let pages_dir = root.join(PAGES_DIR);
     let page_paths = page_paths_for_candidate(root, page_path)?;
     let known_page_paths = known_html_page_paths(&page_paths);
+    let known_asset_paths = known_asset_paths(&page_paths);
     let document = PageDocument::parse(html);
     let display_path = pages_dir.join(page_path);
     let known_page_titles = known_page_titles_for_candidate(root, page_path, &document)?;
 
-    validate_page_structure(&display_path, page_path, manifest.theme, &document)?;
-    validate_note_ids(&display_path, &document)?;
+    let mut check = PageCheck::new(&display_path, page_path, html, &document, false);
+    validate_page_structure(&mut check, manifest.theme)?;
+    validate_note_ids(&mut check)?;
+    validate_heading_anchors(&mut check)?;
+    validate_image_assets(&mut check, &known_asset_paths)?;
     validate_generated_links(
-        &display_path,
-        page_path,
-        &document,
+        &mut check,
+        &pages_dir,
         &known_page_paths,
         &known_page_titles,
     )?;
+    validate_embed_targets(&mut check, &pages_dir, &known_page_paths)?;
 
     let title = document.title().ok_or_else(|| {
         FractalError::invalid_project(format!("missing page title in {}", display_path.display()))
@@ -249,8 +353,142 @@ pub(crate) fn validate_page_html_for_project(
     Ok(())
 }
 
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Rule {
+    pub(crate) id: &'static str,
+    fixable: bool,
+}
+
+const fn rule(id: &'static str, fixable: bool) -> Rule {
+    Rule { id, fixable }
+}
+
+const PROJECT_WORKSPACE: Rule = rule("FR-PROJ-001", true);
+const PROJECT_STYLESHEET: Rule = rule("FR-PROJ-002", true);
+const PROJECT_PAGES_DIR: Rule = rule("FR-PROJ-003", true);
+const PROJECT_DEFAULT_PAGE: Rule = rule("FR-PROJ-004", true);
+const PROJECT_DUPLICATE_LABEL: Rule = rule("FR-PROJ-005", false);
+const DOC_HEAD: Rule = rule("FR-DOC-001", false);
+const DOC_BODY: Rule = rule("FR-DOC-002", false);
+const DOC_MAIN: Rule = rule("FR-DOC-003", false);
+const DOC_NOTES_SECTION: Rule = rule("FR-DOC-004", true);
+const HEAD_CHILD: Rule = rule("FR-HEAD-001", false);
+const HEAD_MISSING_META: Rule = rule("FR-HEAD-002", true);
+const HEAD_DUPLICATE_META: Rule = rule("FR-HEAD-003", false);
+const HEAD_UNSUPPORTED_META: Rule = rule("FR-HEAD-004", false);
+const HEAD_META_CONTENT: Rule = rule("FR-HEAD-005", false);
+const HEAD_VERSION: Rule = rule("FR-HEAD-006", false);
+const HEAD_STYLESHEET: Rule = rule("FR-HEAD-007", true);
+const BODY_CHILD: Rule = rule("FR-BODY-001", false);
+const BODY_MAIN_COUNT: Rule = rule("FR-BODY-002", false);
+const BODY_NOTES_COUNT: Rule = rule("FR-BODY-003", false);
+const BODY_THEME: Rule = rule("FR-BODY-004", true);
+const TITLE_MISSING: Rule = rule("FR-TITLE-001", true);
+const TITLE_DUPLICATE: Rule = rule("FR-TITLE-002", false);
+const TITLE_EMPTY: Rule = rule("FR-TITLE-003", false);
+const TITLE_MISSING_HEADING: Rule = rule("FR-TITLE-004", true);
+const TITLE_HEADING_POSITION: Rule = rule("FR-TITLE-005", false);
+const TITLE_HEADING_COUNT: Rule = rule("FR-TITLE-006", false);
+const TITLE_EMPTY_HEADING: Rule = rule("FR-TITLE-007", false);
+const TITLE_MISMATCH: Rule = rule("FR-TITLE-008", false);
+pub(crate) const CONTENT_EXTRA_H1: Rule = rule("FR-CONTENT-001", false);
+const CONTENT_FORMAT_VERSION: Rule = rule("FR-CONTENT-002", true);
+pub(crate) const CONTENT_ELEMENT: Rule = rule("FR-CONTENT-003", false);
+pub(crate) const CONTENT_DIRECT_CHILD: Rule = rule("FR-CONTENT-004", false);
+const LIST_CHILD: Rule = rule("FR-LIST-001", false);
+const TABLE_CHILD: Rule = rule("FR-TABLE-001", false);
+const TABLE_PARENT: Rule = rule("FR-TABLE-002", false);
+const FIGURE_CAPTION_PARENT: Rule = rule("FR-FIGURE-001", false);
+const FIGURE_CAPTION_COUNT: Rule = rule("FR-FIGURE-002", false);
+const FIGURE_CAPTION_POSITION: Rule = rule("FR-FIGURE-003", false);
+const FIGURE_EMPTY: Rule = rule("FR-FIGURE-004", false);
+const IMAGE_SRC: Rule = rule("FR-IMG-001", false);
+const IMAGE_ALT: Rule = rule("FR-IMG-002", false);
+const IMAGE_SRC_PATH: Rule = rule("FR-IMG-003", false);
+const IMAGE_ASSET: Rule = rule("FR-IMG-004", false);
+const NOTE_SECTION_CHILD: Rule = rule("FR-NOTE-001", false);
+const NOTE_PARENT: Rule = rule("FR-NOTE-002", false);
+const NOTE_MISSING_ID: Rule = rule("FR-NOTE-003", false);
+pub(crate) const NOTE_MALFORMED_ID: Rule = rule("FR-NOTE-004", false);
+pub(crate) const NOTE_DUPLICATE_ID: Rule = rule("FR-NOTE-005", false);
+const LINK_HREF: Rule = rule("FR-LINK-001", false);
+const LINK_MANUAL: Rule = rule("FR-LINK-002", true);
+const LINK_PAGE_UNRESOLVED: Rule = rule("FR-LINK-003", false);
+const LINK_PAGE_MISSING: Rule = rule("FR-LINK-004", false);
+const LINK_PAGE_TEXT: Rule = rule("FR-LINK-005", true);
+const LINK_NOTE_SCOPE: Rule = rule("FR-LINK-006", false);
+const LINK_NOTE_MISSING: Rule = rule("FR-LINK-007", false);
+const LINK_EXTERNAL_SCHEME: Rule = rule("FR-LINK-008", false);
+const LINK_SCOPE: Rule = rule("FR-LINK-009", false);
+const LINK_SECTION_UNRESOLVED: Rule = rule("FR-LINK-010", false);
+const LINK_SECTION_MISSING: Rule = rule("FR-LINK-011", true);
+const LINK_SECTION_TEXT: Rule = rule("FR-LINK-012", true);
+const LINK_NOTE_TARGET: Rule = rule("FR-LINK-013", true);
+const HEADING_ANCHOR: Rule = rule("FR-HEADING-001", true);
+const EMBED_PARENT: Rule = rule("FR-EMBED-001", false);
+const EMBED_TARGET: Rule = rule("FR-EMBED-002", true);
+
+struct PageCheck<'a> {
+    page: &'a Path,
+    page_path: &'a str,
+    source: &'a str,
+    document: &'a PageDocument,
+    collect: bool,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> PageCheck<'a> {
+    fn new(
+        page: &'a Path,
+        page_path: &'a str,
+        source: &'a str,
+        document: &'a PageDocument,
+        collect: bool,
+    ) -> Self {
+        Self {
+            page,
+            page_path,
+            source,
+            document,
+            collect,
+            diagnostics: Vec::new(),
+        }
+    }
+
+    fn fail(&mut self, rule: Rule, node: Option<&NodeRef>, message: String) -> Result<()> {
+        if !self.collect {
+            return Err(FractalError::invalid_project(message));
+        }
+
+        let span = node.and_then(|node| source_span(self.source, self.document, node));
+        let node = match (&span, node) {
+            (Some(span), _) => Some(self.source[span.start..span.end].to_string()),
+            (None, Some(node)) => Some(node_label(node)),
+            (None, None) => None,
+        };
+        self.diagnostics.push(Diagnostic {
+            rule: rule.id.to_string(),
+            severity: DiagnosticSeverity::Error,
+            page: Some(self.page_path.to_string()),
+            message,
+            span,
+            node,
+            fixable: rule.fixable,
+        });
+        Ok(())
+    }
+}
+
 fn warn_duplicate_page_labels(known_page_titles: &BTreeMap<String, String>) -> OperationReport {
     let mut report = OperationReport::new();
+    for (_, message) in duplicate_page_labels(known_page_titles) {
+        report.push(OperationEvent::Warning { message });
+    }
+    report
+}
+
+fn duplicate_page_labels(known_page_titles: &BTreeMap<String, String>) -> Vec<(String, String)> {
+    let mut duplicates = Vec::new();
     let mut owners = BTreeMap::<String, String>::new();
 
     for (path, title) in known_page_titles {
@@ -262,11 +500,12 @@ fn warn_duplicate_page_labels(known_page_titles: &BTreeMap<String, String>) -> O
             let key = link_label_key(&label);
             if let Some(existing_path) = owners.get(&key) {
                 if existing_path != path {
-                    report.push(OperationEvent::Warning {
-                        message: format!(
+                    duplicates.push((
+                        path.clone(),
+                        format!(
                             "duplicate page label `{label}` for {existing_path} and {path}; behavior is undefined"
                         ),
-                    });
+                    ));
                 }
             } else {
                 owners.insert(key, path.clone());
@@ -274,31 +513,29 @@ fn warn_duplicate_page_labels(known_page_titles: &BTreeMap<String, String>) -> O
         }
     }
 
-    report
+    duplicates
 }
 
 fn validate_project_page(
-    page: &Path,
-    page_path: &str,
+    check: &mut PageCheck,
     theme: Theme,
+    pages_dir: &Path,
     known_page_paths: &BTreeSet<String>,
+    known_asset_paths: &BTreeSet<String>,
     known_page_titles: &BTreeMap<String, String>,
 ) -> Result<()> {
-    let document = PageDocument::from_path(page)?;
-    validate_page_structure(page, page_path, theme, &document)?;
-    validate_note_ids(page, &document)?;
-    validate_generated_links(
-        page,
-        page_path,
-        &document,
-        known_page_paths,
-        known_page_titles,
-    )?;
+    validate_page_structure(check, theme)?;
+    validate_note_ids(check)?;
+    validate_heading_anchors(check)?;
+    validate_image_assets(check, known_asset_paths)?;
+    validate_generated_links(check, pages_dir, known_page_paths, known_page_titles)?;
+    validate_embed_targets(check, pages_dir, known_page_paths)?;
     Ok(())
 }
 
 fn fix_page(
     page: &Path,
+    pages_dir: &Path,
     page_path: &str,
     theme: Theme,
     known_page_titles: &BTreeMap<String, String>,
@@ -328,6 +565,56 @@ fn fix_page(
         changed = true;
     }
 
+    if document.repair_heading_anchors() > 0 {
+        changed = true;
+    }
+
+    let mut section_headings = BTreeMap::new();
+    let repaired_sections = document.repair_section_links(page_path, |target| {
+        if target == page_path {
+            return Some(document.headings());
+        }
+        section_headings
+            .entry(target.to_string())
+            .or_insert_with(|| load_page_headings(pages_dir, target))
+            .clone()
+    });
+    if repaired_sections > 0 {
+        changed = true;
+    }
+
+    let mut note_targets = BTreeMap::new();
+    let repaired_notes = document.repair_note_links(page_path, |target| {
+        if target == page_path {
+            return Some(document.notes().into_iter().map(|note| note.id).collect());
+        }
+        note_targets
+            .entry(target.to_string())
+            .or_insert_with(|| load_page_note_ids(pages_dir, target))
+            .clone()
+    });
+    if repaired_notes > 0 {
+        changed = true;
+    }
+
+    let mut embed_fragments = BTreeMap::new();
+    let repaired_embeds = document.repair_embeds(page_path, |source_page| {
+        if source_page == page_path {
+            return Some(document.page_fragments());
+        }
+        embed_fragments
+            .entry(source_page.to_string())
+            .or_insert_with(|| load_page_fragments(pages_dir, source_page))
+            .clone()
+    });
+    if repaired_embeds > 0 {
+        changed = true;
+    }
+
+    if document.uses_format_extensions() && document.upgrade_format_version()? {
+        changed = true;
+    }
+
     if fix_missing_title_or_heading(&document, page_path)? {
         changed = true;
     }
@@ -356,59 +643,96 @@ fn fix_missing_title_or_heading(document: &PageDocument, page_path: &str) -> Res
     document.set_title(&title)
 }
 
-fn validate_page_structure(
-    page: &Path,
-    page_path: &str,
-    theme: Theme,
-    document: &PageDocument,
-) -> Result<()> {
-    let head = single_node(page, document, "head")?;
-    let body = single_node(page, document, "body")?;
-    let main = single_node(page, document, "main")?;
-    let notes_section = single_node(page, document, "section[data-fractal-notes]")?;
-
-    validate_head_children(page, &head)?;
-    validate_body_children(page, &body)?;
-    validate_title_contract(page, document, &main)?;
-    validate_required_meta(page, document)?;
-    validate_stylesheet(page, page_path, &head)?;
-    validate_body_theme(page, theme, &body)?;
-    validate_notes_section(page, &notes_section)?;
-    validate_allowed_body_content(page, &main, true)?;
+fn validate_page_structure(check: &mut PageCheck, theme: Theme) -> Result<()> {
+    let head = single_node(check, "head", DOC_HEAD, DOC_HEAD)?;
+    let body = single_node(check, "body", DOC_BODY, DOC_BODY)?;
+    let main = single_node(check, "main", DOC_MAIN, DOC_MAIN)?;
+    let notes_section = single_node(
+        check,
+        "section[data-fractal-notes]",
+        DOC_NOTES_SECTION,
+        DOC_NOTES_SECTION,
+    )?;
+
+    if let Some(head) = &head {
+        validate_head_children(check, head)?;
+    }
+    if let Some(body) = &body {
+        validate_body_children(check, body)?;
+    }
+    if let Some(main) = &main {
+        validate_title_contract(check, main)?;
+    }
+    validate_required_meta(check)?;
+    if let Some(head) = &head {
+        validate_stylesheet(check, head)?;
+    }
+    if let Some(body) = &body {
+        validate_body_theme(check, theme, body)?;
+    }
+    let format_extensions = check
+        .document
+        .fractal_meta()
+        .get(VERSION_META)
+        .is_some_and(|version| version != LEGACY_VERSION);
+    if let Some(notes_section) = &notes_section {
+        validate_notes_section(check, notes_section, format_extensions)?;
+    }
+    if let Some(main) = &main {
+        validate_allowed_body_content(check, main, true, format_extensions)?;
+    }
 
     Ok(())
 }
 
-fn validate_head_children(page: &Path, head: &NodeRef) -> Result<()> {
+fn validate_head_children(check: &mut PageCheck, head: &NodeRef) -> Result<()> {
+    let page = check.page;
     for child in meaningful_children(head) {
         let Some(element) = child.as_element() else {
-            return Err(FractalError::invalid_project(format!(
-                "invalid direct head child in {}: {}",
-                page.display(),
-                node_label(&child)
-            )));
+            check.fail(
+                HEAD_CHILD,
+                Some(&child),
+                format!(
+                    "invalid direct head child in {}: {}",
+                    page.display(),
+                    node_label(&child)
+                ),
+            )?;
+            continue;
         };
         let name = element.name.local.to_string();
         match name.as_str() {
             "title" | "meta" => {}
             "link" => {
-                let attributes = element.attributes.borrow();
-                let rel = attributes.get("rel").unwrap_or_default();
+                let rel = element
+                    .attributes
+                    .borrow()
+                    .get("rel")
+                    .unwrap_or_default()
+                    .to_string();
                 if !rel
                     .split_whitespace()
                     .any(|token| token.eq_ignore_ascii_case("stylesheet"))
                 {
-                    return Err(FractalError::invalid_project(format!(
-                        "unsupported Fractal head link in {}: rel={rel}",
-                        page.display()
-                    )));
+                    check.fail(
+                        HEAD_CHILD,
+                        Some(&child),
+                        format!(
+                            "unsupported Fractal head link in {}: rel={rel}",
+                            page.display()
+                        ),
+                    )?;
                 }
             }
             _ => {
-                return Err(FractalError::invalid_project(format!(
-                    "unsupported Fractal head element in {}: <{name}>",
-                    page.display()
-                )));
+                check.fail(
+                    HEAD_CHILD,
+                    Some(&child),
+                    format!(
+                        "unsupported Fractal head element in {}: <{name}>",
+                        page.display()
+                    ),
+                )?;
             }
         }
     }
@@ -416,7 +740,8 @@ fn validate_head_children(page: &Path, head: &NodeRef) -> Result<()> {
     Ok(())
 }
 
-fn validate_body_children(page: &Path, body: &NodeRef) -> Result<()> {
+fn validate_body_children(check: &mut PageCheck, body: &NodeRef) -> Result<()> {
+    let page = check.page;
     let mut main_count = 0;
     let mut notes_count = 0;
 
@@ -430,87 +755,133 @@ fn validate_body_children(page: &Path, body: &NodeRef) -> Result<()> {
             continue;
         }
 
-        return Err(FractalError::invalid_project(format!(
-            "invalid direct body child in {}: {}",
-            page.display(),
-            node_label(&child)
-        )));
+        check.fail(
+            BODY_CHILD,
+            Some(&child),
+            format!(
+                "invalid direct body child in {}: {}",
+                page.display(),
+                node_label(&child)
+            ),
+        )?;
     }
 
     if main_count != 1 {
-        return Err(FractalError::invalid_project(format!(
-            "expected exactly one direct main child in {}: found {main_count}",
-            page.display()
-        )));
+        check.fail(
+            BODY_MAIN_COUNT,
+            Some(body),
+            format!(
+                "expected exactly one direct main child in {}: found {main_count}",
+                page.display()
+            ),
+        )?;
     }
     if notes_count != 1 {
-        return Err(FractalError::invalid_project(format!(
-            "expected exactly one direct notes section in {}: found {notes_count}",
-            page.display()
-        )));
+        check.fail(
+            BODY_NOTES_COUNT,
+            Some(body),
+            format!(
+                "expected exactly one direct notes section in {}: found {notes_count}",
+                page.display()
+            ),
+        )?;
     }
 
     Ok(())
 }
 
-fn validate_title_contract(page: &Path, document: &PageDocument, main: &NodeRef) -> Result<()> {
-    let title = single_node(page, document, "title")?;
-    let title_text = normalize_link_label(&title.text_contents());
-    if title_text.is_empty() {
-        return Err(FractalError::invalid_project(format!(
-            "page title cannot be empty in {}",
-            page.display()
-        )));
+fn validate_title_contract(check: &mut PageCheck, main: &NodeRef) -> Result<()> {
+    let page = check.page;
+    if let Some(title) = single_node(check, "title", TITLE_MISSING, TITLE_DUPLICATE)? {
+        let title_text = normalize_link_label(&title.text_contents());
+        if title_text.is_empty() {
+            check.fail(
+                TITLE_EMPTY,
+                Some(&title),
+                format!("page title cannot be empty in {}", page.display()),
+            )?;
+        }
+        validate_main_heading(check, main, Some(title_text))
+    } else {
+        validate_main_heading(check, main, None)
     }
+}
 
+fn validate_main_heading(
+    check: &mut PageCheck,
+    main: &NodeRef,
+    title_text: Option<String>,
+) -> Result<()> {
+    let page = check.page;
     let children = meaningful_children(main);
+    let h1_count = select_nodes(check.document, "main h1").len();
+    let missing_rule = if h1_count == 0 {
+        TITLE_MISSING_HEADING
+    } else {
+        TITLE_HEADING_POSITION
+    };
     let Some(first_child) = children.first() else {
-        return Err(FractalError::invalid_project(format!(
-            "missing main heading in {}",
-            page.display()
-        )));
+        return check.fail(
+            missing_rule,
+            Some(main),
+            format!("missing main heading in {}", page.display()),
+        );
     };
     if !is_element_named(first_child, "h1") {
-        return Err(FractalError::invalid_project(format!(
-            "main heading must be the first meaningful child in {}",
-            page.display()
-        )));
+        return check.fail(
+            missing_rule,
+            Some(first_child),
+            format!(
+                "main heading must be the first meaningful child in {}",
+                page.display()
+            ),
+        );
     }
 
-    let h1_count = select_nodes(document, "main h1").len();
     if h1_count != 1 {
-        return Err(FractalError::invalid_project(format!(
-            "expected exactly one main h1 in {}: found {h1_count}",
-            page.display()
-        )));
+        check.fail(
+            TITLE_HEADING_COUNT,
+            Some(first_child),
+            format!(
+                "expected exactly one main h1 in {}: found {h1_count}",
+                page.display()
+            ),
+        )?;
     }
 
     let heading_text = normalize_link_label(&first_child.text_contents());
     if heading_text.is_empty() {
-        return Err(FractalError::invalid_project(format!(
-            "main heading cannot be empty in {}",
-            page.display()
-        )));
+        return check.fail(
+            TITLE_EMPTY_HEADING,
+            Some(first_child),
+            format!("main heading cannot be empty in {}", page.display()),
+        );
     }
-    if title_text != heading_text {
-        return Err(FractalError::invalid_project(format!(
-            "title and main heading differ in {}: `{title_text}` != `{heading_text}`",
-            page.display()
-        )));
+    if let Some(title_text) = title_text.filter(|title_text| !title_text.is_empty()) {
+        if title_text != heading_text {
+            check.fail(
+                TITLE_MISMATCH,
+                Some(first_child),
+                format!(
+                    "title and main heading differ in {}: `{title_text}` != `{heading_text}`",
+                    page.display()
+                ),
+            )?;
+        }
     }
 
     Ok(())
 }
 
-fn validate_required_meta(page: &Path, document: &PageDocument) -> Result<()> {
+fn validate_required_meta(check: &mut PageCheck) -> Result<()> {
+    let page = check.page;
     let allowed = required_meta_tags().into_iter().collect::<BTreeMap<_, _>>();
-    let mut found = BTreeMap::<String, Vec<String>>::new();
+    let mut found = BTreeMap::<String, Vec<(String, NodeRef)>>::new();
 
-    for element in document
-        .document
-        .select("meta[name]")
-        .expect("static selector should parse")
-    {
+    for node in select_nodes(check.document, "meta[name]") {
+        let Some(element) = node.as_element() else {
+            continue;
+        };
         let attributes = element.attributes.borrow();
         let Some(name) = attributes.get("name") else {
             continue;
@@ -519,52 +890,69 @@ fn validate_required_meta(page: &Path, document: &PageDocument) -> Result<()> {
             continue;
         }
         if !allowed.contains_key(name) {
-            return Err(FractalError::invalid_project(format!(
-                "unsupported Fractal meta tag in {}: {name}",
-                page.display()
-            )));
+            check.fail(
+                HEAD_UNSUPPORTED_META,
+                Some(&node),
+                format!("unsupported Fractal meta tag in {}: {name}", page.display()),
+            )?;
+            continue;
         }
 
         let Some(content) = attributes.get("content") else {
-            return Err(FractalError::invalid_project(format!(
-                "missing content for required meta tag in {}: {name}",
-                page.display()
-            )));
+            check.fail(
+                HEAD_META_CONTENT,
+                Some(&node),
+                format!(
+                    "missing content for required meta tag in {}: {name}",
+                    page.display()
+                ),
+            )?;
+            continue;
         };
         found
             .entry(name.to_string())
             .or_default()
-            .push(content.to_string());
+            .push((content.to_string(), node.clone()));
     }
 
     for (name, expected_content) in allowed {
         let Some(values) = found.get(name) else {
-            return Err(FractalError::invalid_project(format!(
-                "missing required meta tag in {}: {name}",
-                page.display()
-            )));
+            check.fail(
+                HEAD_MISSING_META,
+                None,
+                format!("missing required meta tag in {}: {name}", page.display()),
+            )?;
+            continue;
         };
         if values.len() != 1 {
-            return Err(FractalError::invalid_project(format!(
-                "duplicate required meta tag in {}: {name}",
-                page.display()
-            )));
+            check.fail(
+                HEAD_DUPLICATE_META,
+                Some(&values[1].1),
+                format!("duplicate required meta tag in {}: {name}", page.display()),
+            )?;
+            continue;
         }
-        if name == "fractal:version" && values[0] != expected_content {
-            return Err(FractalError::invalid_project(format!(
-                "unsupported page format version in {}: {} (expected {})",
-                page.display(),
-                values[0],
-                expected_content
-            )));
+        let (value, node) = &values[0];
+        if name == VERSION_META && !is_supported_page_version(value) {
+            check.fail(
+                HEAD_VERSION,
+                Some(node),
+                format!(
+                    "unsupported page format version in {}: {} (expected {} or {})",
+                    page.display(),
+                    value,
+                    LEGACY_VERSION,
+                    expected_content
+                ),
+            )?;
         }
     }
 
     Ok(())
 }
 
-fn validate_stylesheet(page: &Path, page_path: &str, head: &NodeRef) -> Result<()> {
-    let expected = stylesheet_href(Path::new(page_path));
+fn validate_stylesheet(check: &mut PageCheck, head: &NodeRef) -> Result<()> {
+    let expected = stylesheet_href(Path::new(check.page_path));
     let has_expected = head
         .select("link[href][rel]")
         .expect("static selector should parse")
@@ -577,97 +965,139 @@ fn validate_stylesheet(page: &Path, page_path: &str, head: &NodeRef) -> Result<(
         });
 
     if !has_expected {
-        return Err(FractalError::invalid_project(format!(
-            "missing exact Fractal stylesheet link in {}: expected {expected}",
-            page.display()
-        )));
+        let page = check.page;
+        let link = select_nodes(check.document, "head link[rel]")
+            .into_iter()
+            .next();
+        check.fail(
+            HEAD_STYLESHEET,
+            link.as_ref(),
+            format!(
+                "missing exact Fractal stylesheet link in {}: expected {expected}",
+                page.display()
+            ),
+        )?;
     }
 
     Ok(())
 }
 
-fn validate_body_theme(page: &Path, theme: Theme, body: &NodeRef) -> Result<()> {
+fn validate_body_theme(check: &mut PageCheck, theme: Theme, body: &NodeRef) -> Result<()> {
+    let page = check.page;
     let Some(element) = body.as_element() else {
-        return Err(FractalError::invalid_project(format!(
-            "body node is not an element in {}",
-            page.display()
-        )));
+        return check.fail(
+            DOC_BODY,
+            Some(body),
+            format!("body node is not an element in {}", page.display()),
+        );
     };
-    let attributes = element.attributes.borrow();
     let expected = theme.as_str();
-    if attributes.get("data-fractal-theme") != Some(expected) {
-        return Err(FractalError::invalid_project(format!(
-            "body theme mismatch in {}: expected {expected}",
-            page.display()
-        )));
+    let matches = element.attributes.borrow().get("data-fractal-theme") == Some(expected);
+    if !matches {
+        check.fail(
+            BODY_THEME,
+            Some(body),
+            format!(
+                "body theme mismatch in {}: expected {expected}",
+                page.display()
+            ),
+        )?;
     }
 
     Ok(())
 }
 
-fn validate_notes_section(page: &Path, notes_section: &NodeRef) -> Result<()> {
+fn validate_notes_section(
+    check: &mut PageCheck,
+    notes_section: &NodeRef,
+    format_extensions: bool,
+) -> Result<()> {
+    let page = check.page;
     for child in meaningful_children(notes_section) {
         if !is_fractal_note_aside(&child) {
-            return Err(FractalError::invalid_project(format!(
-                "invalid notes section child in {}: {}",
-                page.display(),
-                node_label(&child)
-            )));
+            check.fail(
+                NOTE_SECTION_CHILD,
+                Some(&child),
+                format!(
+                    "invalid notes section child in {}: {}",
+                    page.display(),
+                    node_label(&child)
+                ),
+            )?;
+            continue;
         }
-        validate_allowed_body_content(page, &child, false)?;
+        validate_allowed_body_content(check, &child, false, format_extensions)?;
     }
 
     Ok(())
 }
 
-fn validate_note_ids(page: &Path, document: &PageDocument) -> Result<()> {
-    let notes_section = single_node(page, document, "section[data-fractal-notes]")?;
+fn validate_note_ids(check: &mut PageCheck) -> Result<()> {
+    let page = check.page;
+    let sections = select_nodes(check.document, "section[data-fractal-notes]");
+    let [notes_section] = sections.as_slice() else {
+        return Ok(());
+    };
     let mut seen = BTreeSet::new();
 
-    for element in document
-        .document
-        .select("aside[data-fractal-note]")
-        .expect("static selector should parse")
-    {
-        let node = element.as_node().clone();
+    for node in select_nodes(check.document, "aside[data-fractal-note]") {
         if node.parent() != Some(notes_section.clone()) {
-            return Err(FractalError::invalid_project(format!(
-                "note must be a direct child of the notes section in {}",
-                page.display()
-            )));
+            check.fail(
+                NOTE_PARENT,
+                Some(&node),
+                format!(
+                    "note must be a direct child of the notes section in {}",
+                    page.display()
+                ),
+            )?;
+            continue;
         }
 
-        let attributes = element.attributes.borrow();
-        let Some(id) = attributes.get("id") else {
-            return Err(FractalError::invalid_project(format!(
-                "missing note id in {}",
-                page.display()
-            )));
+        let id = node
+            .as_element()
+            .and_then(|element| element.attributes.borrow().get("id").map(str::to_string));
+        let Some(id) = id else {
+            check.fail(
+                NOTE_MISSING_ID,
+                Some(&node),
+                format!("missing note id in {}", page.display()),
+            )?;
+            continue;
         };
 
-        if !is_valid_note_id(id) {
-            return Err(FractalError::invalid_project(format!(
-                "malformed note id in {}: {id}",
-                page.display()
-            )));
+        if !is_valid_note_id(&id) {
+            check.fail(
+                NOTE_MALFORMED_ID,
+                Some(&node),
+                format!("malformed note id in {}: {id}", page.display()),
+            )?;
+            continue;
         }
 
-        if !seen.insert(id.to_string()) {
-            return Err(FractalError::invalid_project(format!(
-                "duplicate note id in {}: {id}",
-                page.display()
-            )));
+        if !seen.insert(id.clone()) {
+            check.fail(
+                NOTE_DUPLICATE_ID,
+                Some(&node),
+                format!("duplicate note id in {}: {id}", page.display()),
+            )?;
         }
     }
 
     Ok(())
 }
 
-fn validate_allowed_body_content(page: &Path, root: &NodeRef, requires_h1: bool) -> Result<()> {
-    validate_direct_content_children(page, root, requires_h1)?;
+fn validate_allowed_body_content(
+    check: &mut PageCheck,
+    root: &NodeRef,
+    requires_h1: bool,
+    format_extensions: bool,
+) -> Result<()> {
+    let page = check.page;
+    validate_direct_content_children(check, root, requires_h1)?;
     let first_h1 = requires_h1
         .then(|| meaningful_children(root).into_iter().next())
-        .flatten();
+        .flatten()
+        .filter(|node| is_element_named(node, "h1"));
 
     for node in root.descendants() {
         if node == *root {
@@ -681,72 +1111,160 @@ fn validate_allowed_body_content(page: &Path, root: &NodeRef, requires_h1: bool)
             continue;
         }
         if name == "h1" {
-            return Err(FractalError::invalid_project(format!(
-                "unexpected h1 in {}",
-                page.display()
-            )));
+            check.fail(
+                CONTENT_EXTRA_H1,
+                Some(&node),
+                format!("unexpected h1 in {}", page.display()),
+            )?;
+            continue;
         }
-        if !is_allowed_body_element(&name) {
-            return Err(FractalError::invalid_project(format!(
-                "unsupported Fractal body element in {}: <{name}>",
-                page.display()
-            )));
+        if is_inline_element(&name) {
+            if !format_extensions {
+                check.fail(
+                    CONTENT_FORMAT_VERSION,
+                    Some(&node),
+                    format!(
+                        "inline formatting requires fractal:version {DEFAULT_VERSION} in {}: <{name}>",
+                        page.display()
+                    ),
+                )?;
+            }
+            continue;
+        }
+        if is_embedded_content_element(&name) {
+            if !format_extensions {
+                check.fail(
+                    CONTENT_FORMAT_VERSION,
+                    Some(&node),
+                    format!(
+                        "tables, images, and figures require fractal:version {DEFAULT_VERSION} in {}: <{name}>",
+                        page.display()
+                    ),
+                )?;
+                continue;
+            }
+            if name == "img" {
+                validate_image_attributes(check, &node)?;
+            }
+            continue;
+        }
+        if is_embed(&node) {
+            if !format_extensions {
+                check.fail(
+                    CONTENT_FORMAT_VERSION,
+                    Some(&node),
+                    format!(
+                        "embeds require fractal:version {DEFAULT_VERSION} in {}",
+                        page.display()
+                    ),
+                )?;
+            }
+            if node.parent().as_ref() != Some(root) {
+                check.fail(
+                    EMBED_PARENT,
+                    Some(&node),
+                    format!("embed must be a direct child of main in {}", page.display()),
+                )?;
+            }
+            continue;
+        }
+        let versioned_scope = (name == "a")
+            .then(|| {
+                element
+                    .attributes
+                    .borrow()
+                    .get("data-fractal-link")
+                    .map(str::to_string)
+            })
+            .flatten()
+            .filter(|scope| matches!(scope.as_str(), "external" | "section"));
+        if let Some(scope) = versioned_scope.filter(|_| !format_extensions) {
+            check.fail(
+                CONTENT_FORMAT_VERSION,
+                Some(&node),
+                format!(
+                    "{scope} links require fractal:version {DEFAULT_VERSION} in {}",
+                    page.display()
+                ),
+            )?;
+            continue;
+        }
+        if !is_allowed_body_element(&name) && node.parent().as_ref() != Some(root) {
+            check.fail(
+                CONTENT_ELEMENT,
+                Some(&node),
+                format!(
+                    "unsupported Fractal body element in {}: <{name}>",
+                    page.display()
+                ),
+            )?;
         }
     }
 
-    validate_list_children(page, root)?;
+    validate_list_children(check, root)?;
+    validate_table_children(check, root)?;
+    validate_figure_children(check, root)?;
     Ok(())
 }
 
-fn validate_direct_content_children(page: &Path, root: &NodeRef, requires_h1: bool) -> Result<()> {
+fn validate_direct_content_children(
+    check: &mut PageCheck,
+    root: &NodeRef,
+    requires_h1: bool,
+) -> Result<()> {
+    let page = check.page;
     let children = meaningful_children(root);
-    let start = if requires_h1 { 1 } else { 0 };
-
-    if requires_h1
-        && !children
-            .first()
-            .map(|node| is_element_named(node, "h1"))
-            .unwrap_or(false)
-    {
-        return Err(FractalError::invalid_project(format!(
-            "missing first main h1 in {}",
-            page.display()
-        )));
-    }
+    let start = usize::from(
+        requires_h1
+            && children
+                .first()
+                .is_some_and(|node| is_element_named(node, "h1")),
+    );
 
     for child in children.into_iter().skip(start) {
         let Some(element) = child.as_element() else {
-            return Err(FractalError::invalid_project(format!(
-                "unsupported direct content node in {}: {}",
-                page.display(),
-                node_label(&child)
-            )));
+            check.fail(
+                CONTENT_DIRECT_CHILD,
+                Some(&child),
+                format!(
+                    "unsupported direct content node in {}: {}",
+                    page.display(),
+                    node_label(&child)
+                ),
+            )?;
+            continue;
         };
         let name = element.name.local.to_string();
-        if !is_allowed_body_element(&name) {
-            return Err(FractalError::invalid_project(format!(
-                "unsupported direct Fractal body element in {}: <{name}>",
-                page.display()
-            )));
+        let allowed_embed = requires_h1 && is_embed(&child);
+        if !is_allowed_body_element(&name) && !allowed_embed {
+            check.fail(
+                CONTENT_DIRECT_CHILD,
+                Some(&child),
+                format!(
+                    "unsupported direct Fractal body element in {}: <{name}>",
+                    page.display()
+                ),
+            )?;
         }
     }
 
     Ok(())
 }
 
-fn validate_list_children(page: &Path, root: &NodeRef) -> Result<()> {
-    for list in root
-        .select("ul, ol")
-        .expect("static selector should parse")
-        .map(|element| element.as_node().clone())
-    {
+fn validate_list_children(check: &mut PageCheck, root: &NodeRef) -> Result<()> {
+    let page = check.page;
+    for list in select_within(root, "ul, ol") {
         for child in meaningful_children(&list) {
             if !is_element_named(&child, "li") {
-                return Err(FractalError::invalid_project(format!(
-                    "list children must be li elements in {}: {}",
-                    page.display(),
-                    node_label(&child)
-                )));
+                check.fail(
+                    LIST_CHILD,
+                    Some(&child),
+                    format!(
+                        "list children must be li elements in {}: {}",
+                        page.display(),
+                        node_label(&child)
+                    ),
+                )?;
             }
         }
     }
@@ -754,84 +1272,399 @@ fn validate_list_children(page: &Path, root: &NodeRef) -> Result<()> {
     Ok(())
 }
 
+fn validate_table_children(check: &mut PageCheck, root: &NodeRef) -> Result<()> {
+    let page = check.page;
+    for (parent, allowed) in [
+        ("table", &["thead", "tbody"][..]),
+        ("thead, tbody", &["tr"][..]),
+        ("tr", &["th", "td"][..]),
+    ] {
+        for node in select_within(root, parent) {
+            for child in meaningful_children(&node) {
+                if !allowed.iter().any(|name| is_element_named(&child, name)) {
+                    check.fail(
+                        TABLE_CHILD,
+                        Some(&child),
+                        format!(
+                            "{} children must be {} elements in {}: {}",
+                            node_label(&node),
+                            allowed.join(" or "),
+                            page.display(),
+                            node_label(&child)
+                        ),
+                    )?;
+                }
+            }
+        }
+    }
+
+    for (child, parents, rule) in [
+        ("thead, tbody", &["table"][..], TABLE_PARENT),
+        ("tr", &["thead", "tbody"][..], TABLE_PARENT),
+        ("th, td", &["tr"][..], TABLE_PARENT),
+        ("figcaption", &["figure"][..], FIGURE_CAPTION_PARENT),
+    ] {
+        for node in select_within(root, child) {
+            let has_parent = node
+                .parent()
+                .is_some_and(|parent| parents.iter().any(|name| is_element_named(&parent, name)));
+            if !has_parent {
+                check.fail(
+                    rule,
+                    Some(&node),
+                    format!(
+                        "{} must be a child of {} in {}",
+                        node_label(&node),
+                        parents.join(" or "),
+                        page.display()
+                    ),
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_figure_children(check: &mut PageCheck, root: &NodeRef) -> Result<()> {
+    let page = check.page;
+    for figure in select_within(root, "figure") {
+        let children = meaningful_children(&figure);
+        let captions = children
+            .iter()
+            .enumerate()
+            .filter(|(_, child)| is_element_named(child, "figcaption"))
+            .map(|(position, _)| position)
+            .collect::<Vec<_>>();
+        if captions.len() > 1 {
+            check.fail(
+                FIGURE_CAPTION_COUNT,
+                Some(&children[captions[1]]),
+                format!(
+                    "figure may contain only one figcaption in {}",
+                    page.display()
+                ),
+            )?;
+            continue;
+        }
+        if let Some(position) = captions
+            .first()
+            .filter(|position| **position != 0 && **position + 1 != children.len())
+        {
+            check.fail(
+                FIGURE_CAPTION_POSITION,
+                Some(&children[*position]),
+                format!(
+                    "figcaption must be the first or last child of its figure in {}",
+                    page.display()
+                ),
+            )?;
+        }
+        if children.len() == captions.len() {
+            check.fail(
+                FIGURE_EMPTY,
+                Some(&figure),
+                format!(
+                    "figure must contain content besides its caption in {}",
+                    page.display()
+                ),
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_image_attributes(check: &mut PageCheck, node: &NodeRef) -> Result<()> {
+    let page = check.page;
+    let Some(element) = node.as_element() else {
+        return Ok(());
+    };
+    let (src, alt) = {
+        let attributes = element.attributes.borrow();
+        (
+            attributes.get("src").map(str::to_string),
+            attributes.get("alt").map(str::to_string),
+        )
+    };
+    if src.as_deref().is_none_or(|src| src.trim().is_empty()) {
+        return check.fail(
+            IMAGE_SRC,
+            Some(node),
+            format!("image is missing src in {}", page.display()),
+        );
+    }
+    if alt
+        .as_deref()
+        .is_none_or(|alt| normalize_link_label(alt).is_empty())
+    {
+        check.fail(
+            IMAGE_ALT,
+            Some(node),
+            format!(
+                "image is missing alt text in {}: {}",
+                page.display(),
+                src.unwrap_or_default()
+            ),
+        )?;
+    }
+
+    Ok(())
+}
+
+fn validate_image_assets(
+    check: &mut PageCheck,
+    known_asset_paths: &BTreeSet<String>,
+) -> Result<()> {
+    let page = check.page;
+    for node in select_nodes(check.document, "img[src]") {
+        let Some(src) = node
+            .as_element()
+            .and_then(|element| element.attributes.borrow().get("src").map(str::to_string))
+        else {
+            continue;
+        };
+        let Some(target) = resolve_asset_src(check.page_path, &src) else {
+            check.fail(
+                IMAGE_SRC_PATH,
+                Some(&node),
+                format!(
+                    "image src must be a relative path to an asset under pages/ in {}: {}",
+                    page.display(),
+                    src
+                ),
+            )?;
+            continue;
+        };
+        if !known_asset_paths.contains(&target) {
+            check.fail(
+                IMAGE_ASSET,
+                Some(&node),
+                format!("image asset is missing in {}: {}", page.display(), src),
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_heading_anchors(check: &mut PageCheck) -> Result<()> {
+    let page = check.page;
+    for (heading, node) in check.document.heading_nodes() {
+        let id = node
+            .as_element()
+            .and_then(|element| element.attributes.borrow().get("id").map(str::to_string));
+        let Some(id) = id.filter(|id| *id != heading.anchor) else {
+            continue;
+        };
+        check.fail(
+            HEADING_ANCHOR,
+            Some(&node),
+            format!(
+                "heading anchor does not match its text in {}: #{id} (expected #{})",
+                page.display(),
+                heading.anchor
+            ),
+        )?;
+    }
+    Ok(())
+}
+
 fn validate_generated_links(
-    page: &Path,
-    page_path: &str,
-    document: &PageDocument,
+    check: &mut PageCheck,
+    pages_dir: &Path,
     known_page_paths: &BTreeSet<String>,
     known_page_titles: &BTreeMap<String, String>,
 ) -> Result<()> {
-    let note_ids = document
+    let page = check.page;
+    let page_path = check.page_path;
+    let note_ids = check
+        .document
         .notes()
         .into_iter()
         .map(|note| note.id)
         .collect::<BTreeSet<_>>();
+    let mut section_headings =
+        BTreeMap::from([(page_path.to_string(), Some(check.document.headings()))]);
+    let mut note_targets = BTreeMap::new();
 
-    for element in document
-        .document
-        .select("a")
-        .expect("static selector should parse")
-    {
-        let attributes = element.attributes.borrow();
-        let scope = attributes.get("data-fractal-link");
-        let Some(href) = attributes.get("href") else {
-            return Err(FractalError::invalid_project(format!(
-                "generated link is missing href in {}",
-                page.display()
-            )));
+    for node in select_nodes(check.document, "a") {
+        let Some(element) = node.as_element() else {
+            continue;
         };
-        let text = normalize_link_label(&element.text_contents());
+        let (scope, href) = {
+            let attributes = element.attributes.borrow();
+            (
+                attributes.get("data-fractal-link").map(str::to_string),
+                attributes.get("href").map(str::to_string),
+            )
+        };
+        let Some(href) = href else {
+            check.fail(
+                LINK_HREF,
+                Some(&node),
+                format!("generated link is missing href in {}", page.display()),
+            )?;
+            continue;
+        };
+        let text = normalize_link_label(&node.text_contents());
 
         let Some(scope) = scope else {
-            diagnose_manual_link_target(page, page_path, href, &text, known_page_titles)?;
-            return Err(FractalError::invalid_project(format!(
-                "manual link is not valid Fractal in {}",
-                page.display()
-            )));
+            let reported = check.diagnostics.len();
+            diagnose_manual_link_target(check, &node, &href, &text, known_page_titles)?;
+            if check.diagnostics.len() == reported {
+                check.fail(
+                    LINK_MANUAL,
+                    Some(&node),
+                    format!("manual link is not valid Fractal in {}", page.display()),
+                )?;
+            }
+            continue;
         };
 
-        match scope {
+        match scope.as_str() {
             "page" => {
-                let Some(target) = resolve_page_href(page_path, href) else {
-                    return Err(FractalError::invalid_project(format!(
-                        "generated page link does not resolve in {}: {href}",
-                        page.display()
-                    )));
+                let Some(target) = resolve_page_href(page_path, &href) else {
+                    check.fail(
+                        LINK_PAGE_UNRESOLVED,
+                        Some(&node),
+                        format!(
+                            "generated page link does not resolve in {}: {href}",
+                            page.display()
+                        ),
+                    )?;
+                    continue;
                 };
                 if !known_page_paths.contains(&target) {
-                    return Err(FractalError::invalid_project(format!(
-                        "generated page link target is missing in {}: {href}",
-                        page.display()
-                    )));
+                    check.fail(
+                        LINK_PAGE_MISSING,
+                        Some(&node),
+                        format!(
+                            "generated page link target is missing in {}: {href}",
+                            page.display()
+                        ),
+                    )?;
+                    continue;
                 }
                 validate_page_link_text(
-                    page,
+                    check,
+                    &node,
                     "generated",
-                    href,
+                    &href,
                     &text,
                     &target,
                     known_page_titles,
                 )?;
             }
             "note" => {
-                let Some(note_id) = href.strip_prefix('#') else {
-                    return Err(FractalError::invalid_project(format!(
-                        "generated note link must target a page-local note in {}: {href}",
-                        page.display()
-                    )));
+                if let Some(note_id) = href.strip_prefix('#') {
+                    if !note_ids.contains(note_id) {
+                        check.fail(
+                            LINK_NOTE_MISSING,
+                            Some(&node),
+                            format!(
+                                "generated note link target is missing in {}: {href}",
+                                page.display()
+                            ),
+                        )?;
+                    }
+                    continue;
+                }
+                let Some((target, note_id)) = resolve_section_href(page_path, &href) else {
+                    check.fail(
+                        LINK_NOTE_SCOPE,
+                        Some(&node),
+                        format!(
+                            "generated note link must target a note id in {}: {href}",
+                            page.display()
+                        ),
+                    )?;
+                    continue;
                 };
-                if !note_ids.contains(note_id) {
-                    return Err(FractalError::invalid_project(format!(
-                        "generated note link target is missing in {}: {href}",
-                        page.display()
-                    )));
+                let target_notes = if target == page_path {
+                    Some(note_ids.clone())
+                } else if !known_page_paths.contains(&target) {
+                    None
+                } else {
+                    note_targets
+                        .entry(target.clone())
+                        .or_insert_with(|| load_page_note_ids(pages_dir, &target))
+                        .clone()
+                };
+                if !target_notes.is_some_and(|notes| notes.contains(&note_id)) {
+                    check.fail(
+                        LINK_NOTE_TARGET,
+                        Some(&node),
+                        format!(
+                            "cross-page note link target is missing in {}: {href}",
+                            page.display()
+                        ),
+                    )?;
+                }
+            }
+            "section" => {
+                let target = resolve_section_href(page_path, &href)
+                    .filter(|(target, _)| known_page_paths.contains(target));
+                let Some((target, anchor)) = target else {
+                    check.fail(
+                        LINK_SECTION_UNRESOLVED,
+                        Some(&node),
+                        format!(
+                            "generated section link must target a heading on a known page in {}: {href}",
+                            page.display()
+                        ),
+                    )?;
+                    continue;
+                };
+                let headings = section_headings
+                    .entry(target.clone())
+                    .or_insert_with(|| load_page_headings(pages_dir, &target))
+                    .as_deref()
+                    .unwrap_or_default();
+                let Some(heading) = headings.iter().find(|heading| heading.anchor == anchor) else {
+                    check.fail(
+                        LINK_SECTION_MISSING,
+                        Some(&node),
+                        format!(
+                            "generated section link target heading is missing in {}: {href}",
+                            page.display()
+                        ),
+                    )?;
+                    continue;
+                };
+                if link_label_key(&text) != link_label_key(&heading.text) {
+                    let expected = heading.text.clone();
+                    check.fail(
+                        LINK_SECTION_TEXT,
+                        Some(&node),
+                        format!(
+                            "section link text does not identify its heading in {}: `{text}` -> {href} (expected `{expected}`)",
+                            page.display()
+                        ),
+                    )?;
+                }
+            }
+            "external" => {
+                if !is_allowed_external_href(&href) {
+                    check.fail(
+                        LINK_EXTERNAL_SCHEME,
+                        Some(&node),
+                        format!(
+                            "external link must be an http, https, or mailto URL in {}: {href}",
+                            page.display()
+                        ),
+                    )?;
                 }
             }
             _ => {
-                return Err(FractalError::invalid_project(format!(
-                    "unsupported generated link scope in {}: {scope}",
-                    page.display()
-                )));
+                check.fail(
+                    LINK_SCOPE,
+                    Some(&node),
+                    format!(
+                        "unsupported generated link scope in {}: {scope}",
+                        page.display()
+                    ),
+                )?;
             }
         }
     }
@@ -839,9 +1672,44 @@ fn validate_generated_links(
     Ok(())
 }
 
+fn validate_embed_targets(
+    check: &mut PageCheck,
+    pages_dir: &Path,
+    known_page_paths: &BTreeSet<String>,
+) -> Result<()> {
+    let page = check.page;
+    let page_path = check.page_path;
+    let mut page_fragments =
+        BTreeMap::from([(page_path.to_string(), Some(check.document.page_fragments()))]);
+
+    for (target, node) in check.document.embed_nodes() {
+        let resolved = resolve_section_href(page_path, &target)
+            .filter(|(source_page, _)| known_page_paths.contains(source_page))
+            .is_some_and(|(source_page, fragment)| {
+                page_fragments
+                    .entry(source_page.clone())
+                    .or_insert_with(|| load_page_fragments(pages_dir, &source_page))
+                    .as_ref()
+                    .is_some_and(|fragments| fragments.contains(&fragment))
+            });
+        if !resolved {
+            check.fail(
+                EMBED_TARGET,
+                Some(&node),
+                format!(
+                    "embed must target a note or heading on a known page in {}: {target}",
+                    page.display()
+                ),
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
 fn diagnose_manual_link_target(
-    page: &Path,
-    page_path: &str,
+    check: &mut PageCheck,
+    node: &NodeRef,
     href: &str,
     text: &str,
     known_page_titles: &BTreeMap<String, String>,
@@ -850,18 +1718,27 @@ fn diagnose_manual_link_target(
         return Ok(());
     }
 
-    let Some(target) = resolve_page_href(page_path, href) else {
+    let Some(target) = resolve_page_href(check.page_path, href) else {
         return Ok(());
     };
     if !known_page_titles.contains_key(&target) {
         return Ok(());
     }
 
-    validate_page_link_text(page, "manual", href, text, &target, known_page_titles)
+    validate_page_link_text(
+        check,
+        node,
+        "manual",
+        href,
+        text,
+        &target,
+        known_page_titles,
+    )
 }
 
 fn validate_page_link_text(
-    page: &Path,
+    check: &mut PageCheck,
+    node: &NodeRef,
     kind: &str,
     href: &str,
     text: &str,
@@ -875,12 +1752,17 @@ fn validate_page_link_text(
         return Ok(());
     }
 
-    Err(FractalError::invalid_project(format!(
-        "{kind} page link text does not identify its target in {}: `{}` -> {href} (expected {})",
-        page.display(),
-        text,
-        expected_page_labels(target, title)
-    )))
+    let page = check.page;
+    check.fail(
+        LINK_PAGE_TEXT,
+        Some(node),
+        format!(
+            "{kind} page link text does not identify its target in {}: `{}` -> {href} (expected {})",
+            page.display(),
+            text,
+            expected_page_labels(target, title)
+        ),
+    )
 }
 
 fn expected_page_labels(path: &str, title: &str) -> String {
@@ -898,8 +1780,14 @@ fn expected_page_labels(path: &str, title: &str) -> String {
     labels.join(" or ")
 }
 
-fn single_node(page: &Path, document: &PageDocument, selector: &str) -> Result<NodeRef> {
-    let nodes = select_nodes(document, selector);
+fn single_node(
+    check: &mut PageCheck,
+    selector: &str,
+    missing_rule: Rule,
+    duplicate_rule: Rule,
+) -> Result<Option<NodeRef>> {
+    let page = check.page;
+    let nodes = select_nodes(check.document, selector);
     let label = match selector {
         "section[data-fractal-notes]" => "notes section",
         "main" => "main section",
@@ -908,16 +1796,25 @@ fn single_node(page: &Path, document: &PageDocument, selector: &str) -> Result<N
     };
 
     match nodes.as_slice() {
-        [node] => Ok(node.clone()),
-        [] => Err(FractalError::invalid_project(format!(
-            "missing {label} in {}",
-            page.display()
-        ))),
-        _ => Err(FractalError::invalid_project(format!(
-            "duplicate {label} in {}: {} found",
-            page.display(),
-            nodes.len()
-        ))),
+        [node] => Ok(Some(node.clone())),
+        [] => check
+            .fail(
+                missing_rule,
+                None,
+                format!("missing {label} in {}", page.display()),
+            )
+            .map(|()| None),
+        [_, duplicate, ..] => check
+            .fail(
+                duplicate_rule,
+                Some(duplicate),
+                format!(
+                    "duplicate {label} in {}: {} found",
+                    page.display(),
+                    nodes.len()
+                ),
+            )
+            .map(|()| None),
     }
 }
 
@@ -930,6 +1827,169 @@ fn select_nodes(document: &PageDocument, selector: &str) -> Vec<NodeRef> {
         .collect()
 }
 
+fn select_within(root: &NodeRef, selector: &str) -> Vec<NodeRef> {
+    root.select(selector)
+        .expect("static selector should parse")
+        .map(|element| element.as_node().clone())
+        .collect()
+}
+
+/// Maps a parsed node back to its start tag. The nth element of a name is paired
+/// with the nth start tag written in the source, so when the parser implied or
+/// duplicated elements of that name (`tbody`, `body`, misnested formatting) the
+/// pairing is ambiguous and no span is reported.
+pub(crate) fn source_span(
+    source: &str,
+    document: &PageDocument,
+    node: &NodeRef,
+) -> Option<SourceSpan> {
+    let node = if node.as_element().is_some() {
+        node.clone()
+    } else {
+        node.parent()?
+    };
+    let name = node.as_element()?.name.local.to_string();
+    let elements = named_elements(document, &name);
+    let tags = start_tags(source, &name);
+    if elements.len() != tags.len() {
+        return None;
+    }
+    let occurrence = elements.iter().position(|candidate| *candidate == node)?;
+    let (start, end) = tags[occurrence];
+
+    let before = &source[..start];
+    let line_start = before.rfind('\n').map_or(0, |position| position + 1);
+    Some(SourceSpan {
+        start,
+        end,
+        line: before.matches('\n').count() + 1,
+        column: before[line_start..].chars().count() + 1,
+    })
+}
+
+pub(crate) fn node_at_span(
+    source: &str,
+    document: &PageDocument,
+    name: &str,
+    start: usize,
+) -> Option<NodeRef> {
+    let elements = named_elements(document, name);
+    let tags = start_tags(source, name);
+    if elements.len() != tags.len() {
+        return None;
+    }
+    let occurrence = tags.iter().position(|(tag_start, _)| *tag_start == start)?;
+    elements.into_iter().nth(occurrence)
+}
+
+fn named_elements(document: &PageDocument, name: &str) -> Vec<NodeRef> {
+    document
+        .document
+        .descendants()
+        .filter(|candidate| is_element_named(candidate, name))
+        .collect()
+}
+
+/// Elements whose content is text up to the matching end tag, so a `<` inside
+/// them never starts a tag.
+const RAW_TEXT_ELEMENTS: &[&str] = &[
+    "iframe",
+    "noembed",
+    "noframes",
+    "plaintext",
+    "script",
+    "style",
+    "textarea",
+    "title",
+    "xmp",
+];
+
+/// Byte ranges of the start tags named `name` that are written in `source`.
+/// Comments, doctypes, end tags and raw-text element content are skipped, and a
+/// `>` inside a quoted attribute value does not end the tag.
+fn start_tags(source: &str, name: &str) -> Vec<(usize, usize)> {
+    let lowered = source.to_ascii_lowercase();
+    let bytes = source.as_bytes();
+    let mut tags = Vec::new();
+    let mut offset = 0;
+    while let Some(found) = lowered[offset..].find('<') {
+        let start = offset + found;
+        let rest = &lowered[start..];
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            let end = if comment.starts_with('>') {
+                1
+            } else if comment.starts_with("->") {
+                2
+            } else {
+                comment.find("-->").map_or(comment.len(), |end| end + 3)
+            };
+            offset = start + 4 + end;
+            continue;
+        }
+        if rest.starts_with("<!") || rest.starts_with("<?") || rest.starts_with("</") {
+            offset = rest.find('>').map_or(source.len(), |end| start + end + 1);
+            continue;
+        }
+        if !rest[1..].starts_with(|character: char| character.is_ascii_alphabetic()) {
+            offset = start + 1;
+            continue;
+        }
+
+        let name_end = rest[1..]
+            .find(|character: char| {
+                character.is_ascii_whitespace() || matches!(character, '/' | '>')
+            })
+            .map_or(source.len(), |end| start + 1 + end);
+        let tag_name = &lowered[start + 1..name_end];
+        let end = start_tag_end(bytes, name_end);
+        if tag_name.eq_ignore_ascii_case(name) {
+            tags.push((start, end));
+        }
+        offset = end;
+
+        if RAW_TEXT_ELEMENTS.contains(&tag_name) {
+            let closing = format!("</{tag_name}");
+            offset = lowered[offset..]
+                .match_indices(&closing)
+                .map(|(position, _)| offset + position)
+                .find(|position| {
+                    lowered
+                        .as_bytes()
+                        .get(position + closing.len())
+                        .is_none_or(|next| {
+                            next.is_ascii_whitespace() || matches!(next, b'/' | b'>')
+                        })
+                })
+                .unwrap_or(source.len());
+        }
+    }
+    tags
+}
+
+/// Offset just past the `>` that closes a start tag whose attributes begin at
+/// `index`, skipping over quoted attribute values.
+fn start_tag_end(bytes: &[u8], mut index: usize) -> usize {
+    while index < bytes.len() {
+        match bytes[index] {
+            b'>' => return index + 1,
+            b'=' => {
+                index += 1;
+                while bytes.get(index).is_some_and(u8::is_ascii_whitespace) {
+                    index += 1;
+                }
+                if let Some(&quote @ (b'"' | b'\'')) = bytes.get(index) {
+                    index = bytes[index + 1..]
+                        .iter()
+                        .position(|byte| *byte == quote)
+                        .map_or(bytes.len(), |close| index + 1 + close + 1);
+                }
+            }
+            _ => index += 1,
+        }
+    }
+    bytes.len()
+}
+
 pub(crate) fn known_page_titles_for_candidate(
     root: &Path,
     page_path: &str,
@@ -960,6 +2020,14 @@ fn page_paths_for_candidate(root: &Path, page_path: &str) -> Result<Vec<String>>
     Ok(page_paths)
 }
 
+fn known_asset_paths(page_paths: &[String]) -> BTreeSet<String> {
+    page_paths
+        .iter()
+        .filter(|path| file_kind(path) != "page")
+        .cloned()
+        .collect()
+}
+
 fn known_html_page_paths(page_paths: &[String]) -> BTreeSet<String> {
     page_paths
         .iter()
@@ -969,22 +2037,38 @@ fn known_html_page_paths(page_paths: &[String]) -> BTreeSet<String> {
 }
 
 fn known_page_titles(pages_dir: &Path, page_paths: &[String]) -> Result<BTreeMap<String, String>> {
-    let mut titles = BTreeMap::new();
-
-    for page_path in page_paths.iter().filter(|path| is_html_path(path)) {
-        let page = pages_dir.join(page_path);
-        if !page.is_file() {
-            continue;
-        }
+    let page_paths = page_paths
+        .iter()
+        .filter(|path| is_html_path(path) && pages_dir.join(path).is_file())
+        .collect::<Vec<_>>();
 
-        let document = PageDocument::from_path(&page)?;
+    map_pages(&page_paths, |page_path| {
+        let document = PageDocument::from_path(&pages_dir.join(page_path))?;
         let title = document
             .title()
             .unwrap_or_else(|| page_title_from_path(page_path));
-        titles.insert(page_path.clone(), title);
-    }
+        Ok(((*page_path).clone(), title))
+    })
+    .into_iter()
+    .collect()
+}
 
-    Ok(titles)
+fn load_page_headings(pages_dir: &Path, page_path: &str) -> Option<Vec<HeadingEntry>> {
+    PageDocument::from_path(&pages_dir.join(page_path))
+        .ok()
+        .map(|document| document.headings())
+}
+
+fn load_page_note_ids(pages_dir: &Path, page_path: &str) -> Option<BTreeSet<String>> {
+    PageDocument::from_path(&pages_dir.join(page_path))
+        .ok()
+        .map(|document| document.notes().into_iter().map(|note| note.id).collect())
+}
+
+fn load_page_fragments(pages_dir: &Path, page_path: &str) -> Option<BTreeSet<String>> {
+    PageDocument::from_path(&pages_dir.join(page_path))
+        .ok()
+        .map(|document| document.page_fragments())
 }
 
 fn meaningful_children(node: &NodeRef) -> Vec<NodeRef> {
@@ -1036,10 +2120,27 @@ fn is_allowed_body_element(name: &str) -> bool {
             | "pre"
             | "code"
             | "a"
+            | "table"
+            | "figure"
     )
 }
 
-fn node_label(node: &NodeRef) -> String {
+fn is_inline_element(name: &str) -> bool {
+    is_inline_formatting_element(name) || name == "br"
+}
+
+fn is_embedded_content_element(name: &str) -> bool {
+    matches!(
+        name,
+        "table" | "thead" | "tbody" | "tr" | "th" | "td" | "img" | "figure" | "figcaption"
+    )
+}
+
+fn is_supported_page_version(version: &str) -> bool {
+    version == DEFAULT_VERSION || version == LEGACY_VERSION
+}
+
+pub(crate) fn node_label(node: &NodeRef) -> String {
     if let Some(element) = node.as_element() {
         format!("<{}>", element.name.local)
     } else if node.as_text().is_some() {